@@ -0,0 +1,191 @@
+//! # Fixed-point payout math
+//!
+//! u128 intermediates, a single explicit round-down per result, overflow-checked.
+//! Every resolver, `get_worst_payout` and the fee split go through these helpers,
+//! so the amount a player is paid and the amount the pool reserved for it are
+//! computed by the same expression and can never drift apart through rounding.
+//! Bounds that are not payouts themselves (e.g. a chained parlay multiplier)
+//! round up instead, via `mul_div_ceil`.
+//! Estimators that need a logarithm (the HyperLogLog sketch) use `ln_q32`, a
+//! Q32.32 natural log built from integer squaring — no floats on-chain.
+
+/// Basis-point denominator: 10_000 bps = 1.0000×.
+pub const BPS: u64 = 10_000;
+
+/// 1.0 in Q32.32 — the scale of `log2_q32` / `ln_q32` results.
+pub const Q32_ONE: u64 = 1 << 32;
+
+/// ln 2 as `LN2_NUM / LN2_DEN` (16 significant digits).
+const LN2_NUM: u128 = 6_931_471_805_599_453;
+const LN2_DEN: u128 = 10_000_000_000_000_000;
+
+/// `value × num / den`, rounded down.
+/// `None` on a zero denominator or when the result does not fit in u64.
+#[inline(always)]
+pub fn mul_div_floor(value: u64, num: u64, den: u64) -> Option<u64> {
+    if den == 0 { return None; }
+    let r = (value as u128).checked_mul(num as u128)? / den as u128;
+    u64::try_from(r).ok()
+}
+
+/// `value × num / den`, rounded up.
+/// `None` on a zero denominator or when the result does not fit in u64.
+#[inline(always)]
+pub fn mul_div_ceil(value: u64, num: u64, den: u64) -> Option<u64> {
+    if den == 0 { return None; }
+    let r = (value as u128).checked_mul(num as u128)?.div_ceil(den as u128);
+    u64::try_from(r).ok()
+}
+
+/// `value × bps / 10_000`, rounded down. Multipliers above 1.0× are allowed.
+#[inline(always)]
+pub fn apply_bps(value: u64, bps: u64) -> Option<u64> {
+    mul_div_floor(value, bps, BPS)
+}
+
+/// Fee share `value × bps / 10_000`, rounded down. For bps ≤ 10_000 the result
+/// is ≤ `value` and cannot overflow; larger bps saturate at u64::MAX.
+#[inline(always)]
+pub fn share_bps(value: u64, bps: u64) -> u64 {
+    apply_bps(value, bps).unwrap_or(u64::MAX)
+}
+
+/// Gross win payout for a win-probability fraction `win_num / win_den` under
+/// retention numerator `ret_num` (bps):
+///
+/// `bet × ret_num × win_den / (win_num × 10_000)`, rounded down once.
+///
+/// Returns `Some(0)` for `win_num == 0`, `None` if the result overflows u64.
+#[inline(always)]
+pub fn win_payout(bet: u64, ret_num: u64, win_num: u64, win_den: u64) -> Option<u64> {
+    if win_num == 0 { return Some(0); }
+    let n = (bet as u128).checked_mul(ret_num as u128)?.checked_mul(win_den as u128)?;
+    let d = (win_num as u128) * BPS as u128;
+    u64::try_from(n / d).ok()
+}
+
+/// `log2(num / den)` in Q32.32, for `num ≥ den`, rounded down.
+/// Integer part from halvings, then one fractional bit per squaring of the
+/// Q63 mantissa. `None` on a zero denominator or `num < den`.
+pub fn log2_q32(num: u64, den: u64) -> Option<u64> {
+    if den == 0 || num < den { return None; }
+    const ONE: u128 = 1 << 63;
+    let mut x   = ((num as u128) << 63) / den as u128;
+    let mut int = 0u64;
+    while x >= 2 * ONE {
+        x >>= 1;
+        int += 1;
+    }
+    let mut frac = 0u64;
+    for bit in (0..32).rev() {
+        x = (x * x) >> 63;
+        if x >= 2 * ONE {
+            x >>= 1;
+            frac |= 1 << bit;
+        }
+    }
+    Some(int << 32 | frac)
+}
+
+/// `ln(num / den)` in Q32.32, for `num ≥ den`, rounded down.
+/// `None` on a zero denominator or `num < den`.
+pub fn ln_q32(num: u64, den: u64) -> Option<u64> {
+    let l2 = log2_q32(num, den)? as u128;
+    u64::try_from(l2 * LN2_NUM / LN2_DEN).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mul_div_floor_rounds_down_once() {
+        assert_eq!(mul_div_floor(10, 1, 3), Some(3));
+        assert_eq!(mul_div_floor(2, 2, 3), Some(1));        // 4/3, not 2×(2/3) = 0
+        assert_eq!(mul_div_floor(7, 3, 7), Some(3));
+        assert_eq!(mul_div_floor(0, u64::MAX, 1), Some(0));
+    }
+
+    #[test]
+    fn mul_div_floor_uses_wide_intermediates() {
+        assert_eq!(mul_div_floor(u64::MAX, u64::MAX, u64::MAX), Some(u64::MAX));
+        assert_eq!(mul_div_floor(u64::MAX, 3, 4), Some(u64::MAX / 4 * 3 + 2));
+    }
+
+    #[test]
+    fn mul_div_floor_none_on_zero_den_or_overflow() {
+        assert_eq!(mul_div_floor(1, 1, 0), None);
+        assert_eq!(mul_div_floor(0, 0, 0), None);
+        assert_eq!(mul_div_floor(u64::MAX, 2, 1), None);
+        assert_eq!(mul_div_floor(u64::MAX, u64::MAX, u64::MAX - 1), None);
+    }
+
+    #[test]
+    fn mul_div_ceil_rounds_up_only_when_inexact() {
+        assert_eq!(mul_div_ceil(10, 1, 3), Some(4));
+        assert_eq!(mul_div_ceil(9, 1, 3), Some(3));
+        assert_eq!(mul_div_ceil(0, 5, 7), Some(0));
+        assert_eq!(mul_div_ceil(u64::MAX, u64::MAX, u64::MAX), Some(u64::MAX));
+        for (v, n, d) in [(7u64, 3u64, 7u64), (1, 1, 2), (123_456_789, 9_750, 10_000)] {
+            let (lo, hi) = (mul_div_floor(v, n, d).unwrap(), mul_div_ceil(v, n, d).unwrap());
+            assert_eq!(hi - lo, u64::from(!(v as u128 * n as u128).is_multiple_of(d as u128)));
+        }
+    }
+
+    #[test]
+    fn mul_div_ceil_none_on_zero_den_or_overflow() {
+        assert_eq!(mul_div_ceil(1, 1, 0), None);
+        assert_eq!(mul_div_ceil(u64::MAX, 2, 1), None);
+        assert_eq!(mul_div_ceil(u64::MAX, u64::MAX, u64::MAX - 1), None);
+    }
+
+    #[test]
+    fn apply_bps_allows_multipliers_above_one() {
+        assert_eq!(apply_bps(1_000_000, 19_500), Some(1_950_000));
+        assert_eq!(apply_bps(9_999, 1), Some(0));
+        assert_eq!(apply_bps(u64::MAX, BPS), Some(u64::MAX));
+        assert_eq!(apply_bps(u64::MAX, BPS + 1), None);
+    }
+
+    #[test]
+    fn share_bps_never_exceeds_value_and_saturates() {
+        for v in [0, 1, 9_999, 10_000, 123_456_789, u64::MAX] {
+            for bps in [0, 1, 250, 5_000, BPS] {
+                assert!(share_bps(v, bps) <= v);
+            }
+        }
+        assert_eq!(share_bps(1_000_000, 250), 25_000);
+        assert_eq!(share_bps(u64::MAX, 2 * BPS), u64::MAX);
+    }
+
+    #[test]
+    fn log2_q32_is_exact_on_powers_of_two() {
+        assert_eq!(log2_q32(1, 1), Some(0));
+        assert_eq!(log2_q32(8, 1), Some(3 * Q32_ONE));
+        assert_eq!(log2_q32(256, 2), Some(7 * Q32_ONE));
+        assert_eq!(log2_q32(u64::MAX, 1), Some(64 * Q32_ONE - 1));
+        assert_eq!(log2_q32(3, 2), Some(2_512_394_809));              // log2 1.5 × 2^32 = 2_512_394_809.98
+        assert_eq!(log2_q32(1, 2), None);
+        assert_eq!(log2_q32(1, 0), None);
+    }
+
+    #[test]
+    fn ln_q32_tracks_the_natural_log() {
+        assert_eq!(ln_q32(5, 5), Some(0));
+        assert_eq!(ln_q32(2, 1), Some(2_977_044_471));                // ln 2 × 2^32 = 2_977_044_471.8
+        // ln 256 × 2^32 = 23_816_355_774.56; allow a couple of units of squaring truncation
+        let ln256 = ln_q32(256, 1).unwrap();
+        assert!(ln256.abs_diff(23_816_355_774) <= 2);
+        assert_eq!(ln_q32(1, 3), None);
+    }
+
+    #[test]
+    fn win_payout_is_fair_odds_times_retention() {
+        assert_eq!(win_payout(1_000_000, BPS, 1, 2), Some(2_000_000));
+        assert_eq!(win_payout(1_000_000, 9_750, 1, 2), Some(1_950_000));
+        assert_eq!(win_payout(1_000, 9_750, 3, 7), Some(2_275));     // 2_275.0, one rounding only
+        assert_eq!(win_payout(1_000, 9_750, 2, 3), Some(1_462));     // 1_462.5 → down
+        assert_eq!(win_payout(1_000, 9_750, 0, 3), Some(0));
+        assert_eq!(win_payout(u64::MAX, BPS, 1, 2), None);
+    }
+}
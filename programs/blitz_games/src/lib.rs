@@ -1,22 +1,33 @@
 //! # Blitz99 Games — On-Chain Casino Protocol
 //!
-//! Provably fair via Commit-Reveal + multi-slot BLAKE3 seed.
-//! Four game types: Flip | Sector99 | Dice | Tower.
+//! Provably fair via Commit-Reveal + multi-slot BLAKE3 seed, or opt-in VRF (Switchboard On-Demand
+//! or ORAO, chosen per pool).
+//! Twenty-two game types: Flip | Sector99 | Dice | Tower | Mystery | Plinko | Mines | Crash | Roulette | Keno
+//! | Limbo | Wheel | HiLo | Video Poker | Baccarat | Slots | Sic Bo | Dragon Tiger | Dice Duo | Stairs | Goal
+//! | Tower Climb.
 //! Phase-adaptive edge (2.5% → 2.0% → 1.5%) and auto-reinvest.
 //!
 //! Program ID: 9DK1L9UF4EmkrMPpv9FZs4B63RvVPwJR34NGWm9NEbVy
 
 use anchor_lang::prelude::*;
+use anchor_lang::Discriminator;
 use anchor_lang::solana_program::{
+    ed25519_program,
     hash,
-    program::invoke,
+    instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT},
+    program::{invoke, invoke_signed, set_return_data},
     system_instruction,
     system_program,
-    sysvar::slot_hashes,
+    sysvar::{instructions as ix_sysvar, slot_hashes},
 };
 
 declare_id!("9DK1L9UF4EmkrMPpv9FZs4B63RvVPwJR34NGWm9NEbVy");
 
+pub mod fixed;
+pub mod orao;
+pub mod switchboard;
+pub mod sysvars;
+
 #[cfg(not(feature = "no-entrypoint"))]
 use solana_security_txt::security_txt;
 
@@ -40,6 +51,29 @@ security_txt! {
 pub const REVEAL_WINDOW:      u64 = 500;
 /// Slot spacing between the three seed hashes — guarantees different validator leaders.
 pub const SLOT_SPREAD:        u64 = 10;
+/// Slots after forfeit_slot before anyone may force a forfeiture — the player's last chance at the 4% refund.
+pub const FORFEIT_GRACE_SLOTS: u64 = 200;
+/// Slots a settled session is kept as a reveal receipt before close_settled_session may run (~1 min).
+pub const RECEIPT_WINDOW:     u64 = 150;
+/// GlobalPool.vrf_provider: the VRF place_bet_vrf binds bets to. Commit-reveal is always available.
+pub const VRF_SWITCHBOARD:    u8  = 0;
+pub const VRF_ORAO:           u8  = 1;
+/// A held large payout becomes releasable without the co-signer after 24 h.
+pub const COSIGN_FALLBACK_SECS: i64 = 86_400;
+/// place_bet_v3 flag: anyone holding the nonce may reveal via reveal_public.
+pub const PLACE_FLAG_PUBLIC_REVEAL: u8 = 1 << 0;
+/// place_bet_v3 flag: player consents to the stake being cut to max_allowed_bet instead of rejected.
+pub const PLACE_FLAG_DOWNSIZE:      u8 = 1 << 1;
+/// place_bet_v3 flag: a win is held for DOUBLE_WINDOW_SECS instead of paid, open to double_or_nothing.
+pub const PLACE_FLAG_DOUBLE:        u8 = 1 << 2;
+/// place_bet_v3 flag: commitment = SHA-256(nonce || client_seed); settle with reveal_client_seeded.
+pub const PLACE_FLAG_CLIENT_SEED:   u8 = 1 << 3;
+/// Most bets place_bets_batch places, and sessions reveal_batch settles, per call.
+pub const BATCH_MAX_BETS: usize = 5;
+/// Tip a public reveal pays its executor out of a win (0.001 SOL, at most the payout).
+pub const CRANK_TIP_LAMPORTS:  u64 = 1_000_000;
+/// Depth of a room's ReceiptTree (2^20 ≈ 1M settlements before a new tree is needed).
+pub const RECEIPT_TREE_DEPTH: usize = 20;
 
 // ── Anti-rug timelocks ────────────────────────────────────────────────────
 /// 48 h delay before a requested withdrawal can be executed.
@@ -48,10 +82,70 @@ pub const TIMELOCK_SECS:      i64 = 172_800;
 pub const AUTH_TIMELOCK:      i64 = 259_200;
 /// Maximum continuous pause duration. Contract auto-unpauses on next bet attempt.
 pub const MAX_PAUSE_DURATION: i64 = 86_400;
+/// 7 d an LP deposit stays locked before its shares can be redeemed.
+pub const LP_LOCK_SECS:       i64 = 604_800;
+/// Share of total_balance process_lp_queue may pay out to exiting LPs per Solana epoch (10%).
+pub const LP_EXIT_EPOCH_BPS:  u64 = 1_000;
+/// Epoch snapshots an LpLedger keeps before the oldest is overwritten.
+pub const LP_LEDGER_EPOCHS:   usize = 64;
+/// Fixed-point scale of LP share prices: lamports per LP_PRICE_SCALE shares.
+pub const LP_PRICE_SCALE:     u64 = 1_000_000_000;
+/// Share price below which the LP book is written off (a millionth of the 1:1 starting price).
+pub const LP_WRITEOFF_PRICE:  u64 = 1_000;
+/// Smallest LP deposit, and smallest exit ticket short of a whole position (0.01 SOL), so dust
+/// tickets cannot clog the exit queue.
+pub const LP_MIN_TICKET:      u64 = 10_000_000;
+/// Most exit tickets process_lp_queue pays per call — (position, owner) pairs in remaining_accounts.
+pub const LP_QUEUE_BATCH:     usize = 8;
+/// Ceiling on the authority's performance fee (30% of LP profit above the high-water mark).
+pub const PERFORMANCE_FEE_MAX_BPS: u16 = 3_000;
+/// Slots before take_nav_snapshot may refresh a room's NavSnapshot (~1 h).
+pub const NAV_SNAPSHOT_SLOTS: u64 = 9_000;
 
 // ── Pool safety ───────────────────────────────────────────────────────────
 /// Minimum pool balance for bets to be accepted (0.1 SOL).
 pub const MIN_POOL: u64 = 100_000_000;
+/// Ceiling on the share of each bet routed from the house cut to the insurance reserve (1%).
+pub const INSURANCE_MAX_BPS:  u16 = 100;
+/// 24 h between request_insurance_tap and tap_insurance.
+pub const INSURANCE_TAP_SECS: i64 = 86_400;
+
+// ── Rooms (separate liquidity pools, same program) ────────────────────────
+pub const ROOM_MAIN:            u8  = 0;
+pub const ROOM_HIGH_ROLLER:     u8  = 1;
+/// High-roller room: min bet 1 SOL, min pool 10 SOL, bet and payout caps ×1.5.
+/// Whale variance hits its own liquidity, never the main pool.
+pub const HIGH_ROLLER_MIN_BET:  u64 = 1_000_000_000;
+pub const HIGH_ROLLER_MIN_POOL: u64 = 10_000_000_000;
+pub const HIGH_ROLLER_CAP_BPS:  u64 = 15_000;
+
+// ── Unique-player sketch (HyperLogLog) ────────────────────────────────────
+/// Register count (2^8). Standard error ≈ 1.04 / √256 ≈ 6.5%.
+pub const HLL_REGISTERS: usize = 256;
+
+// ── Open-session index ────────────────────────────────────────────────────
+/// Pending sessions per OpenSessionPage PDA.
+pub const OPEN_PAGE_CAP:  usize = 32;
+/// GameSession.index_page sentinel — session was not indexed.
+pub const NOT_INDEXED:    u32   = u32::MAX;
+
+// ── CPI guard ─────────────────────────────────────────────────────────────
+/// Programs allowed to call place_bet via CPI (GlobalPool.cpi_whitelist slots).
+pub const CPI_WHITELIST_LEN: usize = 4;
+
+// ── Signed session authorizations ─────────────────────────────────────────
+/// Domain tag prefixed to the off-chain message signed for create_session_with_sig.
+pub const SESSION_AUTH_DOMAIN:  &[u8] = b"blitz99-session-v1";
+/// The only scope a session key can hold: delegated reveals.
+pub const SESSION_SCOPE_REVEAL: u8    = 1;
+
+// ── Promo vouchers ────────────────────────────────────────────────────────
+/// Domain tag prefixed to the off-chain message the promo attestor signs for a voucher.
+pub const PROMO_VOUCHER_DOMAIN: &[u8] = b"blitz99-promo-voucher-v1";
+
+// ── Partners ──────────────────────────────────────────────────────────────
+/// Maximum partner share of the house cut (50%).
+pub const PARTNER_MAX_FEE_BPS: u16 = 5_000;
 
 // ── Jackpot ───────────────────────────────────────────────────────────────
 /// Minimum bet to be eligible for a jackpot trigger (0.02 SOL).
@@ -60,9 +154,314 @@ pub const JACKPOT_MIN_BET:  u64 = 20_000_000;
 pub const JACKPOT_MIN_POOL: u64 = 100_000_000;
 /// Linear scaling factor: threshold = bet × JACKPOT_RATE / JACKPOT_BASE.
 /// Probability table:
-///   0.02 SOL → ~0.002% | 0.1 SOL → ~0.01% | 1.0 SOL → ~0.1% | 5.0 SOL → ~0.5% (cap)
+///   0.02 SOL → ~0.002% | 0.1 SOL → ~0.01% | 1.0 SOL → ~0.1% | 5.0 SOL → ~0.5% (default cap)
 pub const JACKPOT_RATE:     u64 = 43;
 pub const JACKPOT_BASE:     u64 = 10_000;
+/// Default per-bet trigger probability cap (0.5%) — used while GlobalPool.jackpot_cap_bps is 0.
+pub const JACKPOT_CAP_DEFAULT_BPS: u16 = 50;
+/// Highest cap an operator may configure (2%).
+pub const JACKPOT_CAP_MAX_BPS:     u16 = 200;
+/// Boost window limits: multipliers in bps of 1× (up to 3×), at most 7 days long.
+pub const JACKPOT_BOOST_MAX_BPS:   u16 = 30_000;
+pub const JACKPOT_BOOST_MAX_SECS:  i64 = 7 * 86_400;
+
+// ── Mystery bet ───────────────────────────────────────────────────────────
+/// Bonus applied to the picked game's payout (+1%). Funded by the house — keep stakes low.
+pub const MYSTERY_BONUS_BPS: u64 = 10_100;
+/// Maximum mystery stake (0.1 SOL) — fun mode, not a bankroll risk.
+pub const MYSTERY_MAX_BET:   u64 = 100_000_000;
+/// Fixed configs the mystery resolver dispatches into: Flip | Dice under-50 | Sector r=1 centred.
+pub const MYSTERY_GAMES: [(u8, [u8; 3]); 3] = [(0, [0, 0, 0]), (2, [50, 0, 0]), (1, [7, 7, 1])];
+
+// ── Flip streak (game_config = [flips, sides, 0]) ────────────────────────
+/// Parlay of 2–6 flips on seed bytes 0..flips (bit 0 of each; 1 = heads). Bit i of `sides`
+/// is the side called for flip i; every call must land. flips = 0 is the classic single Flip.
+pub const FLIP_STREAK_MIN:     u8  = 2;
+pub const FLIP_STREAK_MAX:     u8  = 6;
+/// Flat retention on a streak: 0.95 × 2^flips, not phase-adjusted.
+pub const FLIP_STREAK_NUM_BPS: u64 = 9_500;
+
+// ── Dice modes (game_config = [target, mode, hi]) ────────────────────────
+/// Dice mode byte values (game_config[1]).
+pub const DICE_MODE_UNDER:       u8  = 0;
+pub const DICE_MODE_OVER:        u8  = 1;
+pub const DICE_MODE_CHANCE:      u8  = 2;
+/// Chance mode rolls 0..200 → win probability in 0.5% steps.
+pub const DICE_CHANCE_STEPS:     u64 = 200;
+/// Allowed win chance: 2% (4 steps) to 95% (190 steps).
+pub const DICE_CHANCE_MIN_STEPS: u8  = 4;
+pub const DICE_CHANCE_MAX_STEPS: u8  = 190;
+/// Exact mode (game_config = [n, DICE_MODE_EXACT, 0]) wins on roll == n, 0–99: (1, 100) ≈ 98×.
+pub const DICE_MODE_EXACT:       u8  = 3;
+/// Range mode (game_config = [lo, DICE_MODE_RANGE, hi]) wins on lo ≤ roll ≤ hi, 0–99.
+pub const DICE_MODE_RANGE:       u8  = 4;
+/// Widest range accepted — the same 95% ceiling as under/over.
+pub const DICE_RANGE_MAX_WIDTH:  u8  = 95;
+
+// ── Schema registry ───────────────────────────────────────────────────────
+/// Bump on any event or account layout change, in the same commit as the change.
+pub const SCHEMA_VERSION: u32 = 73;
+
+// ── Upgrade handshake ─────────────────────────────────────────────────────
+/// Bump on every release whose upgrade needs migrations or config changes. Money-moving
+/// instructions refuse to run until finalize_upgrade writes it into the pool.
+pub const PROGRAM_VERSION: u32 = 1;
+
+// ── Sector99 (game_config = [x, y, r], config_ext = extra zones) ─────────
+/// Zones one bet may cover. Zone 1 is game_config; place_bet_v2's config_ext adds the rest:
+/// LE bytes 0..3 = zone 2 [x, y, r], 3..6 = zone 3, byte 6 = extra zone count, byte 7 = 0.
+/// Zones must be pairwise disjoint; the win fraction is their combined area over 256.
+pub const SECTOR_MAX_ZONES: usize = 3;
+
+// ── Tower (game_config = [floors, path, doors]) ──────────────────────────
+/// Doors per floor, one of them trapped. 0 reads as 2 — the classic binary tower, path bit i
+/// = door on floor i. With 3 or 4 doors the path packs 2 bits per floor, so floors cap at 4.
+pub const TOWER_MIN_DOORS:       u8 = 2;
+pub const TOWER_MAX_DOORS:       u8 = 4;
+pub const TOWER_MAX_FLOORS:      u8 = 6;
+pub const TOWER_MAX_WIDE_FLOORS: u8 = 4;
+// Tower Climb (game 21, game_config = [floors, 0, doors]) picks one door per floor
+// interactively; `floors` is the top, which cashes out automatically. Same odds per floor.
+
+// ── Plinko (game_config = [rows, risk, 0]) ────────────────────────────────
+/// Board height range — one seed bit per row, read from seed bytes 0..2.
+pub const PLINKO_MIN_ROWS:  u8 = 8;
+pub const PLINKO_MAX_ROWS:  u8 = 16;
+/// Risk tiers (game_config[1]): bucket weight = distance from centre ^ (risk + 1) + 1.
+pub const PLINKO_RISK_LOW:  u8 = 0;
+pub const PLINKO_RISK_MED:  u8 = 1;
+pub const PLINKO_RISK_HIGH: u8 = 2;
+
+// ── Mines (game_config = [mines, 0, 0]) ──────────────────────────────────
+/// 5×5 board, tiles 0..25 row-major.
+pub const MINES_TILES: u8 = 25;
+pub const MINES_MIN:   u8 = 1;
+pub const MINES_MAX:   u8 = 24;
+/// `mines_pick` value while no tile awaits resolve_tile.
+pub const MINES_NO_PICK: u8 = u8::MAX;
+/// Multiplier ceiling (1_000×) — reveal_tile refuses a pick whose payout would exceed it.
+pub const MINES_MAX_MULTIPLIER_BPS: u64 = 10_000_000;
+
+// ── HiLo (game_config = [0, 0, 0]) ───────────────────────────────────────
+/// Ranks 1 (ace) – 13 (king), dealt with replacement. A guess wins on a tie.
+pub const HILO_RANKS:     u8 = 13;
+pub const HILO_LOWER:     u8 = 0; // next ≤ current
+pub const HILO_HIGHER:    u8 = 1; // next ≥ current
+/// `hilo_guess` value while no guess awaits resolve_hilo.
+pub const HILO_NO_GUESS:  u8 = u8::MAX;
+/// Round ceiling — keeps 13^rounds inside u64; resolve_hilo cashes out on reaching it.
+pub const HILO_MAX_ROUNDS: u8 = 16;
+/// Multiplier ceiling (1_000×) — hilo_guess refuses a guess whose payout would exceed it.
+pub const HILO_MAX_MULTIPLIER_BPS: u64 = 10_000_000;
+
+// ── Roulette (game_config = [bet_class, selection, 0]) ────────────────────
+/// European wheel — single zero, 37 pockets. The zero is the whole house edge (2.70%).
+pub const ROULETTE_POCKETS:  u64 = 37;
+/// Bet classes (game_config[1] selects within the class).
+pub const ROULETTE_STRAIGHT: u8  = 0; // pocket 0–36
+pub const ROULETTE_COLOUR:   u8  = 1; // 0 = red, 1 = black
+pub const ROULETTE_PARITY:   u8  = 2; // 0 = odd, 1 = even
+pub const ROULETTE_DOZEN:    u8  = 3; // 0 = 1–12, 1 = 13–24, 2 = 25–36
+pub const ROULETTE_COLUMN:   u8  = 4; // 0 = 1,4..34, 1 = 2,5..35, 2 = 3,6..36
+/// Gross payout multiple per class, stake included: 35:1 | 1:1 | 1:1 | 2:1 | 2:1.
+pub const ROULETTE_PAYOUT_X: [u64; 5] = [36, 2, 2, 3, 3];
+/// Bit n set = pocket n is red.
+pub const ROULETTE_RED: u64 = (1 << 1) | (1 << 3) | (1 << 5) | (1 << 7) | (1 << 9) | (1 << 12) | (1 << 14)
+    | (1 << 16) | (1 << 18) | (1 << 19) | (1 << 21) | (1 << 23) | (1 << 25) | (1 << 27) | (1 << 30)
+    | (1 << 32) | (1 << 34) | (1 << 36);
+
+// ── Keno (game_config = [picks, 0, 0], config_ext = picked-number bitmask) ─
+/// Numbers 1–40 (bit n = number n + 1); the draw takes 10 of them.
+pub const KENO_NUMBERS:   u64 = 40;
+pub const KENO_DRAWN:     u8  = 10;
+pub const KENO_MAX_PICKS: u8  = 10;
+/// Relative payout weight by [picks − 1][hits]. keno_multiplier_bps normalises each row
+/// over the hypergeometric hit distribution so EV = payout_num / 10_000 for every pick count.
+pub const KENO_PAYTABLE: [[u64; 11]; 10] = [
+    [0, 1, 0, 0,  0,  0,   0,    0,    0,    0,      0],
+    [0, 0, 1, 0,  0,  0,   0,    0,    0,    0,      0],
+    [0, 0, 1, 10, 0,  0,   0,    0,    0,    0,      0],
+    [0, 0, 1, 4,  40, 0,   0,    0,    0,    0,      0],
+    [0, 0, 0, 2,  12, 150, 0,    0,    0,    0,      0],
+    [0, 0, 0, 1,  4,  40,  500,  0,    0,    0,      0],
+    [0, 0, 0, 1,  2,  10,  100,  1000, 0,    0,      0],
+    [0, 0, 0, 0,  2,  5,   40,   300,  2000, 0,      0],
+    [0, 0, 0, 0,  1,  3,   15,   100,  1000, 5000,   0],
+    [0, 0, 0, 0,  1,  2,   8,    50,   300,  2000, 10000],
+];
+
+// ── Wheel (game_config = [risk, 0, 0]) ────────────────────────────────────
+pub const WHEEL_RISK_LOW:  u8 = 0;
+pub const WHEEL_RISK_MED:  u8 = 1;
+pub const WHEEL_RISK_HIGH: u8 = 2;
+/// Relative payout weight per segment, in wheel order, by risk (10 | 20 | 40 segments).
+/// wheel_multiplier_bps normalises each table over its uniform segment draw so
+/// EV = payout_num / 10_000 for every risk.
+pub const WHEEL_PAYTABLE: [&[u64]; 3] = [
+    &[2, 0, 2, 3, 0, 2, 0, 2, 3, 0],
+    &[0, 3, 0, 2, 0, 3, 0, 2, 0, 5, 0, 2, 0, 3, 0, 2, 0, 3, 0, 10],
+    &[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
+      0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1],
+];
+
+// ── Video Poker, Jacks or Better (game_config = [hold_mask, 0, 0]) ────────
+/// Cards 0..52: rank = card % 13 (0 = deuce … 12 = ace), suit = card / 13.
+pub const POKER_DECK:       u8 = 52;
+/// Hand classes returned by poker_evaluate, indexing the tables below.
+pub const POKER_NOTHING:    u8 = 0;
+pub const POKER_JACKS:      u8 = 1; // pair of jacks or better
+pub const POKER_TWO_PAIR:   u8 = 2;
+pub const POKER_TRIPS:      u8 = 3;
+pub const POKER_STRAIGHT:   u8 = 4;
+pub const POKER_FLUSH:      u8 = 5;
+pub const POKER_FULL_HOUSE: u8 = 6;
+pub const POKER_QUADS:      u8 = 7;
+pub const POKER_STRAIGHT_FLUSH: u8 = 8;
+pub const POKER_ROYAL:      u8 = 9;
+/// 9/6 Jacks-or-Better paytable (units per unit staked), by hand class.
+pub const POKER_PAYTABLE: [u64; 10] = [0, 1, 2, 3, 4, 6, 9, 25, 50, 800];
+/// Five-card hands per class out of C(52, 5) = 2_598_960 (nothing = the remainder).
+pub const POKER_HAND_COUNTS: [u64; 10] = [2_062_860, 337_920, 123_552, 54_912, 10_200, 5_108, 3_744, 624, 36, 4];
+pub const POKER_HANDS: u64 = 2_598_960;
+
+// ── Baccarat (game_config = [side, 0, 0]) ────────────────────────────────
+pub const BACCARAT_PLAYER: u8 = 0;
+pub const BACCARAT_BANKER: u8 = 1;
+pub const BACCARAT_TIE:    u8 = 2;
+/// Gross payout per side, stake included: 1.95× | 1.90× | 8×. A tie pushes Player/Banker bets.
+pub const BACCARAT_PAYOUT_BPS: [u64; 3] = [19_500, 19_000, 80_000];
+
+// ── Slots (game_config = [0, 0, 0]) ───────────────────────────────────────
+/// Reel symbols. SLOTS_ANY is the payline wildcard, never on a reel.
+pub const SLOTS_CHERRY: u8 = 0;
+pub const SLOTS_LEMON:  u8 = 1;
+pub const SLOTS_BELL:   u8 = 2;
+pub const SLOTS_BAR:    u8 = 3;
+pub const SLOTS_SEVEN:  u8 = 4;
+pub const SLOTS_COIN:   u8 = 5;
+pub const SLOTS_ANY:    u8 = u8::MAX;
+/// Reel strip shared by all three reels. Seed bytes 0..3 each pick a stop (byte % 32, unbiased).
+pub const SLOTS_REEL: [u8; 32] = [
+    0, 1, 2, 0, 3, 1, 5, 2, 0, 1, 4, 3, 0, 1, 2, 5,
+    1, 0, 3, 2, 1, 4, 0, 3, 1, 2, 5, 0, 1, 3, 2, 4,
+];
+/// Paylines, first match wins: (pattern, relative payout weight). Cherries pay from the left.
+/// slots_multiplier_bps normalises the weights over all 32³ spins so E[multiplier] = num.
+pub const SLOTS_PAYLINES: [([u8; 3], u64); 7] = [
+    ([SLOTS_SEVEN,  SLOTS_SEVEN,  SLOTS_SEVEN],  200),
+    ([SLOTS_BAR,    SLOTS_BAR,    SLOTS_BAR],    50),
+    ([SLOTS_BELL,   SLOTS_BELL,   SLOTS_BELL],   20),
+    ([SLOTS_LEMON,  SLOTS_LEMON,  SLOTS_LEMON],  10),
+    ([SLOTS_CHERRY, SLOTS_CHERRY, SLOTS_CHERRY], 10),
+    ([SLOTS_CHERRY, SLOTS_CHERRY, SLOTS_ANY],    4),
+    ([SLOTS_CHERRY, SLOTS_ANY,    SLOTS_ANY],    1),
+];
+/// A COIN anywhere on the line diverts this share of the bet from the house cut to the jackpot.
+pub const SLOTS_JACKPOT_FEED_BPS: u64 = 100;
+
+// ── Sic Bo (game_config = [bet_class, selection, 0]) ─────────────────────
+/// Bet classes (game_config[1] selects within the class). Big and Small lose on any triple.
+pub const SICBO_SMALL:  u8 = 0; // total 4–10, selection 0
+pub const SICBO_BIG:    u8 = 1; // total 11–17, selection 0
+pub const SICBO_TRIPLE: u8 = 2; // all three dice show `selection`, 1–6
+pub const SICBO_TOTAL:  u8 = 3; // dice sum to `selection`, 4–17
+/// Ways to roll each total 0..=18 with three dice, out of 6³ = 216.
+pub const SICBO_TOTAL_WAYS: [u64; 19] = [0, 0, 0, 1, 3, 6, 10, 15, 21, 25, 27, 27, 25, 21, 15, 10, 6, 3, 1];
+/// Winning rolls of a Big or Small bet: 108 totals on its side minus the 3 triples (105 / 216).
+pub const SICBO_SIDE_WAYS: u64 = 105;
+
+// ── Dragon Tiger (game_config = [side, 0, 0]) ────────────────────────────
+pub const DT_DRAGON: u8 = 0;
+pub const DT_TIGER:  u8 = 1;
+pub const DT_TIE:    u8 = 2;
+/// Gross payout per side, stake included: 1.95× | 1.95× | 8×.
+pub const DT_PAYOUT_BPS: [u64; 3] = [19_500, 19_500, 80_000];
+/// Share of a Dragon/Tiger stake returned on a tie. A full push would leave the side bets
+/// returning 12.7/13 = 97.7% — an edge below the 2.5% phase-0 fees. Half back: 93.8%.
+pub const DT_TIE_RETURN_BPS: u64 = 5_000;
+
+// ── Dice Duo (game_config = [mode, target, 0]) ───────────────────────────
+/// Two dice; the bet is on their sum against `target` (2–12).
+pub const DUO_UNDER: u8 = 0; // sum < target, target 3–12
+pub const DUO_OVER:  u8 = 1; // sum > target, target 2–11
+pub const DUO_EXACT: u8 = 2; // sum = target, target 2–12
+/// Fixed retention: true odds (36 / winning rolls) minus a flat 5% edge, not phase-adjusted.
+/// Snake eyes / boxcars (1 roll in 36) is the top line at 34.2×.
+pub const DUO_NUM: u64 = 9_500;
+
+// ── Stairs (game_config = [0, 0, 0]) ─────────────────────────────────────
+/// Step n (1-based) is a fall with probability n / STAIRS_DEN: 5%, 10%, … 50% on the top step.
+pub const STAIRS_DEN:   u64 = 20;
+/// Top of the staircase — resolve_step cashes out on clearing it (~30× at Phase 2).
+pub const STAIRS_STEPS: u8  = 10;
+
+// ── Goal (game_config = [rows, path_lo, path_hi]) ────────────────────────
+/// Tower with three lanes per row, one of them a trap. Path bits 2i..2i+2 = lane on row i.
+pub const GOAL_LANES:    u8  = 3;
+pub const GOAL_MAX_ROWS: u8  = 8;
+/// Fixed per-row payout, compounded: fair 1.5× minus 5%, not phase-adjusted. 8 rows ≈ 17×.
+pub const GOAL_ROW_BPS:  u64 = 14_250;
+
+// ── Parlay (game_config = [legs, 0, 0], config_ext = combined multiplier) ─
+/// 2–4 Flip, Sector99, Dice or Tower legs that must all win, stored in a ParlaySession.
+/// Each leg rides on the previous leg's payout; config_ext pins the chained worst-case
+/// multiplier (bps) so every solvency check sees the whole parlay as one stake.
+pub const PARLAY_MIN_LEGS: u8 = 2;
+pub const PARLAY_MAX_LEGS: u8 = 4;
+
+// ── Double or nothing (game 23, entered only via double_or_nothing) ──────
+/// How long a PLACE_FLAG_DOUBLE win stays open to double_or_nothing before anyone may release it.
+pub const DOUBLE_WINDOW_SECS: i64 = 120;
+/// Retention on a double: 50/50 paying 0.99 × 2 — the 1% fee.
+pub const DOUBLE_NUM_BPS:     u64 = 9_900;
+/// The only fee a double settles with, all to the house — the phase table (up to 2.5%) would
+/// exceed the 1% edge and come out of LP liquidity.
+pub const DOUBLE_FEE_BPS:     u64 = 100;
+
+// ── Multiplier curve (Crash / Limbo) ──────────────────────────────────────
+/// P(result ≥ m) = (1 − edge) / m.
+pub const CURVE_INVERSE:      u8 = 0;
+/// Instant 1.00× bust with probability bust_bps, inverse tail otherwise.
+pub const CURVE_INVERSE_BUST: u8 = 1;
+/// validate_curve refuses any curve whose EV at a sampled target exceeds 96%.
+pub const CURVE_MAX_EV_BPS:          u64 = 9_600;
+/// Hard ceiling for max_multiplier_x100 (10,000×).
+pub const CURVE_MAX_MULTIPLIER_X100: u32 = 1_000_000;
+/// Curve used while GlobalPool.payout_curve is unset: 5% edge, 1,000× max, plain inverse.
+pub const DEFAULT_CURVE: PayoutCurve = PayoutCurve {
+    edge_bps:            500,
+    max_multiplier_x100: 100_000,
+    shape:               CURVE_INVERSE,
+    bust_bps:            0,
+};
+
+// ── RTP alarms ────────────────────────────────────────────────────────────
+/// Per-game counter / band slots in GlobalPool — room for game types 0..24.
+pub const MAX_GAME_TYPES:  usize = 24;
+/// Slots of the original in-place game_counters / rtp_bands / games_paused; types from here
+/// on live in the appended *_hi fields, so the fields behind them never moved.
+pub const LEGACY_GAME_TYPES: usize = 16;
+/// GameSession::LEN before the room `pool` field — the layout sessions opened by the
+/// pre-room binary still have. migrate_session grows them; nothing else reads them.
+pub const LEGACY_SESSION_LEN: usize = 134;
+/// check_rtp / set_rtp_band target selecting the pool-wide lifetime counters.
+pub const RTP_GLOBAL:      u8    = u8::MAX;
+/// rtp_anomalies bit for the global band; bits 0..MAX_GAME_TYPES are per game.
+pub const RTP_GLOBAL_FLAG: u32   = 1 << 31;
+/// Upper bound for a band edge (200% RTP).
+pub const RTP_BAND_MAX_BPS: u16  = 20_000;
+
+// ── Cap smoothing ─────────────────────────────────────────────────────────
+/// balance_ema weight: each settlement moves the EMA 1/32 of the way to total_balance.
+pub const POOL_EMA_WEIGHT: u64 = 32;
+
+// ── Referral economics ────────────────────────────────────────────────────
+/// referral_mode: referrer paid a share of every bet at settlement (default).
+pub const REFERRAL_MODE_TURNOVER: u8     = 0;
+/// referral_mode: referrer accrues a share of referees' net losses per epoch on ReferralAccount.
+pub const REFERRAL_MODE_NET_LOSS: u8     = 1;
+/// Upper bound for referral_share_bps (50% of net losses).
+pub const REFERRAL_MAX_SHARE_BPS: u16    = 5_000;
 
 // ── Phase thresholds ──────────────────────────────────────────────────────
 /// Phase 0→1 transition: 50 SOL. Below = bootstrap mode (E=2.5%).
@@ -90,6 +489,65 @@ pub const PHASE2_THRESHOLD: u64 = 500_000_000_000;
 /// Always-claimable operational portion (0.1% of every bet).
 pub const OPERATIONAL_BPS: u64 = 10;
 
+// ── Notifications (NotificationPrefs.flags / Notify.kind) ─────────────────
+pub const NOTIFY_WIN:     u8 = 1 << 0;
+pub const NOTIFY_LOSS:    u8 = 1 << 1;
+pub const NOTIFY_JACKPOT: u8 = 1 << 2;
+pub const NOTIFY_FORFEIT: u8 = 1 << 3;
+pub const NOTIFY_ALL:     u8 = NOTIFY_WIN | NOTIFY_LOSS | NOTIFY_JACKPOT | NOTIFY_FORFEIT;
+
+// ── Tower race (PvP) ──────────────────────────────────────────────────────
+/// Rake taken from the race pot (2.5%), booked as claimable house fees.
+pub const RACE_RAKE_BPS:  u64 = 250;
+/// Minimum stake per racer (0.01 SOL).
+pub const RACE_MIN_STAKE: u64 = 10_000_000;
+/// Anti-griefing bond the creator posts with an open race (0.001 SOL). Returned with the
+/// race rent when the race completes, or on cancel after RACE_BOND_MIN_OPEN_SLOTS.
+pub const RACE_BOND:      u64 = 1_000_000;
+/// A race cancelled sooner than this after creation (~10 min) forfeits its bond to house fees.
+pub const RACE_BOND_MIN_OPEN_SLOTS: u64 = 1_500;
+
+// ── Rock-Paper-Scissors (PvP) ────────────────────────────────────────────
+/// Moves. Each side commits SHA-256(move || salt); (creator − opponent) mod 3 = 1 → creator wins.
+/// Stake floor, bond and rake are the Tower race ones (RACE_MIN_STAKE, RACE_BOND, RACE_RAKE_BPS).
+pub const RPS_ROCK:       u8 = 0;
+pub const RPS_PAPER:      u8 = 1;
+pub const RPS_SCISSORS:   u8 = 2;
+/// Move slot of a side that has not revealed yet.
+pub const RPS_UNREVEALED: u8 = u8::MAX;
+
+// ── Lottery ──────────────────────────────────────────────────────────────
+/// Prize tiers as (winners, bps of the round pot per winner): 1 × 50%, 2 × 10%, 5 × 3%.
+pub const LOTTERY_TIERS: [(u8, u64); 3] = [(1, 5_000), (2, 1_000), (5, 300)];
+/// Total winning tickets drawn per round — sum of the LOTTERY_TIERS winner counts.
+pub const LOTTERY_MAX_WINNERS: usize = 8;
+/// House rake on the round's ticket sales (5%). The rest not assigned to a tier rolls into the next round.
+pub const LOTTERY_RAKE_BPS: u64 = 500;
+/// Minimum ticket price (0.001 SOL).
+pub const LOTTERY_MIN_TICKET: u64 = 1_000_000;
+/// Slots after the draw during which winners can claim (~1 day); the rest then rolls over.
+pub const LOTTERY_CLAIM_WINDOW: u64 = 216_000;
+/// Round states.
+pub const LOTTERY_OPEN:   u8 = 0;
+pub const LOTTERY_DRAWN:  u8 = 1;
+pub const LOTTERY_CLOSED: u8 = 2;
+
+// ── Gift cards ───────────────────────────────────────────────────────────
+/// Smallest card (0.001 SOL): enough to leave a brand-new wallet above its rent-exempt minimum.
+pub const GIFT_CARD_MIN: u64 = 1_000_000;
+
+// ── Rocket round ─────────────────────────────────────────────────────────
+/// Most entries crank_rocket settles per call — (entry, player) pairs in remaining_accounts,
+/// sized to stay inside one transaction's account and compute limits.
+pub const ROCKET_CRANK_BATCH: usize = 12;
+/// Slots past cutoff_slot a round has to launch (~1h). After that it can no longer launch
+/// and every entry is refunded in full — stakes never sit on an uncranked round.
+pub const ROCKET_REFUND_SLOTS: u64 = 9_000;
+/// Round states.
+pub const ROCKET_OPEN:     u8 = 0;
+pub const ROCKET_LAUNCHED: u8 = 1;
+pub const ROCKET_SETTLED:  u8 = 2;
+
 // ══════════════════════════════════════════════════════════════════════════
 //  UNIFIED PAYOUT FORMULA
 // ══════════════════════════════════════════════════════════════════════════
@@ -102,12 +560,35 @@ pub const OPERATIONAL_BPS: u64 = 10;
 //
 //  Game mapping:
 //    Flip    → (50, 100)          — exact 50% probability
+//    Streak  → (1, 2^flips) under the flat FLIP_STREAK_NUM_BPS
 //    Dice    → (win_range, 100)   — e.g. under-50 → (50, 100)
-//    Sector  → (area, 256)        — area = (2r+1)²; r=0 → 256× max
-//    Tower   → (1, 2^floors)      — e.g. 3 floors → (1, 8) = 8×
+//    Dice %  → (steps, 200)       — chance mode, via dice_chance_multiplier_bps
+//    Sector  → (area, 256)        — area = Σ (2r+1)² over the zones; one r=0 → 256× max
+//    Tower   → ((d−1)^floors, d^floors) for d doors — e.g. 3 floors × 2 doors → (1, 8) = 8×
+//    Mystery → picked game's fraction × MYSTERY_BONUS_BPS / 10_000
+//    Plinko  → bucket table normalised to E = num, via plinko_multiplier_bps
+//    Mines   → (C(25−m, k), C(25, k)) for k safe tiles, via mines_multiplier_bps
+//    Crash   → bet × target; the edge lives in the crash-point curve (session.payout_curve)
+//    Roulette → fixed table (ROULETTE_PAYOUT_X); the single zero is the edge (2.70%)
+//    Keno    → KENO_PAYTABLE row normalised to E = num, via keno_multiplier_bps
+//    Limbo   → bet × target × (1 − curve edge) on a fair 1/U roll
+//    Wheel   → WHEEL_PAYTABLE segment normalised to E = num, via wheel_multiplier_bps
+//    HiLo    → (Π winning ranks, 13^rounds) over the chain, via hilo_multiplier_bps
+//    V.Poker → POKER_PAYTABLE normalised to E = num, via video_poker_multiplier_bps
+//    Baccarat → fixed table (BACCARAT_PAYOUT_BPS); ties push Player/Banker
+//    Slots   → SLOTS_PAYLINES weight normalised to E = num, via slots_multiplier_bps
+//    Sic Bo  → (winning rolls, 216), via sicbo_ways
+//    D.Tiger → fixed table (DT_PAYOUT_BPS); ties return half a Dragon/Tiger stake
+//    D.Duo   → (winning rolls, 36) under the flat DUO_NUM, via dice_duo_ways
+//    Stairs  → (Π (20 − n), 20^steps) over the cleared steps, via stairs_multiplier_bps
+//    Goal    → GOAL_ROW_BPS^rows, flat (not phase-adjusted), via goal_multiplier_bps
+//    T.Climb → Tower's ((d−1)^floors, d^floors) over the cleared floors, via tower_climb_multiplier_bps
 //
+//  All math goes through `fixed` (u128 intermediates, single round-down).
 //  `num` = phase-adjusted retention numerator:
 //    Phase 0 → 9_750 (E=2.5%) | Phase 1 → 9_800 (E=2.0%) | Phase 2 → 9_850 (E=1.5%)
+//  `num` and the curve are pinned on the session at placement (payout_num_bps,
+//  payout_curve); reveals pay from those, never from the pool's current state.
 
 // ══════════════════════════════════════════════════════════════════════════
 //  GAME OUTCOME — internal enum for unified settlement dispatch
@@ -115,11 +596,30 @@ pub const OPERATIONAL_BPS: u64 = 10;
 
 /// Carries game-specific resolution data from the resolver to the event emitter.
 /// Keeps each of the 8 reveal instructions down to ~5 lines.
+/// Borsh-encoded (≤ 32 bytes) into GameReceipt.outcome for verify_game.
+#[derive(AnchorSerialize)]
 enum GameOutcome {
     Flip   { roll: u8 },
+    FlipStreak { flips: u8, sides: u8, results: u8 },
     Sector { strike_x: u8, strike_y: u8 },
-    Dice   { roll: u8, target: u8, is_over: bool },
-    Tower  { floors: u8, death_floor: u8, path: u8, traps: u8 },
+    Dice   { roll: u8, target: u8, is_over: bool, mode: u8, target_hi: u8 },
+    Tower  { floors: u8, death_floor: u8, path: u8, traps: u8, doors: u8 },
+    Mystery { picked_game: u8, result: [u8; 2] },
+    Plinko  { rows: u8, risk: u8, path: u16, bucket: u8, multiplier_bps: u64 },
+    Crash   { crash_point: u32, target: u16 },
+    Roulette { pocket: u8, bet_class: u8, selection: u8 },
+    Keno    { picks: u64, drawn: u64, hits: u8, multiplier_bps: u64 },
+    Limbo   { result: u32, target: u32 },
+    Wheel   { risk: u8, segment: u8, multiplier_bps: u64 },
+    VideoPoker { dealt: [u8; 5], hold: u8, hand: [u8; 5], class: u8, multiplier_bps: u64 },
+    Baccarat { side: u8, player_cards: [u8; 3], banker_cards: [u8; 3], player_total: u8, banker_total: u8 },
+    Slots   { reels: [u8; 3], multiplier_bps: u64 },
+    SicBo   { bet_class: u8, selection: u8, dice: [u8; 3] },
+    DragonTiger { side: u8, dragon: u8, tiger: u8 },
+    DiceDuo { mode: u8, target: u8, dice: [u8; 2] },
+    Goal    { rows: u8, death_row: u8, path: u16, traps: u16 },
+    Parlay  { legs: u8, games: [u8; 4], results: u8 },
+    Double  { roll: u8 },
 }
 
 // ══════════════════════════════════════════════════════════════════════════
@@ -135,6 +635,7 @@ pub mod blitz_games {
     /// @notice Bootstraps the global pool PDA. Called once at deploy.
     pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
         let p = &mut ctx.accounts.pool;
+        p.room                   = ROOM_MAIN;
         p.authority              = ctx.accounts.authority.key();
         p.total_balance          = 0;
         p.jackpot_balance        = 0;
@@ -154,14 +655,32 @@ pub mod blitz_games {
         p.total_reinvested       = 0;
         p.operational_extracted  = 0;
         p.reinvest_request       = None;
+        p.program_version        = PROGRAM_VERSION;
+        bump_seq(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Bootstraps a secondary room pool with its own liquidity. Main-pool authority only.
+    /// @dev    Same GlobalPool layout and admin surface; only the PDA seed and limits differ.
+    pub fn initialize_room(ctx: Context<InitializeRoom>, room: u8) -> Result<()> {
+        require!(room == ROOM_HIGH_ROLLER, BlitzError::InvalidRoom);
+        let p       = &mut ctx.accounts.pool;
+        p.room      = room;
+        p.authority = ctx.accounts.authority.key();
+        p.bump      = ctx.bumps.pool;
+        p.program_version = PROGRAM_VERSION;
+        emit!(RoomInitialized { pool: p.key(), room, authority: p.authority });
+        bump_seq(&mut ctx.accounts.pool);
         Ok(())
     }
 
     // ── Fund Pool ──────────────────────────────────────────────────────────
 
     /// @notice Deposits SOL into the pool. Permissionless — anyone can add liquidity.
+    /// @dev    Once LPs hold shares, the deposit mints house shares so it does not accrue to them.
     pub fn fund_pool(ctx: Context<FundPool>, amount: u64) -> Result<()> {
         require!(amount > 0, BlitzError::BetTooSmall);
+        mint_house_shares(&mut ctx.accounts.pool, amount)?;
         invoke(
             &system_instruction::transfer(&ctx.accounts.funder.key(), &ctx.accounts.pool.key(), amount),
             &[ctx.accounts.funder.to_account_info(), ctx.accounts.pool.to_account_info()],
@@ -172,15 +691,497 @@ pub mod blitz_games {
         Ok(())
     }
 
+    // ── First-bet promo ────────────────────────────────────────────────────
+
+    /// @notice Adds lamports to the room's first-bet promo budget (reserved, not liquid).
+    pub fn fund_promo(ctx: Context<FundPool>, amount: u64) -> Result<()> {
+        require!(amount > 0, BlitzError::BetTooSmall);
+        invoke(
+            &system_instruction::transfer(&ctx.accounts.funder.key(), &ctx.accounts.pool.key(), amount),
+            &[ctx.accounts.funder.to_account_info(), ctx.accounts.pool.to_account_info()],
+        )?;
+        ctx.accounts.pool.promo_balance = checked_add(ctx.accounts.pool.promo_balance, amount)?;
+        let pool_ai = ctx.accounts.pool.to_account_info();
+        sync_balance(&mut ctx.accounts.pool, &pool_ai)?;
+        emit!(PromoFunded { amount, funder: ctx.accounts.funder.key() });
+        Ok(())
+    }
+
+    /// @notice Withdraws uncommitted promo budget back to the authority.
+    pub fn withdraw_promo(ctx: Context<AdminOnly>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(amount > 0 && amount <= pool.promo_balance.saturating_sub(pool.promo_committed), BlitzError::InsufficientLiquidity);
+        pool_transfer(&pool.to_account_info(), &ctx.accounts.authority.to_account_info(), amount)?;
+        pool.promo_balance -= amount;
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        emit!(PromoWithdrawn { amount });
+        Ok(())
+    }
+
+    /// @notice Configures the first-bet promo. `cap_lamports = 0` turns it off.
+    /// @dev    Sybil friction: wallets must hold `min_wallet_lamports` to enroll and, when
+    ///         `attestor` is set, enrollment must be co-signed by it (off-chain checks such as
+    ///         wallet age live with the attestor — they cannot be read on-chain).
+    pub fn set_first_bet_promo(
+        ctx:                 Context<AdminOnly>,
+        cap_lamports:        u64,
+        min_wallet_lamports: u64,
+        attestor:            Pubkey,
+    ) -> Result<()> {
+        let pool              = &mut ctx.accounts.pool;
+        pool.promo_cap        = cap_lamports;
+        pool.promo_min_wallet = min_wallet_lamports;
+        pool.promo_attestor   = attestor;
+        emit!(PromoConfigUpdated { pool: pool.key(), cap_lamports, min_wallet_lamports, attestor });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Sets the single-payout threshold above which reveals need `cosigner`. 0 = off.
+    pub fn set_cosign_policy(ctx: Context<AdminOnly>, threshold: u64, cosigner: Pubkey) -> Result<()> {
+        require!(threshold == 0 || cosigner != Pubkey::default(), BlitzError::InvalidCosigner);
+        ctx.accounts.pool.cosign_threshold = threshold;
+        ctx.accounts.pool.cosigner         = cosigner;
+        emit!(CosignPolicyUpdated { pool: ctx.accounts.pool.key(), threshold, cosigner });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Creates the caller's PlayerStats. Pass it to place_bet to claim the first-bet promo.
+    /// @dev    Never refused: a room running the promo requires PlayerStats on every bet, so a
+    ///         wallet below promo_min_wallet enrolls with its first bet already used (ineligible).
+    pub fn enroll_player_stats(ctx: Context<EnrollPlayerStats>) -> Result<()> {
+        let pool     = &ctx.accounts.pool;
+        let eligible = ctx.accounts.player.lamports() >= pool.promo_min_wallet;
+        let attested = match ctx.accounts.attestor.as_ref() {
+            Some(a) => {
+                require!(a.key() == pool.promo_attestor, BlitzError::InvalidAttestor);
+                true
+            }
+            None => false,
+        };
+        let st            = &mut ctx.accounts.player_stats;
+        st.player         = ctx.accounts.player.key();
+        st.attested       = attested;
+        st.first_bet_used = !eligible;
+        st.bump           = ctx.bumps.player_stats;
+        Ok(())
+    }
+
+    /// @notice Grows a PlayerStats created before the last-bet fields to the current layout.
+    /// @dev    Call once per wallet after upgrade; the player pays the extra rent. New fields
+    ///         are zeroed, so replay_last_bet stays unavailable until the next bet.
+    pub fn migrate_player_stats(_ctx: Context<MigratePlayerStats>) -> Result<()> {
+        Ok(())
+    }
+
+    /// @notice Redeems an attestor-signed voucher as loss insurance on a bet placed this slot —
+    ///         a risk-free bet of up to `amount`, funded from the promo budget like the first-bet promo.
+    /// @dev    The instruction right before this one must be an Ed25519 program verify of
+    ///         promo_voucher_message(pool, player, amount, expires_at, campaign_id) by
+    ///         pool.promo_attestor. Send [place_bet, ed25519 verify, redeem_promo_voucher] in one
+    ///         transaction: the session must be pending, uninsured and committed in this slot,
+    ///         so a voucher can never be attached to a bet whose outcome is already known.
+    ///         The PromoVoucherClaim PDA makes each (pool, player, campaign) voucher single-use;
+    ///         nothing has to exist on-chain per recipient before redemption.
+    pub fn redeem_promo_voucher(
+        ctx:         Context<RedeemPromoVoucher>,
+        amount:      u64,
+        expires_at:  i64,
+        campaign_id: u64,
+    ) -> Result<()> {
+        let clock  = Clock::get()?;
+        let pool   = &mut ctx.accounts.pool;
+        let player = ctx.accounts.player.key();
+        require!(pool.promo_attestor != Pubkey::default(), BlitzError::InvalidAttestor);
+        require!(clock.unix_timestamp <= expires_at,       BlitzError::PromoVoucherExpired);
+        let message = promo_voucher_message(&pool.key(), &player, amount, expires_at, campaign_id);
+        verify_ed25519_ix(&ctx.accounts.instructions.to_account_info(), &pool.promo_attestor, &message)
+            .map_err(|_| BlitzError::InvalidPromoVoucher)?;
+
+        let s     = &mut ctx.accounts.session;
+        require!(s.game_state == 0 && s.insured_amount == 0 && s.commit_slot == clock.slot, BlitzError::PromoVoucherSessionIneligible);
+        let cover = amount.min(s.bet_lamports);
+        require!(cover > 0 && cover <= pool.promo_balance.saturating_sub(pool.promo_committed), BlitzError::InsufficientLiquidity);
+        s.insured_amount      = cover;
+        pool.promo_committed += cover;
+
+        let c         = &mut ctx.accounts.claim;
+        c.player      = player;
+        c.campaign_id = campaign_id;
+        c.expires_at  = expires_at;
+        c.bump        = ctx.bumps.claim;
+        emit!(PromoVoucherRedeemed { pool: pool.key(), player, campaign_id, session: s.key(), amount: cover });
+        Ok(())
+    }
+
+    /// @notice Closes a spent voucher's claim PDA once the voucher has expired, refunding its rent.
+    /// @dev    Safe to close: the signed expiry already stops the voucher from redeeming again.
+    pub fn close_promo_voucher(ctx: Context<ClosePromoVoucher>) -> Result<()> {
+        require!(Clock::get()?.unix_timestamp > ctx.accounts.claim.expires_at, BlitzError::PromoVoucherNotExpired);
+        Ok(())
+    }
+
+    // ── Liquidity providers ────────────────────────────────────────────────
+
+    /// @notice Deposits `amount` into the pool's liquid balance for a pro-rata share of it.
+    /// @dev    Shares are priced off lp_nav (freshly synced), so LPs carry the house edge and the
+    ///         variance alike — or, once the room has a NavSnapshot, at lp_deposit_price. The
+    ///         first deposit of a generation backs the existing NAV with house shares 1:1.
+    ///         Every deposit restarts the position's LP_LOCK_SECS lock.
+    pub fn lp_deposit(ctx: Context<LpDeposit>, amount: u64) -> Result<()> {
+        require!(amount >= LP_MIN_TICKET, BlitzError::LpTicketTooSmall);
+        let pool    = &mut ctx.accounts.pool;
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        lp_writeoff(pool);
+        lp_position_sync(pool, &mut ctx.accounts.position);
+        require!(ctx.accounts.nav_snapshot.is_some() || !pool.nav_snapshot_enabled, BlitzError::NavSnapshotRequired);
+        if pool.lp_shares == 0 { pool.lp_shares = lp_nav(pool); }
+        let shares = match ctx.accounts.nav_snapshot.as_deref() {
+            Some(n) => fixed::mul_div_floor(amount, LP_PRICE_SCALE, lp_deposit_price(pool, n)),
+            None    => lp_shares_for(amount, lp_nav(pool), pool.lp_shares),
+        }.ok_or(BlitzError::LpBookUnpriced)?;
+        require!(shares > 0, BlitzError::BetTooSmall);
+        invoke(
+            &system_instruction::transfer(&ctx.accounts.owner.key(), &pool.key(), amount),
+            &[ctx.accounts.owner.to_account_info(), pool.to_account_info()],
+        )?;
+        pool.lp_shares         = checked_add(pool.lp_shares, shares)?;
+        pool.lp_outside_shares = checked_add(pool.lp_outside_shares, shares)?;
+        sync_balance(pool, &pool_ai)?;
+
+        let locked_until    = Clock::get()?.unix_timestamp + LP_LOCK_SECS;
+        let p               = &mut ctx.accounts.position;
+        p.pool              = pool_ai.key();
+        p.owner             = ctx.accounts.owner.key();
+        p.bump              = ctx.bumps.position;
+        p.shares            = checked_add(p.shares, shares)?;
+        p.locked_until      = locked_until;
+        p.total_deposited   = p.total_deposited.saturating_add(amount);
+        emit!(LpDeposited { pool: p.pool, owner: p.owner, amount, shares, locked_until });
+        Ok(())
+    }
+
+    /// @notice Queues `shares` of an unlocked position for exit. Nothing is paid here:
+    ///         process_lp_queue pays exits in ticket order from the next epoch on.
+    /// @dev    Queued shares keep bearing the pool's P&L until paid, and are priced when paid.
+    ///         One exit per position at a time. A partial exit must be worth LP_MIN_TICKET.
+    pub fn lp_withdraw(ctx: Context<LpWithdraw>, shares: u64) -> Result<()> {
+        let pool  = &mut ctx.accounts.pool;
+        let p     = &mut ctx.accounts.position;
+        let clock = Clock::get()?;
+        lp_position_sync(pool, p);
+        require!(clock.unix_timestamp >= p.locked_until, BlitzError::LpPositionLocked);
+        require!(p.queued_shares == 0,                    BlitzError::PendingWithdrawal);
+        require!(shares > 0 && shares <= p.shares,        BlitzError::InsufficientLpShares);
+        require!(shares == p.shares || lp_value_of(shares, lp_nav(pool), pool.lp_shares) >= LP_MIN_TICKET,
+                 BlitzError::LpTicketTooSmall);
+        p.queued_shares    = shares;
+        p.queue_ticket     = pool.lp_queue_tail;
+        p.queued_epoch     = clock.epoch;
+        pool.lp_queue_tail = pool.lp_queue_tail.saturating_add(1);
+        emit!(LpExitQueued { pool: p.pool, owner: p.owner, shares, ticket: p.queue_ticket });
+        bump_seq(pool);
+        Ok(())
+    }
+
+    /// @notice Permissionless crank: pays up to LP_QUEUE_BATCH exits from the head of the LP
+    ///         queue, passed in ticket order as (position, owner) pairs in `remaining_accounts`,
+    ///         each once its epoch has passed, within this epoch's LP_EXIT_EPOCH_BPS budget.
+    /// @dev    Never pays total_balance below room_min_pool, so exits cannot trip the MIN_POOL
+    ///         breaker mid-game. An exit larger than the headroom is paid in part and stays at
+    ///         the head for the next epoch; the batch stops there, or at the first exit not yet
+    ///         due. Only the first pair has to pay something. Exits are paid at lp_exit_price
+    ///         once the room has a NavSnapshot, at the lp_nav share price before.
+    pub fn process_lp_queue<'info>(ctx: Context<'_, '_, 'info, 'info, ProcessLpQueue<'info>>) -> Result<()> {
+        let pairs = ctx.remaining_accounts;
+        require!(!pairs.is_empty() && pairs.len() & 1 == 0 && pairs.len() <= 2 * LP_QUEUE_BATCH, BlitzError::InvalidBatchSize);
+        let snap  = ctx.accounts.nav_snapshot.as_deref();
+        let pool  = &mut ctx.accounts.pool;
+        require!(snap.is_some() || !pool.nav_snapshot_enabled, BlitzError::NavSnapshotRequired);
+        let key   = pool.key();
+        let epoch = Clock::get()?.epoch;
+        if pool.lp_exit_epoch != epoch {
+            pool.lp_exit_epoch  = epoch;
+            pool.lp_exit_budget = fixed::share_bps(pool.total_balance, LP_EXIT_EPOCH_BPS);
+        }
+        for (i, pair) in pairs.chunks(2).enumerate() {
+            let (position_ai, owner_ai) = (&pair[0], &pair[1]);
+            let mut p = Account::<LpPosition>::try_from(position_ai)?;
+            require!(p.pool == key && p.owner == owner_ai.key(), BlitzError::InvalidLpPosition);
+            lp_position_sync(pool, &mut p);
+            require!(p.queued_shares > 0 && p.queue_ticket == pool.lp_queue_head, BlitzError::NotLpQueueHead);
+            if p.queued_epoch >= epoch {
+                require!(i > 0, BlitzError::LpExitNotDue);
+                break;
+            }
+            let cap   = pool.lp_exit_budget.min(pool.total_balance.saturating_sub(room_min_pool(pool.room)));
+            let price = match snap {
+                Some(n) => lp_exit_price(pool, n),
+                None    => lp_share_price(lp_nav(pool), pool.lp_shares),
+            };
+            let full = fixed::mul_div_floor(p.queued_shares, price, LP_PRICE_SCALE).unwrap_or(u64::MAX);
+            let burn = if full <= cap {
+                p.queued_shares
+            } else {
+                fixed::mul_div_floor(cap, LP_PRICE_SCALE, price).unwrap_or(0).min(p.queued_shares)
+            };
+            if burn == 0 {
+                require!(i > 0, BlitzError::LpExitBudgetSpent);
+                break;
+            }
+            let amount = fixed::mul_div_floor(burn, price, LP_PRICE_SCALE).unwrap_or(0);
+
+            if amount > 0 { pool_transfer(&pool.to_account_info(), owner_ai, amount)?; }
+            pool.lp_exit_budget    = pool.lp_exit_budget.saturating_sub(amount);
+            pool.lp_shares         = pool.lp_shares.saturating_sub(burn);
+            pool.lp_outside_shares = pool.lp_outside_shares.saturating_sub(burn);
+            p.shares               = p.shares.saturating_sub(burn);
+            p.queued_shares        = p.queued_shares.saturating_sub(burn);
+            p.total_withdrawn      = p.total_withdrawn.saturating_add(amount);
+            let pool_ai = pool.to_account_info();
+            sync_balance(pool, &pool_ai)?;
+            emit!(LpWithdrawn { pool: p.pool, owner: p.owner, shares: burn, amount, queued: p.queued_shares });
+            p.exit(&crate::ID)?;
+            if p.queued_shares > 0 { break; }
+            pool.lp_queue_head = pool.lp_queue_head.saturating_add(1);
+        }
+        Ok(())
+    }
+
+    /// @notice Creates the room's LpLedger — a ring of the last LP_LEDGER_EPOCHS epoch
+    ///         snapshots, so LPs can check their realized returns on-chain.
+    pub fn init_lp_ledger(ctx: Context<InitLpLedger>) -> Result<()> {
+        let mut l = ctx.accounts.lp_ledger.load_init()?;
+        l.pool    = ctx.accounts.pool.key();
+        l.bump    = ctx.bumps.lp_ledger;
+        Ok(())
+    }
+
+    /// @notice Permissionless crank, once per Solana epoch: snapshots the LP share price and
+    ///         the epoch's P&L into the LpLedger, and accrues the performance fee.
+    /// @dev    The fee is performance_fee_bps of the LP shares' gain above lp_hwm_price. It moves
+    ///         out of total_balance into the performance_fees compartment, charged to LP shares
+    ///         only (see charge_lp_fee), and the mark then moves to the post-fee price. A
+    ///         losing epoch leaves the mark where it is, so the fee is only ever charged on net
+    ///         new profit. The first close only sets the mark.
+    pub fn close_lp_epoch(ctx: Context<CloseLpEpoch>) -> Result<()> {
+        let pool    = &mut ctx.accounts.pool;
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        require!(pool.lp_shares > 0, BlitzError::InsufficientLpShares);
+        let mut l = ctx.accounts.lp_ledger.load_mut()?;
+        let epoch = Clock::get()?.epoch;
+        require!(l.count == 0 || l.last().epoch < epoch, BlitzError::LpEpochNotOver);
+
+        let mut price = lp_share_price(lp_nav(pool), pool.lp_shares);
+        let mut fee   = 0;
+        if pool.lp_hwm_price == 0 {
+            pool.lp_hwm_price = price;
+        } else if price > pool.lp_hwm_price {
+            let gain   = fixed::mul_div_floor(pool.lp_outside_shares, price - pool.lp_hwm_price, LP_PRICE_SCALE).unwrap_or(0);
+            fee        = fixed::share_bps(gain, pool.performance_fee_bps as u64);
+            if fee > 0 {
+                charge_lp_fee(pool, fee)?;
+                sync_balance(pool, &pool_ai)?;
+                emit!(PerformanceFeeAccrued { pool: pool.key(), epoch, fee, accrued: pool.performance_fees });
+            }
+            price             = lp_share_price(lp_nav(pool), pool.lp_shares);
+            pool.lp_hwm_price = price;
+        }
+        let prev = if l.count == 0 { price } else { l.last().share_price };
+        let pnl  = (price as i128 - prev as i128) * pool.lp_shares as i128 / LP_PRICE_SCALE as i128;
+        let snap = LpEpochSnapshot {
+            epoch,
+            total_balance: pool.total_balance,
+            lp_shares:     pool.lp_shares,
+            share_price:   price,
+            hwm_price:     pool.lp_hwm_price,
+            pnl:           pnl.clamp(i64::MIN as i128, i64::MAX as i128) as i64,
+            fee,
+        };
+        l.push(snap);
+        emit!(LpEpochClosed { pool: pool.key(), epoch, share_price: price, hwm_price: pool.lp_hwm_price, pnl: snap.pnl, fee });
+        bump_seq(pool);
+        Ok(())
+    }
+
+    /// @notice Creates the room's NavSnapshot and takes its first snapshot. Authority only, once.
+    /// @dev    Sets pool.nav_snapshot_enabled: from then on lp_deposit and process_lp_queue must
+    ///         pass the snapshot and price against it (lp_deposit_price / lp_exit_price).
+    pub fn init_nav_snapshot(ctx: Context<InitNavSnapshot>) -> Result<()> {
+        let pool    = &mut ctx.accounts.pool;
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        let n  = &mut ctx.accounts.nav_snapshot;
+        n.pool = pool.key();
+        n.bump = ctx.bumps.nav_snapshot;
+        nav_snapshot_take(pool, n, Clock::get()?.slot);
+        emit!(NavSnapshotTaken { pool: n.pool, slot: n.slot, deposit_price: n.deposit_price, exit_price: n.exit_price });
+        pool.nav_snapshot_enabled = true;
+        bump_config(pool);
+        Ok(())
+    }
+
+    /// @notice Permissionless crank: refreshes the room's NavSnapshot, at most once per
+    ///         NAV_SNAPSHOT_SLOTS.
+    /// @dev    The spacing is what makes the snapshot useful: nobody can re-take it the moment a
+    ///         pending session's outcome is known, so it only ever prices against them.
+    pub fn take_nav_snapshot(ctx: Context<TakeNavSnapshot>) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let n    = &mut ctx.accounts.nav_snapshot;
+        require!(slot >= n.slot.saturating_add(NAV_SNAPSHOT_SLOTS), BlitzError::NavSnapshotTooSoon);
+        let pool    = &mut ctx.accounts.pool;
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        nav_snapshot_take(pool, n, slot);
+        emit!(NavSnapshotTaken { pool: n.pool, slot, deposit_price: n.deposit_price, exit_price: n.exit_price });
+        Ok(())
+    }
+
+    // ── Player vault ───────────────────────────────────────────────────────
+
+    /// @notice Deposits `amount` into the player's PlayerVault, creating it on first use.
+    /// @dev    A vault is a chip stack: place_bet_from_vault stakes from it and its wins are
+    ///         credited back to it, so a session of bets needs no wallet transfer per bet.
+    pub fn deposit(ctx: Context<VaultDeposit>, amount: u64) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        require!(amount > 0, BlitzError::BetTooSmall);
+        let v = &mut ctx.accounts.vault;
+        v.player          = ctx.accounts.player.key();
+        v.bump            = ctx.bumps.vault;
+        v.total_deposited = v.total_deposited.saturating_add(amount);
+        invoke(
+            &system_instruction::transfer(&v.player, &v.key(), amount),
+            &[ctx.accounts.player.to_account_info(), v.to_account_info()],
+        )?;
+        emit!(VaultDeposited { player: v.player, amount, balance: vault_free(&v.to_account_info())? });
+        Ok(())
+    }
+
+    /// @notice Withdraws `amount` of the vault's free balance to the player's wallet.
+    pub fn withdraw_vault(ctx: Context<VaultWithdraw>, amount: u64) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let vault_ai = ctx.accounts.vault.to_account_info();
+        require!(amount > 0 && amount <= vault_free(&vault_ai)?, BlitzError::InsufficientVaultBalance);
+        move_lamports(&vault_ai, &ctx.accounts.player.to_account_info(), amount)?;
+        let v = &mut ctx.accounts.vault;
+        v.total_withdrawn = v.total_withdrawn.saturating_add(amount);
+        emit!(VaultWithdrawn { player: v.player, amount, balance: vault_free(&vault_ai)? });
+        Ok(())
+    }
+
+    /// @notice Funds `beneficiary`'s play. Without constraints `amount` tops up their PlayerVault,
+    ///         which becomes theirs outright. With constraints it is escrowed in the
+    ///         SponsorGrant [b"sponsor_grant", sponsor, beneficiary]: the beneficiary can only stake
+    ///         it, via place_bet_from_vault and within the constraints, and wins go to their vault.
+    ///         The sponsor reclaims what is left once the grant expires.
+    /// @dev    Topping up a live grant adds to it and replaces its constraints.
+    pub fn sponsor_deposit(
+        ctx:         Context<SponsorDeposit>,
+        beneficiary: Pubkey,
+        amount:      u64,
+        constraints: Option<SponsorConstraints>,
+    ) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        require!(amount > 0, BlitzError::BetTooSmall);
+        let sponsor = ctx.accounts.sponsor.key();
+        let target  = match constraints {
+            None => {
+                let v = ctx.accounts.vault.as_mut().ok_or(BlitzError::InvalidSponsorGrant)?;
+                v.player          = beneficiary;
+                v.bump            = ctx.bumps.vault.unwrap_or_default();
+                v.total_deposited = v.total_deposited.saturating_add(amount);
+                v.to_account_info()
+            }
+            Some(c) => {
+                require!(c.expires_slot > Clock::get()?.slot, BlitzError::SponsorGrantExpired);
+                let g = ctx.accounts.sponsor_grant.as_mut().ok_or(BlitzError::InvalidSponsorGrant)?;
+                g.sponsor       = sponsor;
+                g.beneficiary   = beneficiary;
+                g.amount        = checked_add(g.amount, amount)?;
+                g.total_granted = g.total_granted.saturating_add(amount);
+                g.constraints   = c;
+                g.bump          = ctx.bumps.sponsor_grant.unwrap_or_default();
+                g.to_account_info()
+            }
+        };
+        invoke(
+            &system_instruction::transfer(&sponsor, target.key, amount),
+            &[ctx.accounts.sponsor.to_account_info(), target],
+        )?;
+        let expires_slot = constraints.map_or(0, |c| c.expires_slot);
+        emit!(SponsorDeposited { sponsor, beneficiary, amount, expires_slot });
+        Ok(())
+    }
+
+    /// @notice Returns an expired SponsorGrant's unspent escrow and rent to its sponsor.
+    pub fn reclaim_sponsor_grant(ctx: Context<ReclaimSponsorGrant>) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let g = &ctx.accounts.sponsor_grant;
+        require!(Clock::get()?.slot > g.constraints.expires_slot, BlitzError::SponsorGrantNotExpired);
+        emit!(SponsorGrantReclaimed { sponsor: g.sponsor, beneficiary: g.beneficiary, amount: g.amount });
+        Ok(())
+    }
+
+    /// @notice place_bet_v3 staked from the player's PlayerVault instead of their wallet.
+    ///         Winnings are paid to the vault (session.payout_to = vault).
+    /// @dev    Only the session's rent still comes from the wallet. Terminal refunds go to the wallet.
+    ///         Passing a SponsorGrant stakes from the grant instead, within its constraints;
+    ///         its wins and refunds go to the vault and wallet like any other stake.
+    pub fn place_bet_from_vault(
+        ctx:          Context<PlaceBetFromVault>,
+        game_type:    u8,
+        commitment:   [u8; 32],
+        bet_lamports: u64,
+        game_config:  [u8; 3],
+        config_ext:   u64,
+        flags:        u8,
+    ) -> Result<()> {
+        require!(game_type != 22, BlitzError::InvalidParlay);
+        require!(game_type != 23, BlitzError::InvalidGameType);
+        let a = ctx.accounts;
+        place_bet_with(BetAccounts {
+            player:        &a.player,
+            referrer:      &a.referrer,
+            pool:          &mut a.pool,
+            session:       &mut a.session,
+            session_bump:  ctx.bumps.session,
+            instructions:  a.instructions.as_ref(),
+            payout_wallet: None,
+            partner:       None,
+            player_sketch: a.player_sketch.as_mut(),
+            player_stats:  a.player_stats.as_mut(),
+            open_sessions: a.open_sessions.as_mut(),
+            vault:         Some(&mut a.vault),
+            sponsor_grant: a.sponsor_grant.as_mut(),
+            referral:      a.referral.as_deref(),
+        }, game_type, commitment, bet_lamports, game_config, config_ext, flags)
+    }
+
     // ── Place Bet ──────────────────────────────────────────────────────────
 
     /// @notice Commits a bet via Commit-Reveal. Transfers `bet_lamports` into the pool.
     /// @dev    `commitment` = SHA-256(nonce); nonce supplied at reveal time.
     ///         Auto-expires a stale pause if MAX_PAUSE_DURATION has elapsed.
-    /// @param game_type   0=Flip | 1=Sector99 | 2=Dice | 3=Tower
+    ///         Optional `payout_wallet` account redirects winnings (e.g. to a cold wallet).
+    /// @param game_type   0=Flip | 1=Sector99 | 2=Dice | 3=Tower | 4=Mystery | 5=Plinko | 6=Mines | 7=Crash | 8=Roulette | 9=Keno | 10=Limbo | 11=Wheel | 12=HiLo | 13=Video Poker | 14=Baccarat | 15=Slots | 16=Sic Bo | 17=Dragon Tiger | 18=Dice Duo | 19=Stairs | 20=Goal | 21=Tower Climb
     /// @param commitment  SHA-256 of the player's secret nonce
     /// @param bet_lamports Wager in lamports (min 0.01 SOL)
-    /// @param game_config  [x,y,r] for Sector | [target,is_over,0] for Dice | [floors,path,0] for Tower
+    /// @param game_config  [x,y,r] for Sector | [target,is_over,0] or [steps,2,0] or [n,3,0] or [lo,4,hi] for Dice | [floors,path,doors] for Tower
+    ///                     | [0,0,0] for Flip and Mystery ([flips,sides,0] for a Flip streak) | [picks,0,0] for Keno and [0,0,0] for Limbo (place_bet_v2 only)
+    ///                     | [risk,0,0] for Wheel (0 = 10, 1 = 20, 2 = 40 segments) | [0,0,0] for HiLo
+    ///                     | [hold_mask,0,0] for Video Poker (bit i = keep dealt card i)
+    ///                     | [side,0,0] for Baccarat (0 = Player, 1 = Banker, 2 = Tie) | [0,0,0] for Slots
+    ///                     | [bet_class,selection,0] for Sic Bo (0 = Small, 1 = Big, 2 = triple of n, 3 = total n)
+    ///                     | [side,0,0] for Dragon Tiger (0 = Dragon, 1 = Tiger, 2 = Tie)
+    ///                     | [mode,target,0] for Dice Duo (0 = sum under, 1 = over, 2 = exactly target)
+    ///                     | [0,0,0] for Stairs
+    ///                     | [rows,path_lo,path_hi] for Goal (path u16 LE, 2 bits per row = lane 0–2)
+    ///                     | [floors,0,doors] for Tower Climb (doors 0 = 2)
+    ///                     | parlays (game 22) are placed with place_parlay
     pub fn place_bet(
         ctx:          Context<PlaceBet>,
         game_type:    u8,
@@ -188,63 +1189,332 @@ pub mod blitz_games {
         bet_lamports: u64,
         game_config:  [u8; 3],
     ) -> Result<()> {
-        let clock      = Clock::get()?;
-        let pool       = &mut ctx.accounts.pool;
-        let player_key = ctx.accounts.player.key();
-
-        // Auto-expire a forgotten pause (player-protective, no owner action needed)
-        if pool.paused && clock.unix_timestamp >= pool.pause_expires_at {
-            pool.paused           = false;
-            pool.pause_expires_at = 0;
-        }
+        place_bet_inner(ctx, game_type, commitment, bet_lamports, game_config, 0, 0)
+    }
 
-        require!(!pool.paused,                   BlitzError::ContractPaused);
-        require!(pool.total_balance >= MIN_POOL, BlitzError::PoolTooLow);
-        require!(bet_lamports >= 10_000_000,     BlitzError::BetTooSmall);
-        require!(game_type <= 3,                 BlitzError::InvalidGameType);
+    /// @notice place_bet with a `config_ext` word for configs wider than 3 bytes (Keno picks).
+    /// @param config_ext  Keno: bitmask of picked numbers (bit n = number n + 1) | Limbo: target × 100
+    ///                    | Sector99: extra target zones (see SECTOR_MAX_ZONES) | 0 for every other game
+    pub fn place_bet_v2(
+        ctx:          Context<PlaceBet>,
+        game_type:    u8,
+        commitment:   [u8; 32],
+        bet_lamports: u64,
+        game_config:  [u8; 3],
+        config_ext:   u64,
+    ) -> Result<()> {
+        place_bet_inner(ctx, game_type, commitment, bet_lamports, game_config, config_ext, 0)
+    }
 
-        validate_game_config(game_type, &game_config)?;
+    /// @notice place_bet_v2 with placement `flags`.
+    /// @param flags  PLACE_FLAG_PUBLIC_REVEAL — opt in to reveal_public: once the nonce is
+    ///               published, any executor may settle the bet and earns CRANK_TIP_LAMPORTS
+    ///               out of a win. Not available for the interactive games (Mines, HiLo, Stairs, Tower Climb).
+    ///               | PLACE_FLAG_DOWNSIZE — a stake above max_allowed_bet is lowered to it
+    ///               (BetDownsized) instead of failing on the room max bet, payout cap or liquidity.
+    ///               | PLACE_FLAG_DOUBLE — a win is held for DOUBLE_WINDOW_SECS and may be re-staked
+    ///               with double_or_nothing. Not available for the interactive games.
+    ///               | PLACE_FLAG_CLIENT_SEED — `commitment` also covers a player client seed that
+    ///               is mixed into the game seed; settle with reveal_client_seeded. Single-shot
+    ///               games only, not with PLACE_FLAG_PUBLIC_REVEAL.
+    pub fn place_bet_v3(
+        ctx:          Context<PlaceBet>,
+        game_type:    u8,
+        commitment:   [u8; 32],
+        bet_lamports: u64,
+        game_config:  [u8; 3],
+        config_ext:   u64,
+        flags:        u8,
+    ) -> Result<()> {
+        place_bet_inner(ctx, game_type, commitment, bet_lamports, game_config, config_ext, flags)
+    }
 
-        let max_bet = get_max_bet(pool.total_balance, game_type, &game_config);
-        require!(bet_lamports <= max_bet, BlitzError::BetExceedsLimit);
+    /// @notice place_bet_v3 settled from VRF randomness instead of a nonce: no reveal for the
+    ///         player to send, so no reveal window to miss. Anyone may settle it with settle_vrf
+    ///         once the oracle has revealed. The pool's vrf_provider picks the backend.
+    /// @param commitment  Key of the randomness account, passed as the first remaining account.
+    ///                    It must not be revealed yet (Switchboard: committed in the previous
+    ///                    slot; ORAO: request still pending).
+    /// @dev    Single-shot games only; PLACE_FLAG_PUBLIC_REVEAL and PLACE_FLAG_CLIENT_SEED do
+    ///         not apply. The session PDA is seeded by the randomness key, so an account backs
+    ///         one open bet at a time.
+    pub fn place_bet_vrf(
+        ctx:          Context<PlaceBet>,
+        game_type:    u8,
+        commitment:   [u8; 32],
+        bet_lamports: u64,
+        game_config:  [u8; 3],
+        config_ext:   u64,
+        flags:        u8,
+    ) -> Result<()> {
+        let ai  = ctx.remaining_accounts.first().ok_or(BlitzError::InvalidRandomness)?;
+        require!(ai.key().to_bytes() == commitment, BlitzError::InvalidRandomness);
+        let vrf = Vrf { provider: ctx.accounts.pool.vrf_provider, account: ai };
+        vrf.bind(&mut ctx.accounts.session, Clock::get()?.slot)?;
+        require!(flags & (PLACE_FLAG_PUBLIC_REVEAL | PLACE_FLAG_CLIENT_SEED) == 0 && !matches!(game_type, 6 | 12 | 19 | 21), BlitzError::InvalidBetFlags);
+        place_bet_inner(ctx, game_type, commitment, bet_lamports, game_config, config_ext, flags)
+    }
 
-        let worst = get_worst_payout(bet_lamports, game_type, &game_config);
-        require!(worst <= get_max_payout_cap(pool.total_balance), BlitzError::PayoutExceedsPoolCap);
-        require!(
-            pool.total_balance.saturating_add(bet_lamports) >= worst,
-            BlitzError::InsufficientLiquidity
-        );
+    /// @notice "Bet again": re-places the player's last bet — same game, config, amount and
+    ///         flags, read from PlayerStats — under a fresh commitment.
+    /// @param commitment  SHA-256 of the new secret nonce
+    /// @dev    32 bytes of instruction data. The last bet is recorded at placement whenever
+    ///         PlayerStats is passed; every current limit is re-checked as for a new bet.
+    pub fn replay_last_bet(ctx: Context<ReplayBet>, commitment: [u8; 32]) -> Result<()> {
+        let st = &ctx.accounts.player_stats;
+        require!(st.last_bet_lamports > 0, BlitzError::NoBetToReplay);
+        let (game_type, bet, cfg, ext, flags) = (st.last_game_type, st.last_bet_lamports, st.last_config, st.last_config_ext, st.last_flags);
+        let a = ctx.accounts;
+        place_bet_with(BetAccounts {
+            player:        &a.player,
+            referrer:      &a.referrer,
+            pool:          &mut a.pool,
+            session:       &mut a.session,
+            session_bump:  ctx.bumps.session,
+            instructions:  a.instructions.as_ref(),
+            payout_wallet: a.payout_wallet.as_ref(),
+            partner:       a.partner.as_mut(),
+            player_sketch: a.player_sketch.as_mut(),
+            player_stats:  Some(&mut a.player_stats),
+            open_sessions: a.open_sessions.as_mut(),
+            vault:         None,
+            sponsor_grant: None,
+            referral:      a.referral.as_deref(),
+        }, game_type, commitment, bet, cfg, ext, flags)
+    }
 
-        let ref_key = ctx.accounts.referrer.key();
+    /// @notice Places one logical bet too large for a single room by splitting it across the
+    ///         main and high-roller pools, proportionally to their max bets for this config.
+    /// @dev    Creates two linked sessions with a shared resolve_slot and seed input (the total
+    ///         stake), so both legs resolve from the same seed to the same outcome. Each leg is
+    ///         revealed with the usual reveal_* instruction (same nonce) and settles from its own pool.
+    ///         Leg PDAs: main = [b"session", player, commitment], high roller = [.., b"split"].
+    pub fn place_split_bet(
+        ctx:          Context<PlaceSplitBet>,
+        game_type:    u8,
+        commitment:   [u8; 32],
+        bet_lamports: u64,
+        game_config:  [u8; 3],
+    ) -> Result<()> {
+        let clock      = Clock::get()?;
+        let player_key = ctx.accounts.player.key();
+        let ref_key    = ctx.accounts.referrer.key();
         if ref_key != system_program::ID && ref_key != player_key {
             require!(*ctx.accounts.referrer.owner == system_program::ID, BlitzError::InvalidReferrer);
         }
+        check_cpi_caller(&ctx.accounts.main_pool, ctx.accounts.instructions.as_ref())?;
+
+        let has_stats    = ctx.accounts.player_stats.is_some();
+        let (main, high) = (&mut ctx.accounts.main_pool, &mut ctx.accounts.high_roller_pool);
+        for pool in [&mut **main, &mut **high] {
+            expire_pause(pool, clock.unix_timestamp);
+            require!(!pool.paused, BlitzError::ContractPaused);
+            promo_gate(pool, has_stats)?;
+        }
+        let cap_main = get_room_max_bet(main.room, main.game_basis(game_type), game_type, &game_config, 0);
+        let cap_high = get_room_max_bet(high.room, high.game_basis(game_type), game_type, &game_config, 0);
+        require!(bet_lamports > cap_main && bet_lamports > cap_high, BlitzError::SplitNotNeeded);
+        let main_leg = fixed::mul_div_floor(bet_lamports, cap_main, cap_main.saturating_add(cap_high))
+            .ok_or(BlitzError::MathOverflow)?;
+        let high_leg = bet_lamports - main_leg;
+        check_bet(main, game_type, &game_config, 0, main_leg)?;
+        check_bet(high, game_type, &game_config, 0, high_leg)?;
+
+        let resolve_slot = get_resolve_slot(clock.slot, bet_lamports);
+        let (main_key, high_key) = (ctx.accounts.session.key(), ctx.accounts.linked_session.key());
+        let net_loss_ref = |pool: &GlobalPool, referral: bool| -> Result<bool> {
+            let bound = pool.referral_mode == REFERRAL_MODE_NET_LOSS && is_valid_referrer(ref_key, player_key);
+            require!(!bound || referral, BlitzError::ReferralAccountRequired);
+            Ok(bound)
+        };
+        let main_ref = net_loss_ref(main, ctx.accounts.main_referral.is_some())?;
+        let high_ref = net_loss_ref(high, ctx.accounts.high_roller_referral.is_some())?;
+        let legs = [
+            (&mut ctx.accounts.session,        main_leg, main.key(), high_key, ctx.bumps.session,        payout_num(main.total_balance), main.curve(), main_ref, main.total_balance),
+            (&mut ctx.accounts.linked_session, high_leg, high.key(), main_key, ctx.bumps.linked_session, payout_num(high.total_balance), high.curve(), high_ref, high.total_balance),
+        ];
+        let has_ref = is_valid_referrer(ref_key, player_key);
+        for (s, amount, pool_key, linked, bump, num, curve, bound, balance) in legs {
+            s.player         = player_key;
+            s.referrer       = ref_key;
+            s.net_loss_ref   = bound;
+            s.bet_lamports   = amount;
+            s.commitment     = commitment;
+            s.commit_slot    = clock.slot;
+            s.resolve_slot   = resolve_slot;
+            s.forfeit_slot   = clock.slot + REVEAL_WINDOW;
+            s.game_type      = game_type;
+            s.game_state     = 0;
+            s.target_x       = game_config[0];
+            s.target_y       = game_config[1];
+            s.target_radius  = game_config[2];
+            s.bump           = bump;
+            s.pool           = pool_key;
+            s.payout_to      = player_key;
+            s.linked         = linked;
+            s.seed_lamports  = bet_lamports;
+            s.payout_num_bps = num as u16;
+            s.payout_curve   = curve;
+            s.fee_bps        = pin_fee_bps(balance, has_ref);
+        }
+        index_session(main, ctx.accounts.main_open_sessions.as_mut(), &mut ctx.accounts.session)?;
+        index_session(high, ctx.accounts.high_roller_open_sessions.as_mut(), &mut ctx.accounts.linked_session)?;
 
-        let s           = &mut ctx.accounts.session;
-        s.player        = player_key;
-        s.referrer      = ref_key;
-        s.bet_lamports  = bet_lamports;
-        s.commitment    = commitment;
-        s.commit_slot   = clock.slot;
-        s.resolve_slot  = get_resolve_slot(clock.slot, bet_lamports);
-        s.forfeit_slot  = clock.slot + REVEAL_WINDOW;
-        s.game_type     = game_type;
-        s.game_state    = 0;
-        s.target_x      = game_config[0];
-        s.target_y      = game_config[1];
-        s.target_radius = game_config[2];
-        s.bump          = ctx.bumps.session;
-
-         let pool_ai = pool.to_account_info();
-        invoke(
-            &system_instruction::transfer(&player_key, pool_ai.key, bet_lamports),
-            &[ctx.accounts.player.to_account_info(), pool_ai.clone()],
-        )?;
-        sync_balance(pool, &pool_ai)?;
-        pool.total_wagered = pool.total_wagered.saturating_add(bet_lamports);
-        pool.total_bets    = pool.total_bets.saturating_add(1);
+        let player_ai = ctx.accounts.player.to_account_info();
+        for (pool, amount) in [(main, main_leg), (high, high_leg)] {
+            let pool_ai = pool.to_account_info();
+            invoke(
+                &system_instruction::transfer(&player_key, pool_ai.key, amount),
+                &[player_ai.clone(), pool_ai.clone()],
+            )?;
+            sync_balance(pool, &pool_ai)?;
+            pool.open_stakes      = pool.open_stakes.saturating_add(amount);
+            pool.total_wagered    = pool.total_wagered.saturating_add(amount);
+            pool.total_bets       = pool.total_bets.saturating_add(1);
+            pool.lifetime_wagered = add_u128(pool.lifetime_wagered, amount)?;
+            pool.record_game_bet(game_type, amount);
+        }
+        if let Some(stats) = ctx.accounts.player_stats.as_mut() {
+            stats.total_bets     = stats.total_bets.saturating_add(1);
+            stats.total_wagered  = add_u128(stats.total_wagered, bet_lamports)?;
+            stats.first_bet_used = true; // split bets are never insured, but still spend the first bet
+        }
+
+        emit!(SplitBetPlaced { player: player_key, game_type, main_session: main_key, main_amount: main_leg,
+                               high_roller_session: high_key, high_roller_amount: high_leg, resolve_slot });
+        Ok(())
+    }
+
+    /// @notice Places up to BATCH_MAX_BETS independent bets — any mix of games, each under its own
+    ///         commitment — in one transaction. Every bet goes through the same limits as place_bet_v3.
+    /// @dev    Session PDAs [b"session", player, commitment] are passed writable in `remaining_accounts`,
+    ///         one per bet in order, and created here. Bets are admitted one after another, so each
+    ///         sees the liquidity the earlier ones left. Any rejected bet fails the whole batch.
+    pub fn place_bets_batch<'info>(
+        ctx:  Context<'_, '_, 'info, 'info, PlaceBetsBatch<'info>>,
+        bets: Vec<BatchBet>,
+    ) -> Result<()> {
+        require!(!bets.is_empty() && bets.len() <= BATCH_MAX_BETS,  BlitzError::InvalidBatchSize);
+        require!(ctx.remaining_accounts.len() == bets.len(),        BlitzError::InvalidBatchSize);
+        let a         = ctx.accounts;
+        let mut total = 0u64;
+        for (b, session_ai) in bets.iter().zip(ctx.remaining_accounts.iter()) {
+            require!(b.game_type != 22, BlitzError::InvalidParlay);
+            require!(b.game_type != 23, BlitzError::InvalidGameType);
+            let (mut session, bump) = create_session_pda(&a.player, &a.system_program, session_ai, &b.commitment)?;
+            let before = a.pool.total_wagered;
+            place_bet_with(BetAccounts {
+                player:        &a.player,
+                referrer:      &a.referrer,
+                pool:          &mut a.pool,
+                session:       &mut session,
+                session_bump:  bump,
+                instructions:  a.instructions.as_ref(),
+                payout_wallet: a.payout_wallet.as_ref(),
+                partner:       a.partner.as_mut(),
+                player_sketch: a.player_sketch.as_mut(),
+                player_stats:  a.player_stats.as_mut(),
+                open_sessions: a.open_sessions.as_mut(),
+                vault:         None,
+                sponsor_grant: None,
+                referral:      a.referral.as_deref(),
+            }, b.game_type, b.commitment, b.bet_lamports, b.game_config, b.config_ext, b.flags)?;
+            session.exit(&crate::ID)?;
+            total = total.saturating_add(a.pool.total_wagered.saturating_sub(before));
+        }
+        emit!(BetsBatchPlaced { player: a.player.key(), pool: a.pool.key(), count: bets.len() as u8, total });
+        Ok(())
+    }
+
+    /// @notice Places a parlay (game 22): 2–4 Flip, Sector99, Dice or Tower legs that must all
+    ///         win, settled by one reveal_parlay. Each leg's payout becomes the next leg's stake.
+    /// @dev    The legs are stored in the ParlaySession PDA [b"parlay", session]. The session
+    ///         carries [legs, 0, 0] and, in config_ext, the chained worst-case multiplier, so the
+    ///         usual max-bet, payout-cap and liquidity checks apply to the parlay as a whole.
+    pub fn place_parlay(
+        ctx:          Context<PlaceParlay>,
+        commitment:   [u8; 32],
+        bet_lamports: u64,
+        legs:         Vec<ParlayLeg>,
+    ) -> Result<()> {
+        require!((PARLAY_MIN_LEGS as usize..=PARLAY_MAX_LEGS as usize).contains(&legs.len()), BlitzError::InvalidParlay);
+        for l in &legs {
+            require!(l.game_type <= 3,                                         BlitzError::InvalidParlay);
+            require!(ctx.accounts.pool.paused_games() & (1 << l.game_type) == 0, BlitzError::GamePaused);
+            validate_game_config(l.game_type, &l.game_config, l.config_ext)?;
+        }
+        let n       = legs.len() as u8;
+        let p       = &mut ctx.accounts.parlay;
+        p.session   = ctx.accounts.session.key();
+        p.legs      = n;
+        for (i, l) in legs.iter().enumerate() {
+            p.games[i]   = l.game_type;
+            p.configs[i] = l.game_config;
+            p.exts[i]    = l.config_ext;
+        }
+        p.bump      = ctx.bumps.parlay;
+        let a = ctx.accounts;
+        place_bet_with(BetAccounts {
+            player:        &a.player,
+            referrer:      &a.referrer,
+            pool:          &mut a.pool,
+            session:       &mut a.session,
+            session_bump:  ctx.bumps.session,
+            instructions:  a.instructions.as_ref(),
+            payout_wallet: a.payout_wallet.as_ref(),
+            partner:       a.partner.as_mut(),
+            player_sketch: a.player_sketch.as_mut(),
+            player_stats:  a.player_stats.as_mut(),
+            open_sessions: a.open_sessions.as_mut(),
+            vault:         None,
+            sponsor_grant: None,
+            referral:      a.referral.as_deref(),
+        }, 22, commitment, bet_lamports, [n, 0, 0], parlay_multiplier_bps(&legs), 0)
+    }
+
+    /// @notice Read-only dry run of place_bet. Simulate it to pre-validate a bet with on-chain logic.
+    /// @dev    Never fails on a rejected bet — `accepted = false` plus the BlitzError code instead.
+    ///         Result is delivered via return data (`BetQuote`).
+    /// @param config_ext  Wide config as passed to place_bet_v2 (Keno picks, Limbo target); 0 otherwise.
+    pub fn quote_bet(
+        ctx:          Context<QuoteBet>,
+        game_type:    u8,
+        game_config:  [u8; 3],
+        config_ext:   u64,
+        bet_lamports: u64,
+        has_referrer: bool,
+    ) -> Result<BetQuote> {
+        let pool   = &ctx.accounts.pool;
+        let paused = pool.paused && Clock::get()?.unix_timestamp < pool.pause_expires_at;
+        Ok(bet_quote(pool, paused, game_type, &game_config, config_ext, bet_lamports, has_referrer))
+    }
+
+    /// @notice Creates the global unique-player sketch. Authority only, once.
+    pub fn initialize_player_sketch(ctx: Context<InitializePlayerSketch>) -> Result<()> {
+        let k       = &mut ctx.accounts.player_sketch;
+        k.epoch     = Clock::get()?.epoch;
+        k.lifetime  = [0u8; HLL_REGISTERS];
+        k.current   = [0u8; HLL_REGISTERS];
+        k.bump      = ctx.bumps.player_sketch;
+        Ok(())
+    }
 
-        emit!(BetPlaced { player: s.player, game_type, amount: bet_lamports, resolve_slot: s.resolve_slot });
+    /// @notice Creates an open-session index page for a pool. Authority only.
+    /// @dev    Dashboards read all pages instead of getProgramAccounts; settlement swap-removes entries.
+    ///         The first page sets pool.session_index_enabled: from then on every placement must
+    ///         pass a page, so the index lists every pending session of the room.
+    pub fn create_open_session_page(ctx: Context<CreateOpenSessionPage>, page: u32) -> Result<()> {
+        require!(page != NOT_INDEXED, BlitzError::InvalidGameConfig);
+        let p   = &mut ctx.accounts.open_sessions;
+        p.pool  = ctx.accounts.pool.key();
+        p.page  = page;
+        p.count = 0;
+        p.bump  = ctx.bumps.open_sessions;
+        let pool = &mut ctx.accounts.pool;
+        if !pool.session_index_enabled {
+            pool.session_index_enabled = true;
+            bump_config(pool);
+        }
         Ok(())
     }
 
@@ -252,942 +1522,8047 @@ pub mod blitz_games {
 
     /// @notice Reveals nonce and settles a Flip bet. Player must sign.
      pub fn reveal_flip(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
-        let (won, payout, outcome, seed) = resolve(0, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce, ctx.accounts.pool.total_balance)?;
-        settle(&mut ctx.accounts.pool, &ctx.accounts.player, &ctx.accounts.referrer, &ctx.accounts.session, won, payout, seed)?;
-        ctx.accounts.session.game_state = 2;
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(0, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
         emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
     /// @notice Reveals nonce and settles a Sector99 bet. Player must sign.
      pub fn reveal_sector(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
-        let (won, payout, outcome, seed) = resolve(1, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce, ctx.accounts.pool.total_balance)?;
-        settle(&mut ctx.accounts.pool, &ctx.accounts.player, &ctx.accounts.referrer, &ctx.accounts.session, won, payout, seed)?;
-        ctx.accounts.session.game_state = 2;
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(1, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
         emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        record_strike(&ctx.accounts.sector_heatmap, &outcome)?;
         Ok(())
     }
 
     /// @notice Reveals nonce and settles a Dice bet. Player must sign.
      pub fn reveal_dice(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
-        let (won, payout, outcome, seed) = resolve(2, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce, ctx.accounts.pool.total_balance)?;
-        settle(&mut ctx.accounts.pool, &ctx.accounts.player, &ctx.accounts.referrer, &ctx.accounts.session, won, payout, seed)?;
-        ctx.accounts.session.game_state = 2;
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(2, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
         emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
     /// @notice Reveals nonce and settles a Tower bet. Player must sign.
      pub fn reveal_tower(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
-        let (won, payout, outcome, seed) = resolve(3, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce, ctx.accounts.pool.total_balance)?;
-        settle(&mut ctx.accounts.pool, &ctx.accounts.player, &ctx.accounts.referrer, &ctx.accounts.session, won, payout, seed)?;
-        ctx.accounts.session.game_state = 2;
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(3, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
         emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    // ── Reveal: delegated (session key — zero wallet popup) ───────────────
-
-    /// @notice Delegated Flip reveal. Ephemeral session key signs — no wallet popup.
-     pub fn reveal_flip_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
-        check_session_token(&ctx.accounts.session_token)?;
-        let (won, payout, outcome, seed) = resolve(0, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce, ctx.accounts.pool.total_balance)?;
-        settle(&mut ctx.accounts.pool, &ctx.accounts.player, &ctx.accounts.referrer, &ctx.accounts.session, won, payout, seed)?;
-        ctx.accounts.session.game_state = 2;
+    /// @notice Reveals nonce and settles a Mystery bet. Player must sign.
+    pub fn reveal_mystery(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(4, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
         emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        record_strike(&ctx.accounts.sector_heatmap, &outcome)?;
         Ok(())
     }
 
-    /// @notice Delegated Sector99 reveal.
-     pub fn reveal_sector_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
-        check_session_token(&ctx.accounts.session_token)?;
-        let (won, payout, outcome, seed) = resolve(1, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce, ctx.accounts.pool.total_balance)?;
-        settle(&mut ctx.accounts.pool, &ctx.accounts.player, &ctx.accounts.referrer, &ctx.accounts.session, won, payout, seed)?;
-        ctx.accounts.session.game_state = 2;
+    /// @notice Reveals nonce and settles a Plinko bet. Player must sign.
+    pub fn reveal_plinko(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(5, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
         emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Delegated Dice reveal.
-     pub fn reveal_dice_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
-        check_session_token(&ctx.accounts.session_token)?;
-        let (won, payout, outcome, seed) = resolve(2, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce, ctx.accounts.pool.total_balance)?;
-        settle(&mut ctx.accounts.pool, &ctx.accounts.player, &ctx.accounts.referrer, &ctx.accounts.session, won, payout, seed)?;
-        ctx.accounts.session.game_state = 2;
+    /// @notice Reveals nonce and settles a Crash bet. Player must sign.
+    pub fn reveal_crash(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(7, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
         emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Delegated Tower reveal.
-     pub fn reveal_tower_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
-        check_session_token(&ctx.accounts.session_token)?;
-        let (won, payout, outcome, seed) = resolve(3, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce, ctx.accounts.pool.total_balance)?;
-        settle(&mut ctx.accounts.pool, &ctx.accounts.player, &ctx.accounts.referrer, &ctx.accounts.session, won, payout, seed)?;
-        ctx.accounts.session.game_state = 2;
+    /// @notice Reveals nonce and settles a Roulette bet. Player must sign.
+    pub fn reveal_roulette(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(8, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
         emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    // ── Forfeit / Refund paths ─────────────────────────────────────────────
+    /// @notice Reveals nonce and settles a Keno bet. Player must sign.
+    pub fn reveal_keno(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(9, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-    /// @notice Permissionless cleanup after forfeit window + 200 slot grace period.
-    /// @dev    Entire bet stays in pool. No bounty = no bot-griefing incentive.
-    pub fn claim_forfeit(ctx: Context<ClaimForfeit>) -> Result<()> {
-        let clock = Clock::get()?;
-        require!(
-            ctx.accounts.session.game_state < 2
-                && clock.slot > ctx.accounts.session.forfeit_slot.saturating_add(200),
-            BlitzError::ForfeitNotAvailable
-        );
-        let pool_ai = ctx.accounts.pool.to_account_info();
-        sync_balance(&mut ctx.accounts.pool, &pool_ai)?;
-        ctx.accounts.session.game_state = 2;
-        emit!(BetForfeited { player: ctx.accounts.session.player, amount: ctx.accounts.session.bet_lamports });
+    /// @notice Reveals nonce and settles a Limbo bet. Player must sign.
+    pub fn reveal_limbo(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(10, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Player voluntarily forfeits while slot hash is still accessible.
-    /// @dev    4% refund makes selective-reveal EV-negative:
-    ///         EV(flip) = 0.5×0.95 + 0.5×(0.04−1) = −0.005 SOL. Attack unprofitable.
-    pub fn voluntary_forfeit(ctx: Context<VoluntaryForfeit>) -> Result<()> {
-        let clock = Clock::get()?;
-        require!(ctx.accounts.session.game_state == 0,                             BlitzError::SessionNotPending);
-        require!(ctx.accounts.session.player == ctx.accounts.player.key(),         BlitzError::NotSessionPlayer);
-        require!(clock.slot > ctx.accounts.session.forfeit_slot,                   BlitzError::ForfeitNotAvailable);
-        require!(clock.slot.saturating_sub(ctx.accounts.session.resolve_slot) < 490, BlitzError::UseEmergencyRefund);
-        send_refund(&mut ctx.accounts.pool, &ctx.accounts.player, ctx.accounts.session.bet_lamports, 4)?;
-        ctx.accounts.session.game_state = 2;
-        emit!(BetForfeited { player: ctx.accounts.session.player, amount: ctx.accounts.session.bet_lamports.saturating_mul(96) / 100 });
+    /// @notice Reveals nonce and settles a Wheel bet. Player must sign.
+    pub fn reveal_wheel(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(11, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Refunds 4% when the slot hash has expired (>490 slots). Genuine failure path.
-    /// @dev    96% stays in pool — deters deliberate non-reveal abuse.
-    pub fn emergency_refund(ctx: Context<EmergencyRefund>) -> Result<()> {
-        let clock = Clock::get()?;
-        require!(ctx.accounts.session.game_state == 0,                              BlitzError::SessionNotPending);
-        require!(ctx.accounts.session.player == ctx.accounts.player.key(),          BlitzError::NotSessionPlayer);
-        require!(clock.slot > ctx.accounts.session.forfeit_slot,                    BlitzError::ForfeitNotAvailable);
-        require!(clock.slot.saturating_sub(ctx.accounts.session.resolve_slot) >= 490, BlitzError::SlotHashStillAvailable);
-        send_refund(&mut ctx.accounts.pool, &ctx.accounts.player, ctx.accounts.session.bet_lamports, 4)?;
-        ctx.accounts.session.game_state = 2;
-        emit!(BetForfeited { player: ctx.accounts.session.player, amount: ctx.accounts.session.bet_lamports.saturating_mul(96) / 100 });
+    /// @notice Reveals nonce and settles a Video Poker bet. Player must sign.
+    pub fn reveal_video_poker(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(13, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Permissionless circuit breaker when pool cannot cover worst-case payout.
-    /// @dev    50% refund is fair — insolvency is not the player's fault.
-    pub fn emergency_player_refund(ctx: Context<BotRefund>) -> Result<()> {
-        require!(ctx.accounts.session.game_state == 0,                     BlitzError::SessionNotPending);
-        require!(ctx.accounts.session.player == ctx.accounts.player.key(), BlitzError::NotSessionPlayer);
-        let cfg   = [ctx.accounts.session.target_x, ctx.accounts.session.target_y, ctx.accounts.session.target_radius];
-        let worst = get_worst_payout(ctx.accounts.session.bet_lamports, ctx.accounts.session.game_type, &cfg);
-        require!(ctx.accounts.pool.total_balance < worst, BlitzError::InsufficientLiquidity);
-        send_refund(&mut ctx.accounts.pool, &ctx.accounts.player, ctx.accounts.session.bet_lamports, 50)?;
-        ctx.accounts.session.game_state = 2;
-        emit!(BetForfeited { player: ctx.accounts.session.player, amount: ctx.accounts.session.bet_lamports.saturating_mul(50) / 100 });
+    /// @notice Reveals nonce and settles a Baccarat bet. Player must sign.
+    pub fn reveal_baccarat(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(14, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    // ── Session Keys ───────────────────────────────────────────────────────
+    /// @notice Reveals nonce and settles a Slots spin. Player must sign.
+    pub fn reveal_slots(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(15, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-    /// @notice Creates a time-bounded delegate key for wallet-popup-free auto-reveals.
-    /// @dev    validity_secs capped at 24h. Gas forwarded to delegate up to 0.01 SOL.
-    pub fn create_session(ctx: Context<CreateSession>, validity_secs: i64, gas_lamports: u64) -> Result<()> {
-        require!(validity_secs > 0 && validity_secs <= 86400, BlitzError::InvalidSessionDuration);
-        require!(gas_lamports <= 10_000_000,                  BlitzError::GasTooHigh);
-        let clock   = Clock::get()?;
-        let t       = &mut ctx.accounts.session_token;
-        t.player    = ctx.accounts.player.key();
-        t.delegate  = ctx.accounts.delegate.key();
-        t.expires_at = clock.unix_timestamp + validity_secs;
-        t.bump      = ctx.bumps.session_token;
-        if gas_lamports > 0 {
-            invoke(
-                &system_instruction::transfer(&t.player, &t.delegate, gas_lamports),
-                &[ctx.accounts.player.to_account_info(), ctx.accounts.delegate.to_account_info()],
-            )?;
-        }
-        emit!(SessionCreated { player: t.player, delegate: t.delegate, expires_at: t.expires_at });
+    /// @notice Reveals nonce and settles a Sic Bo roll. Player must sign.
+    pub fn reveal_sic_bo(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(16, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Closes the session token PDA, reclaiming rent to the player.
-    pub fn close_session(_ctx: Context<CloseSession>) -> Result<()> { Ok(()) }
-
-    // ── Admin ──────────────────────────────────────────────────────────────
-
-    /// @notice Pauses or unpauses the contract.
-    /// @dev    Pause is hard-capped at MAX_PAUSE_DURATION (24h).
-    ///         place_bet auto-expires a forgotten pause — funds can never be frozen permanently.
-    pub fn set_paused(ctx: Context<AdminOnly>, paused: bool) -> Result<()> {
-        let pool = &mut ctx.accounts.pool;
-        pool.paused = paused;
-        if paused {
-            pool.pause_expires_at = Clock::get()?.unix_timestamp + MAX_PAUSE_DURATION;
-            emit!(ContractPaused { authority: pool.authority, expires_at: pool.pause_expires_at });
-        } else {
-            pool.pause_expires_at = 0;
-            emit!(ContractUnpaused { authority: pool.authority });
-        }
+    /// @notice Reveals nonce and settles a Dragon Tiger hand. Player must sign.
+    pub fn reveal_dragon_tiger(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(17, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Initiates a withdrawal (48h timelock, max 20% of pool per request).
-    pub fn request_withdrawal(ctx: Context<AdminOnly>, amount: u64) -> Result<()> {
-        let pool  = &mut ctx.accounts.pool;
-        let clock = Clock::get()?;
-        require!(pool.withdrawal_request.is_none(),  BlitzError::PendingWithdrawal);
-        require!(amount <= pool.total_balance / 5,   BlitzError::WithdrawalTooLarge);
-        pool.withdrawal_request = Some(WithdrawalRequest {
-            amount,
-            requested_at: clock.unix_timestamp,
-            unlocks_at:   clock.unix_timestamp + TIMELOCK_SECS,
-        });
-        emit!(WithdrawalRequested { amount, unlocks_at: clock.unix_timestamp + TIMELOCK_SECS });
+    /// @notice Reveals nonce and settles a Dice Duo roll. Player must sign.
+    pub fn reveal_dice_duo(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(18, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Executes a pending withdrawal after the 48h timelock.
-    pub fn execute_withdrawal(ctx: Context<AdminOnly>) -> Result<()> {
-        let pool  = &mut ctx.accounts.pool;
-        let clock = Clock::get()?;
-        let req   = pool.withdrawal_request.clone().ok_or(BlitzError::NoWithdrawalRequest)?;
-        require!(clock.unix_timestamp >= req.unlocks_at, BlitzError::TimelockActive);
-        require!(pool.total_balance >= req.amount,        BlitzError::InsufficientLiquidity);
-        **pool.to_account_info().try_borrow_mut_lamports()? -= req.amount;
-        **ctx.accounts.authority.try_borrow_mut_lamports()? += req.amount;
-        pool.withdrawal_request = None;
-        let pool_ai = pool.to_account_info();
-        sync_balance(pool, &pool_ai)?;
-        emit!(WithdrawalExecuted { amount: req.amount });
+    /// @notice Reveals nonce and settles a Goal run. Player must sign.
+    pub fn reveal_goal(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(20, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Cancels a pending withdrawal request.
-    pub fn cancel_withdrawal(ctx: Context<AdminOnly>) -> Result<()> {
-        ctx.accounts.pool.withdrawal_request = None;
+    /// @notice Reveals nonce and settles a double_or_nothing re-stake. Player must sign.
+    pub fn reveal_double(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let (won, payout, outcome, seed) = resolve(23, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Withdraws the claimable portion of house fees.
-    /// @dev    Only `house_fees_earned` is withdrawable. Liquid pool balance is protected.
-    pub fn claim_house_fees(ctx: Context<ClaimHouseFeesCtx>, amount: u64) -> Result<()> {
-        let pool = &mut ctx.accounts.pool;
-        require!(amount > 0 && amount <= pool.house_fees_earned, BlitzError::InsufficientLiquidity);
-        let rent = Rent::get()?.minimum_balance(pool.to_account_info().data_len());
-        require!(pool.to_account_info().lamports().saturating_sub(rent) >= amount, BlitzError::InsufficientLiquidity);
-        **pool.to_account_info().try_borrow_mut_lamports()? -= amount;
-        **ctx.accounts.authority.try_borrow_mut_lamports()? += amount;
-        pool.house_fees_earned     = pool.house_fees_earned.saturating_sub(amount);
-        pool.operational_extracted = pool.operational_extracted.saturating_add(amount);
-        let pool_ai = pool.to_account_info();
-        sync_balance(pool, &pool_ai)?;
-        emit!(HouseFeesClaimed { amount, authority: ctx.accounts.authority.key() });
+    // ── Reveal: delegated (session key — zero wallet popup) ───────────────
+
+    /// @notice Delegated Flip reveal. Ephemeral session key signs — no wallet popup.
+     pub fn reveal_flip_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(0, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Initiates a request to reinvest house fees back to the liquid pool (24h timelock).
-    pub fn request_reinvest(ctx: Context<AdminOnly>, amount: u64) -> Result<()> {
-        let pool  = &mut ctx.accounts.pool;
-        let clock = Clock::get()?;
-        require!(amount > 0 && amount <= pool.house_fees_earned, BlitzError::InsufficientLiquidity);
-        require!(pool.reinvest_request.is_none(),  BlitzError::PendingWithdrawal);
-        pool.reinvest_request = Some(WithdrawalRequest {
-            amount,
-            requested_at: clock.unix_timestamp,
-            unlocks_at:   clock.unix_timestamp + MAX_PAUSE_DURATION, // 24h delay
-        });
-        emit!(ReinvestRequested { amount, unlocks_at: clock.unix_timestamp + MAX_PAUSE_DURATION });
+    /// @notice Delegated Sector99 reveal.
+     pub fn reveal_sector_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(1, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        record_strike(&ctx.accounts.sector_heatmap, &outcome)?;
         Ok(())
     }
 
-    /// @notice Executes a pending house fee reinvestment after the 24h timelock.
-    /// @dev    Purely internal accounting — no lamports leave the account.
-    pub fn execute_reinvest(ctx: Context<AdminOnly>) -> Result<()> {
-        let pool  = &mut ctx.accounts.pool;
-        let clock = Clock::get()?;
-        let req   = pool.reinvest_request.clone().ok_or(BlitzError::NoWithdrawalRequest)?;
-        require!(clock.unix_timestamp >= req.unlocks_at, BlitzError::TimelockActive);
-        require!(req.amount <= pool.house_fees_earned, BlitzError::InsufficientLiquidity);
-        pool.house_fees_earned = pool.house_fees_earned.saturating_sub(req.amount);
-        pool.total_reinvested  = pool.total_reinvested.saturating_add(req.amount);
-        pool.reinvest_request  = None;
-        let pool_ai = pool.to_account_info();
-        sync_balance(pool, &pool_ai)?;
-        emit!(ReinvestExecuted { amount: req.amount });
+    /// @notice Delegated Dice reveal.
+     pub fn reveal_dice_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(2, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    // ── Authority Transfer (72h timelock, new key must co-sign) ──────────
+    /// @notice Delegated Tower reveal.
+     pub fn reveal_tower_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(3, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-    /// @notice Proposes a new authority (72h timelock + new key must confirm).
-    pub fn propose_authority_transfer(ctx: Context<AdminOnly>, new_authority: Pubkey) -> Result<()> {
-        let pool = &mut ctx.accounts.pool;
-        require!(new_authority != pool.authority && new_authority != Pubkey::default(), BlitzError::InvalidAuthority);
-        let now = Clock::get()?.unix_timestamp;
-        pool.pending_authority     = Some(new_authority);
-        pool.authority_transfer_at = now + AUTH_TIMELOCK;
-        emit!(AuthorityTransferProposed { current: pool.authority, proposed: new_authority, unlocks_at: pool.authority_transfer_at });
+    /// @notice Delegated Mystery reveal.
+    pub fn reveal_mystery_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(4, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        record_strike(&ctx.accounts.sector_heatmap, &outcome)?;
         Ok(())
     }
 
-    /// @notice Cancels a pending authority transfer.
-    pub fn cancel_authority_transfer(ctx: Context<AdminOnly>) -> Result<()> {
-        let pool = &mut ctx.accounts.pool;
-        require!(pool.pending_authority.is_some(), BlitzError::NoWithdrawalRequest);
-        pool.pending_authority     = None;
-        pool.authority_transfer_at = 0;
-        emit!(AuthorityTransferCancelled { authority: pool.authority });
+    /// @notice Delegated Plinko reveal.
+    pub fn reveal_plinko_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(5, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    /// @notice Finalises authority transfer. New authority must sign to confirm.
-    pub fn execute_authority_transfer(ctx: Context<ExecuteAuthorityTransfer>) -> Result<()> {
-        let pool     = &mut ctx.accounts.pool;
-        let new_auth = pool.pending_authority.ok_or(BlitzError::NoWithdrawalRequest)?;
-        require!(ctx.accounts.new_authority.key() == new_auth,          BlitzError::InvalidAuthority);
-        require!(Clock::get()?.unix_timestamp >= pool.authority_transfer_at, BlitzError::TimelockActive);
-        let old        = pool.authority;
-        pool.authority = new_auth;
-        pool.pending_authority     = None;
-        pool.authority_transfer_at = 0;
-        emit!(AuthorityTransferred { old_authority: old, new_authority: new_auth });
+    /// @notice Delegated Crash reveal.
+    pub fn reveal_crash_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(7, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
 
-    // ── One-time migration (remove after upgrade) ────────────────────────
+    /// @notice Delegated Roulette reveal.
+    pub fn reveal_roulette_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(8, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-    /// @notice Expands GlobalPool from old layout to new layout (adds reinvest_request).
-    /// @dev    Call once after program upgrade. Safe to delete from code after migration.
-    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
-        let pool = &mut ctx.accounts.pool;
-        pool.reinvest_request = None;
+    /// @notice Delegated Keno reveal.
+    pub fn reveal_keno_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(9, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
         Ok(())
     }
-}
 
-// ══════════════════════════════════════════════════════════════════════════
-//  PRIVATE HELPERS
-// ══════════════════════════════════════════════════════════════════════════
+    /// @notice Delegated Limbo reveal.
+    pub fn reveal_limbo_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(10, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-// ── Session guard ─────────────────────────────────────────────────────────
+    /// @notice Delegated Wheel reveal.
+    pub fn reveal_wheel_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(11, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-#[inline]
-fn check_session_token(token: &Account<SessionToken>) -> Result<()> {
-    require!(Clock::get()?.unix_timestamp < token.expires_at, BlitzError::SessionExpired);
-    Ok(())
-}
+    /// @notice Delegated Video Poker reveal.
+    pub fn reveal_video_poker_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(13, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-// ── Refund helper ─────────────────────────────────────────────────────────
+    /// @notice Delegated Baccarat reveal.
+    pub fn reveal_baccarat_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(14, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-/// Transfers `pct`% of `bet` from pool to `dest`, then syncs balance.
-fn send_refund<'info>(
-    pool: &mut Account<'info, GlobalPool>,
-    dest: &AccountInfo<'info>,
-    bet:  u64,
-    pct:  u64,
-) -> Result<()> {
-    let refund = bet.saturating_mul(pct) / 100;
-    require!(pool.total_balance >= refund, BlitzError::InsufficientLiquidity);
-    let pool_ai = pool.to_account_info();
-    **pool_ai.try_borrow_mut_lamports()? -= refund;
-    **dest.try_borrow_mut_lamports()?    += refund;
-    sync_balance(pool, &pool_ai)
-}
+    /// @notice Delegated Slots reveal.
+    pub fn reveal_slots_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(15, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-// ── Referrer check ────────────────────────────────────────────────────────
+    /// @notice Delegated Sic Bo reveal.
+    pub fn reveal_sic_bo_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(16, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-#[inline(always)]
-fn is_valid_referrer(referrer: Pubkey, player: Pubkey) -> bool {
-    referrer != system_program::ID && referrer != player
-}
+    /// @notice Delegated Dragon Tiger reveal.
+    pub fn reveal_dragon_tiger_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(17, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-// ── Balance sync ──────────────────────────────────────────────────────────
+    /// @notice Delegated Dice Duo reveal.
+    pub fn reveal_dice_duo_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(18, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-/// @dev Derives `total_balance` from physical lamports every time funds move.
-///      Single source of truth — eliminates accounting desync.
-fn sync_balance(pool: &mut Account<GlobalPool>, ai: &AccountInfo) -> Result<()> {
-    let rent     = Rent::get()?.minimum_balance(ai.data_len());
-    let physical = ai.lamports().saturating_sub(rent);
-    let reserved = pool.house_fees_earned.saturating_add(pool.jackpot_balance);
-    require!(physical >= reserved, BlitzError::AccountingBroken);
-    pool.total_balance = physical.saturating_sub(reserved);
-    Ok(())
-}
+    /// @notice Delegated Goal reveal.
+    pub fn reveal_goal_delegated(ctx: Context<RevealDelegated>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        check_session_token(&ctx.accounts.session_token)?;
+        let (won, payout, outcome, seed) = resolve(20, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-// ── Fee schedule ──────────────────────────────────────────────────────────
+    // ── Reveal: public (opt-in, executor tip) ─────────────────────────────
+
+    /// @notice Settles a PLACE_FLAG_PUBLIC_REVEAL bet of any single-shot game for whoever holds
+    ///         the published nonce. A win pays the executor CRANK_TIP_LAMPORTS (capped at the
+    ///         payout) out of the player's payout; a loss pays no tip, nor does a held win.
+    pub fn reveal_public(ctx: Context<RevealPublic>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        require!(ctx.accounts.session.public_reveal, BlitzError::PublicRevealNotAllowed);
+        let game = ctx.accounts.session.game_type;
+        let (won, payout, outcome, seed) = resolve(game, &ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &nonce)?;
+        let dest     = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner  = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign   = ctx.accounts.cosigner.is_some();
+        let executor = ctx.accounts.executor.to_account_info();
+        let plan     = settle_tipped(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed, Some((&executor, CRANK_TIP_LAMPORTS)))?;
+        let tip      = plan.executor_tip;
+        if tip > 0 {
+            emit!(PublicRevealTipped { session: ctx.accounts.session.key(), executor: executor.key(), tip });
+        }
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout - tip, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        record_strike(&ctx.accounts.sector_heatmap, &outcome)?;
+        Ok(())
+    }
 
-fn get_fee_bps(pool_balance: u64, has_ref: bool) -> (u64, u64, u64) {
-    match (pool_balance < PHASE1_THRESHOLD, pool_balance < PHASE2_THRESHOLD, has_ref) {
-        (true,  _,    true)  => (50,  150, 50), // Phase 0 + ref
-        (true,  _,    false) => (200, 0,   50), // Phase 0 solo
-        (false, true, true)  => (50,  100, 50), // Phase 1 + ref
-        (false, true, false) => (150, 0,   50), // Phase 1 solo
-        (false, false,true)  => (50,  75,  25), // Phase 2 + ref
-        (false, false,false) => (125, 0,   25), // Phase 2 solo
+    // ── Reveal: client seed (player signs) ────────────────────────────────
+
+    /// @notice Reveals nonce and client seed and settles a PLACE_FLAG_CLIENT_SEED bet of any
+    ///         single-shot game. Player must sign.
+    /// @param client_seed  Player-chosen seed, mixed into the game seed next to the nonce.
+    ///                     Together they must hash to the commitment: SHA-256(nonce || client_seed).
+    pub fn reveal_client_seeded(ctx: Context<RevealGame>, nonce: [u8; 32], client_seed: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let game = ctx.accounts.session.game_type;
+        let cr   = CommitReveal { slot_hashes: &ctx.accounts.slot_hashes.to_account_info(), nonce: &nonce, client_seed: Some(&client_seed) };
+        let (won, payout, outcome, seed) = resolve_with(game, &ctx.accounts.session, &cr)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        record_strike(&ctx.accounts.sector_heatmap, &outcome)?;
+        Ok(())
     }
-}
 
-// ── Auto-reinvest split ───────────────────────────────────────────────────
+    // ── Settle: Switchboard VRF (permissionless) ──────────────────────────
+
+    /// @notice Settles a place_bet_vrf bet from its revealed VRF randomness. Anyone may call
+    ///         it; the house cranks them, so the player need not.
+    /// @dev    Decoded with the provider pinned on the session at placement. Same forfeit_slot
+    ///         cutoff as a reveal: past it the terminal policy applies.
+    pub fn settle_vrf(ctx: Context<SettleVrf>) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let s   = &ctx.accounts.session;
+        let vrf = Vrf { provider: s.vrf_provider, account: &ctx.accounts.randomness };
+        let (won, payout, outcome, seed) = resolve_with(s.game_type, s, &vrf)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        write_game_receipt(&mut ctx.accounts.game_receipt, &ctx.accounts.session, &seed, payout, &outcome)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        record_strike(&ctx.accounts.sector_heatmap, &outcome)?;
+        Ok(())
+    }
 
-/// Returns (claimable, reinvested). INVARIANT: claimable + reinvested == house_cut.
-fn split_house_fees(pool_balance: u64, house_cut: u64, bet: u64) -> (u64, u64) {
-    let operational = (bet.saturating_mul(OPERATIONAL_BPS) / 10_000).min(house_cut);
-    let remainder   = house_cut.saturating_sub(operational);
-    if pool_balance < PHASE1_THRESHOLD {
-        (operational, remainder)                                             // Phase 0: lock all
-    } else if pool_balance < PHASE2_THRESHOLD {
-        let reinvest = remainder / 2;
-        (operational.saturating_add(remainder.saturating_sub(reinvest)), reinvest) // Phase 1: 50/50
-    } else {
-        (house_cut, 0)                                                       // Phase 2: all claimable
+    // ── Reveal: batch (player signs) ──────────────────────────────────────
+
+    /// @notice Settles up to BATCH_MAX_BETS of the player's single-shot sessions in one call,
+    ///         one nonce per session, for one transaction fee.
+    /// @dev    `remaining_accounts` holds writable (session, referrer) pairs in `nonces` order.
+    ///         Sessions must be the signer's, in this pool, paid to the player, not placed by a
+    ///         partner and not bound to net-loss referral (net_loss_ref) — partner fees and
+    ///         referral accrual need accounts the batch does not carry, so those go through their
+    ///         reveal_* instruction. Indexed sessions must share the one open_sessions page
+    ///         passed. Settled sessions are skipped.
+    pub fn reveal_batch<'info>(
+        ctx:    Context<'_, '_, 'info, 'info, RevealBatch<'info>>,
+        nonces: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        let pairs = ctx.remaining_accounts;
+        require!(!nonces.is_empty() && nonces.len() <= BATCH_MAX_BETS, BlitzError::InvalidBatchSize);
+        require!(pairs.len() == 2 * nonces.len(),                      BlitzError::InvalidBatchSize);
+        let a         = ctx.accounts;
+        let pool_key  = a.pool.key();
+        let player_ai = a.player.to_account_info();
+        let cosign    = a.cosigner.is_some();
+        let (mut count, mut paid_out) = (0u8, 0u64);
+        for (pair, nonce) in pairs.chunks(2).zip(nonces.iter()) {
+            let (session_ai, referrer_ai) = (&pair[0], &pair[1]);
+            let mut session = Account::<GameSession>::try_from(session_ai)?;
+            require!(session.player == a.player.key() && session.pool == pool_key,       BlitzError::InvalidBatchSession);
+            require!(referrer_ai.key() == session.referrer,                               BlitzError::InvalidBatchSession);
+            require!(!matches!(session.game_type, 6 | 12 | 19 | 21 | 22) && !session.partner, BlitzError::InvalidBatchSession);
+            require!(!session.net_loss_ref,                                               BlitzError::InvalidBatchSession);
+            if session.game_state == 2 { continue; }
+            if session.index_page != NOT_INDEXED {
+                require!(a.open_sessions.as_ref().is_some_and(|p| p.page == session.index_page), BlitzError::IndexPageRequired);
+            }
+            let (won, payout, outcome, seed) = resolve(session.game_type, &session, &a.slot_hashes.to_account_info(), nonce)?;
+            let dest    = payout_dest(&session, &player_ai, &None)?;
+            let plan    = settle(&mut a.pool, &dest, referrer_ai, &session, None, None, a.firehose.as_mut(), cosign, won, payout, seed)?;
+            unindex_session(&mut a.open_sessions, &session)?;
+            record_settlement(&mut session, a.receipt_tree.as_deref_mut(), &player_ai, won, &plan)?;
+            emit_outcome(session.player, won, payout, &outcome);
+            notify_settled(&a.notification_prefs, session.player, won, plan.jackpot_prize);
+            record_strike(&a.sector_heatmap, &outcome)?;
+            session.exit(&crate::ID)?;
+            count   += 1;
+            paid_out = paid_out.saturating_add(plan.player_gets);
+        }
+        emit!(BatchRevealed { player: a.player.key(), pool: pool_key, count, paid_out });
+        Ok(())
     }
-}
 
-// ── Payout numerator ──────────────────────────────────────────────────────
+    // ── Double or nothing ─────────────────────────────────────────────────
 
-#[inline(always)]
-fn payout_num(pool_balance: u64) -> u64 {
-    if      pool_balance < PHASE1_THRESHOLD { 9_750 }
-    else if pool_balance < PHASE2_THRESHOLD { 9_800 }
-    else                                    { 9_850 }
-}
+    /// @notice Re-stakes a held PLACE_FLAG_DOUBLE win on a 50/50 (game 23) that pays 0.99 × 2,
+    ///         within DOUBLE_WINDOW_SECS of the settlement. Settle it with reveal_double.
+    /// @param commitment  SHA-256 of a fresh secret nonce
+    /// @dev    The held lamports never leave the pool: they move from held_payouts back to
+    ///         liquidity as the new stake, and the session becomes a pending bet again. The
+    ///         stake passes check_bet like any bet. A double won is held again, so it chains.
+    pub fn double_or_nothing(ctx: Context<DoubleOrNothing>, commitment: [u8; 32]) -> Result<()> {
+        let clock = Clock::get()?;
+        let s     = &ctx.accounts.session;
+        require!(s.game_state == 2 && s.double_offer && s.held_payout > 0, BlitzError::NoDoubleOffer);
+        require!(clock.unix_timestamp < s.held_until,                     BlitzError::DoubleWindowClosed);
+        let stake = s.held_payout;
 
-// ── Unified payout formula ────────────────────────────────────────────────
+        let pool = &mut ctx.accounts.pool;
+        expire_pause(pool, clock.unix_timestamp);
+        require!(!pool.paused, BlitzError::ContractPaused);
+        check_bet(pool, 23, &[0, 0, 0], 0, stake)?;
+        pool.held_payouts = pool.held_payouts.saturating_sub(stake);
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        pool.open_stakes      = pool.open_stakes.saturating_add(stake);
+        pool.total_wagered    = pool.total_wagered.saturating_add(stake);
+        pool.total_bets       = pool.total_bets.saturating_add(1);
+        pool.lifetime_wagered = add_u128(pool.lifetime_wagered, stake)?;
+        pool.record_game_bet(23, stake);
+
+        let s            = &mut ctx.accounts.session;
+        s.bet_lamports   = stake;
+        s.seed_lamports  = 0;
+        s.commitment     = commitment;
+        s.commit_slot    = clock.slot;
+        s.resolve_slot   = get_resolve_slot(clock.slot, stake);
+        s.forfeit_slot   = clock.slot + REVEAL_WINDOW;
+        s.game_type      = 23;
+        s.game_state     = 0;
+        s.target_x       = 0;
+        s.target_y       = 0;
+        s.target_radius  = 0;
+        s.config_ext     = 0;
+        s.public_reveal  = false;
+        s.insured_amount = 0;
+        s.held_payout    = 0;
+        s.held_until     = 0;
+        s.won            = false;
+        s.paid_out       = 0;
+        s.jackpot_won    = 0;
+        s.receipt_index  = 0;
+        s.index_page     = NOT_INDEXED;
+        emit!(DoubleOrNothingPlaced { session: s.key(), player: s.player, stake, resolve_slot: s.resolve_slot, forfeit_slot: s.forfeit_slot });
+        Ok(())
+    }
 
-/// @notice Core payout calculator shared by all four games.
-/// @param bet              Wager in lamports.
-/// @param win_chance_num   Numerator of win probability fraction.
-/// @param win_chance_den   Denominator of win probability fraction.
-/// @param pool_balance     Selects the phase retention multiplier.
-#[inline(always)]
-fn calc_payout(bet: u64, win_chance_num: u64, win_chance_den: u64, pool_balance: u64) -> u64 {
-    if win_chance_num == 0 { return 0; }
-    ((bet as u128)
-        .saturating_mul(payout_num(pool_balance) as u128)
-        .saturating_mul(win_chance_den as u128)
-        / (win_chance_num as u128)
-        / 10_000) as u64
-}
+    // ── Reveal: parlay ────────────────────────────────────────────────────
 
-// ── Config validation ─────────────────────────────────────────────────────
+    /// @notice Reveals nonce and settles a parlay: legs resolve in order, each from its own
+    ///         sub-seed of the reveal seed, and the first lost leg loses the whole parlay.
+    ///         The ParlaySession is closed to the player. Player must sign.
+    pub fn reveal_parlay(ctx: Context<RevealParlay>, nonce: [u8; 32]) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let clock   = Clock::get()?;
+        let s       = &ctx.accounts.session;
+        let seed    = extract_and_validate_seed(s, &ctx.accounts.slot_hashes.to_account_info(), &clock, &nonce, 22)?;
+        let (won, payout, outcome) = resolve_parlay(&ctx.accounts.parlay, s.bet_lamports, &seed, s.payout_num_bps as u64, &s.payout_curve)?;
+        let dest    = payout_dest(&ctx.accounts.session, &ctx.accounts.player, &ctx.accounts.payout_wallet)?;
+        let partner = partner_of(&ctx.accounts.session, &mut ctx.accounts.partner, &ctx.accounts.player)?;
+        let cosign  = ctx.accounts.cosigner.is_some();
+        let plan    = settle(&mut ctx.accounts.pool, &dest, &ctx.accounts.referrer, &ctx.accounts.session, partner, ctx.accounts.referral.as_mut(), ctx.accounts.firehose.as_mut(), cosign, won, payout, seed)?;
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        record_settlement(&mut ctx.accounts.session, ctx.accounts.receipt_tree.as_deref_mut(), &ctx.accounts.player, won, &plan)?;
+        emit_outcome(ctx.accounts.session.player, won, payout, &outcome);
+        notify_settled(&ctx.accounts.notification_prefs, ctx.accounts.session.player, won, plan.jackpot_prize);
+        Ok(())
+    }
 
-fn validate_game_config(game_type: u8, cfg: &[u8; 3]) -> Result<()> {
-    match game_type {
-        0 => require!(*cfg == [0, 0, 0],              BlitzError::InvalidGameConfig),
-        1 => {
-            require!(cfg[0] < 16,                     BlitzError::InvalidCoordinate);
-            require!(cfg[1] < 16,                     BlitzError::InvalidCoordinate);
-            require!(cfg[2] <= 3,                     BlitzError::InvalidRadius);
+    // ── Mines: interactive board ───────────────────────────────────────────
+
+    /// @notice Reveals the commit nonce and opens the Mines board (game_state = 1).
+    /// @dev    The board seed is fixed here, but no tile is drawn from it alone: resolve_tile
+    ///         mixes in a slot hash that did not exist when the tile was picked, so knowing
+    ///         the seed never tells the player where the mines are.
+    pub fn start_mines(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        let clock = Clock::get()?;
+        let seed  = extract_and_validate_seed(&ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &clock, &nonce, 6)?;
+        let s     = &mut ctx.accounts.session;
+        s.game_state     = 1;
+        s.mines_seed     = seed;
+        s.mines_revealed = 0;
+        s.mines_pick     = MINES_NO_PICK;
+        s.forfeit_slot   = clock.slot + REVEAL_WINDOW;
+        emit!(MinesStarted { player: s.player, mines: s.target_x, forfeit_slot: s.forfeit_slot });
+        Ok(())
+    }
+
+    /// @notice Picks the next tile. It is drawn by resolve_tile once pick_slot + SLOT_SPREAD has a hash.
+    /// @dev    Refreshes forfeit_slot — a pick left unresolved forfeits like an unrevealed bet.
+    pub fn reveal_tile(ctx: Context<MinesAction>, tile: u8) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let s    = &mut ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 6, BlitzError::MinesNotActive);
+        require!(slot <= s.forfeit_slot,                BlitzError::RevealWindowExpired);
+        require!(s.mines_pick == MINES_NO_PICK,         BlitzError::MinesPickPending);
+        require!(tile < MINES_TILES,                    BlitzError::InvalidTile);
+        require!(s.mines_revealed & (1 << tile) == 0,   BlitzError::TileAlreadyRevealed);
+        let next = s.mines_revealed.count_ones() as u8 + 1;
+        require!(
+            mines_multiplier_bps(s.target_x, next, payout_num(PHASE2_THRESHOLD)) <= MINES_MAX_MULTIPLIER_BPS,
+            BlitzError::MinesCapReached
+        );
+        s.mines_pick      = tile;
+        s.mines_pick_slot = slot;
+        s.forfeit_slot    = slot + REVEAL_WINDOW;
+        let claim = scale_payout(s.bet_lamports, mines_multiplier_bps(s.target_x, next, s.payout_num_bps as u64));
+        reserve_step(&mut ctx.accounts.pool, s, claim)?;
+        emit!(MinesTilePicked { player: s.player, tile, pick_slot: slot });
+        Ok(())
+    }
+
+    /// @notice Draws the picked tile. A mine settles the bet as lost; clearing the last
+    ///         safe tile cashes out automatically.
+    pub fn resolve_tile(ctx: Context<RevealGame>) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let slot = Clock::get()?.slot;
+        let s    = &ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 6,  BlitzError::MinesNotActive);
+        require!(s.mines_pick != MINES_NO_PICK,          BlitzError::NoMinesPick);
+        require!(slot > s.mines_pick_slot + SLOT_SPREAD, BlitzError::TooEarlyToReveal);
+        require!(slot <= s.forfeit_slot,                 BlitzError::RevealWindowExpired);
+        let entropy = slot_hash_from(&ctx.accounts.slot_hashes.to_account_info(), s.mines_pick_slot + SLOT_SPREAD)?;
+        let tile    = s.mines_pick;
+        let safe    = s.mines_revealed.count_ones() as u8;
+        if mines_draw(&s.mines_seed, tile, safe, s.target_x, &entropy) {
+            return finish_mines(ctx.accounts, false, tile);
         }
-        2 => {
-            require!(cfg[1] <= 1,                     BlitzError::InvalidGameConfig);
-            if cfg[1] == 0 {
-                require!(cfg[0] >= 2 && cfg[0] <= 95, BlitzError::InvalidDiceTarget);
-            } else {
-                require!(cfg[0] >= 4 && cfg[0] <= 97, BlitzError::InvalidDiceTarget);
+        let s = &mut ctx.accounts.session;
+        s.mines_revealed |= 1 << tile;
+        s.mines_pick      = MINES_NO_PICK;
+        let multiplier_bps = mines_multiplier_bps(s.target_x, safe + 1, s.payout_num_bps as u64);
+        emit!(MinesTileRevealed { player: s.player, tile, safe_tiles: safe + 1, multiplier_bps });
+        if safe + 1 == MINES_TILES - s.target_x {
+            return finish_mines(ctx.accounts, true, MINES_NO_PICK);
+        }
+        Ok(())
+    }
+
+    /// @notice Locks the current multiplier and settles. Not allowed while a pick is pending.
+    pub fn cash_out_mines(ctx: Context<RevealGame>) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let s = &ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 6, BlitzError::MinesNotActive);
+        require!(s.mines_pick == MINES_NO_PICK,         BlitzError::MinesPickPending);
+        require!(s.mines_revealed != 0,                 BlitzError::MinesNothingToCashOut);
+        finish_mines(ctx.accounts, true, MINES_NO_PICK)
+    }
+
+    // ── HiLo: chained guesses ──────────────────────────────────────────────
+
+    /// @notice Reveals the commit nonce and deals the starting card (game_state = 1).
+    /// @dev    Like Mines, later cards mix a slot hash from after each guess into the seed,
+    ///         so the seed alone never tells the player the next card.
+    pub fn start_hilo(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        let clock = Clock::get()?;
+        let seed  = extract_and_validate_seed(&ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &clock, &nonce, 12)?;
+        let s     = &mut ctx.accounts.session;
+        s.game_state    = 1;
+        s.mines_seed    = seed;
+        s.hilo_card     = (u64::from_le_bytes(seed[0..8].try_into().unwrap()) % HILO_RANKS as u64) as u8 + 1;
+        s.hilo_guess    = HILO_NO_GUESS;
+        s.hilo_rounds   = 0;
+        s.hilo_odds_den = 1;
+        s.forfeit_slot  = clock.slot + REVEAL_WINDOW;
+        emit!(HiloStarted { player: s.player, card: s.hilo_card, forfeit_slot: s.forfeit_slot });
+        Ok(())
+    }
+
+    /// @notice Guesses whether the next card is higher (HILO_HIGHER) or lower (HILO_LOWER) than
+    ///         the current one; ties win. Drawn by resolve_hilo once pick_slot + SLOT_SPREAD has a hash.
+    /// @dev    A guess that cannot lose (lower on a king, higher on an ace) is refused.
+    ///         Refreshes forfeit_slot — a guess left unresolved forfeits like an unrevealed bet.
+    pub fn hilo_guess(ctx: Context<MinesAction>, guess: u8) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let s    = &mut ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 12, BlitzError::HiloNotActive);
+        require!(slot <= s.forfeit_slot,                 BlitzError::RevealWindowExpired);
+        require!(s.hilo_guess == HILO_NO_GUESS,          BlitzError::HiloGuessPending);
+        let odds = hilo_odds(s.hilo_card, guess).ok_or(BlitzError::InvalidHiloGuess)?;
+        require!(
+            hilo_multiplier_bps(s.hilo_odds_den * odds, s.hilo_rounds + 1, payout_num(PHASE2_THRESHOLD)) <= HILO_MAX_MULTIPLIER_BPS,
+            BlitzError::HiloCapReached
+        );
+        s.hilo_guess      = guess;
+        s.mines_pick_slot = slot;
+        s.forfeit_slot    = slot + REVEAL_WINDOW;
+        let claim = scale_payout(s.bet_lamports, hilo_multiplier_bps(s.hilo_odds_den * odds, s.hilo_rounds + 1, s.payout_num_bps as u64));
+        reserve_step(&mut ctx.accounts.pool, s, claim)?;
+        emit!(HiloGuessed { player: s.player, card: s.hilo_card, guess, pick_slot: slot });
+        Ok(())
+    }
+
+    /// @notice Draws the next card. A wrong guess settles the bet as lost; reaching
+    ///         HILO_MAX_ROUNDS cashes out automatically.
+    pub fn resolve_hilo(ctx: Context<RevealGame>) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let slot = Clock::get()?.slot;
+        let s    = &ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 12, BlitzError::HiloNotActive);
+        require!(s.hilo_guess != HILO_NO_GUESS,          BlitzError::NoHiloGuess);
+        require!(slot > s.mines_pick_slot + SLOT_SPREAD, BlitzError::TooEarlyToReveal);
+        require!(slot <= s.forfeit_slot,                 BlitzError::RevealWindowExpired);
+        let entropy = slot_hash_from(&ctx.accounts.slot_hashes.to_account_info(), s.mines_pick_slot + SLOT_SPREAD)?;
+        let next    = hilo_draw(&s.mines_seed, s.hilo_rounds, &entropy);
+        let current = s.hilo_card;
+        let won     = if s.hilo_guess == HILO_HIGHER { next >= current } else { next <= current };
+        let odds    = hilo_odds(current, s.hilo_guess).unwrap_or(HILO_RANKS as u64);
+        let s = &mut ctx.accounts.session;
+        s.hilo_card  = next;
+        s.hilo_guess = HILO_NO_GUESS;
+        if !won {
+            return finish_hilo(ctx.accounts, false);
+        }
+        s.hilo_rounds  += 1;
+        s.hilo_odds_den = s.hilo_odds_den.saturating_mul(odds);
+        let multiplier_bps = hilo_multiplier_bps(s.hilo_odds_den, s.hilo_rounds, s.payout_num_bps as u64);
+        emit!(HiloCardDrawn { player: s.player, card: next, rounds: s.hilo_rounds, multiplier_bps });
+        if s.hilo_rounds == HILO_MAX_ROUNDS {
+            return finish_hilo(ctx.accounts, true);
+        }
+        Ok(())
+    }
+
+    /// @notice Locks the compounded multiplier and settles. Not allowed while a guess is pending.
+    pub fn hilo_cashout(ctx: Context<RevealGame>) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let s = &ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 12, BlitzError::HiloNotActive);
+        require!(s.hilo_guess == HILO_NO_GUESS,          BlitzError::HiloGuessPending);
+        require!(s.hilo_rounds > 0,                      BlitzError::HiloNothingToCashOut);
+        finish_hilo(ctx.accounts, true)
+    }
+
+    // ── Stairs: step-by-step climb ─────────────────────────────────────────
+
+    /// @notice Reveals the commit nonce and puts the player on the ground floor (game_state = 1).
+    /// @dev    As with Mines and HiLo, each step mixes in a slot hash from after it was taken,
+    ///         so the seed alone never tells the player which step gives way.
+    pub fn start_stairs(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        let clock = Clock::get()?;
+        let seed  = extract_and_validate_seed(&ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &clock, &nonce, 19)?;
+        let s     = &mut ctx.accounts.session;
+        s.game_state     = 1;
+        s.mines_seed     = seed;
+        s.stairs_step    = 0;
+        s.stairs_pending = false;
+        s.forfeit_slot   = clock.slot + REVEAL_WINDOW;
+        emit!(StairsStarted { player: s.player, forfeit_slot: s.forfeit_slot });
+        Ok(())
+    }
+
+    /// @notice Takes the next step. It is drawn by resolve_step once pick_slot + SLOT_SPREAD has a hash.
+    /// @dev    Refreshes forfeit_slot — a step left unresolved forfeits like an unrevealed bet.
+    pub fn advance_step(ctx: Context<MinesAction>) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let s    = &mut ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 19, BlitzError::StairsNotActive);
+        require!(slot <= s.forfeit_slot,                 BlitzError::RevealWindowExpired);
+        require!(!s.stairs_pending,                      BlitzError::StairsStepPending);
+        s.stairs_pending  = true;
+        s.mines_pick_slot = slot;
+        s.forfeit_slot    = slot + REVEAL_WINDOW;
+        let claim = scale_payout(s.bet_lamports, stairs_multiplier_bps(s.stairs_step + 1, s.payout_num_bps as u64));
+        reserve_step(&mut ctx.accounts.pool, s, claim)?;
+        emit!(StairsStepTaken { player: s.player, step: s.stairs_step + 1, pick_slot: slot });
+        Ok(())
+    }
+
+    /// @notice Draws the pending step. A fall settles the bet as lost; clearing
+    ///         STAIRS_STEPS cashes out automatically.
+    pub fn resolve_step(ctx: Context<RevealGame>) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let slot = Clock::get()?.slot;
+        let s    = &ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 19, BlitzError::StairsNotActive);
+        require!(s.stairs_pending,                       BlitzError::NoStairsStep);
+        require!(slot > s.mines_pick_slot + SLOT_SPREAD, BlitzError::TooEarlyToReveal);
+        require!(slot <= s.forfeit_slot,                 BlitzError::RevealWindowExpired);
+        let entropy = slot_hash_from(&ctx.accounts.slot_hashes.to_account_info(), s.mines_pick_slot + SLOT_SPREAD)?;
+        let step    = s.stairs_step + 1;
+        if stairs_draw(&s.mines_seed, step, &entropy) {
+            return finish_stairs(ctx.accounts, false);
+        }
+        let s = &mut ctx.accounts.session;
+        s.stairs_step    = step;
+        s.stairs_pending = false;
+        let multiplier_bps = stairs_multiplier_bps(step, s.payout_num_bps as u64);
+        emit!(StairsStepCleared { player: s.player, step, multiplier_bps });
+        if step == STAIRS_STEPS {
+            return finish_stairs(ctx.accounts, true);
+        }
+        Ok(())
+    }
+
+    /// @notice Locks the multiplier of the steps cleared so far and settles. Not allowed while
+    ///         a step is pending.
+    pub fn cash_out_stairs(ctx: Context<RevealGame>) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let s = &ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 19, BlitzError::StairsNotActive);
+        require!(!s.stairs_pending,                      BlitzError::StairsStepPending);
+        require!(s.stairs_step > 0,                      BlitzError::StairsNothingToCashOut);
+        finish_stairs(ctx.accounts, true)
+    }
+
+    // ── Tower Climb: floor-by-floor Tower ─────────────────────────────────
+
+    /// @notice Reveals the commit nonce and opens the climb at the ground floor (game_state = 1).
+    /// @dev    Like Stairs, each door is drawn with a slot hash from after it was picked, so the
+    ///         revealed seed never tells the player where the trap is.
+    pub fn start_tower_climb(ctx: Context<RevealGame>, nonce: [u8; 32]) -> Result<()> {
+        let clock = Clock::get()?;
+        let seed  = extract_and_validate_seed(&ctx.accounts.session, &ctx.accounts.slot_hashes.to_account_info(), &clock, &nonce, 21)?;
+        let s     = &mut ctx.accounts.session;
+        s.game_state           = 1;
+        s.mines_seed           = seed;
+        s.mines_pick           = MINES_NO_PICK;
+        s.tower_floor          = 0;
+        s.tower_multiplier_bps = 0;
+        s.forfeit_slot         = clock.slot + REVEAL_WINDOW;
+        emit!(TowerClimbStarted { player: s.player, floors: s.target_x, doors: tower_doors(&[s.target_x, s.target_y, s.target_radius]), forfeit_slot: s.forfeit_slot });
+        Ok(())
+    }
+
+    /// @notice Picks a door on the next floor. It is drawn by resolve_tower_floor once
+    ///         pick_slot + SLOT_SPREAD has a hash.
+    /// @dev    Refreshes forfeit_slot — a pick left unresolved forfeits like an unrevealed bet.
+    pub fn tower_advance(ctx: Context<MinesAction>, door: u8) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let s    = &mut ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 21, BlitzError::TowerClimbNotActive);
+        require!(slot <= s.forfeit_slot,                 BlitzError::RevealWindowExpired);
+        require!(s.mines_pick == MINES_NO_PICK,          BlitzError::TowerDoorPending);
+        require!(door < tower_doors(&[s.target_x, s.target_y, s.target_radius]), BlitzError::InvalidTowerDoor);
+        s.mines_pick      = door;
+        s.mines_pick_slot = slot;
+        s.forfeit_slot    = slot + REVEAL_WINDOW;
+        let doors = tower_doors(&[s.target_x, s.target_y, s.target_radius]);
+        let claim = scale_payout(s.bet_lamports, tower_climb_multiplier_bps(s.tower_floor + 1, doors, s.payout_num_bps as u64));
+        reserve_step(&mut ctx.accounts.pool, s, claim)?;
+        emit!(TowerDoorPicked { player: s.player, floor: s.tower_floor + 1, door, pick_slot: slot });
+        Ok(())
+    }
+
+    /// @notice Draws the trap on the pending floor. Hitting it settles the bet as lost;
+    ///         clearing the top floor cashes out automatically.
+    pub fn resolve_tower_floor(ctx: Context<RevealGame>) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let slot = Clock::get()?.slot;
+        let s    = &ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 21, BlitzError::TowerClimbNotActive);
+        require!(s.mines_pick != MINES_NO_PICK,          BlitzError::NoTowerDoor);
+        require!(slot > s.mines_pick_slot + SLOT_SPREAD, BlitzError::TooEarlyToReveal);
+        require!(slot <= s.forfeit_slot,                 BlitzError::RevealWindowExpired);
+        let entropy = slot_hash_from(&ctx.accounts.slot_hashes.to_account_info(), s.mines_pick_slot + SLOT_SPREAD)?;
+        let cfg     = [s.target_x, s.target_y, s.target_radius];
+        let (floor, doors) = (s.tower_floor + 1, tower_doors(&cfg));
+        let trap    = tower_climb_trap(&s.mines_seed, floor, doors, &entropy);
+        let door    = s.mines_pick;
+        emit!(TowerFloorResolved { player: s.player, floor, door, trap });
+        if door == trap {
+            return finish_tower_climb(ctx.accounts, false);
+        }
+        let s = &mut ctx.accounts.session;
+        s.tower_floor          = floor;
+        s.tower_multiplier_bps = tower_climb_multiplier_bps(floor, doors, s.payout_num_bps as u64);
+        s.mines_pick           = MINES_NO_PICK;
+        if floor == cfg[0] {
+            return finish_tower_climb(ctx.accounts, true);
+        }
+        Ok(())
+    }
+
+    /// @notice Settles at the accumulated multiplier of the floors cleared so far. Not allowed
+    ///         while a door is pending.
+    pub fn tower_cashout(ctx: Context<RevealGame>) -> Result<()> {
+        if already_settled(&ctx.accounts.session)? { return Ok(()); }
+        let s = &ctx.accounts.session;
+        require!(s.game_state == 1 && s.game_type == 21, BlitzError::TowerClimbNotActive);
+        require!(s.mines_pick == MINES_NO_PICK,          BlitzError::TowerDoorPending);
+        require!(s.tower_floor > 0,                      BlitzError::TowerNothingToCashOut);
+        finish_tower_climb(ctx.accounts, true)
+    }
+
+    // ── Forfeit / Refund paths ─────────────────────────────────────────────
+
+    /// @notice The one cleanup path for a bet that will not be revealed. Inspects the session and
+    ///         pool, applies the policy terminal_policy picks — full forfeiture or a partial
+    ///         refund — closes the session and emits TerminalStateResolved with the reason.
+    /// @dev    Forfeiture keeps the entire stake in the pool, with no bounty, so bots have no
+    ///         griefing incentive. Session rent goes back to the player unless a forfeiture
+    ///         finds `forfeit_rent_to_pool` set.
+    pub fn resolve_terminal_state(ctx: Context<ResolveTerminal>) -> Result<()> {
+        let clock = Clock::get()?;
+        let s     = &ctx.accounts.session;
+        let cfg   = [s.target_x, s.target_y, s.target_radius];
+        let insolvent = ctx.accounts.pool.total_balance < get_worst_payout(s.bet_lamports, s.game_type, &cfg, s.config_ext);
+        let (reason, pct) = terminal_policy(s, ctx.accounts.caller.key() == s.player, insolvent, clock.slot)
+            .ok_or(BlitzError::TerminalStateNotReached)?;
+        require!(s.game_type != 22 || ctx.accounts.parlay.is_some(), BlitzError::ParlayRequired);
+        let (player, amount, s_game, fees) = (s.player, s.bet_lamports, s.game_type, s.fee_bps);
+        let refund = amount.saturating_mul(pct) / 100;
+        if let Some(r) = ctx.accounts.referral.as_mut() {
+            referral_roll(&mut ctx.accounts.pool, r, clock.epoch);
+        }
+        accrue_net_loss(&ctx.accounts.pool, ctx.accounts.referral.as_mut(), &ctx.accounts.session, refund)?;
+
+        release_promo(&mut ctx.accounts.pool, &ctx.accounts.session);
+        release_step(&mut ctx.accounts.pool, &ctx.accounts.session);
+        ctx.accounts.pool.open_stakes = ctx.accounts.pool.open_stakes.saturating_sub(amount);
+        unindex_session(&mut ctx.accounts.open_sessions, &ctx.accounts.session)?;
+        ctx.accounts.session.game_state = 2;
+        let rent_dest = if pct == 0 && ctx.accounts.pool.forfeit_rent_to_pool {
+            ctx.accounts.pool.to_account_info()
+        } else {
+            ctx.accounts.player.to_account_info()
+        };
+        ctx.accounts.session.close(rent_dest)?;
+        if let Some(p) = ctx.accounts.parlay.as_ref() {
+            p.close(ctx.accounts.player.to_account_info())?;
+        }
+        if pct > 0 {
+            ctx.accounts.pool.record_game_outflow(s_game, refund);
+            send_refund(&mut ctx.accounts.pool, &ctx.accounts.player, amount, pct, fees)?;
+        } else {
+            accrue_terminal_fees(&mut ctx.accounts.pool, amount, amount, fees)?;
+            let pool_ai = ctx.accounts.pool.to_account_info();
+            sync_balance(&mut ctx.accounts.pool, &pool_ai)?;
+        }
+        emit!(TerminalStateResolved { session: ctx.accounts.session.key(), player, reason, refund, kept: amount - refund });
+        emit!(BetForfeited { player, amount: amount - refund });
+        notify(&ctx.accounts.notification_prefs, player, NOTIFY_FORFEIT);
+        Ok(())
+    }
+
+    // ── Tower Race (PvP) ───────────────────────────────────────────────────
+
+    /// @notice Opens a head-to-head Tower race. Creator's stake and RACE_BOND are escrowed in the race PDA.
+    /// @dev    Paths are public — both racers share one seed, so seeing the rival path gives no edge.
+    ///         The bond rides along with the rent: it only leaves for the pool on an early cancel.
+    /// @param commitment  SHA-256 of the creator's secret nonce
+    /// @param stake       Lamports each racer puts up
+    /// @param floors      1–6, shared by both racers
+    /// @param path        Creator's door bitmask (bit i = door on floor i)
+    pub fn create_tower_race(
+        ctx:        Context<CreateTowerRace>,
+        commitment: [u8; 32],
+        stake:      u64,
+        floors:     u8,
+        path:       u8,
+    ) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        require!(!ctx.accounts.pool.paused,  BlitzError::ContractPaused);
+        require!(stake >= RACE_MIN_STAKE,    BlitzError::BetTooSmall);
+        validate_game_config(3, &[floors, path, 0], 0)?;
+        let r                = &mut ctx.accounts.race;
+        r.creator            = ctx.accounts.creator.key();
+        r.opponent           = Pubkey::default();
+        r.stake              = stake;
+        r.floors             = floors;
+        r.creator_path       = path;
+        r.creator_commitment = commitment;
+        r.created_slot       = Clock::get()?.slot;
+        r.state              = 0;
+        r.bond               = RACE_BOND;
+        r.bump               = ctx.bumps.race;
+        invoke(
+            &system_instruction::transfer(&r.creator, &r.key(), checked_add(stake, RACE_BOND)?),
+            &[ctx.accounts.creator.to_account_info(), r.to_account_info()],
+        )?;
+        emit!(TowerRaceCreated { race: r.key(), creator: r.creator, stake, floors });
+        Ok(())
+    }
+
+    /// @notice Accepts an open race with an equal stake. Starts the shared resolve clock.
+    pub fn join_tower_race(ctx: Context<JoinTowerRace>, commitment: [u8; 32], path: u8) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        require!(!ctx.accounts.pool.paused, BlitzError::ContractPaused);
+        let clock = Clock::get()?;
+        let r     = &mut ctx.accounts.race;
+        let me    = ctx.accounts.opponent.key();
+        require!(r.state == 0,    BlitzError::RaceNotOpen);
+        require!(me != r.creator, BlitzError::CannotJoinOwnRace);
+        validate_game_config(3, &[r.floors, path, 0], 0)?;
+        r.opponent            = me;
+        r.opponent_path       = path;
+        r.opponent_commitment = commitment;
+        r.resolve_slot        = get_resolve_slot(clock.slot, r.stake.saturating_mul(2));
+        r.forfeit_slot        = clock.slot + REVEAL_WINDOW;
+        r.state               = 1;
+        invoke(
+            &system_instruction::transfer(&me, &r.key(), r.stake),
+            &[ctx.accounts.opponent.to_account_info(), r.to_account_info()],
+        )?;
+        emit!(TowerRaceJoined { race: r.key(), opponent: me, resolve_slot: r.resolve_slot });
+        Ok(())
+    }
+
+    /// @notice Creator withdraws an unaccepted race — stake and rent returned in full. The bond
+    ///         is returned too once the race has been open RACE_BOND_MIN_OPEN_SLOTS, else it
+    ///         goes to house fees.
+    pub fn cancel_tower_race(ctx: Context<CancelTowerRace>) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let r = &ctx.accounts.race;
+        require!(r.state == 0, BlitzError::RaceNotOpen);
+        let forfeited = if Clock::get()?.slot < r.created_slot + RACE_BOND_MIN_OPEN_SLOTS { r.bond } else { 0 };
+        if forfeited > 0 {
+            let pool = &mut ctx.accounts.pool;
+            let pool_ai = pool.to_account_info();
+            move_lamports(&ctx.accounts.race.to_account_info(), &pool_ai, forfeited)?;
+            pool.house_fees_earned = checked_add(pool.house_fees_earned, forfeited)?;
+            sync_balance(pool, &pool_ai)?;
+        }
+        emit!(TowerRaceCancelled { race: ctx.accounts.race.key(), bond_forfeited: forfeited });
+        Ok(())
+    }
+
+    /// @notice Each racer reveals their nonce. The second reveal resolves the race.
+    /// @dev    Seed = build_seed(SHA-256(creator_nonce || opponent_nonce)) — neither side controls it alone.
+    ///         Most floors survived wins; ties broken by seed byte 31 (even → creator).
+    ///         Withholding the second reveal is pointless: the revealed side claims the pot on timeout.
+    pub fn reveal_tower_race(ctx: Context<TowerRaceAction>, nonce: [u8; 32]) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let clock  = Clock::get()?;
+        let signer = ctx.accounts.signer.key();
+        let r      = &mut ctx.accounts.race;
+        require!(r.state == 1,                                  BlitzError::RaceNotActive);
+        require!(clock.slot > r.resolve_slot + SLOT_SPREAD * 2, BlitzError::TooEarlyToReveal);
+        require!(clock.slot <= r.forfeit_slot,                  BlitzError::RevealWindowExpired);
+        let side = if signer == r.creator { 1u8 } else if signer == r.opponent { 2u8 } else {
+            return Err(BlitzError::NotRaceParticipant.into());
+        };
+        require!(r.revealed & side == 0, BlitzError::AlreadyRevealed);
+        let commitment = if side == 1 { r.creator_commitment } else { r.opponent_commitment };
+        require!(hash::hash(&nonce).to_bytes() == commitment, BlitzError::InvalidNonce);
+        if side == 1 { r.creator_nonce = nonce; } else { r.opponent_nonce = nonce; }
+        r.revealed |= side;
+        if r.revealed != 3 { return Ok(()); }
+
+        require!(clock.slot.saturating_sub(r.resolve_slot) < 490, BlitzError::SlotTooOld);
+        let mixed = hash::hashv(&[&r.creator_nonce, &r.opponent_nonce]).to_bytes();
+        let pot   = r.stake.saturating_mul(2);
+        let seed  = build_seed(&ctx.accounts.slot_hashes.to_account_info(), r.resolve_slot, &mixed, None, pot)?;
+        let (c_death, traps) = tower_walk(&seed, r.floors, r.creator_path, TOWER_MIN_DOORS);
+        let (o_death, _)     = tower_walk(&seed, r.floors, r.opponent_path, TOWER_MIN_DOORS);
+        let survived         = |d: u8, f: u8| if d == 0 { f } else { d - 1 };
+        let (c_floors, o_floors) = (survived(c_death, r.floors), survived(o_death, r.floors));
+        let creator_wins     = c_floors > o_floors || (c_floors == o_floors && seed[31] % 2 == 0);
+        r.state = 2;
+
+        let race_key = r.key();
+        let winner   = if creator_wins { ctx.accounts.creator.to_account_info() } else { ctx.accounts.opponent.to_account_info() };
+        let race_ai  = ctx.accounts.race.to_account_info();
+        let prize    = pay_race(&mut ctx.accounts.pool, &race_ai, &winner, pot)?;
+        ctx.accounts.race.close(ctx.accounts.creator.to_account_info())?;
+        emit!(TowerRaceSettled { race: race_key, winner: winner.key(), creator_floors: c_floors, opponent_floors: o_floors, traps, payout: prize });
+        Ok(())
+    }
+
+    /// @notice Resolves a race once the reveal window has closed.
+    /// @dev    Exactly one side revealed → that side takes the pot minus rake.
+    ///         Neither revealed → both stakes refunded, no rake.
+    pub fn claim_tower_race_timeout(ctx: Context<TowerRaceAction>) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let r = &ctx.accounts.race;
+        require!(r.state == 1,                           BlitzError::RaceNotActive);
+        require!(Clock::get()?.slot > r.forfeit_slot,    BlitzError::ForfeitNotAvailable);
+        let (race_key, stake, revealed) = (r.key(), r.stake, r.revealed);
+        let race_ai = ctx.accounts.race.to_account_info();
+        let winner  = match revealed {
+            1 => Some(ctx.accounts.creator.to_account_info()),
+            2 => Some(ctx.accounts.opponent.to_account_info()),
+            _ => None,
+        };
+        let payout = match &winner {
+            Some(w) => pay_race(&mut ctx.accounts.pool, &race_ai, w, stake.saturating_mul(2))?,
+            None    => {
+                move_lamports(&race_ai, &ctx.accounts.opponent.to_account_info(), stake)?;
+                0 // creator's stake returns with the rent on close
             }
+        };
+        ctx.accounts.race.state = 2;
+        ctx.accounts.race.close(ctx.accounts.creator.to_account_info())?;
+        emit!(TowerRaceTimedOut { race: race_key, winner: winner.map(|w| w.key()).unwrap_or_default(), payout });
+        Ok(())
+    }
+
+    // ── Rock-Paper-Scissors (PvP) ──────────────────────────────────────────
+
+    /// @notice Opens a Rock-Paper-Scissors match. Creator's stake and RACE_BOND are escrowed in the match PDA.
+    /// @param commitment  rps_commitment(creator, move, salt) of the creator's hidden move
+    /// @param stake       Lamports each player puts up
+    /// @dev    The bond follows the Tower race rules: forfeited on a cancel before RACE_BOND_MIN_OPEN_SLOTS.
+    pub fn create_rps_match(ctx: Context<CreateRpsMatch>, commitment: [u8; 32], stake: u64) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        require!(!ctx.accounts.pool.paused, BlitzError::ContractPaused);
+        require!(stake >= RACE_MIN_STAKE,   BlitzError::BetTooSmall);
+        let m                = &mut ctx.accounts.rps_match;
+        m.creator            = ctx.accounts.creator.key();
+        m.opponent           = Pubkey::default();
+        m.stake              = stake;
+        m.creator_commitment = commitment;
+        m.creator_move       = RPS_UNREVEALED;
+        m.opponent_move      = RPS_UNREVEALED;
+        m.created_slot       = Clock::get()?.slot;
+        m.state              = 0;
+        m.bond               = RACE_BOND;
+        m.bump               = ctx.bumps.rps_match;
+        invoke(
+            &system_instruction::transfer(&m.creator, &m.key(), checked_add(stake, RACE_BOND)?),
+            &[ctx.accounts.creator.to_account_info(), m.to_account_info()],
+        )?;
+        emit!(RpsMatchCreated { rps_match: m.key(), creator: m.creator, stake });
+        Ok(())
+    }
+
+    /// @notice Accepts an open match with an equal stake and a committed move. Starts the reveal window.
+    /// @dev    The commitment is bound to the committer's key, so copying the creator's
+    ///         commitment can never be revealed by the opponent.
+    pub fn join_rps_match(ctx: Context<JoinRpsMatch>, commitment: [u8; 32]) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        require!(!ctx.accounts.pool.paused, BlitzError::ContractPaused);
+        let m  = &mut ctx.accounts.rps_match;
+        let me = ctx.accounts.opponent.key();
+        require!(m.state == 0,    BlitzError::RaceNotOpen);
+        require!(me != m.creator, BlitzError::CannotJoinOwnRace);
+        m.opponent            = me;
+        m.opponent_commitment = commitment;
+        m.forfeit_slot        = Clock::get()?.slot + REVEAL_WINDOW;
+        m.state               = 1;
+        invoke(
+            &system_instruction::transfer(&me, &m.key(), m.stake),
+            &[ctx.accounts.opponent.to_account_info(), m.to_account_info()],
+        )?;
+        emit!(RpsMatchJoined { rps_match: m.key(), opponent: me, forfeit_slot: m.forfeit_slot });
+        Ok(())
+    }
+
+    /// @notice Creator withdraws an unaccepted match — same refund and bond rules as cancel_tower_race.
+    pub fn cancel_rps_match(ctx: Context<CancelRpsMatch>) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let m = &ctx.accounts.rps_match;
+        require!(m.state == 0, BlitzError::RaceNotOpen);
+        let forfeited = if Clock::get()?.slot < m.created_slot + RACE_BOND_MIN_OPEN_SLOTS { m.bond } else { 0 };
+        if forfeited > 0 {
+            let pool    = &mut ctx.accounts.pool;
+            let pool_ai = pool.to_account_info();
+            move_lamports(&ctx.accounts.rps_match.to_account_info(), &pool_ai, forfeited)?;
+            pool.house_fees_earned = checked_add(pool.house_fees_earned, forfeited)?;
+            sync_balance(pool, &pool_ai)?;
         }
-        3 => {
-            require!(cfg[0] >= 1 && cfg[0] <= 6,     BlitzError::InvalidTowerFloors);
-            require!(cfg[1] & !((1u8 << cfg[0]).wrapping_sub(1)) == 0, BlitzError::InvalidGameConfig);
+        emit!(RpsMatchCancelled { rps_match: ctx.accounts.rps_match.key(), bond_forfeited: forfeited });
+        Ok(())
+    }
+
+    /// @notice Each player reveals their move and salt. The second reveal settles the match.
+    /// @dev    Winner takes the pot minus RACE_RAKE_BPS. A tie refunds both stakes with no rake.
+    ///         A commitment to an invalid move cannot be revealed, so it loses on timeout; so
+    ///         does withholding a losing reveal.
+    pub fn reveal_rps(ctx: Context<RpsAction>, mv: u8, salt: [u8; 32]) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let signer = ctx.accounts.signer.key();
+        let m      = &mut ctx.accounts.rps_match;
+        require!(m.state == 1,                         BlitzError::RaceNotActive);
+        require!(Clock::get()?.slot <= m.forfeit_slot, BlitzError::RevealWindowExpired);
+        require!(mv <= RPS_SCISSORS,                   BlitzError::InvalidRpsMove);
+        let side = if signer == m.creator { 1u8 } else if signer == m.opponent { 2u8 } else {
+            return Err(BlitzError::NotRaceParticipant.into());
+        };
+        require!(m.revealed & side == 0, BlitzError::AlreadyRevealed);
+        let commitment = if side == 1 { m.creator_commitment } else { m.opponent_commitment };
+        require!(rps_commitment(&signer, mv, &salt) == commitment, BlitzError::InvalidNonce);
+        if side == 1 { m.creator_move = mv; } else { m.opponent_move = mv; }
+        m.revealed |= side;
+        if m.revealed != 3 { return Ok(()); }
+
+        let (c_move, o_move, stake) = (m.creator_move, m.opponent_move, m.stake);
+        m.state = 2;
+        let match_key = m.key();
+        let match_ai  = ctx.accounts.rps_match.to_account_info();
+        let winner    = match (3 + c_move - o_move) % 3 {
+            1 => Some(ctx.accounts.creator.to_account_info()),
+            2 => Some(ctx.accounts.opponent.to_account_info()),
+            _ => None,
+        };
+        let payout = match &winner {
+            Some(w) => pay_race(&mut ctx.accounts.pool, &match_ai, w, stake.saturating_mul(2))?,
+            None    => {
+                move_lamports(&match_ai, &ctx.accounts.opponent.to_account_info(), stake)?;
+                0 // creator's stake returns with the rent on close
+            }
+        };
+        ctx.accounts.rps_match.close(ctx.accounts.creator.to_account_info())?;
+        emit!(RpsMatchSettled { rps_match: match_key, winner: winner.map(|w| w.key()).unwrap_or_default(), creator_move: c_move, opponent_move: o_move, payout });
+        Ok(())
+    }
+
+    /// @notice Settles a match once the reveal window has closed — same rules as claim_tower_race_timeout.
+    /// @dev    Exactly one side revealed → that side takes the pot minus rake.
+    ///         Neither revealed → both stakes refunded, no rake.
+    pub fn claim_rps_timeout(ctx: Context<RpsAction>) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let m = &ctx.accounts.rps_match;
+        require!(m.state == 1,                        BlitzError::RaceNotActive);
+        require!(Clock::get()?.slot > m.forfeit_slot, BlitzError::ForfeitNotAvailable);
+        let (match_key, stake, revealed) = (m.key(), m.stake, m.revealed);
+        let match_ai = ctx.accounts.rps_match.to_account_info();
+        let winner   = match revealed {
+            1 => Some(ctx.accounts.creator.to_account_info()),
+            2 => Some(ctx.accounts.opponent.to_account_info()),
+            _ => None,
+        };
+        let payout = match &winner {
+            Some(w) => pay_race(&mut ctx.accounts.pool, &match_ai, w, stake.saturating_mul(2))?,
+            None    => {
+                move_lamports(&match_ai, &ctx.accounts.opponent.to_account_info(), stake)?;
+                0
+            }
+        };
+        ctx.accounts.rps_match.state = 2;
+        ctx.accounts.rps_match.close(ctx.accounts.creator.to_account_info())?;
+        emit!(RpsMatchTimedOut { rps_match: match_key, winner: winner.map(|w| w.key()).unwrap_or_default(), payout });
+        Ok(())
+    }
+
+    // ── Lottery ────────────────────────────────────────────────────────────
+
+    /// @notice Opens lottery round `round`. Tickets sell until `draw_slot`.
+    /// @param round        Round index. PDA [b"lottery_round", pool, round]
+    /// @param ticket_price Lamports per ticket, at least LOTTERY_MIN_TICKET
+    /// @param draw_slot    First slot at which the round can be drawn
+    /// @dev    Unclaimed prizes of round − 1 move in through rollover_lottery.
+    pub fn open_lottery_round(ctx: Context<OpenLotteryRound>, round: u64, ticket_price: u64, draw_slot: u64) -> Result<()> {
+        require!(ticket_price >= LOTTERY_MIN_TICKET,           BlitzError::BetTooSmall);
+        require!(draw_slot > Clock::get()?.slot + SLOT_SPREAD, BlitzError::InvalidLotteryRound);
+        let r          = &mut ctx.accounts.lottery_round;
+        r.pool         = ctx.accounts.pool.key();
+        r.round        = round;
+        r.ticket_price = ticket_price;
+        r.draw_slot    = draw_slot;
+        r.cutoff_slot  = draw_slot;
+        r.tickets      = 0;
+        r.entropy      = hash::hashv(&[&r.pool.to_bytes(), &round.to_le_bytes()]).to_bytes();
+        r.pot          = 0;
+        r.state        = LOTTERY_OPEN;
+        r.winners      = [u32::MAX; LOTTERY_MAX_WINNERS];
+        r.prizes       = [0; LOTTERY_MAX_WINNERS];
+        r.bump         = ctx.bumps.lottery_round;
+        emit!(LotteryRoundOpened { lottery_round: r.key(), round, ticket_price, draw_slot });
+        Ok(())
+    }
+
+    /// @notice Buys the next numbered ticket. `entropy` is folded into the round's draw seed.
+    /// @dev    Sales close at cutoff_slot, before its slot hash exists, so the last buyer
+    ///         cannot see the final seed. A rescheduled draw does not reopen them.
+    pub fn buy_lottery_ticket(ctx: Context<BuyLotteryTicket>, entropy: [u8; 32]) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        require!(!ctx.accounts.pool.paused, BlitzError::ContractPaused);
+        let player = ctx.accounts.player.key();
+        let r      = &mut ctx.accounts.lottery_round;
+        require!(r.state == LOTTERY_OPEN && Clock::get()?.slot < r.cutoff_slot, BlitzError::LotteryClosed);
+        let index = r.tickets;
+        r.tickets = index.checked_add(1).ok_or(BlitzError::MathOverflow)?;
+        r.pot     = checked_add(r.pot, r.ticket_price)?;
+        r.entropy = hash::hashv(&[&r.entropy, &player.to_bytes(), &entropy]).to_bytes();
+        let t      = &mut ctx.accounts.ticket;
+        t.round    = r.key();
+        t.owner    = player;
+        t.index    = index;
+        t.bump     = ctx.bumps.ticket;
+        invoke(
+            &system_instruction::transfer(&player, &r.key(), r.ticket_price),
+            &[ctx.accounts.player.to_account_info(), r.to_account_info()],
+        )?;
+        emit!(LotteryTicketBought { lottery_round: r.key(), owner: player, ticket: index });
+        Ok(())
+    }
+
+    /// @notice Permissionless draw once draw_slot has a hash. Picks distinct winning tickets per
+    ///         LOTTERY_TIERS from SHA-256(entropy || slot hash) and books the rake as house fees.
+    /// @dev    If draw_slot has aged out of SlotHashes the draw is rescheduled SLOT_SPREAD slots
+    ///         ahead instead (re-emitting LotteryRoundOpened), so a round can never get stuck;
+    ///         ticket sales stay closed at cutoff_slot. Tiers left without a ticket roll over.
+    ///         The rake is taken on this round's ticket sales only — a rolled-over pot was
+    ///         already raked in the round that sold it.
+    pub fn draw_lottery(ctx: Context<DrawLottery>) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let r    = &mut ctx.accounts.lottery_round;
+        require!(r.state == LOTTERY_OPEN && slot > r.draw_slot, BlitzError::LotteryNotDrawable);
+        let slot_hash = match slot_hash_from(&ctx.accounts.slot_hashes.to_account_info(), r.draw_slot) {
+            Ok(h)  => h,
+            Err(_) => {
+                r.draw_slot = slot + SLOT_SPREAD;
+                emit!(LotteryRoundOpened { lottery_round: r.key(), round: r.round, ticket_price: r.ticket_price, draw_slot: r.draw_slot });
+                return Ok(());
+            }
+        };
+        let seed = hash::hashv(&[&r.entropy, &slot_hash]).to_bytes();
+        let (winners, prizes) = lottery_pick(&seed, r.tickets, r.pot);
+        r.winners    = winners;
+        r.prizes     = prizes;
+        r.state      = LOTTERY_DRAWN;
+        r.drawn_slot = slot;
+        let rake        = fixed::share_bps((r.tickets as u64).saturating_mul(r.ticket_price), LOTTERY_RAKE_BPS);
+        let (key, pot)  = (r.key(), r.pot);
+        let pool        = &mut ctx.accounts.pool;
+        let pool_ai     = pool.to_account_info();
+        move_lamports(&ctx.accounts.lottery_round.to_account_info(), &pool_ai, rake)?;
+        pool.house_fees_earned = checked_add(pool.house_fees_earned, rake)?;
+        sync_balance(pool, &pool_ai)?;
+        emit!(LotteryDrawn { lottery_round: key, tickets: ctx.accounts.lottery_round.tickets, pot, winners, prizes });
+        Ok(())
+    }
+
+    /// @notice Closes a ticket of a drawn round, returning its rent. A winning ticket is paid its
+    ///         prize if claimed within LOTTERY_CLAIM_WINDOW and before the round rolled over.
+    pub fn claim_lottery_ticket(ctx: Context<ClaimLotteryTicket>) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let r = &mut ctx.accounts.lottery_round;
+        require!(r.state != LOTTERY_OPEN, BlitzError::LotteryNotDrawn);
+        let index  = ctx.accounts.ticket.index;
+        let mut paid = 0;
+        if r.state == LOTTERY_DRAWN && Clock::get()?.slot <= r.drawn_slot + LOTTERY_CLAIM_WINDOW {
+            for i in 0..LOTTERY_MAX_WINNERS {
+                if r.winners[i] == index && r.claimed & (1 << i) == 0 {
+                    r.claimed |= 1 << i;
+                    paid = checked_add(paid, r.prizes[i])?;
+                }
+            }
         }
-        _ => return Err(BlitzError::InvalidGameType.into()),
+        if paid > 0 {
+            move_lamports(&ctx.accounts.lottery_round.to_account_info(), &ctx.accounts.owner.to_account_info(), paid)?;
+            emit!(LotteryPrizeClaimed { lottery_round: ctx.accounts.lottery_round.key(), owner: ctx.accounts.owner.key(), ticket: index, amount: paid });
+        }
+        Ok(())
+    }
+
+    /// @notice Permissionless. After the claim window, moves everything the drawn round still
+    ///         holds above rent — unclaimed prizes, unfilled tiers, the rollover share — into the
+    ///         next round's pot and closes the old round to further claims.
+    pub fn rollover_lottery(ctx: Context<RolloverLottery>) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let r = &ctx.accounts.lottery_round;
+        require!(r.state == LOTTERY_DRAWN,                                 BlitzError::LotteryNotDrawn);
+        require!(Clock::get()?.slot > r.drawn_slot + LOTTERY_CLAIM_WINDOW, BlitzError::LotteryClaimWindowOpen);
+        require!(ctx.accounts.next_round.state == LOTTERY_OPEN,            BlitzError::LotteryClosed);
+        let from_ai = ctx.accounts.lottery_round.to_account_info();
+        let rent    = Rent::get()?.minimum_balance(from_ai.data_len());
+        let amount  = from_ai.lamports().saturating_sub(rent);
+        move_lamports(&from_ai, &ctx.accounts.next_round.to_account_info(), amount)?;
+        ctx.accounts.lottery_round.state = LOTTERY_CLOSED;
+        let next = &mut ctx.accounts.next_round;
+        next.pot = checked_add(next.pot, amount)?;
+        emit!(LotteryRolledOver { from: ctx.accounts.lottery_round.key(), to: next.key(), amount });
+        Ok(())
+    }
+
+    // ── Gift cards ─────────────────────────────────────────────────────────
+
+    /// @notice Escrows `amount` in a gift card redeemable by whoever holds the code.
+    ///         Open to the operator or any other funder.
+    /// @param code_key     Public key of the ed25519 keypair seeded by SHA-256(code); also the
+    ///                     card's PDA seed
+    /// @param recipient    Only wallet the card may pay, or Pubkey::default() for any
+    /// @param expires_slot After this slot the card can no longer be redeemed, only reclaimed
+    /// @dev    The code never goes on-chain: redemption is signed by the code's key, and that
+    ///         signature covers the recipient, so a redemption cannot be front-run to another
+    ///         wallet and codes cannot be guessed by submitting transactions. Codes still need
+    ///         enough entropy (≥ 128 bits) that code_key cannot be searched offline.
+    pub fn mint_gift_card(ctx: Context<MintGiftCard>, code_key: Pubkey, amount: u64, recipient: Pubkey, expires_slot: u64) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        require!(amount >= GIFT_CARD_MIN,           BlitzError::BetTooSmall);
+        require!(expires_slot > Clock::get()?.slot, BlitzError::GiftCardExpired);
+        let funder     = ctx.accounts.funder.key();
+        let g          = &mut ctx.accounts.gift_card;
+        g.funder       = funder;
+        g.code_key     = code_key;
+        g.recipient    = recipient;
+        g.amount       = amount;
+        g.expires_slot = expires_slot;
+        g.bump         = ctx.bumps.gift_card;
+        invoke(
+            &system_instruction::transfer(&funder, &g.key(), amount),
+            &[ctx.accounts.funder.to_account_info(), g.to_account_info()],
+        )?;
+        emit!(GiftCardMinted { gift_card: g.key(), funder, amount, recipient, expires_slot });
+        Ok(())
+    }
+
+    /// @notice Redeems a gift card into `recipient`'s PlayerVault; the card's rent returns to its funder.
+    ///         The credit is playable via place_bet_from_vault and withdrawable like any deposit.
+    /// @dev    Signed by the code's key, not the recipient, so any fee payer (e.g. a sponsor's
+    ///         relayer) can submit it for a wallet that does not hold SOL yet. The fee payer
+    ///         funds the vault's rent if the recipient has no vault yet.
+    pub fn redeem_gift_card(ctx: Context<RedeemGiftCard>) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let g = &ctx.accounts.gift_card;
+        let to = ctx.accounts.recipient.key();
+        require!(Clock::get()?.slot <= g.expires_slot,                   BlitzError::GiftCardExpired);
+        require!(g.recipient == Pubkey::default() || g.recipient == to,  BlitzError::GiftCardRecipientLocked);
+        let amount   = g.amount;
+        let vault_ai = ctx.accounts.vault.to_account_info();
+        move_lamports(&ctx.accounts.gift_card.to_account_info(), &vault_ai, amount)?;
+        let v = &mut ctx.accounts.vault;
+        v.player          = to;
+        v.bump            = ctx.bumps.vault;
+        v.total_deposited = v.total_deposited.saturating_add(amount);
+        emit!(GiftCardRedeemed { gift_card: ctx.accounts.gift_card.key(), recipient: to, amount });
+        emit!(VaultDeposited { player: to, amount, balance: vault_free(&vault_ai)? });
+        Ok(())
+    }
+
+    /// @notice Returns an expired, unredeemed card's escrow and rent to its funder.
+    pub fn reclaim_gift_card(ctx: Context<ReclaimGiftCard>) -> Result<()> {
+        require_current_version(&ctx.accounts.pool)?;
+        let g = &ctx.accounts.gift_card;
+        require!(Clock::get()?.slot > g.expires_slot, BlitzError::GiftCardNotExpired);
+        emit!(GiftCardReclaimed { gift_card: g.key(), funder: g.funder, amount: g.amount });
+        Ok(())
+    }
+
+    // ── Rocket round (shared Crash) ────────────────────────────────────────
+
+    /// @notice Opens rocket round `round`. Players join until `cutoff_slot`; one crash point
+    ///         drawn from the round seed then settles every entry.
+    /// @param round       Round index. PDA [b"rocket_round", pool, round]
+    /// @param cutoff_slot Last slot (exclusive) to join; its slot hash seeds the crash point
+    /// @dev    The pool's payout curve is pinned on the round here, as on a session at placement.
+    ///         The round must launch by cutoff_slot + ROCKET_REFUND_SLOTS or its entries refund.
+    pub fn open_rocket_round(ctx: Context<OpenRocketRound>, round: u64, cutoff_slot: u64) -> Result<()> {
+        require!(cutoff_slot > Clock::get()?.slot + SLOT_SPREAD, BlitzError::InvalidRocketRound);
+        let r         = &mut ctx.accounts.rocket_round;
+        r.pool        = ctx.accounts.pool.key();
+        r.round       = round;
+        r.cutoff_slot = cutoff_slot;
+        r.refund_slot = cutoff_slot.saturating_add(ROCKET_REFUND_SLOTS);
+        r.curve       = ctx.accounts.pool.curve();
+        r.entries     = 0;
+        r.settled     = 0;
+        r.stakes      = 0;
+        r.liability   = 0;
+        r.entropy     = hash::hashv(&[b"rocket", &r.pool.to_bytes(), &round.to_le_bytes()]).to_bytes();
+        r.crash_point = 0;
+        r.state       = ROCKET_OPEN;
+        r.bump        = ctx.bumps.rocket_round;
+        emit!(RocketRoundOpened { rocket_round: r.key(), round, cutoff_slot });
+        Ok(())
+    }
+
+    /// @notice Joins an open round with a pre-committed cash-out multiplier. The stake moves into
+    ///         the pool; `entropy` is folded into the round's crash seed.
+    /// @param target_x100 Cash-out multiplier × 100 (≥ 101), paid if the rocket reaches it
+    /// @dev    Each entry passes the Crash (game 7) bet checks, and the round's summed worst case
+    ///         must also fit the room payout cap — every entry settles on one draw. The entry's
+    ///         worst case is reserved in pool.rocket_exposure until it is cranked or refunded, and
+    ///         must stay under the co-sign threshold: the permissionless crank has no co-signer.
+    ///         Counted in the Crash game counters.
+    pub fn join_rocket(ctx: Context<JoinRocket>, stake: u64, target_x100: u16, entropy: [u8; 32]) -> Result<()> {
+        let player = ctx.accounts.player.key();
+        let pool   = &mut ctx.accounts.pool;
+        let r      = &mut ctx.accounts.rocket_round;
+        require!(!pool.paused,                                                 BlitzError::ContractPaused);
+        require!(r.state == ROCKET_OPEN && Clock::get()?.slot < r.cutoff_slot, BlitzError::RocketClosed);
+        require!(target_x100 as u32 <= r.curve.max_multiplier_x100,            BlitzError::InvalidCrashTarget);
+        let t         = target_x100.to_le_bytes();
+        let worst     = check_bet(pool, 7, &[t[0], t[1], 0], 0, stake)?;
+        let liability = checked_add(r.liability, worst)?;
+        require!(
+            liability <= get_room_payout_cap(pool.room, pool.game_basis(7))
+                && worst <= pool.total_balance.saturating_add(stake)
+                && (pool.cosign_threshold == 0 || worst <= pool.cosign_threshold),
+            BlitzError::RocketLiabilityCap
+        );
+        r.entries   = r.entries.checked_add(1).ok_or(BlitzError::MathOverflow)?;
+        r.stakes    = checked_add(r.stakes, stake)?;
+        r.liability = liability;
+        r.entropy   = hash::hashv(&[&r.entropy, &player.to_bytes(), &entropy]).to_bytes();
+        let e         = &mut ctx.accounts.entry;
+        e.round       = r.key();
+        e.player      = player;
+        e.stake       = stake;
+        e.target_x100 = target_x100;
+        e.bump        = ctx.bumps.entry;
+        e.reserved    = worst;
+        e.fee_bps     = pin_fee_bps(pool.total_balance, false);
+        let pool_ai = pool.to_account_info();
+        invoke(
+            &system_instruction::transfer(&player, pool_ai.key, stake),
+            &[ctx.accounts.player.to_account_info(), pool_ai.clone()],
+        )?;
+        pool.rocket_exposure = checked_add(pool.rocket_exposure, worst)?;
+        sync_balance(pool, &pool_ai)?;
+        pool.total_wagered    = pool.total_wagered.saturating_add(stake);
+        pool.total_bets       = pool.total_bets.saturating_add(1);
+        pool.lifetime_wagered = add_u128(pool.lifetime_wagered, stake)?;
+        pool.record_game_bet(7, stake);
+        emit!(RocketJoined { rocket_round: r.key(), player, stake, target_x100 });
+        Ok(())
+    }
+
+    /// @notice Permissionless launch once cutoff_slot has a hash: fixes the crash point from
+    ///         SHA-256(entropy || slot hash) on the round's pinned curve.
+    /// @dev    If cutoff_slot has aged out of SlotHashes the cutoff moves SLOT_SPREAD slots
+    ///         ahead instead (re-emitting RocketRoundOpened), as draw_lottery does. Refused past
+    ///         refund_slot, so a round is either drawn or refunded, never both.
+    pub fn launch_rocket(ctx: Context<LaunchRocket>) -> Result<()> {
+        let slot = Clock::get()?.slot;
+        let r    = &mut ctx.accounts.rocket_round;
+        require!(r.state == ROCKET_OPEN && slot > r.cutoff_slot && slot <= r.refund_slot, BlitzError::RocketNotLaunchable);
+        let slot_hash = match slot_hash_from(&ctx.accounts.slot_hashes.to_account_info(), r.cutoff_slot) {
+            Ok(h)  => h,
+            Err(_) => {
+                r.cutoff_slot = slot + SLOT_SPREAD;
+                emit!(RocketRoundOpened { rocket_round: r.key(), round: r.round, cutoff_slot: r.cutoff_slot });
+                return Ok(());
+            }
+        };
+        let seed      = hash::hashv(&[&r.entropy, &slot_hash]).to_bytes();
+        r.crash_point = multiplier_roll_x100(&seed, &r.curve, fixed::BPS.saturating_sub(r.curve.edge_bps as u64));
+        r.entropy     = seed;
+        r.state       = if r.entries == 0 { ROCKET_SETTLED } else { ROCKET_LAUNCHED };
+        emit!(RocketLaunched { rocket_round: r.key(), crash_point: r.crash_point, entries: r.entries, stakes: r.stakes });
+        Ok(())
+    }
+
+    /// @notice Permissionless crank: settles up to ROCKET_CRANK_BATCH entries of a launched round,
+    ///         passed as (entry, player) pairs in `remaining_accounts`. A target at or below the
+    ///         crash point wins stake × target; each entry is closed to its player.
+    /// @dev    Entries settle through compute_settlement like any bet (settle_rocket_entry):
+    ///         phase fees, insurance and the jackpot roll on the stake, the entry's reservation
+    ///         released first. Runs the Crash RTP band check once its sample is reached.
+    ///         A closed entry no longer deserializes, so an entry can never be settled twice.
+    pub fn crank_rocket<'info>(ctx: Context<'_, '_, 'info, 'info, CrankRocket<'info>>) -> Result<()> {
+        let pairs = ctx.remaining_accounts;
+        require!(pairs.len() & 1 == 0 && pairs.len() <= 2 * ROCKET_CRANK_BATCH, BlitzError::InvalidRocketBatch);
+        let r = &mut ctx.accounts.rocket_round;
+        require!(r.state == ROCKET_LAUNCHED, BlitzError::RocketNotLaunched);
+        let key  = r.key();
+        let pool = &mut ctx.accounts.pool;
+        for pair in pairs.chunks(2) {
+            let (entry_ai, player_ai) = (&pair[0], &pair[1]);
+            let entry = Account::<RocketEntry>::try_from(entry_ai)?;
+            require!(entry.round == key && entry.player == player_ai.key(), BlitzError::InvalidRocketEntry);
+            let gross = if entry.target_x100 as u32 <= r.crash_point {
+                fixed::mul_div_floor(entry.stake, entry.target_x100 as u64, 100).unwrap_or(u64::MAX)
+            } else { 0 };
+            let seed = hash::hashv(&[&r.entropy, &entry.player.to_bytes()]).to_bytes();
+            let plan = settle_rocket_entry(pool, player_ai, &entry, gross, seed)?;
+            r.settled += 1;
+            emit!(RocketEntrySettled { rocket_round: key, player: entry.player, stake: entry.stake, target_x100: entry.target_x100, payout: plan.player_gets });
+            entry.close(player_ai.clone())?;
+        }
+        if r.settled == r.entries { r.state = ROCKET_SETTLED; }
+        let band = pool.rtp_band(7);
+        if band.max_bps > 0 && pool.counters(7).bets >= (band.min_bets as u64).max(1) {
+            run_rtp_check(pool, 7)?;
+        }
+        Ok(())
+    }
+
+    /// @notice Permissionless refund of an entry whose round never launched by its refund_slot:
+    ///         the full stake goes back to the player and the entry closes to them.
+    /// @dev    Releases the entry's reservation. No fees — the round was never drawn.
+    pub fn refund_rocket_entry(ctx: Context<RefundRocketEntry>) -> Result<()> {
+        let r = &mut ctx.accounts.rocket_round;
+        require!(r.state == ROCKET_OPEN && Clock::get()?.slot > r.refund_slot, BlitzError::RocketNotRefundable);
+        let (player, stake, reserved) = (ctx.accounts.entry.player, ctx.accounts.entry.stake, ctx.accounts.entry.reserved);
+        let pool             = &mut ctx.accounts.pool;
+        pool.rocket_exposure = pool.rocket_exposure.saturating_sub(reserved);
+        pool.record_game_outflow(7, stake);
+        let pool_ai = pool.to_account_info();
+        pool_transfer(&pool_ai, &ctx.accounts.player.to_account_info(), stake)?;
+        sync_balance(pool, &pool_ai)?;
+        r.settled += 1;
+        emit!(RocketEntryRefunded { rocket_round: r.key(), player, stake });
+        Ok(())
+    }
+
+    /// @notice Closes a finished round to the pool authority once every entry is cranked or refunded.
+    pub fn close_rocket_round(ctx: Context<CloseRocketRound>) -> Result<()> {
+        let r    = &ctx.accounts.rocket_round;
+        let done = r.state == ROCKET_SETTLED || (r.state == ROCKET_OPEN && Clock::get()?.slot > r.refund_slot);
+        require!(done && r.settled == r.entries, BlitzError::RocketRoundActive);
+        emit!(RocketRoundClosed { rocket_round: r.key(), round: r.round });
+        Ok(())
+    }
+
+    // ── Auditor access (watch-only) ────────────────────────────────────────
+
+    /// @notice Grants `auditor` watch-only access: audit_pool and export_game_counters.
+    /// @param min_interval_slots Minimum slots between two audit calls by this auditor
+    /// @dev    The Auditor PDA is the whole grant. Neither instruction takes the pool mutably,
+    ///         so an auditor key cannot move funds or change config however it is used.
+    pub fn grant_auditor(ctx: Context<GrantAuditor>, min_interval_slots: u64) -> Result<()> {
+        let a                = &mut ctx.accounts.auditor_account;
+        a.pool               = ctx.accounts.pool.key();
+        a.auditor            = ctx.accounts.auditor.key();
+        a.min_interval_slots = min_interval_slots;
+        a.bump               = ctx.bumps.auditor_account;
+        emit!(AuditorGranted { pool: a.pool, auditor: a.auditor, min_interval_slots });
+        Ok(())
+    }
+
+    /// @notice Revokes an auditor grant and returns its rent to the authority.
+    pub fn revoke_auditor(ctx: Context<RevokeAuditor>) -> Result<()> {
+        emit!(AuditorRevoked { pool: ctx.accounts.pool.key(), auditor: ctx.accounts.auditor_account.auditor });
+        Ok(())
+    }
+
+    /// @notice Auditor-only solvency snapshot: every reserved compartment against physical lamports.
+    /// @dev    Reports a broken invariant as `solvent = false` instead of failing. Rate limited per auditor.
+    ///         Result is delivered via return data (`PoolAudit`).
+    pub fn audit_pool(ctx: Context<Audit>) -> Result<PoolAudit> {
+        let slot     = touch_auditor(&mut ctx.accounts.auditor_account)?;
+        let pool     = &ctx.accounts.pool;
+        let pool_ai  = pool.to_account_info();
+        let physical = pool_ai.lamports().saturating_sub(Rent::get()?.minimum_balance(pool_ai.data_len()));
+        let reserved = pool.reserved();
+        let solvent  = physical >= reserved && pool.total_balance == physical - reserved;
+        emit!(PoolAudited { pool: pool.key(), auditor: ctx.accounts.auditor.key(), seq: pool.state_seq, solvent });
+        Ok(PoolAudit {
+            slot,
+            state_seq:         pool.state_seq,
+            program_version:   pool.program_version,
+            physical,
+            reserved,
+            total_balance:     pool.total_balance,
+            house_fees_earned: pool.house_fees_earned,
+            jackpot_balance:   pool.jackpot_balance,
+            promo_balance:     pool.promo_balance,
+            held_payouts:      pool.held_payouts,
+            referral_accrued:  pool.referral_accrued,
+            performance_fees:  pool.performance_fees,
+            insurance_balance: pool.insurance_balance,
+            rocket_exposure:   pool.rocket_exposure,
+            solvent,
+        })
+    }
+
+    /// @notice Auditor-only export of the per-game RTP counters and alarm bits. Rate limited per auditor.
+    pub fn export_game_counters(ctx: Context<Audit>) -> Result<GameCountersExport> {
+        let slot = touch_auditor(&mut ctx.accounts.auditor_account)?;
+        let pool = &ctx.accounts.pool;
+        Ok(GameCountersExport {
+            slot,
+            state_seq:     pool.state_seq,
+            counters:      core::array::from_fn(|g| *pool.counters(g as u8)),
+            rtp_anomalies: pool.rtp_anomalies,
+            games_paused:  pool.paused_games(),
+        })
+    }
+
+    // ── Post-settlement assertion ──────────────────────────────────────────
+
+    /// @notice Append after a reveal: fails the whole transaction unless `player`
+    ///         was credited at least `min_lamports` by a settlement in this slot.
+    /// @dev    Reads the pool's last-settlement record — a user-side net against settlement bugs.
+    pub fn assert_player_received(ctx: Context<AssertPlayerReceived>, min_lamports: u64) -> Result<()> {
+        let pool = &ctx.accounts.pool;
+        require!(
+            pool.last_settled_player == ctx.accounts.player.key()
+                && pool.last_settled_slot == Clock::get()?.slot
+                && pool.last_settled_payout >= min_lamports,
+            BlitzError::PayoutBelowExpected
+        );
+        Ok(())
+    }
+
+    // ── Expiry reminders ───────────────────────────────────────────────────
+
+    /// @notice Permissionless crank: emits SessionExpiringSoon for every pending session in
+    ///         `remaining_accounts` whose reveal window closes within `within_slots`.
+    /// @dev    Non-session, foreign-pool or settled accounts are skipped, so bots can pass
+    ///         a stale list without the whole call failing.
+    pub fn ping_expiring_sessions<'info>(
+        ctx:          Context<'_, '_, 'info, 'info, PingExpiringSessions<'info>>,
+        within_slots: u64,
+    ) -> Result<()> {
+        require!(within_slots <= REVEAL_WINDOW, BlitzError::InvalidPingWindow);
+        let slot     = Clock::get()?.slot;
+        let pool_key = ctx.accounts.pool.key();
+        for ai in ctx.remaining_accounts.iter() {
+            let Ok(s) = Account::<GameSession>::try_from(ai) else { continue };
+            if s.pool != pool_key || s.game_state != 0 || s.forfeit_slot < slot { continue; }
+            let slots_left = s.forfeit_slot - slot;
+            if slots_left <= within_slots {
+                emit!(SessionExpiringSoon { session: ai.key(), player: s.player, forfeit_slot: s.forfeit_slot, slots_left });
+            }
+        }
+        Ok(())
+    }
+
+    /// @notice Permissionless crank: closes a settled session once its receipt window has
+    ///         passed — or immediately when the settlement was logged to the room's
+    ///         ReceiptTree (only a held payout keeps such a session past its reveal).
+    ///         Rent always goes back to the player.
+    pub fn close_settled_session(ctx: Context<CloseSettledSession>) -> Result<()> {
+        let s = &ctx.accounts.session;
+        require!(s.game_state == 2,                                        BlitzError::SessionNotSettled);
+        require!(
+            s.receipt_index != 0 || Clock::get()?.slot > s.settled_slot + RECEIPT_WINDOW,
+            BlitzError::ReceiptWindowActive
+        );
+        require!(s.held_payout == 0,                                       BlitzError::PayoutStillHeld);
+        Ok(())
+    }
+
+    /// @notice Creates the room's ReceiptTree — compressed receipt mode for high-volume rooms.
+    /// @dev    Reveals that pass the tree append the settlement's leaf (see receipt_leaf) and
+    ///         close the session in the same instruction, rent back to the player — a bet's
+    ///         account lives only from placement to reveal. The leaf plus a merkle proof against
+    ///         `root` stands in for the on-chain receipt in verification and history APIs.
+    ///         A racing second reveal then fails instead of returning a RevealReceipt.
+    pub fn init_receipt_tree(ctx: Context<InitReceiptTree>) -> Result<()> {
+        let t  = &mut ctx.accounts.receipt_tree;
+        t.pool = ctx.accounts.pool.key();
+        t.root = receipt_root(&t.frontier, 0);
+        t.bump = ctx.bumps.receipt_tree;
+        emit!(ReceiptTreeInitialized { pool: t.pool, tree: t.key(), depth: RECEIPT_TREE_DEPTH as u8 });
+        Ok(())
+    }
+
+    /// @notice Opens the GameReceipt of a pending single-shot session. Anyone may pay for it.
+    /// @dev    The settling reveal_* / reveal_*_delegated / reveal_public / settle_vrf call
+    ///         writes it when passed (reveal_batch does not). It outlives the session, so
+    ///         verify_game can replay the bet long after close_settled_session.
+    pub fn init_game_receipt(ctx: Context<InitGameReceipt>) -> Result<()> {
+        let s = &ctx.accounts.session;
+        require!(s.game_state == 0,                               BlitzError::SessionNotPending);
+        require!(!matches!(s.game_type, 6 | 12 | 19 | 21 | 22),   BlitzError::WrongGameType);
+        let r     = &mut ctx.accounts.game_receipt;
+        r.session = s.key();
+        r.player  = s.player;
+        r.pool    = s.pool;
+        r.bump    = ctx.bumps.game_receipt;
+        Ok(())
+    }
+
+    /// @notice Replays a settled bet from its GameReceipt: the stored seed and pinned terms go
+    ///         back through resolve_seed and the result is compared with the stored outcome.
+    /// @dev    Read-only. Result is delivered via return data and logged as GameVerified.
+    ///         Proves the outcome follows from the seed; the seed itself is bound to the bet by
+    ///         the commitment (commit-reveal) or the randomness account (`vrf`).
+    pub fn verify_game(ctx: Context<VerifyGame>) -> Result<bool> {
+        let r = &ctx.accounts.game_receipt;
+        require!(r.settled_slot != 0, BlitzError::GameReceiptPending);
+        let (won, gross, outcome) = resolve_seed(r.game_type, &r.config, r.config_ext, r.bet_lamports, &r.seed, r.payout_num_bps as u64, &r.payout_curve)?;
+        let matches = won == r.won && gross == r.gross_payout && outcome_bytes(&outcome) == r.outcome;
+        emit!(GameVerified { session: r.session, game_type: r.game_type, won: r.won, matches });
+        Ok(matches)
+    }
+
+    /// @notice Creates the room's Firehose — one fixed-size account holding the latest
+    ///         settlement, for dashboards that subscribe to account updates instead of logs.
+    /// @dev    Sets pool.firehose_enabled; from then on settle refuses a reveal that leaves
+    ///         the Firehose out, so the account never lags the latest settlement.
+    pub fn init_firehose(ctx: Context<InitFirehose>) -> Result<()> {
+        let f  = &mut ctx.accounts.firehose;
+        f.pool = ctx.accounts.pool.key();
+        f.bump = ctx.bumps.firehose;
+        let pool = &mut ctx.accounts.pool;
+        pool.firehose_enabled = true;
+        bump_config(pool);
+        Ok(())
+    }
+
+    /// @notice Creates the room's SectorHeatmap — a live 16×16 strike-count grid.
+    /// @dev    Zero-copy. Reveals that pass it count every Sector strike, including Sector
+    ///         picked by Mystery; anyone can read the grid to check strike uniformity.
+    pub fn init_sector_heatmap(ctx: Context<InitSectorHeatmap>) -> Result<()> {
+        let mut h = ctx.accounts.sector_heatmap.load_init()?;
+        h.pool    = ctx.accounts.pool.key();
+        h.bump    = ctx.bumps.sector_heatmap;
+        Ok(())
+    }
+
+    /// @notice Pays out a held large win: immediately with the co-signer, or by anyone once
+    ///         COSIGN_FALLBACK_SECS have passed — the house can delay but never withhold.
+    pub fn release_held_payout(ctx: Context<ReleaseHeldPayout>) -> Result<()> {
+        let amount = ctx.accounts.session.held_payout;
+        require!(amount > 0, BlitzError::NoHeldPayout);
+        require!(
+            ctx.accounts.cosigner.is_some()
+                || (ctx.accounts.player.is_some() && ctx.accounts.session.double_offer)
+                || Clock::get()?.unix_timestamp >= ctx.accounts.session.held_until,
+            BlitzError::TimelockActive
+        );
+        let pool = &mut ctx.accounts.pool;
+        pool_transfer(&pool.to_account_info(), &ctx.accounts.payout_wallet.to_account_info(), amount)?;
+        pool.held_payouts = pool.held_payouts.saturating_sub(amount);
+        ctx.accounts.session.held_payout = 0;
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        emit!(PayoutReleased { player: ctx.accounts.session.player, amount, cosigned: ctx.accounts.cosigner.is_some() });
+        Ok(())
+    }
+
+    // ── RTP alarms ─────────────────────────────────────────────────────────
+
+    /// @notice Permissionless RTP alarm. Compares a game's paid/wagered ratio — or, with
+    ///         game = RTP_GLOBAL, the pool's lifetime counters — against its configured band
+    ///         once the band's `min_bets` sample is reached.
+    /// @dev    Out of band sets the game's rtp_anomalies bit and, if the band has auto_pause,
+    ///         stops new bets on that game (global: pauses the pool under the usual 24h cap).
+    ///         Back in band clears the bit; a game pause stays until resume_game.
+    ///         Per-game paid_out excludes jackpots, which are not attributable to one game.
+    pub fn check_rtp(ctx: Context<CheckRtp>, game: u8) -> Result<()> {
+        run_rtp_check(&mut ctx.accounts.pool, game)
+    }
+
+    // ── Session Keys ───────────────────────────────────────────────────────
+
+    /// @notice Creates a time-bounded delegate key for wallet-popup-free auto-reveals.
+    /// @dev    validity_secs capped at 24h. Gas forwarded to delegate up to 0.01 SOL.
+    pub fn create_session(ctx: Context<CreateSession>, validity_secs: i64, gas_lamports: u64) -> Result<()> {
+        require!(validity_secs > 0 && validity_secs <= 86400, BlitzError::InvalidSessionDuration);
+        require!(gas_lamports <= 10_000_000,                  BlitzError::GasTooHigh);
+        let clock   = Clock::get()?;
+        let t       = &mut ctx.accounts.session_token;
+        t.player    = ctx.accounts.player.key();
+        t.delegate  = ctx.accounts.delegate.key();
+        t.expires_at = clock.unix_timestamp + validity_secs;
+        t.bump      = ctx.bumps.session_token;
+        if gas_lamports > 0 {
+            invoke(
+                &system_instruction::transfer(&t.player, &t.delegate, gas_lamports),
+                &[ctx.accounts.player.to_account_info(), ctx.accounts.delegate.to_account_info()],
+            )?;
+        }
+        emit!(SessionCreated { player: t.player, delegate: t.delegate, expires_at: t.expires_at });
+        Ok(())
+    }
+
+    /// @notice Closes the session token PDA, reclaiming rent to the player.
+    /// @dev    Also bumps the player's SessionNonce, so no signature made for
+    ///         create_session_with_sig before the revocation can recreate the key.
+    pub fn close_session(ctx: Context<CloseSession>) -> Result<()> {
+        let n    = &mut ctx.accounts.session_nonce;
+        n.player = ctx.accounts.player.key();
+        n.nonce  = n.nonce.wrapping_add(1);
+        n.bump   = ctx.bumps.session_nonce;
+        Ok(())
+    }
+
+    /// @notice Creates a session key from an off-chain player signature, for wallets (Ledger)
+    ///         that can sign a message but not comfortably build the transaction.
+    /// @dev    The instruction right before this one must be an Ed25519 program verify of
+    ///         session_auth_message(player, delegate, expires_at, scope, nonce) by the player,
+    ///         nonce being the player's current SessionNonce (0 before the first close_session).
+    ///         `payer` (usually the dApp relayer) funds the PDAs; no gas is forwarded.
+    pub fn create_session_with_sig(ctx: Context<CreateSessionWithSig>, expires_at: i64, scope: u8) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        require!(expires_at > now && expires_at <= now + 86400, BlitzError::InvalidSessionDuration);
+        require!(scope == SESSION_SCOPE_REVEAL,                BlitzError::InvalidSessionScope);
+        let player   = ctx.accounts.player.key();
+        let delegate = ctx.accounts.delegate.key();
+        let n        = &mut ctx.accounts.session_nonce;
+        n.player     = player;
+        n.bump       = ctx.bumps.session_nonce;
+        let message  = session_auth_message(&player, &delegate, expires_at, scope, n.nonce);
+        verify_ed25519_ix(&ctx.accounts.instructions.to_account_info(), &player, &message)?;
+        let t        = &mut ctx.accounts.session_token;
+        t.player     = player;
+        t.delegate   = delegate;
+        t.expires_at = expires_at;
+        t.bump       = ctx.bumps.session_token;
+        emit!(SessionCreated { player, delegate, expires_at });
+        Ok(())
+    }
+
+    // ── Notification Preferences ───────────────────────────────────────────
+
+    /// @notice Opts the player in to `Notify` events for a relayer.
+    /// @dev    Only a hash of the webhook / Telegram identifier is stored — never the raw handle.
+    /// @param id_hash  SHA-256 of the off-chain delivery identifier
+    /// @param flags    NOTIFY_* bitmask of event kinds to receive
+    pub fn set_notification_prefs(ctx: Context<SetNotificationPrefs>, id_hash: [u8; 32], flags: u8) -> Result<()> {
+        require!(flags != 0 && flags & !NOTIFY_ALL == 0, BlitzError::InvalidNotifyFlags);
+        let n     = &mut ctx.accounts.notification_prefs;
+        n.player  = ctx.accounts.player.key();
+        n.id_hash = id_hash;
+        n.flags   = flags;
+        n.bump    = ctx.bumps.notification_prefs;
+        emit!(NotificationPrefsUpdated { player: n.player, id_hash, flags });
+        Ok(())
+    }
+
+    /// @notice Opts out of notifications, reclaiming rent to the player.
+    pub fn close_notification_prefs(ctx: Context<CloseNotificationPrefs>) -> Result<()> {
+        emit!(NotificationPrefsUpdated { player: ctx.accounts.player.key(), id_hash: [0u8; 32], flags: 0 });
+        Ok(())
+    }
+
+    // ── Admin ──────────────────────────────────────────────────────────────
+
+    /// @notice Pauses or unpauses the contract.
+    /// @dev    Pause is hard-capped at MAX_PAUSE_DURATION (24h).
+    ///         place_bet auto-expires a forgotten pause — funds can never be frozen permanently.
+    pub fn set_paused(ctx: Context<AdminOnly>, paused: bool) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.paused = paused;
+        if paused {
+            pool.pause_expires_at = Clock::get()?.unix_timestamp + MAX_PAUSE_DURATION;
+            emit!(ContractPaused { authority: pool.authority, expires_at: pool.pause_expires_at });
+        } else {
+            pool.pause_expires_at = 0;
+            emit!(ContractUnpaused { authority: pool.authority });
+        }
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Sets the programs allowed to call place_bet via CPI. Default pubkey = empty slot.
+    /// @dev    Only direct CPI from a whitelisted top-level program is accepted — no nested wrappers.
+    pub fn set_cpi_whitelist(ctx: Context<AdminOnly>, programs: [Pubkey; CPI_WHITELIST_LEN]) -> Result<()> {
+        ctx.accounts.pool.cpi_whitelist = programs;
+        emit!(CpiWhitelistUpdated { pool: ctx.accounts.pool.key(), programs });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Chooses where forfeited session rent goes. Default (false) = back to the player.
+    pub fn set_forfeit_rent_policy(ctx: Context<AdminOnly>, to_pool: bool) -> Result<()> {
+        ctx.accounts.pool.forfeit_rent_to_pool = to_pool;
+        emit!(ForfeitRentPolicyUpdated { pool: ctx.accounts.pool.key(), to_pool });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Sets the per-bet jackpot trigger probability cap in bps (1–200, i.e. 0.01%–2%).
+    pub fn set_jackpot_trigger_cap(ctx: Context<AdminOnly>, cap_bps: u16) -> Result<()> {
+        require!(cap_bps > 0 && cap_bps <= JACKPOT_CAP_MAX_BPS, BlitzError::InvalidJackpotCap);
+        ctx.accounts.pool.jackpot_cap_bps = cap_bps;
+        emit!(JackpotTriggerCapUpdated { pool: ctx.accounts.pool.key(), cap_bps });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Schedules a promotional jackpot boost for [start, end): the trigger threshold and
+    ///         the jackpot contribution are scaled by `trigger_bps` / `contrib_bps` (10_000 = 1×).
+    /// @dev    Replaces any earlier window; start = end = 0 clears it. The trigger cap still
+    ///         bounds the boosted threshold, and extra contribution comes out of the house cut.
+    pub fn schedule_jackpot_boost(
+        ctx:         Context<AdminOnly>,
+        start:       i64,
+        end:         i64,
+        trigger_bps: u16,
+        contrib_bps: u16,
+    ) -> Result<()> {
+        let now   = Clock::get()?.unix_timestamp;
+        let boost = JackpotBoost { start, end, trigger_bps, contrib_bps };
+        if boost != JackpotBoost::default() {
+            require!(end > start && end > now && end - start <= JACKPOT_BOOST_MAX_SECS, BlitzError::InvalidJackpotBoost);
+            require!(
+                (fixed::BPS as u16..=JACKPOT_BOOST_MAX_BPS).contains(&trigger_bps)
+                    && (fixed::BPS as u16..=JACKPOT_BOOST_MAX_BPS).contains(&contrib_bps),
+                BlitzError::InvalidJackpotBoost
+            );
+        }
+        ctx.accounts.pool.jackpot_boost = boost;
+        emit!(JackpotBoostScheduled { pool: ctx.accounts.pool.key(), start, end, trigger_bps, contrib_bps });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Sets the Crash/Limbo payout curve. Rejected unless validate_curve passes (EV ≤ 96%).
+    pub fn set_payout_curve(ctx: Context<AdminOnly>, curve: PayoutCurve) -> Result<()> {
+        validate_curve(&curve)?;
+        ctx.accounts.pool.payout_curve = curve;
+        emit!(PayoutCurveUpdated { pool: ctx.accounts.pool.key(), edge_bps: curve.edge_bps,
+                                   max_multiplier_x100: curve.max_multiplier_x100, shape: curve.shape, bust_bps: curve.bust_bps });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Sets the expected RTP band for a game (or RTP_GLOBAL). max_bps = 0 disables it.
+    pub fn set_rtp_band(ctx: Context<AdminOnly>, game: u8, band: RtpBand) -> Result<()> {
+        require!(game == RTP_GLOBAL || (game as usize) < MAX_GAME_TYPES,      BlitzError::InvalidGameType);
+        require!(band.min_bps <= band.max_bps && band.max_bps <= RTP_BAND_MAX_BPS, BlitzError::InvalidRtpBand);
+        let pool = &mut ctx.accounts.pool;
+        if game == RTP_GLOBAL { pool.global_rtp_band = band; } else { pool.set_rtp_band(game, band); }
+        emit!(RtpBandUpdated { pool: pool.key(), game, min_bps: band.min_bps, max_bps: band.max_bps,
+                               min_bets: band.min_bets, auto_pause: band.auto_pause });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Sets the authority's performance fee on LP profit above the high-water mark,
+    ///         accrued by close_lp_epoch. At most PERFORMANCE_FEE_MAX_BPS; 0 = off.
+    pub fn set_performance_fee(ctx: Context<AdminOnly>, bps: u16) -> Result<()> {
+        require!(bps <= PERFORMANCE_FEE_MAX_BPS, BlitzError::InvalidPerformanceFee);
+        ctx.accounts.pool.performance_fee_bps = bps;
+        emit!(PerformanceFeeUpdated { pool: ctx.accounts.pool.key(), bps, hwm_price: ctx.accounts.pool.lp_hwm_price });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Picks the VRF backend place_bet_vrf binds new bets to (VRF_SWITCHBOARD | VRF_ORAO).
+    /// @dev    Open VRF bets keep the provider they were placed with.
+    pub fn set_vrf_provider(ctx: Context<AdminOnly>, provider: u8) -> Result<()> {
+        require!(provider <= VRF_ORAO, BlitzError::InvalidVrfProvider);
+        ctx.accounts.pool.vrf_provider = provider;
+        emit!(VrfProviderUpdated { pool: ctx.accounts.pool.key(), provider });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Caps the share of the pool `game` sizes its bets from (10_000 = whole pool, 0 = no
+    ///         budget). The game's drawdown is charged against it, so a losing streak on one
+    ///         high-variance game cannot eat the liquidity the others size from.
+    pub fn set_game_budget(ctx: Context<AdminOnly>, game: u8, bps: u16) -> Result<()> {
+        require!((game as usize) < MAX_GAME_TYPES, BlitzError::InvalidGameType);
+        require!(bps as u64 <= fixed::BPS,          BlitzError::InvalidGameBudget);
+        let pool = &mut ctx.accounts.pool;
+        pool.game_budget_bps[game as usize] = bps;
+        emit!(GameBudgetUpdated { pool: pool.key(), game, bps, drawdown: pool.game_drawdown[game as usize] });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Clears a game's RTP anomaly flag and lifts its auto-pause. RTP_GLOBAL clears
+    ///         the global flag only — use set_paused to unpause the pool.
+    pub fn resume_game(ctx: Context<AdminOnly>, game: u8) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        if game == RTP_GLOBAL {
+            pool.rtp_anomalies &= !RTP_GLOBAL_FLAG;
+        } else {
+            require!((game as usize) < MAX_GAME_TYPES, BlitzError::InvalidGameType);
+            pool.rtp_anomalies &= !(1u32 << game);
+            let bits = pool.paused_games() & !(1u32 << game);
+            pool.set_paused_games(bits);
+        }
+        emit!(GameResumed { pool: pool.key(), game });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    // ── Referral revenue share ─────────────────────────────────────────────
+
+    /// @notice Selects the pool's referral economics.
+    /// @param mode      REFERRAL_MODE_TURNOVER (share of every bet, paid at settlement) or
+    ///                  REFERRAL_MODE_NET_LOSS (share of referees' net losses, accrued per epoch).
+    /// @param share_bps Net-loss share, ≤ REFERRAL_MAX_SHARE_BPS. Ignored in turnover mode.
+    /// @dev    Switching mid-epoch is safe: open epochs close on the next accrual or claim.
+    pub fn set_referral_mode(ctx: Context<AdminOnly>, mode: u8, share_bps: u16) -> Result<()> {
+        require!(mode <= REFERRAL_MODE_NET_LOSS,          BlitzError::InvalidReferralConfig);
+        require!(share_bps <= REFERRAL_MAX_SHARE_BPS,     BlitzError::InvalidReferralConfig);
+        ctx.accounts.pool.referral_mode      = mode;
+        ctx.accounts.pool.referral_share_bps = share_bps;
+        emit!(ReferralModeUpdated { pool: ctx.accounts.pool.key(), mode, share_bps });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Creates the caller's ReferralAccount for this pool. In net-loss mode a referred
+    ///         bet can only be placed with it, so referrers open it before sharing links.
+    pub fn open_referral_account(ctx: Context<OpenReferralAccount>) -> Result<()> {
+        let r      = &mut ctx.accounts.referral;
+        r.pool     = ctx.accounts.pool.key();
+        r.referrer = ctx.accounts.referrer.key();
+        r.epoch    = Clock::get()?.epoch;
+        r.bump     = ctx.bumps.referral;
+        Ok(())
+    }
+
+    /// @notice Pays out the commission of every closed epoch. The current epoch's net
+    ///         losses stay open until the next epoch boundary.
+    pub fn claim_referral(ctx: Context<ClaimReferral>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let r    = &mut ctx.accounts.referral;
+        referral_roll(pool, r, Clock::get()?.epoch);
+        let amount = r.claimable;
+        require!(amount > 0, BlitzError::NoReferralCommission);
+        pool_transfer(&pool.to_account_info(), &ctx.accounts.referrer.to_account_info(), amount)?;
+        pool.referral_accrued = pool.referral_accrued.saturating_sub(amount);
+        r.claimable           = 0;
+        r.total_claimed       = r.total_claimed.saturating_add(amount);
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        emit!(ReferralClaimed { referrer: r.referrer, pool: r.pool, amount });
+        Ok(())
+    }
+
+    // ── Custodial partners ─────────────────────────────────────────────────
+
+    /// @notice Registers a custodial partner key. Bets it signs are labelled with its
+    ///         Partner PDA and earn `fee_bps` of the house cut at settlement.
+    /// @dev    The partner signs place_bet/reveal as the player, so its sessions live in
+    ///         its own seed namespace ([b"session", partner_authority, commitment]).
+    pub fn register_partner(ctx: Context<RegisterPartner>, fee_bps: u16) -> Result<()> {
+        require!(fee_bps <= PARTNER_MAX_FEE_BPS, BlitzError::InvalidPartnerFee);
+        let p       = &mut ctx.accounts.partner;
+        p.authority = ctx.accounts.partner_authority.key();
+        p.fee_bps   = fee_bps;
+        p.active    = true;
+        p.bump      = ctx.bumps.partner;
+        emit!(PartnerUpdated { partner: p.key(), authority: p.authority, fee_bps, active: true });
+        Ok(())
+    }
+
+    /// @notice Updates a partner's fee share or suspends new bets. Open sessions still settle.
+    pub fn update_partner(ctx: Context<UpdatePartner>, fee_bps: u16, active: bool) -> Result<()> {
+        require!(fee_bps <= PARTNER_MAX_FEE_BPS, BlitzError::InvalidPartnerFee);
+        let p     = &mut ctx.accounts.partner;
+        p.fee_bps = fee_bps;
+        p.active  = active;
+        emit!(PartnerUpdated { partner: p.key(), authority: p.authority, fee_bps, active });
+        Ok(())
+    }
+
+    /// @notice Initiates a withdrawal (48h timelock, max 20% of pool per request).
+    pub fn request_withdrawal(ctx: Context<AdminOnly>, amount: u64) -> Result<()> {
+        let pool  = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
+        require!(pool.withdrawal_request.is_none(),  BlitzError::PendingWithdrawal);
+        require!(amount <= pool.total_balance / 5,   BlitzError::WithdrawalTooLarge);
+        pool.withdrawal_request = Some(WithdrawalRequest {
+            amount,
+            requested_at: clock.unix_timestamp,
+            unlocks_at:   clock.unix_timestamp + TIMELOCK_SECS,
+        });
+        emit!(WithdrawalRequested { amount, unlocks_at: clock.unix_timestamp + TIMELOCK_SECS });
+        bump_seq(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Executes a pending withdrawal after the 48h timelock.
+    pub fn execute_withdrawal(ctx: Context<AdminOnly>) -> Result<()> {
+        let pool  = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
+        let req   = pool.withdrawal_request.clone().ok_or(BlitzError::NoWithdrawalRequest)?;
+        require!(clock.unix_timestamp >= req.unlocks_at, BlitzError::TimelockActive);
+        require!(pool.total_balance >= req.amount,        BlitzError::InsufficientLiquidity);
+        burn_house_shares(pool, req.amount)?;
+        pool_transfer(&pool.to_account_info(), &ctx.accounts.authority.to_account_info(), req.amount)?;
+        pool.withdrawal_request = None;
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        emit!(WithdrawalExecuted { amount: req.amount });
+        Ok(())
+    }
+
+    /// @notice Cancels a pending withdrawal request.
+    pub fn cancel_withdrawal(ctx: Context<AdminOnly>) -> Result<()> {
+        ctx.accounts.pool.withdrawal_request = None;
+        bump_seq(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Withdraws the claimable portion of house fees.
+    /// @dev    Only `house_fees_earned` is withdrawable. Liquid pool balance is protected.
+    pub fn claim_house_fees(ctx: Context<ClaimHouseFeesCtx>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(amount > 0 && amount <= pool.house_fees_earned, BlitzError::InsufficientLiquidity);
+        pool_transfer(&pool.to_account_info(), &ctx.accounts.authority.to_account_info(), amount)?;
+        pool.house_fees_earned     = pool.house_fees_earned.saturating_sub(amount);
+        pool.operational_extracted = pool.operational_extracted.saturating_add(amount);
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        emit!(HouseFeesClaimed { amount, authority: ctx.accounts.authority.key() });
+        Ok(())
+    }
+
+    /// @notice Withdraws accrued LP performance fees.
+    /// @dev    Only `performance_fees` is withdrawable; it was set aside from LP profit at epoch close.
+    pub fn claim_performance_fee(ctx: Context<ClaimHouseFeesCtx>, amount: u64) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(amount > 0 && amount <= pool.performance_fees, BlitzError::InsufficientLiquidity);
+        pool_transfer(&pool.to_account_info(), &ctx.accounts.authority.to_account_info(), amount)?;
+        pool.performance_fees = pool.performance_fees.saturating_sub(amount);
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        emit!(PerformanceFeeClaimed { pool: pool.key(), amount, authority: ctx.accounts.authority.key() });
+        Ok(())
+    }
+
+    /// @notice Initiates a request to reinvest house fees back to the liquid pool (24h timelock).
+    pub fn request_reinvest(ctx: Context<AdminOnly>, amount: u64) -> Result<()> {
+        let pool  = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
+        require!(amount > 0 && amount <= pool.house_fees_earned, BlitzError::InsufficientLiquidity);
+        require!(pool.reinvest_request.is_none(),  BlitzError::PendingWithdrawal);
+        pool.reinvest_request = Some(WithdrawalRequest {
+            amount,
+            requested_at: clock.unix_timestamp,
+            unlocks_at:   clock.unix_timestamp + MAX_PAUSE_DURATION, // 24h delay
+        });
+        emit!(ReinvestRequested { amount, unlocks_at: clock.unix_timestamp + MAX_PAUSE_DURATION });
+        bump_seq(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Executes a pending house fee reinvestment after the 24h timelock.
+    /// @dev    Purely internal accounting — no lamports leave the account.
+    pub fn execute_reinvest(ctx: Context<AdminOnly>) -> Result<()> {
+        let pool  = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
+        let req   = pool.reinvest_request.clone().ok_or(BlitzError::NoWithdrawalRequest)?;
+        require!(clock.unix_timestamp >= req.unlocks_at, BlitzError::TimelockActive);
+        require!(req.amount <= pool.house_fees_earned, BlitzError::InsufficientLiquidity);
+        mint_house_shares(pool, req.amount)?;
+        pool.house_fees_earned = pool.house_fees_earned.saturating_sub(req.amount);
+        pool.total_reinvested  = pool.total_reinvested.saturating_add(req.amount);
+        pool.reinvest_request  = None;
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        emit!(ReinvestExecuted { amount: req.amount });
+        Ok(())
+    }
+
+    // ── Insurance reserve ────────────────────────────────────────────────
+
+    /// @notice Sets the insurance reserve policy: `bps` of every bet is routed from the house
+    ///         cut into insurance_balance, and the reserve may only be tapped while
+    ///         total_balance is below `threshold`.
+    pub fn set_insurance_policy(ctx: Context<AdminOnly>, bps: u16, threshold: u64) -> Result<()> {
+        require!(bps <= INSURANCE_MAX_BPS, BlitzError::InvalidInsurancePolicy);
+        let pool = &mut ctx.accounts.pool;
+        pool.insurance_bps       = bps;
+        pool.insurance_threshold = threshold;
+        emit!(InsurancePolicyUpdated { pool: pool.key(), bps, threshold });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Requests moving `amount` of the insurance reserve into the liquid pool
+    ///         (INSURANCE_TAP_SECS timelock). Only while total_balance is below the threshold.
+    pub fn request_insurance_tap(ctx: Context<AdminOnly>, amount: u64) -> Result<()> {
+        let pool  = &mut ctx.accounts.pool;
+        let clock = Clock::get()?;
+        require!(pool.total_balance < pool.insurance_threshold,         BlitzError::InsuranceNotNeeded);
+        require!(amount > 0 && amount <= pool.insurance_balance,         BlitzError::InsufficientLiquidity);
+        require!(pool.insurance_tap.is_none(),                           BlitzError::PendingWithdrawal);
+        let unlocks_at = clock.unix_timestamp + INSURANCE_TAP_SECS;
+        pool.insurance_tap = Some(WithdrawalRequest { amount, requested_at: clock.unix_timestamp, unlocks_at });
+        emit!(InsuranceTapRequested { pool: pool.key(), amount, unlocks_at });
+        bump_seq(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Executes a pending insurance tap once its timelock has passed, if total_balance
+    ///         is still below the threshold.
+    /// @dev    Internal accounting only — no lamports leave the account. No shares are minted:
+    ///         the reserve backs the book it insures, so every outstanding share — LP and house
+    ///         alike — gains from the tap. This also works on a drained pool, where there is no
+    ///         share price left to mint at.
+    pub fn tap_insurance(ctx: Context<AdminOnly>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        let req  = pool.insurance_tap.clone().ok_or(BlitzError::NoWithdrawalRequest)?;
+        require!(Clock::get()?.unix_timestamp >= req.unlocks_at,  BlitzError::TimelockActive);
+        require!(pool.total_balance < pool.insurance_threshold,   BlitzError::InsuranceNotNeeded);
+        require!(req.amount <= pool.insurance_balance,            BlitzError::InsufficientLiquidity);
+        pool.insurance_balance = pool.insurance_balance.saturating_sub(req.amount);
+        pool.insurance_tap     = None;
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+        emit!(InsuranceTapped { pool: pool.key(), amount: req.amount, total_balance: pool.total_balance });
+        Ok(())
+    }
+
+    /// @notice Cancels a pending insurance tap.
+    pub fn cancel_insurance_tap(ctx: Context<AdminOnly>) -> Result<()> {
+        ctx.accounts.pool.insurance_tap = None;
+        bump_seq(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    // ── Liquidity Migration between rooms (48h timelock) ─────────────────
+
+    /// @notice Requests moving `amount` of liquid balance from one room pool to another.
+    /// @dev    Same 48h timelock and 20% cap as withdrawals — capital never touches a wallet.
+    pub fn request_liquidity_migration(ctx: Context<MigrateLiquidity>, amount: u64) -> Result<()> {
+        let to    = ctx.accounts.to_pool.key();
+        let from  = &mut ctx.accounts.from_pool;
+        let clock = Clock::get()?;
+        require!(from.key() != to,                  BlitzError::InvalidRoom);
+        require!(from.migration_request.is_none(),  BlitzError::PendingWithdrawal);
+        require!(amount > 0 && amount <= from.total_balance / 5, BlitzError::WithdrawalTooLarge);
+        from.migration_request = Some(LiquidityMigration {
+            to,
+            amount,
+            requested_at: clock.unix_timestamp,
+            unlocks_at:   clock.unix_timestamp + TIMELOCK_SECS,
+        });
+        emit!(LiquidityMigrationRequested { from: from.key(), to, amount, unlocks_at: clock.unix_timestamp + TIMELOCK_SECS });
+        bump_seq(&mut ctx.accounts.from_pool);
+        Ok(())
+    }
+
+    /// @notice Executes a pending migration after the timelock.
+    /// @dev    Invariants: source keeps its reserved compartments intact (sync_balance),
+    ///         destination is re-synced so the lamports land as liquid balance.
+    pub fn execute_liquidity_migration(ctx: Context<MigrateLiquidity>) -> Result<()> {
+        let req = ctx.accounts.from_pool.migration_request.clone().ok_or(BlitzError::NoWithdrawalRequest)?;
+        require!(req.to == ctx.accounts.to_pool.key(),             BlitzError::InvalidRoom);
+        require!(Clock::get()?.unix_timestamp >= req.unlocks_at,   BlitzError::TimelockActive);
+        require!(ctx.accounts.from_pool.total_balance >= req.amount, BlitzError::InsufficientLiquidity);
+        burn_house_shares(&mut ctx.accounts.from_pool, req.amount)?;
+        mint_house_shares(&mut ctx.accounts.to_pool, req.amount)?;
+        let from_ai = ctx.accounts.from_pool.to_account_info();
+        let to_ai   = ctx.accounts.to_pool.to_account_info();
+        pool_transfer(&from_ai, &to_ai, req.amount)?;
+        ctx.accounts.from_pool.migration_request = None;
+        sync_balance(&mut ctx.accounts.from_pool, &from_ai)?;
+        sync_balance(&mut ctx.accounts.to_pool,   &to_ai)?;
+        emit!(LiquidityMigrated { from: from_ai.key(), to: to_ai.key(), amount: req.amount });
+        Ok(())
+    }
+
+    /// @notice Cancels a pending liquidity migration.
+    pub fn cancel_liquidity_migration(ctx: Context<AdminOnly>) -> Result<()> {
+        require!(ctx.accounts.pool.migration_request.is_some(), BlitzError::NoWithdrawalRequest);
+        ctx.accounts.pool.migration_request = None;
+        emit!(LiquidityMigrationCancelled { from: ctx.accounts.pool.key() });
+        bump_seq(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    // ── Authority Transfer (72h timelock, new key must co-sign) ──────────
+
+    /// @notice Proposes a new authority (72h timelock + new key must confirm).
+    pub fn propose_authority_transfer(ctx: Context<AdminOnly>, new_authority: Pubkey) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(new_authority != pool.authority && new_authority != Pubkey::default(), BlitzError::InvalidAuthority);
+        let now = Clock::get()?.unix_timestamp;
+        pool.pending_authority     = Some(new_authority);
+        pool.authority_transfer_at = now + AUTH_TIMELOCK;
+        emit!(AuthorityTransferProposed { current: pool.authority, proposed: new_authority, unlocks_at: pool.authority_transfer_at });
+        bump_seq(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Cancels a pending authority transfer.
+    pub fn cancel_authority_transfer(ctx: Context<AdminOnly>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        require!(pool.pending_authority.is_some(), BlitzError::NoWithdrawalRequest);
+        pool.pending_authority     = None;
+        pool.authority_transfer_at = 0;
+        emit!(AuthorityTransferCancelled { authority: pool.authority });
+        bump_seq(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Finalises authority transfer. New authority must sign to confirm.
+    pub fn execute_authority_transfer(ctx: Context<ExecuteAuthorityTransfer>) -> Result<()> {
+        let pool     = &mut ctx.accounts.pool;
+        let new_auth = pool.pending_authority.ok_or(BlitzError::NoWithdrawalRequest)?;
+        require!(ctx.accounts.new_authority.key() == new_auth,          BlitzError::InvalidAuthority);
+        require!(Clock::get()?.unix_timestamp >= pool.authority_transfer_at, BlitzError::TimelockActive);
+        let old        = pool.authority;
+        pool.authority = new_auth;
+        pool.pending_authority     = None;
+        pool.authority_transfer_at = 0;
+        emit!(AuthorityTransferred { old_authority: old, new_authority: new_auth });
+        bump_seq(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    // ── One-time migration (remove after upgrade) ────────────────────────
+
+    /// @notice Expands GlobalPool from old layout to new layout (adds reinvest_request and later trailing fields).
+    /// @dev    Call once per pool after program upgrade. New trailing fields are zeroed (room 0 = main);
+    ///         lifetime counters are seeded from the legacy u64 totals the first time.
+    pub fn migrate_pool(ctx: Context<MigratePool>) -> Result<()> {
+        let pool = &mut ctx.accounts.pool;
+        pool.reinvest_request = None;
+        if pool.lifetime_wagered == 0 {
+            pool.lifetime_wagered     = pool.total_wagered as u128;
+            pool.lifetime_paid_out    = pool.total_paid_out as u128;
+            pool.lifetime_jackpot_won = pool.total_jackpot_won as u128;
+        }
+        bump_seq(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    /// @notice Grows a GameSession opened by the pre-room binary (LEGACY_SESSION_LEN) to the
+    ///         current layout, so it can be revealed, forfeited or refunded again.
+    /// @dev    Gated on the legacy length: a session already on the current layout is refused.
+    ///         Every pre-room session sits in the main pool. Fields the old binary never had get
+    ///         the values place_bet would have written — terms from the pool as it stands now,
+    ///         fee pin left unset — and an unsettled stake is added back to open_stakes, which
+    ///         migrate_pool started at zero. The payer covers the extra rent.
+    ///         Terms are pinned when this runs, so only the session's player or the pool
+    ///         authority may run it: nobody else can choose the moment for them.
+    pub fn migrate_session(ctx: Context<MigrateSession>) -> Result<()> {
+        let ai = ctx.accounts.session.to_account_info();
+        {
+            let data = ai.try_borrow_data()?;
+            require!(data.len() == 8 + LEGACY_SESSION_LEN && data[..8] == GameSession::DISCRIMINATOR,
+                     BlitzError::NotLegacySession);
+            let player = Pubkey::try_from(&data[8..40]).map_err(|_| BlitzError::NotLegacySession)?;
+            let payer  = ctx.accounts.payer.key();
+            require!(payer == player || payer == ctx.accounts.pool.authority, BlitzError::MigrationNotAuthorized);
+        }
+        let space = 8 + GameSession::LEN;
+        let topup = Rent::get()?.minimum_balance(space).saturating_sub(ai.lamports());
+        if topup > 0 {
+            invoke(
+                &system_instruction::transfer(&ctx.accounts.payer.key(), ai.key, topup),
+                &[ctx.accounts.payer.to_account_info(), ai.clone()],
+            )?;
+        }
+        ai.realloc(space, true)?;
+
+        let pool_key = ctx.accounts.pool.key();
+        let pool     = &mut ctx.accounts.pool;
+        let mut s    = GameSession::try_deserialize(&mut &ai.try_borrow_data()?[..])?;
+        migrate_legacy_session(pool, pool_key, &mut s);
+        s.try_serialize(&mut &mut ai.try_borrow_mut_data()?[..])?;
+        bump_seq(pool);
+        Ok(())
+    }
+
+    /// @notice Marks the pool as running the deployed binary and re-enables money movement.
+    /// @dev    Run after migrate_pool and any config updates the release notes call for.
+    ///         Until then every money-moving instruction fails with UpgradeNotFinalized
+    ///         (require_current_version). Refuses to record a version older than the stored one.
+    pub fn finalize_upgrade(ctx: Context<AdminOnly>) -> Result<()> {
+        let from = ctx.accounts.pool.program_version;
+        require!(from < PROGRAM_VERSION, BlitzError::UpgradeAlreadyFinalized);
+        ctx.accounts.pool.program_version = PROGRAM_VERSION;
+        emit!(UpgradeFinalized { pool: ctx.accounts.pool.key(), from, to: PROGRAM_VERSION });
+        bump_config(&mut ctx.accounts.pool);
+        Ok(())
+    }
+
+    // ── Build attestation ────────────────────────────────────────────────
+
+    /// @notice Records which source commit the deployed bytecode was built from.
+    /// @param commit_hash Git commit (SHA-1) of the released source tree.
+    /// @param build_hash  Verifiable-build hash of the deployed executable (solana-verify).
+    /// @dev    Main-pool authority only. An optional third-party verifier co-signs after
+    ///         reproducing the build; its key is stored so players can tell a self-attested
+    ///         build from an independently verified one. Overwritten on every upgrade.
+    pub fn attest_build(ctx: Context<AttestBuild>, commit_hash: [u8; 20], build_hash: [u8; 32]) -> Result<()> {
+        let verifier = ctx.accounts.verifier.as_ref().map(|v| v.key()).unwrap_or_default();
+        let a        = &mut ctx.accounts.build_attestation;
+        a.commit_hash     = commit_hash;
+        a.build_hash      = build_hash;
+        a.schema_version  = SCHEMA_VERSION;
+        a.program_version = PROGRAM_VERSION;
+        a.attested_by     = ctx.accounts.authority.key();
+        a.verifier        = verifier;
+        a.attested_slot   = Clock::get()?.slot;
+        a.bump            = ctx.bumps.build_attestation;
+        emit!(BuildAttested { commit_hash, build_hash, program_version: PROGRAM_VERSION, verifier });
+        Ok(())
+    }
+
+    // ── Pool metadata (48h timelock) ─────────────────────────────────────
+
+    /// @notice Stages new branding for the room's PoolMetadata PDA [b"pool_metadata", pool].
+    ///         Front-ends and aggregators read pools and their branding straight from chain.
+    /// @dev    Goes live via apply_pool_metadata after TIMELOCK_SECS, so a compromised authority
+    ///         cannot silently repoint a pool's operator URL. Replaces any staged update.
+    pub fn propose_pool_metadata(ctx: Context<ProposePoolMetadata>, branding: PoolBranding) -> Result<()> {
+        require!(branding.is_valid(), BlitzError::InvalidPoolMetadata);
+        let unlocks_at = Clock::get()?.unix_timestamp + TIMELOCK_SECS;
+        let m          = &mut ctx.accounts.pool_metadata;
+        m.pool         = ctx.accounts.pool.key();
+        m.pending      = branding.clone();
+        m.has_pending  = true;
+        m.unlocks_at   = unlocks_at;
+        m.bump         = ctx.bumps.pool_metadata;
+        emit!(PoolMetadataProposed { pool: m.pool, branding, unlocks_at });
+        Ok(())
+    }
+
+    /// @notice Permissionless once the timelock has passed: makes the staged branding live.
+    pub fn apply_pool_metadata(ctx: Context<ApplyPoolMetadata>) -> Result<()> {
+        let m = &mut ctx.accounts.pool_metadata;
+        let now = Clock::get()?.unix_timestamp;
+        require!(m.has_pending,       BlitzError::NoPendingPoolMetadata);
+        require!(now >= m.unlocks_at, BlitzError::TimelockActive);
+        m.live        = m.pending.clone();
+        m.has_pending = false;
+        m.unlocks_at  = 0;
+        m.updated_at  = now;
+        emit!(PoolMetadataUpdated { pool: m.pool, branding: m.live.clone() });
+        Ok(())
+    }
+
+    /// @notice Drops a staged branding update.
+    pub fn cancel_pool_metadata(ctx: Context<CancelPoolMetadata>) -> Result<()> {
+        let m = &mut ctx.accounts.pool_metadata;
+        require!(m.has_pending, BlitzError::NoPendingPoolMetadata);
+        m.has_pending = false;
+        m.unlocks_at  = 0;
+        emit!(PoolMetadataCancelled { pool: m.pool });
+        Ok(())
+    }
+
+    // ── Schema registry ──────────────────────────────────────────────────
+
+    /// @notice Publishes the deployed binary's SCHEMA_VERSION and layout hash to the registry PDA.
+    /// @dev    Permissionless — the values are compiled in, so any caller (the upgrade script,
+    ///         an indexer) can sync them after an upgrade. No-op when nothing changed.
+    pub fn sync_schema(ctx: Context<SyncSchema>) -> Result<()> {
+        let reg  = &mut ctx.accounts.schema_registry;
+        let hash = schema_hash();
+        if reg.version == SCHEMA_VERSION && reg.layout_hash == hash { return Ok(()); }
+        require!(reg.version <= SCHEMA_VERSION, BlitzError::SchemaDowngrade);
+        let previous_hash = reg.layout_hash;
+        reg.version       = SCHEMA_VERSION;
+        reg.layout_hash   = hash;
+        reg.updated_slot  = Clock::get()?.slot;
+        reg.bump          = ctx.bumps.schema_registry;
+        emit!(SchemaUpdated { version: SCHEMA_VERSION, layout_hash: hash, previous_hash });
+        Ok(())
+    }
+
+    // ── Devnet QA tooling (--features devnet) ────────────────────────────
+
+    /// @notice Fabricates a reproducible UI state in one call: funds the pool, overwrites
+    ///         jackpot/fee/analytics fields, optionally pauses it, queues a withdrawal and
+    ///         creates one already-forfeitable session owned by the authority.
+    /// @dev    Only `devnet` builds compile the fabricating body and its accounts. Anchor 0.30's
+    ///         dispatch ignores `#[cfg]` on handlers, so other builds keep a stub that takes only a
+    ///         signer and always fails with DevnetOnly.
+    ///         Reserved compartments must still fit the physical balance — sync_balance rejects
+    ///         an impossible state with AccountingBroken.
+    pub fn dev_seed_state(ctx: Context<DevSeedState>, params: DevSeedParams) -> Result<()> {
+        dev_seed_apply(ctx, params)
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════
+//  PRIVATE HELPERS
+// ══════════════════════════════════════════════════════════════════════════
+
+// ── Session guard ─────────────────────────────────────────────────────────
+
+#[inline]
+fn check_session_token(token: &Account<SessionToken>) -> Result<()> {
+    require!(Clock::get()?.unix_timestamp < token.expires_at, BlitzError::SessionExpired);
+    Ok(())
+}
+
+/// Message a player signs off-chain to authorize a session key:
+/// SESSION_AUTH_DOMAIN || program id || player || delegate || expires_at (LE) || scope || nonce (LE).
+pub fn session_auth_message(player: &Pubkey, delegate: &Pubkey, expires_at: i64, scope: u8, nonce: u64) -> Vec<u8> {
+    let mut m = Vec::with_capacity(SESSION_AUTH_DOMAIN.len() + 32 * 3 + 17);
+    m.extend_from_slice(SESSION_AUTH_DOMAIN);
+    m.extend_from_slice(crate::ID.as_ref());
+    m.extend_from_slice(player.as_ref());
+    m.extend_from_slice(delegate.as_ref());
+    m.extend_from_slice(&expires_at.to_le_bytes());
+    m.push(scope);
+    m.extend_from_slice(&nonce.to_le_bytes());
+    m
+}
+
+/// Message the promo attestor signs off-chain to issue a voucher:
+/// PROMO_VOUCHER_DOMAIN || program id || pool || player || amount (LE) || expires_at (LE) || campaign_id (LE).
+pub fn promo_voucher_message(pool: &Pubkey, player: &Pubkey, amount: u64, expires_at: i64, campaign_id: u64) -> Vec<u8> {
+    let mut m = Vec::with_capacity(PROMO_VOUCHER_DOMAIN.len() + 32 * 3 + 24);
+    m.extend_from_slice(PROMO_VOUCHER_DOMAIN);
+    m.extend_from_slice(crate::ID.as_ref());
+    m.extend_from_slice(pool.as_ref());
+    m.extend_from_slice(player.as_ref());
+    m.extend_from_slice(&amount.to_le_bytes());
+    m.extend_from_slice(&expires_at.to_le_bytes());
+    m.extend_from_slice(&campaign_id.to_le_bytes());
+    m
+}
+
+/// Checks that the previous instruction is a single-signature Ed25519 verify of `message`
+/// by `signer`, with all offsets pointing into its own data. The precompile itself has
+/// already rejected the transaction if the signature is bad.
+fn verify_ed25519_ix(ixs: &AccountInfo, signer: &Pubkey, message: &[u8]) -> Result<()> {
+    let ix = ix_sysvar::get_instruction_relative(-1, ixs).map_err(|_| BlitzError::InvalidSessionSignature)?;
+    require!(ix.program_id == ed25519_program::ID, BlitzError::InvalidSessionSignature);
+    let d = &ix.data;
+    require!(d.len() >= 16 && d[0] == 1,            BlitzError::InvalidSessionSignature);
+    let u16_at = |o: usize| u16::from_le_bytes([d[o], d[o + 1]]);
+    // Ed25519SignatureOffsets: sig, sig_ix, pubkey, pubkey_ix, msg, msg_len, msg_ix
+    let (pk, msg, len) = (u16_at(6) as usize, u16_at(10) as usize, u16_at(12) as usize);
+    require!(
+        u16_at(4) == u16::MAX && u16_at(8) == u16::MAX && u16_at(14) == u16::MAX,
+        BlitzError::InvalidSessionSignature
+    );
+    require!(d.len() >= pk + 32 && d.len() >= msg + len,      BlitzError::InvalidSessionSignature);
+    require!(&d[pk..pk + 32] == signer.as_ref(),              BlitzError::InvalidSessionSignature);
+    require!(&d[msg..msg + len] == message,                   BlitzError::InvalidSessionSignature);
+    Ok(())
+}
+
+// ── Devnet state seeding ──────────────────────────────────────────────────
+
+#[cfg(feature = "devnet")]
+fn dev_seed_apply(ctx: Context<DevSeedState>, params: DevSeedParams) -> Result<()> {
+    let clock = Clock::get()?;
+    if params.fund_lamports > 0 {
+        invoke(
+            &system_instruction::transfer(ctx.accounts.authority.key, &ctx.accounts.pool.key(), params.fund_lamports),
+            &[ctx.accounts.authority.to_account_info(), ctx.accounts.pool.to_account_info()],
+        )?;
+    }
+    let pool = &mut ctx.accounts.pool;
+    pool.jackpot_balance   = params.jackpot_balance;
+    pool.house_fees_earned = params.house_fees_earned;
+    pool.total_wagered     = params.total_wagered;
+    pool.total_bets        = params.total_bets;
+    pool.total_wins        = params.total_wins;
+    pool.biggest_win       = params.biggest_win;
+    pool.total_paid_out    = params.total_paid_out;
+    pool.lifetime_wagered  = params.total_wagered as u128;
+    pool.lifetime_paid_out = params.total_paid_out as u128;
+    pool.paused            = params.paused;
+    pool.pause_expires_at  = if params.paused { clock.unix_timestamp + MAX_PAUSE_DURATION } else { 0 };
+    pool.withdrawal_request = (params.pending_withdrawal > 0).then(|| WithdrawalRequest {
+        amount:       params.pending_withdrawal,
+        requested_at: clock.unix_timestamp,
+        unlocks_at:   clock.unix_timestamp + TIMELOCK_SECS,
+    });
+    let pool_key = pool.key();
+    let pool_ai  = pool.to_account_info();
+    sync_balance(pool, &pool_ai)?;
+    let (num, curve) = (payout_num(pool.total_balance), pool.curve());
+
+    if let Some(s) = ctx.accounts.session.as_mut() {
+        let commit_slot = clock.slot.saturating_sub(REVEAL_WINDOW + 300);
+        s.player         = ctx.accounts.authority.key();
+        s.referrer       = system_program::ID;
+        s.bet_lamports   = params.expired_session_bet;
+        s.commitment     = params.session_commitment;
+        s.commit_slot    = commit_slot;
+        s.resolve_slot   = get_resolve_slot(commit_slot, params.expired_session_bet);
+        s.forfeit_slot   = commit_slot + REVEAL_WINDOW;
+        s.game_type      = 0;
+        s.game_state     = 0;
+        s.bump           = ctx.bumps.session.unwrap_or_default();
+        s.pool           = pool_key;
+        s.index_page     = NOT_INDEXED;
+        s.payout_to      = s.player;
+        s.payout_num_bps = num as u16;
+        s.payout_curve   = curve;
+        s.fee_bps        = pin_fee_bps(pool.total_balance, false);
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "devnet"))]
+fn dev_seed_apply(_ctx: Context<DevSeedState>, _params: DevSeedParams) -> Result<()> {
+    err!(BlitzError::DevnetOnly)
+}
+
+// ── Pause expiry ──────────────────────────────────────────────────────────
+
+/// Auto-expires a forgotten pause (player-protective, no owner action needed).
+#[inline]
+fn expire_pause(pool: &mut GlobalPool, now: i64) {
+    if pool.paused && now >= pool.pause_expires_at {
+        pool.paused           = false;
+        pool.pause_expires_at = 0;
+        pool.config_seq       = pool.config_seq.wrapping_add(1);
+    }
+}
+
+// ── Bet placement ─────────────────────────────────────────────────────────
+
+/// Accounts of one placement — borrowed from the placement instruction's context.
+struct BetAccounts<'a, 'info> {
+    player:        &'a Signer<'info>,
+    referrer:      &'a AccountInfo<'info>,
+    pool:          &'a mut Account<'info, GlobalPool>,
+    session:       &'a mut Account<'info, GameSession>,
+    session_bump:  u8,
+    instructions:  Option<&'a UncheckedAccount<'info>>,
+    payout_wallet: Option<&'a UncheckedAccount<'info>>,
+    partner:       Option<&'a mut Account<'info, Partner>>,
+    player_sketch: Option<&'a mut Box<Account<'info, PlayerSketch>>>,
+    player_stats:  Option<&'a mut Box<Account<'info, PlayerStats>>>,
+    open_sessions: Option<&'a mut Box<Account<'info, OpenSessionPage>>>,
+    vault:         Option<&'a mut Box<Account<'info, PlayerVault>>>,
+    sponsor_grant: Option<&'a mut Box<Account<'info, SponsorGrant>>>,
+    referral:      Option<&'a Account<'info, ReferralAccount>>,
+}
+
+/// Shared body of place_bet / place_bet_v2 / place_bet_v3.
+fn place_bet_inner(
+    ctx:          Context<PlaceBet>,
+    game_type:    u8,
+    commitment:   [u8; 32],
+    bet_lamports: u64,
+    game_config:  [u8; 3],
+    config_ext:   u64,
+    flags:        u8,
+) -> Result<()> {
+    require!(game_type != 22, BlitzError::InvalidParlay);
+    require!(game_type != 23, BlitzError::InvalidGameType);
+    let a = ctx.accounts;
+    place_bet_with(BetAccounts {
+        player:        &a.player,
+        referrer:      &a.referrer,
+        pool:          &mut a.pool,
+        session:       &mut a.session,
+        session_bump:  ctx.bumps.session,
+        instructions:  a.instructions.as_ref(),
+        payout_wallet: a.payout_wallet.as_ref(),
+        partner:       a.partner.as_mut(),
+        player_sketch: a.player_sketch.as_mut(),
+        player_stats:  a.player_stats.as_mut(),
+        open_sessions: a.open_sessions.as_mut(),
+        vault:         None,
+        sponsor_grant: None,
+        referral:      a.referral.as_deref(),
+    }, game_type, commitment, bet_lamports, game_config, config_ext, flags)
+}
+
+/// Placement itself: limits, session init, deposit, counters. Records the bet in PlayerStats
+/// for replay_last_bet when passed.
+fn place_bet_with(
+    a:            BetAccounts,
+    game_type:    u8,
+    commitment:   [u8; 32],
+    bet_lamports: u64,
+    game_config:  [u8; 3],
+    config_ext:   u64,
+    flags:        u8,
+) -> Result<()> {
+    let clock      = Clock::get()?;
+    let pool       = a.pool;
+    let player_key = a.player.key();
+
+    expire_pause(pool, clock.unix_timestamp);
+    require!(!pool.paused, BlitzError::ContractPaused);
+    promo_gate(pool, a.player_stats.is_some())?;
+    require!(flags & !(PLACE_FLAG_PUBLIC_REVEAL | PLACE_FLAG_DOWNSIZE | PLACE_FLAG_DOUBLE | PLACE_FLAG_CLIENT_SEED) == 0, BlitzError::InvalidBetFlags);
+    require!(flags & PLACE_FLAG_PUBLIC_REVEAL == 0 || !matches!(game_type, 6 | 12 | 19 | 21 | 22), BlitzError::InvalidBetFlags);
+    require!(flags & PLACE_FLAG_DOUBLE == 0        || !matches!(game_type, 6 | 12 | 19 | 21),      BlitzError::InvalidBetFlags);
+    require!(flags & PLACE_FLAG_CLIENT_SEED == 0   || !matches!(game_type, 6 | 12 | 19 | 21 | 22), BlitzError::InvalidBetFlags);
+    require!(flags & (PLACE_FLAG_CLIENT_SEED | PLACE_FLAG_PUBLIC_REVEAL) != (PLACE_FLAG_CLIENT_SEED | PLACE_FLAG_PUBLIC_REVEAL), BlitzError::InvalidBetFlags);
+    let requested    = bet_lamports;
+    let bet_lamports = if flags & PLACE_FLAG_DOWNSIZE != 0 {
+        requested.min(max_allowed_bet(pool, game_type, &game_config, config_ext))
+    } else {
+        requested
+    };
+    check_bet(pool, game_type, &game_config, config_ext, bet_lamports)?;
+    check_cpi_caller(pool, a.instructions)?;
+
+    let ref_key = a.referrer.key();
+    if ref_key != system_program::ID && ref_key != player_key {
+        require!(*a.referrer.owner == system_program::ID, BlitzError::InvalidReferrer);
+    }
+    let net_loss_ref = pool.referral_mode == REFERRAL_MODE_NET_LOSS && is_valid_referrer(ref_key, player_key);
+    require!(!net_loss_ref || a.referral.is_some(), BlitzError::ReferralAccountRequired);
+
+    let payout_to = match (a.payout_wallet, &a.vault) {
+        (Some(w), _) => {
+            require!(*w.owner == system_program::ID, BlitzError::InvalidPayoutWallet);
+            w.key()
+        }
+        (None, Some(v)) => v.key(),
+        (None, None)    => player_key,
+    };
+
+    let s           = a.session;
+    s.player         = player_key;
+    s.referrer       = ref_key;
+    s.net_loss_ref   = net_loss_ref;
+    s.bet_lamports   = bet_lamports;
+    s.commitment     = commitment;
+    s.commit_slot    = clock.slot;
+    s.resolve_slot   = get_resolve_slot(clock.slot, bet_lamports);
+    s.forfeit_slot   = clock.slot + REVEAL_WINDOW;
+    s.game_type      = game_type;
+    s.game_state     = 0;
+    s.target_x       = game_config[0];
+    s.target_y       = game_config[1];
+    s.target_radius  = game_config[2];
+    s.config_ext     = config_ext;
+    s.public_reveal  = flags & PLACE_FLAG_PUBLIC_REVEAL != 0;
+    s.double_offer   = flags & PLACE_FLAG_DOUBLE != 0;
+    s.client_seeded  = flags & PLACE_FLAG_CLIENT_SEED != 0;
+    s.payout_num_bps = payout_num(pool.total_balance) as u16;
+    s.payout_curve   = pool.curve();
+    s.fee_bps        = pin_fee_bps(pool.total_balance, is_valid_referrer(ref_key, player_key));
+    s.bump           = a.session_bump;
+    s.pool           = pool.key();
+    s.payout_to      = payout_to;
+
+    let pool_ai = pool.to_account_info();
+    if let Some(g) = a.sponsor_grant {
+        require!(sponsor_grant_allows(g, game_type, bet_lamports, clock.slot), BlitzError::SponsorGrantConstraint);
+        move_lamports(&g.to_account_info(), &pool_ai, bet_lamports)?;
+        g.amount       -= bet_lamports;
+        g.total_staked  = g.total_staked.saturating_add(bet_lamports);
+    } else if let Some(v) = a.vault {
+        let vault_ai = v.to_account_info();
+        require!(vault_free(&vault_ai)? >= bet_lamports, BlitzError::InsufficientVaultBalance);
+        move_lamports(&vault_ai, &pool_ai, bet_lamports)?;
+        v.total_wagered = v.total_wagered.saturating_add(bet_lamports);
+    } else {
+        invoke(
+            &system_instruction::transfer(&player_key, pool_ai.key, bet_lamports),
+            &[a.player.to_account_info(), pool_ai.clone()],
+        )?;
+    }
+    sync_balance(pool, &pool_ai)?;
+    pool.open_stakes      = pool.open_stakes.saturating_add(bet_lamports);
+    pool.total_wagered    = pool.total_wagered.saturating_add(bet_lamports);
+    pool.total_bets       = pool.total_bets.saturating_add(1);
+    pool.lifetime_wagered = add_u128(pool.lifetime_wagered, bet_lamports)?;
+    pool.record_game_bet(game_type, bet_lamports);
+
+    s.partner = a.partner.is_some();
+    if let Some(partner) = a.partner {
+        partner.total_bets    = partner.total_bets.saturating_add(1);
+        partner.total_wagered = add_u128(partner.total_wagered, bet_lamports)?;
+    }
+    if let Some(sketch) = a.player_sketch {
+        sketch.observe(&player_key, clock.epoch);
+    }
+    if let Some(stats) = a.player_stats {
+        stats.total_bets    = stats.total_bets.saturating_add(1);
+        stats.total_wagered = add_u128(stats.total_wagered, bet_lamports)?;
+        if game_type != 22 { // a parlay's legs live outside the session — not replayable
+            stats.last_game_type    = game_type;
+            stats.last_config       = game_config;
+            stats.last_config_ext   = config_ext;
+            stats.last_bet_lamports = bet_lamports;
+            stats.last_flags        = flags;
+        }
+        if !stats.first_bet_used {
+            stats.first_bet_used  = true;
+            s.insured_amount      = promo_cover(pool, stats, bet_lamports);
+            pool.promo_committed += s.insured_amount;
+            if s.insured_amount > 0 {
+                emit!(FirstBetInsured { player: player_key, amount: s.insured_amount });
+            }
+        }
+    }
+    index_session(pool, a.open_sessions, s)?;
+
+    emit!(BetPlaced {
+        player:         s.player,
+        game_type,
+        amount:         bet_lamports,
+        resolve_slot:   s.resolve_slot,
+        forfeit_slot:   s.forfeit_slot,
+        grace_deadline: s.forfeit_slot + FORFEIT_GRACE_SLOTS,
+        config_seq:     pool.config_seq,
+    });
+    if bet_lamports < requested {
+        emit!(BetDownsized { player: s.player, session: s.key(), requested, placed: bet_lamports });
+    }
+    Ok(())
+}
+
+/// Creates the session PDA [b"session", player, commitment] passed in `ai` — what `init`
+/// does for a single placement — and returns it ready for place_bet_with, with its bump.
+fn create_session_pda<'info>(
+    player:     &Signer<'info>,
+    system:     &Program<'info, System>,
+    ai:         &'info AccountInfo<'info>,
+    commitment: &[u8; 32],
+) -> Result<(Account<'info, GameSession>, u8)> {
+    let player_key = player.key();
+    let (key, bump) = Pubkey::find_program_address(&[b"session", player_key.as_ref(), commitment], &crate::ID);
+    require!(ai.key() == key && ai.is_writable && ai.lamports() == 0, BlitzError::InvalidBatchSession);
+    let space = 8 + GameSession::LEN;
+    invoke_signed(
+        &system_instruction::create_account(&player_key, &key, Rent::get()?.minimum_balance(space), space as u64, &crate::ID),
+        &[player.to_account_info(), ai.clone(), system.to_account_info()],
+        &[&[b"session", player_key.as_ref(), commitment, &[bump]]],
+    )?;
+    Ok((Account::try_from_unchecked(ai)?, bump))
+}
+
+// ── LP shares ─────────────────────────────────────────────────────────────
+
+/// @notice Net asset value LP shares are priced against: total_balance less the stakes of
+///         unsettled bets, which still belong to their sessions. Counting a stake as pool money
+///         before its bet settles would let a deposit buy in ahead of pending wins.
+pub fn lp_nav(pool: &GlobalPool) -> u64 {
+    pool.total_balance.saturating_sub(pool.open_stakes)
+}
+
+/// Writes off a book whose shares are worth less than LP_WRITEOFF_PRICE: every share is
+/// cancelled and lp_generation moves on, so positions from the old book read as empty and the
+/// exit queue restarts. Nothing is done while bets are open — their settlement can still
+/// restore the book. Without this a drained pool could never price a deposit again.
+fn lp_writeoff(pool: &mut Account<GlobalPool>) {
+    if pool.lp_shares == 0 || pool.open_stakes > 0 || lp_share_price(lp_nav(pool), pool.lp_shares) >= LP_WRITEOFF_PRICE {
+        return;
+    }
+    emit!(LpBookWrittenOff { pool: pool.key(), generation: pool.lp_generation, shares: pool.lp_shares, nav: lp_nav(pool) });
+    pool.lp_shares         = 0;
+    pool.lp_outside_shares = 0;
+    pool.lp_hwm_price      = 0;
+    pool.lp_queue_head     = pool.lp_queue_tail;
+    pool.lp_generation     = pool.lp_generation.wrapping_add(1);
+}
+
+/// Empties a position left over from a written-off generation before it is used.
+fn lp_position_sync(pool: &GlobalPool, p: &mut LpPosition) {
+    if p.generation != pool.lp_generation {
+        p.shares        = 0;
+        p.queued_shares = 0;
+        p.generation    = pool.lp_generation;
+    }
+}
+
+/// @notice Shares `amount` lamports buy from a pool of `balance` liquid lamports with `shares`
+///         outstanding. 1:1 into an empty book; None if shares exist but back nothing.
+pub fn lp_shares_for(amount: u64, balance: u64, shares: u64) -> Option<u64> {
+    if shares == 0 { return Some(amount); }
+    fixed::mul_div_floor(amount, shares, balance)
+}
+
+/// @notice Lamports `burn` shares redeem from a pool of `balance` with `shares` outstanding.
+pub fn lp_value_of(burn: u64, balance: u64, shares: u64) -> u64 {
+    fixed::mul_div_floor(burn, balance, shares).unwrap_or(0)
+}
+
+/// @notice Lamports LP_PRICE_SCALE shares are worth in a pool of `balance` with `shares` out.
+pub fn lp_share_price(balance: u64, shares: u64) -> u64 {
+    fixed::mul_div_floor(balance, LP_PRICE_SCALE, shares).unwrap_or(u64::MAX)
+}
+
+/// @notice Share price a deposit buys at under a NavSnapshot: the higher of the snapshot's and
+///         the live price with open stakes counted as the pool's. A deposit placed while a
+///         pending bet is known to lose therefore pays for that stake already, and one placed
+///         after a loss since the snapshot pays the pre-loss price.
+pub fn lp_deposit_price(pool: &GlobalPool, snap: &NavSnapshot) -> u64 {
+    let live = lp_share_price(pool.total_balance, pool.lp_shares);
+    if snap.generation == pool.lp_generation { live.max(snap.deposit_price) } else { live }
+}
+
+/// @notice Share price an exit is paid at under a NavSnapshot: the lower of the snapshot's and
+///         the live lp_nav price, which leaves open stakes out. An exit never collects a pending
+///         bet's stake, nor a gain made since the snapshot — that stays with the remaining shares.
+pub fn lp_exit_price(pool: &GlobalPool, snap: &NavSnapshot) -> u64 {
+    let live = lp_share_price(lp_nav(pool), pool.lp_shares);
+    if snap.generation == pool.lp_generation && snap.exit_price > 0 { live.min(snap.exit_price) } else { live }
+}
+
+/// Records the live deposit and exit prices on `n` (0 while the book has no shares).
+fn nav_snapshot_take(pool: &GlobalPool, n: &mut NavSnapshot, slot: u64) {
+    let priced      = pool.lp_shares > 0;
+    n.slot          = slot;
+    n.generation    = pool.lp_generation;
+    n.deposit_price = if priced { lp_share_price(pool.total_balance, pool.lp_shares) } else { 0 };
+    n.exit_price    = if priced { lp_share_price(lp_nav(pool), pool.lp_shares) } else { 0 };
+}
+
+/// Mints house shares for `amount` about to join total_balance, so house capital added after
+/// LPs arrive does not dilute into their positions. No-op before the first LP deposit.
+fn mint_house_shares(pool: &mut Account<GlobalPool>, amount: u64) -> Result<()> {
+    lp_writeoff(pool);
+    if pool.lp_shares == 0 { return Ok(()); }
+    let minted     = lp_shares_for(amount, lp_nav(pool), pool.lp_shares).ok_or(BlitzError::LpBookUnpriced)?;
+    pool.lp_shares = checked_add(pool.lp_shares, minted)?;
+    Ok(())
+}
+
+/// Moves `fee` lamports of LP value out of total_balance into performance_fees. Mints house
+/// shares so the house's own stake keeps its value and the whole fee falls on LP shares:
+/// with the fee worth f shares, the house gets house × f / (outside − f).
+fn charge_lp_fee(pool: &mut GlobalPool, fee: u64) -> Result<()> {
+    let f      = fixed::mul_div_floor(fee, pool.lp_shares, lp_nav(pool)).ok_or(BlitzError::MathOverflow)?;
+    let rest   = pool.lp_outside_shares.checked_sub(f).filter(|r| *r > 0).ok_or(BlitzError::MathOverflow)?;
+    let minted = fixed::mul_div_floor(pool.lp_shares - pool.lp_outside_shares, f, rest).ok_or(BlitzError::MathOverflow)?;
+    pool.lp_shares        = checked_add(pool.lp_shares, minted)?;
+    pool.performance_fees = checked_add(pool.performance_fees, fee)?;
+    Ok(())
+}
+
+/// Burns the house shares backing `amount` about to leave total_balance (rounded against the
+/// house). Fails if the house's own shares do not cover it — LP capital is not the house's.
+fn burn_house_shares(pool: &mut GlobalPool, amount: u64) -> Result<()> {
+    if pool.lp_shares == 0 { return Ok(()); }
+    let nav  = lp_nav(pool);
+    let keep = fixed::mul_div_floor(nav.saturating_sub(amount), pool.lp_shares, nav)
+        .ok_or(BlitzError::LpBookUnpriced)?;
+    let burn = pool.lp_shares - keep;
+    require!(burn <= pool.lp_shares - pool.lp_outside_shares, BlitzError::LpCapitalLocked);
+    pool.lp_shares = keep;
+    Ok(())
+}
+
+// ── Player vault ──────────────────────────────────────────────────────────
+
+/// Whether `g` may stake `bet` on `game_type` at `slot`: unexpired, enough escrow left, and
+/// within its game mask and max bet (0 = no limit for either).
+pub fn sponsor_grant_allows(g: &SponsorGrant, game_type: u8, bet: u64, slot: u64) -> bool {
+    let c = &g.constraints;
+    slot <= c.expires_slot
+        && bet <= g.amount
+        && (c.max_bet == 0 || bet <= c.max_bet)
+        && (c.games_mask == 0 || c.games_mask & (1 << game_type) != 0)
+}
+
+/// Lamports a PlayerVault can spend: everything above its rent-exempt minimum.
+fn vault_free(vault_ai: &AccountInfo) -> Result<u64> {
+    Ok(vault_ai.lamports().saturating_sub(Rent::get()?.minimum_balance(vault_ai.data_len())))
+}
+
+// ── Bet admission ─────────────────────────────────────────────────────────
+
+/// Every place_bet limit except pause and CPI checks. Shared with quote_bet.
+/// Returns the worst-case payout of the bet.
+fn check_bet(pool: &GlobalPool, game_type: u8, cfg: &[u8; 3], ext: u64, bet: u64) -> Result<u64> {
+    require!(pool.total_balance >= room_min_pool(pool.room),  BlitzError::PoolTooLow);
+    require!(bet >= room_min_bet(pool.room),                  BlitzError::BetTooSmall);
+    require!(game_type <= 23,                                 BlitzError::InvalidGameType);
+    require!(pool.paused_games() & (1 << game_type) == 0,     BlitzError::GamePaused);
+
+    validate_game_config(game_type, cfg, ext)?;
+    if game_type == 7 {
+        require!(u16::from_le_bytes([cfg[0], cfg[1]]) as u32 <= pool.curve().max_multiplier_x100, BlitzError::InvalidCrashTarget);
+    }
+    if game_type == 10 {
+        require!(ext <= pool.curve().max_multiplier_x100 as u64, BlitzError::InvalidLimboTarget);
+    }
+
+    let basis   = pool.game_basis(game_type);
+    let max_bet = get_room_max_bet(pool.room, basis, game_type, cfg, ext);
+    let worst   = get_worst_payout(bet, game_type, cfg, ext);
+    let refusal = if bet > max_bet {
+        Some(BlitzError::BetExceedsLimit)
+    } else if worst > get_room_payout_cap(pool.room, basis) {
+        Some(BlitzError::CapWouldTruncatePayout)
+    } else if pool.total_balance.saturating_add(bet) < worst {
+        Some(BlitzError::InsufficientLiquidity)
+    } else {
+        None
+    };
+    if let Some(e) = refusal {
+        set_return_data(&max_allowed_bet(pool, game_type, cfg, ext).to_le_bytes());
+        return Err(e.into());
+    }
+    Ok(worst)
+}
+
+/// Body of quote_bet: check_bet's verdict plus the limits it was judged against, all for the
+/// same (game_type, cfg, ext) a placement would carry.
+pub fn bet_quote(pool: &GlobalPool, paused: bool, game_type: u8, cfg: &[u8; 3], ext: u64, bet: u64, has_referrer: bool) -> BetQuote {
+    let check = if paused { Err(BlitzError::ContractPaused.into()) } else { check_bet(pool, game_type, cfg, ext, bet) };
+    let (house_bps, ref_bps, jackpot_bps) = get_fee_bps(pool.total_balance, has_referrer);
+    BetQuote {
+        accepted:        check.is_ok(),
+        error_code:      match check { Ok(_) => 0, Err(Error::AnchorError(e)) => e.error_code_number, Err(_) => u32::MAX },
+        worst_payout:    get_worst_payout(bet, game_type, cfg, ext),
+        min_bet:         room_min_bet(pool.room),
+        max_bet:         get_room_max_bet(pool.room, pool.game_basis(game_type), game_type, cfg, ext),
+        payout_cap:      get_room_payout_cap(pool.room, pool.game_basis(game_type)),
+        house_bps,
+        ref_bps,
+        jackpot_bps,
+        max_allowed_bet: max_allowed_bet(pool, game_type, cfg, ext),
+    }
+}
+
+/// Largest stake check_bet's size limits accept right now: room max bet, payout cap and
+/// pool liquidity (0 = none). Worst payout is linear in the stake up to rounding, so the
+/// bound comes from one 1-SOL reference quote and is then walked down past the rounding.
+pub fn max_allowed_bet(pool: &GlobalPool, game_type: u8, cfg: &[u8; 3], ext: u64) -> u64 {
+    const REF: u64 = 1_000_000_000;
+    let basis  = pool.game_basis(game_type);
+    let cap    = get_room_payout_cap(pool.room, basis);
+    let per    = get_worst_payout(REF, game_type, cfg, ext).max(1);
+    let by_cap = fixed::mul_div_floor(cap, REF, per).unwrap_or(u64::MAX);
+    let by_liq = if per > REF { fixed::mul_div_floor(pool.total_balance, REF, per - REF).unwrap_or(u64::MAX) } else { u64::MAX };
+    let fits   = |b: u64| {
+        let w = get_worst_payout(b, game_type, cfg, ext);
+        w <= cap && w <= pool.total_balance.saturating_add(b)
+    };
+    let mut bet = get_room_max_bet(pool.room, basis, game_type, cfg, ext).min(by_cap).min(by_liq);
+    for _ in 0..16 {
+        if bet == 0 || fits(bet) { return bet; }
+        bet -= bet / 10_000 + 1;
+    }
+    0
+}
+
+// ── CPI guard ─────────────────────────────────────────────────────────────
+
+/// Fills the fields a pre-room GameSession never had, as place_bet would have, and books its
+/// stake in open_stakes unless it already settled (game_state 2). A pending reveal (0) and an
+/// interactive game in play (1) both still hold their stake.
+fn migrate_legacy_session(pool: &mut GlobalPool, pool_key: Pubkey, s: &mut GameSession) {
+    s.pool           = pool_key;
+    s.index_page     = NOT_INDEXED;
+    s.payout_to      = s.player;
+    s.mines_pick     = MINES_NO_PICK;
+    s.hilo_guess     = HILO_NO_GUESS;
+    s.payout_num_bps = payout_num(pool.total_balance) as u16;
+    s.payout_curve   = pool.curve();
+    if s.game_state != 2 {
+        pool.open_stakes = pool.open_stakes.saturating_add(s.bet_lamports);
+    }
+}
+
+/// Rejects place_bet invoked via CPI unless the outer program is whitelisted.
+/// Stops wrappers from bracketing a bet with instructions that revert unfavourable outcomes.
+fn check_cpi_caller(pool: &GlobalPool, instructions: Option<&UncheckedAccount>) -> Result<()> {
+    let height = get_stack_height();
+    if height == TRANSACTION_LEVEL_STACK_HEIGHT { return Ok(()); }
+    require!(height == TRANSACTION_LEVEL_STACK_HEIGHT + 1, BlitzError::CpiNotAllowed);
+    let ixs    = instructions.ok_or(BlitzError::CpiNotAllowed)?;
+    let caller = ix_sysvar::get_instruction_relative(0, &ixs.to_account_info())?.program_id;
+    require!(
+        caller != Pubkey::default() && pool.cpi_whitelist.contains(&caller),
+        BlitzError::CpiNotAllowed
+    );
+    Ok(())
+}
+
+// ── Notification filter ───────────────────────────────────────────────────
+
+/// Emits a compact `Notify` only if the player opted in to `kind`.
+/// Relayers subscribe to this single event instead of decoding every settlement.
+fn notify(prefs: &Option<Account<NotificationPrefs>>, player: Pubkey, kind: u8) {
+    if let Some(p) = prefs {
+        if p.flags & kind != 0 { emit!(Notify { player, kind }); }
+    }
+}
+
+/// @dev Counts a Sector strike — direct or Mystery-picked — on the heatmap, when passed.
+fn record_strike(heatmap: &Option<AccountLoader<SectorHeatmap>>, outcome: &GameOutcome) -> Result<()> {
+    let (x, y) = match outcome {
+        GameOutcome::Sector { strike_x, strike_y }     => (*strike_x, *strike_y),
+        GameOutcome::Mystery { picked_game: 1, result } => (result[0], result[1]),
+        _                                               => return Ok(()),
+    };
+    let Some(loader) = heatmap else { return Ok(()) };
+    let mut h = loader.load_mut()?;
+    let cell  = &mut h.counts[y as usize % 16][x as usize % 16];
+    *cell     = cell.saturating_add(1);
+    h.strikes = h.strikes.saturating_add(1);
+    Ok(())
+}
+
+fn notify_settled(prefs: &Option<Account<NotificationPrefs>>, player: Pubkey, won: bool, jackpot: u64) {
+    notify(prefs, player, if won { NOTIFY_WIN } else { NOTIFY_LOSS });
+    if jackpot > 0 { notify(prefs, player, NOTIFY_JACKPOT); }
+}
+
+// ── Open-session index ────────────────────────────────────────────────────
+
+/// Swap-removes a terminal session from its index page.
+/// An indexed session must be settled with its page — keeps the feed exact.
+/// @dev Appends a new session to `page` (already bound to the session's pool by its seeds).
+///      Once the room indexes sessions a placement without a page is refused, so no pending
+///      session is missing from the index.
+fn index_session(pool: &GlobalPool, page: Option<&mut Box<Account<OpenSessionPage>>>, session: &mut Account<GameSession>) -> Result<()> {
+    session.index_page = NOT_INDEXED;
+    let Some(page) = page else {
+        require!(!pool.session_index_enabled, BlitzError::SessionIndexRequired);
+        return Ok(());
+    };
+    let n = page.count as usize;
+    require!(n < OPEN_PAGE_CAP, BlitzError::IndexPageFull);
+    page.sessions[n]   = session.key();
+    page.count        += 1;
+    session.index_page = page.page;
+    Ok(())
+}
+
+fn unindex_session(page: &mut Option<Box<Account<OpenSessionPage>>>, session: &Account<GameSession>) -> Result<()> {
+    if session.index_page == NOT_INDEXED { return Ok(()); }
+    let p   = page.as_mut().ok_or(BlitzError::IndexPageRequired)?;
+    let key = session.key();
+    let n   = p.count as usize;
+    if let Some(i) = p.sessions[..n].iter().position(|k| *k == key) {
+        p.sessions[i]     = p.sessions[n - 1];
+        p.sessions[n - 1] = Pubkey::default();
+        p.count          -= 1;
+    }
+    Ok(())
+}
+
+// ── Reveal receipts ───────────────────────────────────────────────────────
+
+/// Marks the session settled and keeps the outcome on it as a receipt until
+/// close_settled_session returns the rent to the player.
+/// A payout held for co-signing is recorded with its unconditional release time.
+/// With the room's ReceiptTree passed, the receipt is appended to it as a leaf instead and
+/// the session is closed to `player` right here — compressed mode keeps no per-bet account
+/// past the settlement. A held payout keeps the session open until it is released.
+fn record_settlement<'info>(
+    session:  &mut Account<'info, GameSession>,
+    receipts: Option<&mut Account<ReceiptTree>>,
+    player:   &AccountInfo<'info>,
+    won:      bool,
+    plan:     &SettlementPlan,
+) -> Result<()> {
+    session.game_state   = 2;
+    session.settled_slot = Clock::get()?.slot;
+    session.won          = won;
+    session.paid_out     = plan.player_gets;
+    session.jackpot_won  = plan.jackpot_prize;
+    session.held_payout  = plan.held;
+    session.double_offer = plan.double_offer;
+    if plan.double_offer {
+        session.held_until = Clock::get()?.unix_timestamp + DOUBLE_WINDOW_SECS;
+        emit!(DoubleOffered { session: session.key(), player: session.player, amount: plan.held, expires_at: session.held_until });
+    } else if plan.held > 0 {
+        session.held_until = Clock::get()?.unix_timestamp + COSIGN_FALLBACK_SECS;
+        emit!(PayoutHeld { player: session.player, amount: plan.held, release_at: session.held_until });
+    }
+    if let Some(t) = receipts {
+        if t.leaf_count < 1u64 << RECEIPT_TREE_DEPTH {
+            let leaf = receipt_leaf(&session.key(), session);
+            let index = receipt_append(t, leaf);
+            session.receipt_index = index + 1;
+            emit!(ReceiptLogged { tree: t.key(), index, leaf, root: t.root });
+        }
+    }
+    if session.receipt_index != 0 && session.held_payout == 0 {
+        session.close(player.clone())?;
+    }
+    Ok(())
+}
+
+/// Copies a settled single-shot session's seed, pinned terms and outcome into its
+/// GameReceipt when one was passed. Call after record_settlement.
+fn write_game_receipt(
+    receipt: &mut Option<Box<Account<GameReceipt>>>,
+    session: &GameSession,
+    seed:    &[u8; 32],
+    gross:   u64,
+    outcome: &GameOutcome,
+) -> Result<()> {
+    let Some(r) = receipt else { return Ok(()) };
+    require!(r.settled_slot == 0, BlitzError::GameReceiptWritten);
+    r.game_type      = session.game_type;
+    r.config         = [session.target_x, session.target_y, session.target_radius];
+    r.config_ext     = session.config_ext;
+    r.bet_lamports   = session.bet_lamports;
+    r.payout_num_bps = session.payout_num_bps;
+    r.payout_curve   = session.payout_curve;
+    r.seed           = *seed;
+    r.vrf            = session.vrf;
+    r.settled_slot   = session.settled_slot;
+    r.won            = session.won;
+    r.gross_payout   = gross;
+    r.outcome        = outcome_bytes(outcome);
+    Ok(())
+}
+
+/// Borsh encoding of `outcome`, zero-padded to 32 bytes (the largest variant takes 26).
+fn outcome_bytes(outcome: &GameOutcome) -> [u8; 32] {
+    let mut out = [0u8; 32];
+    outcome.serialize(&mut &mut out[..]).expect("every GameOutcome fits 32 bytes");
+    out
+}
+
+// ── Compressed receipts ───────────────────────────────────────────────────
+
+/// @notice Leaf committed to the ReceiptTree for a settled session. Covers everything the
+///         fairness check needs: the commitment the nonce must hash to, the game inputs and
+///         the settled outcome.
+pub fn receipt_leaf(session_key: &Pubkey, s: &GameSession) -> [u8; 32] {
+    hash::hashv(&[
+        b"blitz-receipt-v1",
+        session_key.as_ref(),
+        s.player.as_ref(),
+        &s.commitment,
+        &[s.game_type, s.target_x, s.target_y, s.target_radius, s.won as u8],
+        &s.config_ext.to_le_bytes(),
+        &s.bet_lamports.to_le_bytes(),
+        &s.resolve_slot.to_le_bytes(),
+        &s.settled_slot.to_le_bytes(),
+        &s.paid_out.to_le_bytes(),
+        &s.jackpot_won.to_le_bytes(),
+    ]).to_bytes()
+}
+
+fn receipt_node(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    hash::hashv(&[left, right]).to_bytes()
+}
+
+/// Appends `leaf` at index leaf_count (incremental merkle tree: only the frontier — the
+/// latest left sibling per level — is stored) and refreshes `root`. Returns the index.
+fn receipt_append(t: &mut ReceiptTree, leaf: [u8; 32]) -> u64 {
+    let index = t.leaf_count;
+    let mut node = leaf;
+    let mut i    = index;
+    for level in 0..RECEIPT_TREE_DEPTH {
+        if i & 1 == 0 { t.frontier[level] = node; break; }
+        node = receipt_node(&t.frontier[level], &node);
+        i >>= 1;
+    }
+    t.leaf_count = index + 1;
+    t.root       = receipt_root(&t.frontier, t.leaf_count);
+    index
+}
+
+/// Root of a tree holding `count` leaves, missing leaves being all-zero.
+fn receipt_root(frontier: &[[u8; 32]; RECEIPT_TREE_DEPTH], count: u64) -> [u8; 32] {
+    let mut node = [0u8; 32];
+    let mut zero = [0u8; 32];
+    let mut size = count;
+    for sibling in frontier.iter() {
+        node = if size & 1 == 1 { receipt_node(sibling, &node) } else { receipt_node(&node, &zero) };
+        zero = receipt_node(&zero, &zero);
+        size >>= 1;
+    }
+    node
+}
+
+/// @notice Checks a ReceiptTree inclusion proof: `proof[level]` is the sibling at each level,
+///         bottom-up. Roots change with every append, so verify against the `root` of the
+///         ReceiptLogged event (or any later root the indexer recomputed the proof for).
+pub fn verify_receipt_proof(root: &[u8; 32], leaf: &[u8; 32], index: u64, proof: &[[u8; 32]; RECEIPT_TREE_DEPTH]) -> bool {
+    let mut node = *leaf;
+    for (level, sibling) in proof.iter().enumerate() {
+        node = if (index >> level) & 1 == 0 { receipt_node(&node, sibling) } else { receipt_node(sibling, &node) };
+    }
+    node == *root
+}
+
+/// @dev Player and delegate may race to reveal the same session. The loser of the race
+///      gets Ok with a RevealReceipt in return data instead of an error, as long as it
+///      lands within RECEIPT_WINDOW of the settlement; later attempts fail with AlreadySettled.
+fn already_settled(session: &GameSession) -> Result<bool> {
+    if session.game_state != 2 { return Ok(false); }
+    require!(Clock::get()?.slot <= session.settled_slot + RECEIPT_WINDOW, BlitzError::AlreadySettled);
+    let receipt = RevealReceipt {
+        won:          session.won,
+        paid_out:     session.paid_out,
+        jackpot_won:  session.jackpot_won,
+        settled_slot: session.settled_slot,
+        held_payout:  session.held_payout,
+    };
+    set_return_data(&receipt.try_to_vec()?);
+    Ok(true)
+}
+
+// ── First-bet promo ───────────────────────────────────────────────────────
+
+/// Insured part of a wallet's first recorded bet: the stake up to promo_cap, limited by the
+/// uncommitted budget. 0 when the room runs no promo or required attestation is missing.
+/// The first bet consumes the promo either way — it never carries over to a later bet.
+fn promo_cover(pool: &GlobalPool, stats: &PlayerStats, bet: u64) -> u64 {
+    if pool.promo_attestor != Pubkey::default() && !stats.attested { return 0; }
+    let free = pool.promo_balance.saturating_sub(pool.promo_committed);
+    bet.min(pool.promo_cap).min(free)
+}
+
+/// @dev A room running the first-bet promo needs PlayerStats on every bet, so a wallet's first
+///      bet there is always the one the promo sees: it cannot bet around the promo and insure
+///      a later, larger bet instead.
+fn promo_gate(pool: &GlobalPool, has_stats: bool) -> Result<()> {
+    require!(has_stats || pool.promo_cap == 0, BlitzError::PlayerStatsRequired);
+    Ok(())
+}
+
+/// Releases an insured stake's reservation when the session ends without a reveal.
+fn release_promo(pool: &mut GlobalPool, session: &GameSession) {
+    pool.promo_committed = pool.promo_committed.saturating_sub(session.insured_amount);
+}
+
+// ── RTP alarm ─────────────────────────────────────────────────────────────
+
+/// Body of check_rtp; crank_rocket also runs it for Crash once the band's sample is reached.
+fn run_rtp_check(pool: &mut Account<GlobalPool>, game: u8) -> Result<()> {
+    let (band, bets, wagered, paid, bit) = if game == RTP_GLOBAL {
+        (pool.global_rtp_band, pool.total_bets, pool.lifetime_wagered, pool.lifetime_paid_out, RTP_GLOBAL_FLAG)
+    } else {
+        require!((game as usize) < MAX_GAME_TYPES, BlitzError::InvalidGameType);
+        let c = *pool.counters(game);
+        (pool.rtp_band(game), c.bets, c.wagered as u128, c.paid_out as u128, 1u32 << game)
+    };
+    require!(band.max_bps > 0,                        BlitzError::RtpBandNotSet);
+    require!(bets >= (band.min_bets as u64).max(1),   BlitzError::RtpSampleTooSmall);
+
+    let rtp_bps = u64::try_from(paid * 10_000 / wagered.max(1)).unwrap_or(u64::MAX);
+    let before  = (pool.rtp_anomalies, pool.paused_games(), pool.paused);
+    if rtp_bps >= band.min_bps as u64 && rtp_bps <= band.max_bps as u64 {
+        pool.rtp_anomalies &= !bit;
+    } else {
+        pool.rtp_anomalies |= bit;
+        if band.auto_pause && game == RTP_GLOBAL && !pool.paused {
+            pool.paused           = true;
+            pool.pause_expires_at = Clock::get()?.unix_timestamp + MAX_PAUSE_DURATION;
+            emit!(ContractPaused { authority: pool.authority, expires_at: pool.pause_expires_at });
+        } else if band.auto_pause && game != RTP_GLOBAL {
+            let bits = pool.paused_games() | 1 << game;
+            pool.set_paused_games(bits);
+        }
+        emit!(RtpAnomaly { pool: pool.key(), game, rtp_bps, min_bps: band.min_bps, max_bps: band.max_bps,
+                           bets, paused: band.auto_pause });
+    }
+    let after = (pool.rtp_anomalies, pool.paused_games(), pool.paused);
+    if (after.1, after.2) != (before.1, before.2) { bump_config(pool); } else if after != before { bump_seq(pool); }
+    Ok(())
+}
+
+// ── Step exposure ─────────────────────────────────────────────────────────
+
+/// Grows an open round's reservation to `claim` — what it pays if the step being committed
+/// succeeds — out of free liquidity. Reservations sit in pool.step_exposure, a reserved
+/// compartment, so every open interactive round stays payable in full until it settles.
+fn reserve_step(pool: &mut Account<GlobalPool>, session: &mut GameSession, claim: u64) -> Result<()> {
+    let increase = claim.saturating_sub(session.step_reserved);
+    if increase == 0 { return Ok(()); }
+    require!(increase <= pool.total_balance, BlitzError::InsufficientLiquidity);
+    pool.step_exposure     = checked_add(pool.step_exposure, increase)?;
+    session.step_reserved += increase;
+    let pool_ai = pool.to_account_info();
+    sync_balance(pool, &pool_ai)?;
+    emit!(StepExposureReserved { pool: pool.key(), player: session.player, reserved: session.step_reserved, pool_exposure: pool.step_exposure });
+    Ok(())
+}
+
+/// Returns a closing round's reservation to free liquidity (settle and resolve_terminal_state).
+fn release_step(pool: &mut GlobalPool, session: &GameSession) {
+    pool.step_exposure = pool.step_exposure.saturating_sub(session.step_reserved);
+}
+
+// ── Rocket settlement ─────────────────────────────────────────────────────
+
+/// Settles one cranked rocket entry through compute_settlement, as settle does a session: the
+/// entry's reservation returns to liquidity, then the phase fees, insurance and jackpot roll
+/// apply to its stake. Entries carry no referrer or partner, and join_rocket keeps them under
+/// the co-sign threshold, so nothing is held.
+fn settle_rocket_entry<'info>(
+    pool:      &mut Account<'info, GlobalPool>,
+    player_ai: &AccountInfo<'info>,
+    entry:     &RocketEntry,
+    gross:     u64,
+    seed:      [u8; 32],
+) -> Result<SettlementPlan> {
+    pool.rocket_exposure = pool.rocket_exposure.saturating_sub(entry.reserved);
+    let pool_ai = pool.to_account_info();
+    sync_balance(pool, &pool_ai)?;
+    let boost = pool.jackpot_boost.at(Clock::get()?.unix_timestamp);
+    let rent  = Rent::get()?.minimum_balance(pool_ai.data_len());
+    let plan  = compute_settlement(&SettlementInputs {
+        bet:               entry.stake,
+        won:               gross > entry.stake,
+        gross_payout:      gross,
+        seed,
+        has_ref:           false,
+        ref_net_loss:      false,
+        referrer_lamports: 0,
+        partner_fee_bps:   None,
+        total_balance:     pool.total_balance,
+        jackpot_balance:   pool.jackpot_balance,
+        available:         pool_ai.lamports().saturating_sub(rent).saturating_sub(pool.reserved()),
+        jackpot_cap_bps:   pool.jackpot_cap_bps,
+        insured:           0,
+        cosign_threshold:  0,
+        cosigned:          false,
+        jackpot_feed_bps:  0,
+        boost_trigger_bps: boost.trigger_bps as u64,
+        boost_contrib_bps: boost.contrib_bps as u64,
+        hold_for_double:   false,
+        insurance_bps:     pool.insurance_bps as u64,
+        flat_fee_bps:      None,
+        fee_bps:           entry.fee_bps,
+        executor_tip:      0,
+    })?;
+    if plan.player_gets > 0 {
+        pool_transfer(&pool_ai, player_ai, plan.player_gets)?;
+    }
+    pool.jackpot_balance   = checked_add(pool.jackpot_balance.saturating_sub(plan.jackpot_prize), plan.jackpot_cut)?;
+    pool.insurance_balance = checked_add(pool.insurance_balance, plan.insurance_cut)?;
+    pool.house_fees_earned = checked_add(pool.house_fees_earned, plan.claimable)?;
+    pool.total_reinvested  = pool.total_reinvested.saturating_add(plan.reinvested);
+    if plan.won { pool.total_wins = pool.total_wins.saturating_add(1); }
+    if plan.win_payout > 0 {
+        pool.total_paid_out    = pool.total_paid_out.saturating_add(plan.win_payout);
+        pool.lifetime_paid_out = add_u128(pool.lifetime_paid_out, plan.win_payout)?;
+        if plan.win_payout > pool.biggest_win { pool.biggest_win = plan.win_payout; }
+        let game = pool.counters_mut(7);
+        game.paid_out = game.paid_out.saturating_add(plan.win_payout);
+        pool.record_game_outflow(7, plan.win_payout);
+    }
+    if plan.jackpot_prize > 0 {
+        pool.total_jackpot_won    = pool.total_jackpot_won.saturating_add(plan.jackpot_prize);
+        pool.total_paid_out       = pool.total_paid_out.saturating_add(plan.jackpot_prize);
+        pool.lifetime_jackpot_won = add_u128(pool.lifetime_jackpot_won, plan.jackpot_prize)?;
+        pool.lifetime_paid_out    = add_u128(pool.lifetime_paid_out, plan.jackpot_prize)?;
+        emit!(JackpotWon { player: entry.player, amount: plan.jackpot_prize });
+    }
+    sync_balance(pool, &pool_ai)?;
+    Ok(plan)
+}
+
+// ── Payout destination ────────────────────────────────────────────────────
+
+/// Winnings go to `session.payout_to`; the reveal itself is still authorized by
+/// the player or their delegate. The wallet account is only required when it differs.
+fn payout_dest<'info>(
+    session: &GameSession,
+    player:  &AccountInfo<'info>,
+    wallet:  &Option<UncheckedAccount<'info>>,
+) -> Result<AccountInfo<'info>> {
+    if session.payout_to == session.player || session.payout_to == Pubkey::default() {
+        return Ok(player.clone());
+    }
+    let w = wallet.as_ref().ok_or(BlitzError::PayoutWalletRequired)?;
+    Ok(w.to_account_info())
+}
+
+// ── Partner share ─────────────────────────────────────────────────────────
+
+/// Partner sessions must settle with their Partner account so the fee share and
+/// reporting are never skipped. The share is paid to the partner key (= session.player).
+fn partner_of<'a, 'info>(
+    session: &GameSession,
+    partner: &'a mut Option<Account<'info, Partner>>,
+    player:  &AccountInfo<'info>,
+) -> Result<Option<(&'a mut Account<'info, Partner>, AccountInfo<'info>)>> {
+    if !session.partner { return Ok(None); }
+    let p = partner.as_mut().ok_or(BlitzError::PartnerRequired)?;
+    Ok(Some((p, player.clone())))
+}
+
+// ── Terminal-path fee accrual ─────────────────────────────────────────────
+
+/// @dev TERMINAL-PATH INVARIANT: a stake that ends without a reveal (forfeit or refund)
+///      accrues the same jackpot, insurance and house compartments a settled bet would, with the
+///      referral share folded into the house (as settle does for an ineligible referrer).
+///      Cuts are capped by the retained part of the stake and by liquid balance, so a
+///      refund never dips into reserved compartments. Such stakes are never jackpot-eligible.
+///      `fee_bps` is the session's pinned table, as settle would have charged.
+fn accrue_terminal_fees(pool: &mut GlobalPool, bet: u64, kept: u64, fee_bps: [u16; 3]) -> Result<()> {
+    let kept = kept.min(pool.total_balance);
+    let (house_bps, ref_bps, jackpot_bps) = fee_table(fee_bps, pool.total_balance, false);
+    let jackpot_cut = fixed::share_bps(bet, jackpot_bps).min(kept);
+    let house_cut   = fixed::share_bps(bet, house_bps + ref_bps).min(kept - jackpot_cut);
+    let insurance   = fixed::share_bps(bet, pool.insurance_bps as u64).min(house_cut);
+    let (claimable, reinvested) = split_house_fees(pool.total_balance, house_cut - insurance, bet);
+    pool.jackpot_balance   = checked_add(pool.jackpot_balance, jackpot_cut)?;
+    pool.insurance_balance = checked_add(pool.insurance_balance, insurance)?;
+    pool.house_fees_earned = checked_add(pool.house_fees_earned, claimable)?;
+    pool.total_reinvested  = pool.total_reinvested.saturating_add(reinvested);
+    Ok(())
+}
+
+// ── Terminal states ───────────────────────────────────────────────────────
+
+/// Terminal reasons reported by TerminalStateResolved.
+pub const TERMINAL_INSOLVENT:    u8 = 0;
+pub const TERMINAL_HASH_EXPIRED: u8 = 1;
+pub const TERMINAL_VOLUNTARY:    u8 = 2;
+pub const TERMINAL_FORFEITED:    u8 = 3;
+
+/// @notice The decision table for an unrevealable session: (reason, refund %), or None if
+///         no terminal policy applies yet. First matching row wins.
+///
+///   state       | condition                                        | caller | reason       | refund
+///   pending     | pool liquidity < the bet's worst payout          | anyone | INSOLVENT    | 50%
+///   pending     | past forfeit_slot, slot hash aged out (≥ 490)    | player | HASH_EXPIRED | 4%
+///   pending     | past forfeit_slot, slot hash still readable      | player | VOLUNTARY    | 4%
+///   pending/open| past forfeit_slot + FORFEIT_GRACE_SLOTS          | anyone | FORFEITED    | 0%
+///
+/// @dev 50% on insolvency is fair — it is not the player's fault. 4% makes selective
+///      non-reveal EV-negative: EV(flip) = 0.5×0.95 + 0.5×(0.04−1) = −0.005 SOL.
+pub fn terminal_policy(s: &GameSession, caller_is_player: bool, insolvent: bool, slot: u64) -> Option<(u8, u64)> {
+    let pending = s.game_state == 0;
+    let expired = slot > s.forfeit_slot;
+    if pending && insolvent {
+        return Some((TERMINAL_INSOLVENT, 50));
+    }
+    if pending && expired && caller_is_player {
+        let aged = slot.saturating_sub(s.resolve_slot) >= 490;
+        return Some((if aged { TERMINAL_HASH_EXPIRED } else { TERMINAL_VOLUNTARY }, 4));
+    }
+    if s.game_state < 2 && slot > s.forfeit_slot.saturating_add(FORFEIT_GRACE_SLOTS) {
+        return Some((TERMINAL_FORFEITED, 0));
+    }
+    None
+}
+
+// ── Refund helper ─────────────────────────────────────────────────────────
+
+/// Transfers `pct`% of `bet` from pool to `dest`, accrues fees on the retained rest, then syncs balance.
+fn send_refund<'info>(
+    pool: &mut Account<'info, GlobalPool>,
+    dest: &AccountInfo<'info>,
+    bet:  u64,
+    pct:  u64,
+    fees: [u16; 3],
+) -> Result<()> {
+    let refund = bet.saturating_mul(pct) / 100;
+    require!(pool.total_balance >= refund, BlitzError::InsufficientLiquidity);
+    accrue_terminal_fees(pool, bet, bet - refund, fees)?;
+    let pool_ai = pool.to_account_info();
+    pool_transfer(&pool_ai, dest, refund)?;
+    sync_balance(pool, &pool_ai)
+}
+
+// ── Checked lamport math ──────────────────────────────────────────────────
+
+#[inline(always)]
+fn checked_add(a: u64, b: u64) -> Result<u64> {
+    a.checked_add(b).ok_or_else(|| error!(BlitzError::MathOverflow))
+}
+
+#[inline(always)]
+fn add_u128(counter: u128, amount: u64) -> Result<u128> {
+    counter.checked_add(amount as u128).ok_or_else(|| error!(BlitzError::MathOverflow))
+}
+
+fn debit(ai: &AccountInfo, amount: u64) -> Result<()> {
+    let mut l = ai.try_borrow_mut_lamports()?;
+    **l = l.checked_sub(amount).ok_or(BlitzError::MathOverflow)?;
+    Ok(())
+}
+
+fn credit(ai: &AccountInfo, amount: u64) -> Result<()> {
+    let mut l = ai.try_borrow_mut_lamports()?;
+    **l = l.checked_add(amount).ok_or(BlitzError::MathOverflow)?;
+    Ok(())
+}
+
+/// Debits `from` and credits `to`; any underflow/overflow aborts the whole instruction.
+fn move_lamports(from: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    debit(from, amount)?;
+    credit(to, amount)
+}
+
+/// @dev The only way lamports leave a pool PDA. Refuses any debit that would
+///      leave the account below its rent-exempt minimum, whatever the callers'
+///      own liquidity checks concluded after a config or layout change.
+fn pool_debit(pool_ai: &AccountInfo, amount: u64) -> Result<()> {
+    let rent = Rent::get()?.minimum_balance(pool_ai.data_len());
+    require!(
+        pool_ai.lamports().saturating_sub(rent) >= amount,
+        BlitzError::WouldBreakRentExemption
+    );
+    debit(pool_ai, amount)
+}
+
+/// `pool_debit` + credit to a single recipient.
+fn pool_transfer(pool_ai: &AccountInfo, to: &AccountInfo, amount: u64) -> Result<()> {
+    pool_debit(pool_ai, amount)?;
+    credit(to, amount)
+}
+
+// ── Referrer check ────────────────────────────────────────────────────────
+
+#[inline(always)]
+fn is_valid_referrer(referrer: Pubkey, player: Pubkey) -> bool {
+    referrer != system_program::ID && referrer != player
+}
+
+// ── State sequence ────────────────────────────────────────────────────────
+
+/// @dev Every pool write bumps `state_seq` (sync_balance covers all lamport moves,
+///      admin setters call this directly). Subscribers order websocket updates by it
+///      and treat a gap as a missed notification.
+fn bump_seq(pool: &mut Account<GlobalPool>) {
+    pool.state_seq = pool.state_seq.wrapping_add(1);
+    emit!(PoolStateChanged { pool: pool.key(), seq: pool.state_seq, config_seq: pool.config_seq });
+}
+
+/// @dev bump_seq for writes that change what clients cache — limits, fees, curve, pause
+///      state, policies. BetPlaced and PoolStateChanged carry `config_seq`, so a client
+///      refetches the pool only when it moves instead of on every bet.
+fn bump_config(pool: &mut Account<GlobalPool>) {
+    pool.config_seq = pool.config_seq.wrapping_add(1);
+    bump_seq(pool);
+}
+
+// ── Schema hash ───────────────────────────────────────────────────────────
+
+/// @dev BLAKE3 over SCHEMA_VERSION, every account's (discriminator, LEN) and every
+///      release event's discriminator. Catches renames and size changes automatically;
+///      a same-size field reorder only shows up through the SCHEMA_VERSION bump.
+fn schema_hash() -> [u8; 32] {
+    let accounts: [([u8; 8], usize); 32] = [
+        (GlobalPool::DISCRIMINATOR,        GlobalPool::LEN),
+        (GameSession::DISCRIMINATOR,       GameSession::LEN),
+        (SessionToken::DISCRIMINATOR,      SessionToken::LEN),
+        (SessionNonce::DISCRIMINATOR,      SessionNonce::LEN),
+        (TowerRace::DISCRIMINATOR,         TowerRace::LEN),
+        (RpsMatch::DISCRIMINATOR,          RpsMatch::LEN),
+        (PlayerSketch::DISCRIMINATOR,      PlayerSketch::LEN),
+        (OpenSessionPage::DISCRIMINATOR,   OpenSessionPage::LEN),
+        (Partner::DISCRIMINATOR,           Partner::LEN),
+        (PlayerStats::DISCRIMINATOR,       PlayerStats::LEN),
+        (NotificationPrefs::DISCRIMINATOR, NotificationPrefs::LEN),
+        (SchemaRegistry::DISCRIMINATOR,    SchemaRegistry::LEN),
+        (ReferralAccount::DISCRIMINATOR,   ReferralAccount::LEN),
+        (ReceiptTree::DISCRIMINATOR,       ReceiptTree::LEN),
+        (Firehose::DISCRIMINATOR,          Firehose::LEN),
+        (SectorHeatmap::DISCRIMINATOR,     SectorHeatmap::LEN),
+        (BuildAttestation::DISCRIMINATOR,  BuildAttestation::LEN),
+        (LotteryRound::DISCRIMINATOR,      LotteryRound::LEN),
+        (LotteryTicket::DISCRIMINATOR,     LotteryTicket::LEN),
+        (Auditor::DISCRIMINATOR,           Auditor::LEN),
+        (GiftCard::DISCRIMINATOR,          GiftCard::LEN),
+        (RocketRound::DISCRIMINATOR,       RocketRound::LEN),
+        (RocketEntry::DISCRIMINATOR,       RocketEntry::LEN),
+        (PoolMetadata::DISCRIMINATOR,      PoolMetadata::LEN),
+        (PromoVoucherClaim::DISCRIMINATOR, PromoVoucherClaim::LEN),
+        (ParlaySession::DISCRIMINATOR,     ParlaySession::LEN),
+        (PlayerVault::DISCRIMINATOR,       PlayerVault::LEN),
+        (LpPosition::DISCRIMINATOR,        LpPosition::LEN),
+        (LpLedger::DISCRIMINATOR,          LpLedger::LEN),
+        (GameReceipt::DISCRIMINATOR,       GameReceipt::LEN),
+        (NavSnapshot::DISCRIMINATOR,       NavSnapshot::LEN),
+        (SponsorGrant::DISCRIMINATOR,      SponsorGrant::LEN),
+    ];
+    let events: [[u8; 8]; 146] = [
+        PoolFunded::DISCRIMINATOR, PromoFunded::DISCRIMINATOR, PromoWithdrawn::DISCRIMINATOR,
+        PromoConfigUpdated::DISCRIMINATOR, FirstBetInsured::DISCRIMINATOR, FirstBetRefunded::DISCRIMINATOR,
+        CosignPolicyUpdated::DISCRIMINATOR, PayoutHeld::DISCRIMINATOR, PayoutReleased::DISCRIMINATOR,
+        RoomInitialized::DISCRIMINATOR, LiquidityMigrationRequested::DISCRIMINATOR, LiquidityMigrated::DISCRIMINATOR,
+        LiquidityMigrationCancelled::DISCRIMINATOR, UniquePlayersEpochClosed::DISCRIMINATOR, CpiWhitelistUpdated::DISCRIMINATOR,
+        ForfeitRentPolicyUpdated::DISCRIMINATOR, PoolStateChanged::DISCRIMINATOR, JackpotTriggerCapUpdated::DISCRIMINATOR,
+        PayoutCurveUpdated::DISCRIMINATOR, PartnerUpdated::DISCRIMINATOR, PartnerSettled::DISCRIMINATOR,
+        BetPlaced::DISCRIMINATOR, SessionExpiringSoon::DISCRIMINATOR, SplitBetPlaced::DISCRIMINATOR,
+        FlipSettled::DISCRIMINATOR, DiceSettled::DISCRIMINATOR, SectorSettled::DISCRIMINATOR,
+        TowerSettled::DISCRIMINATOR, MysterySettled::DISCRIMINATOR, PlinkoSettled::DISCRIMINATOR,
+        BetForfeited::DISCRIMINATOR, JackpotWon::DISCRIMINATOR, WithdrawalRequested::DISCRIMINATOR,
+        WithdrawalExecuted::DISCRIMINATOR, HouseFeesClaimed::DISCRIMINATOR, ReinvestRequested::DISCRIMINATOR,
+        ReinvestExecuted::DISCRIMINATOR, SessionCreated::DISCRIMINATOR, ContractPaused::DISCRIMINATOR,
+        ContractUnpaused::DISCRIMINATOR, AuthorityTransferProposed::DISCRIMINATOR, AuthorityTransferCancelled::DISCRIMINATOR,
+        AuthorityTransferred::DISCRIMINATOR, NotificationPrefsUpdated::DISCRIMINATOR, Notify::DISCRIMINATOR,
+        TowerRaceCreated::DISCRIMINATOR, TowerRaceJoined::DISCRIMINATOR, TowerRaceCancelled::DISCRIMINATOR,
+        TowerRaceSettled::DISCRIMINATOR, TowerRaceTimedOut::DISCRIMINATOR, SchemaUpdated::DISCRIMINATOR,
+        MinesStarted::DISCRIMINATOR, MinesTilePicked::DISCRIMINATOR, MinesTileRevealed::DISCRIMINATOR,
+        MinesSettled::DISCRIMINATOR, CrashSettled::DISCRIMINATOR, RtpBandUpdated::DISCRIMINATOR,
+        RtpAnomaly::DISCRIMINATOR, GameResumed::DISCRIMINATOR, RouletteSettled::DISCRIMINATOR,
+        KenoSettled::DISCRIMINATOR, LimboSettled::DISCRIMINATOR, ReferralModeUpdated::DISCRIMINATOR,
+        ReferralEpochClosed::DISCRIMINATOR, ReferralClaimed::DISCRIMINATOR, ReceiptTreeInitialized::DISCRIMINATOR,
+        ReceiptLogged::DISCRIMINATOR, WheelSettled::DISCRIMINATOR, HiloStarted::DISCRIMINATOR,
+        HiloGuessed::DISCRIMINATOR, HiloCardDrawn::DISCRIMINATOR, HiloSettled::DISCRIMINATOR,
+        VideoPokerSettled::DISCRIMINATOR, PublicRevealTipped::DISCRIMINATOR, BaccaratSettled::DISCRIMINATOR,
+        UpgradeFinalized::DISCRIMINATOR, BuildAttested::DISCRIMINATOR, SlotsSettled::DISCRIMINATOR,
+        RpsMatchCreated::DISCRIMINATOR, RpsMatchJoined::DISCRIMINATOR, RpsMatchCancelled::DISCRIMINATOR,
+        RpsMatchSettled::DISCRIMINATOR, RpsMatchTimedOut::DISCRIMINATOR, BetDownsized::DISCRIMINATOR,
+        LotteryRoundOpened::DISCRIMINATOR, LotteryTicketBought::DISCRIMINATOR, LotteryDrawn::DISCRIMINATOR,
+        LotteryPrizeClaimed::DISCRIMINATOR, LotteryRolledOver::DISCRIMINATOR, SicBoSettled::DISCRIMINATOR,
+        DragonTigerSettled::DISCRIMINATOR, AuditorGranted::DISCRIMINATOR, AuditorRevoked::DISCRIMINATOR,
+        PoolAudited::DISCRIMINATOR, DiceDuoSettled::DISCRIMINATOR, GiftCardMinted::DISCRIMINATOR,
+        GiftCardRedeemed::DISCRIMINATOR, GiftCardReclaimed::DISCRIMINATOR, StairsStarted::DISCRIMINATOR,
+        StairsStepTaken::DISCRIMINATOR, StairsStepCleared::DISCRIMINATOR, StairsSettled::DISCRIMINATOR, GoalSettled::DISCRIMINATOR,
+        RocketRoundOpened::DISCRIMINATOR, RocketJoined::DISCRIMINATOR, RocketLaunched::DISCRIMINATOR,
+        RocketEntrySettled::DISCRIMINATOR, PoolMetadataProposed::DISCRIMINATOR, PoolMetadataUpdated::DISCRIMINATOR,
+        PoolMetadataCancelled::DISCRIMINATOR, PromoVoucherRedeemed::DISCRIMINATOR, TowerClimbStarted::DISCRIMINATOR,
+        TowerDoorPicked::DISCRIMINATOR, TowerFloorResolved::DISCRIMINATOR, TowerClimbSettled::DISCRIMINATOR,
+        StepExposureReserved::DISCRIMINATOR, JackpotBoostScheduled::DISCRIMINATOR, FlipStreakSettled::DISCRIMINATOR,
+        TerminalStateResolved::DISCRIMINATOR,
+        BetsBatchPlaced::DISCRIMINATOR,
+        BatchRevealed::DISCRIMINATOR,
+        ParlaySettled::DISCRIMINATOR,
+        DoubleOffered::DISCRIMINATOR,
+        DoubleOrNothingPlaced::DISCRIMINATOR,
+        DoubleOrNothingSettled::DISCRIMINATOR,
+        VaultDeposited::DISCRIMINATOR,
+        VaultWithdrawn::DISCRIMINATOR,
+        LpDeposited::DISCRIMINATOR,
+        LpWithdrawn::DISCRIMINATOR,
+        LpExitQueued::DISCRIMINATOR,
+        GameBudgetUpdated::DISCRIMINATOR,
+        LpEpochClosed::DISCRIMINATOR,
+        PerformanceFeeUpdated::DISCRIMINATOR,
+        PerformanceFeeAccrued::DISCRIMINATOR,
+        PerformanceFeeClaimed::DISCRIMINATOR,
+        InsurancePolicyUpdated::DISCRIMINATOR,
+        InsuranceTapRequested::DISCRIMINATOR,
+        InsuranceTapped::DISCRIMINATOR,
+        VrfProviderUpdated::DISCRIMINATOR,
+        GameVerified::DISCRIMINATOR,
+        RocketEntryRefunded::DISCRIMINATOR,
+        RocketRoundClosed::DISCRIMINATOR,
+        LpBookWrittenOff::DISCRIMINATOR,
+        NavSnapshotTaken::DISCRIMINATOR,
+        SponsorDeposited::DISCRIMINATOR,
+        SponsorGrantReclaimed::DISCRIMINATOR,
+    ];
+    let mut h = blake3::Hasher::new();
+    h.update(&SCHEMA_VERSION.to_le_bytes());
+    for (disc, len) in accounts.iter() {
+        h.update(disc);
+        h.update(&(*len as u64).to_le_bytes());
+    }
+    for disc in events.iter() { h.update(disc); }
+    *h.finalize().as_bytes()
+}
+
+// ── Balance sync ──────────────────────────────────────────────────────────
+
+/// @dev The upgrade handshake. sync_balance runs it; instructions that move lamports
+///      without syncing the pool (vaults, PvP escrows, lottery, gift cards) call it first.
+fn require_current_version(pool: &GlobalPool) -> Result<()> {
+    require!(pool.program_version == PROGRAM_VERSION, BlitzError::UpgradeNotFinalized);
+    Ok(())
+}
+
+/// @dev Derives `total_balance` from physical lamports every time funds move.
+///      Single source of truth — eliminates accounting desync.
+///      Also the upgrade gate: no lamports move until finalize_upgrade has run.
+fn sync_balance(pool: &mut Account<GlobalPool>, ai: &AccountInfo) -> Result<()> {
+    require_current_version(pool)?;
+    bump_seq(pool);
+    let rent     = Rent::get()?.minimum_balance(ai.data_len());
+    let physical = ai.lamports().saturating_sub(rent);
+    let reserved = pool.reserved();
+    require!(physical >= reserved, BlitzError::AccountingBroken);
+    pool.total_balance = physical.saturating_sub(reserved);
+    Ok(())
+}
+
+// ── Fee schedule ──────────────────────────────────────────────────────────
+
+/// (house, ref, jackpot) bps as pinned on a session at placement (see fee_table).
+fn pin_fee_bps(pool_balance: u64, has_ref: bool) -> [u16; 3] {
+    let (house, r, jackpot) = get_fee_bps(pool_balance, has_ref);
+    [house as u16, r as u16, jackpot as u16]
+}
+
+/// A session's pinned fee bps; the phase table at `pool_balance` for one placed before the pin.
+fn fee_table(pinned: [u16; 3], pool_balance: u64, has_ref: bool) -> (u64, u64, u64) {
+    match pinned {
+        [0, 0, 0]           => get_fee_bps(pool_balance, has_ref),
+        [house, r, jackpot] => (house as u64, r as u64, jackpot as u64),
+    }
+}
+
+fn get_fee_bps(pool_balance: u64, has_ref: bool) -> (u64, u64, u64) {
+    match (pool_balance < PHASE1_THRESHOLD, pool_balance < PHASE2_THRESHOLD, has_ref) {
+        (true,  _,    true)  => (50,  150, 50), // Phase 0 + ref
+        (true,  _,    false) => (200, 0,   50), // Phase 0 solo
+        (false, true, true)  => (50,  100, 50), // Phase 1 + ref
+        (false, true, false) => (150, 0,   50), // Phase 1 solo
+        (false, false,true)  => (50,  75,  25), // Phase 2 + ref
+        (false, false,false) => (125, 0,   25), // Phase 2 solo
+    }
+}
+
+// ── Auto-reinvest split ───────────────────────────────────────────────────
+
+/// Returns (claimable, reinvested). INVARIANT: claimable + reinvested == house_cut.
+fn split_house_fees(pool_balance: u64, house_cut: u64, bet: u64) -> (u64, u64) {
+    let operational = fixed::share_bps(bet, OPERATIONAL_BPS).min(house_cut);
+    let remainder   = house_cut.saturating_sub(operational);
+    if pool_balance < PHASE1_THRESHOLD {
+        (operational, remainder)                                             // Phase 0: lock all
+    } else if pool_balance < PHASE2_THRESHOLD {
+        let reinvest = remainder / 2;
+        (operational.saturating_add(remainder.saturating_sub(reinvest)), reinvest) // Phase 1: 50/50
+    } else {
+        (house_cut, 0)                                                       // Phase 2: all claimable
+    }
+}
+
+// ── Payout numerator ──────────────────────────────────────────────────────
+
+#[inline(always)]
+fn payout_num(pool_balance: u64) -> u64 {
+    if      pool_balance < PHASE1_THRESHOLD { 9_750 }
+    else if pool_balance < PHASE2_THRESHOLD { 9_800 }
+    else                                    { 9_850 }
+}
+
+// ── Unified payout formula ────────────────────────────────────────────────
+
+/// @notice Core payout calculator shared by all four games.
+/// @param bet              Wager in lamports.
+/// @param win_chance_num   Numerator of win probability fraction.
+/// @param win_chance_den   Denominator of win probability fraction.
+/// @param num              Retention numerator in bps (payout_num of the phase).
+/// @dev    Overflow saturates to u64::MAX — worst-case and solvency checks then reject the bet.
+#[inline(always)]
+fn calc_payout(bet: u64, win_chance_num: u64, win_chance_den: u64, num: u64) -> u64 {
+    fixed::win_payout(bet, num, win_chance_num, win_chance_den).unwrap_or(u64::MAX)
+}
+
+/// Applies a bps multiplier to a payout via `fixed`, saturating on overflow.
+#[inline(always)]
+fn scale_payout(amount: u64, bps: u64) -> u64 {
+    fixed::apply_bps(amount, bps).unwrap_or(u64::MAX)
+}
+
+// ── Config validation ─────────────────────────────────────────────────────
+
+/// `ext` = place_bet_v2 config_ext; must be 0 for every game that does not use it.
+fn validate_game_config(game_type: u8, cfg: &[u8; 3], ext: u64) -> Result<()> {
+    require!(ext == 0 || matches!(game_type, 1 | 9 | 10 | 22), BlitzError::InvalidGameConfig);
+    match game_type {
+        0 if cfg[0] != 0 => {
+            require!((FLIP_STREAK_MIN..=FLIP_STREAK_MAX).contains(&cfg[0]), BlitzError::InvalidFlipStreak);
+            require!(cfg[1] >> cfg[0] == 0 && cfg[2] == 0, BlitzError::InvalidFlipStreak);
+        }
+        0 => require!(*cfg == [0, 0, 0],              BlitzError::InvalidGameConfig),
+        1 => {
+            require!(cfg[0] < 16,                     BlitzError::InvalidCoordinate);
+            require!(cfg[1] < 16,                     BlitzError::InvalidCoordinate);
+            require!(cfg[2] <= 3,                     BlitzError::InvalidRadius);
+            let b = ext.to_le_bytes();
+            require!((b[6] as usize) < SECTOR_MAX_ZONES && b[7] == 0, BlitzError::InvalidSectorZones);
+            let (zones, n) = sector_zones(cfg, ext);
+            require!(zones[n..].iter().all(|z| *z == [0, 0, 0]), BlitzError::InvalidSectorZones);
+            for (i, z) in zones[..n].iter().enumerate().skip(1) {
+                require!(z[0] < 16 && z[1] < 16,      BlitzError::InvalidCoordinate);
+                require!(z[2] <= 3,                   BlitzError::InvalidRadius);
+                for o in &zones[..i] {
+                    require!(z[0].abs_diff(o[0]).max(z[1].abs_diff(o[1])) > z[2] + o[2], BlitzError::SectorZonesOverlap);
+                }
+            }
+        }
+        2 => {
+            require!(cfg[1] <= DICE_MODE_RANGE,       BlitzError::InvalidGameConfig);
+            if cfg[1] == DICE_MODE_EXACT {
+                require!(cfg[2] == 0,                 BlitzError::InvalidGameConfig);
+                require!(cfg[0] <= 99,                BlitzError::InvalidDiceTarget);
+            } else if cfg[1] == DICE_MODE_RANGE {
+                require!(cfg[0] <= cfg[2] && cfg[2] <= 99, BlitzError::InvalidDiceTarget);
+                require!(cfg[2] - cfg[0] < DICE_RANGE_MAX_WIDTH, BlitzError::InvalidDiceTarget);
+            } else if cfg[1] == DICE_MODE_CHANCE {
+                require!(cfg[2] == 0,                 BlitzError::InvalidGameConfig);
+                require!(cfg[0] >= DICE_CHANCE_MIN_STEPS && cfg[0] <= DICE_CHANCE_MAX_STEPS, BlitzError::InvalidDiceTarget);
+            } else if cfg[1] == 0 {
+                require!(cfg[0] >= 2 && cfg[0] <= 95, BlitzError::InvalidDiceTarget);
+            } else {
+                require!(cfg[0] >= 4 && cfg[0] <= 97, BlitzError::InvalidDiceTarget);
+            }
+        }
+        3 => {
+            let doors = tower_doors(cfg);
+            require!((TOWER_MIN_DOORS..=TOWER_MAX_DOORS).contains(&doors), BlitzError::InvalidTowerDoors);
+            if doors == 2 {
+                require!(cfg[0] >= 1 && cfg[0] <= TOWER_MAX_FLOORS, BlitzError::InvalidTowerFloors);
+                require!(cfg[1] & !((1u8 << cfg[0]).wrapping_sub(1)) == 0, BlitzError::InvalidGameConfig);
+            } else {
+                require!(cfg[0] >= 1 && cfg[0] <= TOWER_MAX_WIDE_FLOORS, BlitzError::InvalidTowerFloors);
+                let used = 2 * cfg[0] as u32;
+                require!((cfg[1] as u16) >> used == 0,  BlitzError::InvalidGameConfig);
+                require!((0..cfg[0]).all(|i| (cfg[1] >> (2 * i)) & 3 < doors), BlitzError::InvalidGameConfig);
+            }
+        }
+        4 => require!(*cfg == [0, 0, 0],              BlitzError::InvalidGameConfig),
+        22 => {
+            require!((PARLAY_MIN_LEGS..=PARLAY_MAX_LEGS).contains(&cfg[0]), BlitzError::InvalidParlay);
+            require!(cfg[1] == 0 && cfg[2] == 0 && ext > 0, BlitzError::InvalidParlay);
+        }
+        5 => {
+            require!(cfg[0] >= PLINKO_MIN_ROWS && cfg[0] <= PLINKO_MAX_ROWS, BlitzError::InvalidPlinkoConfig);
+            require!(cfg[1] <= PLINKO_RISK_HIGH,      BlitzError::InvalidPlinkoConfig);
+            require!(cfg[2] == 0,                     BlitzError::InvalidGameConfig);
+        }
+        6 => {
+            require!(cfg[0] >= MINES_MIN && cfg[0] <= MINES_MAX, BlitzError::InvalidMinesConfig);
+            require!(cfg[1] == 0 && cfg[2] == 0,      BlitzError::InvalidGameConfig);
+        }
+        7 => {
+            require!(u16::from_le_bytes([cfg[0], cfg[1]]) >= 101, BlitzError::InvalidCrashTarget);
+            require!(cfg[2] == 0,                     BlitzError::InvalidGameConfig);
+        }
+        8 => {
+            let max_sel = match cfg[0] {
+                ROULETTE_STRAIGHT                  => 36,
+                ROULETTE_COLOUR | ROULETTE_PARITY  => 1,
+                ROULETTE_DOZEN  | ROULETTE_COLUMN  => 2,
+                _ => return Err(BlitzError::InvalidRouletteBet.into()),
+            };
+            require!(cfg[1] <= max_sel,               BlitzError::InvalidRouletteBet);
+            require!(cfg[2] == 0,                     BlitzError::InvalidGameConfig);
+        }
+        9 => {
+            require!(cfg[0] >= 1 && cfg[0] <= KENO_MAX_PICKS, BlitzError::InvalidKenoPicks);
+            require!(ext >> KENO_NUMBERS == 0 && ext.count_ones() == cfg[0] as u32, BlitzError::InvalidKenoPicks);
+            require!(cfg[1] == 0 && cfg[2] == 0,      BlitzError::InvalidGameConfig);
+        }
+        10 => {
+            require!(ext >= 101 && ext <= CURVE_MAX_MULTIPLIER_X100 as u64, BlitzError::InvalidLimboTarget);
+            require!(*cfg == [0, 0, 0],               BlitzError::InvalidGameConfig);
+        }
+        11 => {
+            require!(cfg[0] <= WHEEL_RISK_HIGH,       BlitzError::InvalidWheelRisk);
+            require!(cfg[1] == 0 && cfg[2] == 0,      BlitzError::InvalidGameConfig);
+        }
+        12 => require!(*cfg == [0, 0, 0],             BlitzError::InvalidGameConfig),
+        13 => {
+            require!(cfg[0] < 1 << 5,                 BlitzError::InvalidPokerHold);
+            require!(cfg[1] == 0 && cfg[2] == 0,      BlitzError::InvalidGameConfig);
+        }
+        14 => {
+            require!(cfg[0] <= BACCARAT_TIE,          BlitzError::InvalidBaccaratSide);
+            require!(cfg[1] == 0 && cfg[2] == 0,      BlitzError::InvalidGameConfig);
+        }
+        15 => require!(*cfg == [0, 0, 0],             BlitzError::InvalidGameConfig),
+        16 => {
+            require!(sicbo_ways(cfg[0], cfg[1]) > 0,  BlitzError::InvalidSicBoBet);
+            require!(cfg[2] == 0,                     BlitzError::InvalidGameConfig);
+        }
+        17 => {
+            require!(cfg[0] <= DT_TIE,                BlitzError::InvalidDragonTigerSide);
+            require!(cfg[1] == 0 && cfg[2] == 0,      BlitzError::InvalidGameConfig);
+        }
+        18 => {
+            require!(dice_duo_ways(cfg[0], cfg[1]) > 0, BlitzError::InvalidDiceDuoBet);
+            require!(cfg[2] == 0,                       BlitzError::InvalidGameConfig);
+        }
+        19 => require!(*cfg == [0, 0, 0],             BlitzError::InvalidGameConfig),
+        20 => {
+            let path = u16::from_le_bytes([cfg[1], cfg[2]]);
+            require!(cfg[0] >= 1 && cfg[0] <= GOAL_MAX_ROWS, BlitzError::InvalidGoalConfig);
+            require!(
+                (path as u32) >> (2 * cfg[0]) == 0 && (0..cfg[0]).all(|i| (path >> (2 * i)) & 3 < GOAL_LANES as u16),
+                BlitzError::InvalidGoalConfig
+            );
+        }
+        21 => {
+            let doors = tower_doors(cfg);
+            require!((TOWER_MIN_DOORS..=TOWER_MAX_DOORS).contains(&doors), BlitzError::InvalidTowerDoors);
+            require!(cfg[0] >= 1 && cfg[0] <= TOWER_MAX_FLOORS, BlitzError::InvalidTowerFloors);
+            require!(cfg[1] == 0,                     BlitzError::InvalidGameConfig);
+        }
+        23 => require!(*cfg == [0, 0, 0],             BlitzError::InvalidGameConfig),
+        _ => return Err(BlitzError::InvalidGameType.into()),
+    }
+    Ok(())
+}
+
+// ── Unified resolver ──────────────────────────────────────────────────────
+
+/// Commit-reveal resolve: validates the reveal against SlotHashes, then resolve_with.
+fn resolve<'a>(
+    game_type:    u8,
+    session:      &Account<'a, GameSession>,
+    slot_hashes:  &AccountInfo<'a>,
+    nonce:        &[u8; 32],
+) -> Result<(bool, u64, GameOutcome, [u8; 32])> {
+    resolve_with(game_type, session, &CommitReveal { slot_hashes, nonce, client_seed: None })
+}
+
+/// Draws the seed from `provider`, resolves the game on the terms pinned at placement
+/// (payout_num_bps, payout_curve) — config changes since never reach it.
+/// Returns (won, gross_payout, GameOutcome, seed).
+fn resolve_with(
+    game_type: u8,
+    session:   &GameSession,
+    provider:  &impl RandomnessProvider,
+) -> Result<(bool, u64, GameOutcome, [u8; 32])> {
+    require!(session.game_state == 0,        BlitzError::SessionNotPending);
+    require!(session.game_type == game_type, BlitzError::WrongGameType);
+    let seed = provider.seed(session, &Clock::get()?)?;
+    let cfg  = [session.target_x, session.target_y, session.target_radius];
+    let (won, gross, outcome) = resolve_seed(game_type, &cfg, session.config_ext, session.bet_lamports, &seed, session.payout_num_bps as u64, &session.payout_curve)?;
+    Ok((won, gross, outcome, seed))
+}
+
+/// Pure seed → outcome dispatch. Returns (won, gross_payout, GameOutcome).
+/// `num` = retention numerator and `curve` = multiplier curve (Crash / Limbo), both as pinned
+/// on the session; `ext` = config_ext (Keno / Limbo).
+/// Game bytes: 0..8 | jackpot bytes: 24..28 (settle) | mystery pick: byte 28.
+fn resolve_seed(
+    game_type:    u8,
+    cfg:          &[u8; 3],
+    ext:          u64,
+    bet:          u64,
+    seed:         &[u8; 32],
+    num:          u64,
+    curve:        &PayoutCurve,
+) -> Result<(bool, u64, GameOutcome)> {
+    Ok(match game_type {
+        0 if cfg[0] != 0 => {
+            let (flips, sides) = (cfg[0], cfg[1]);
+            let results = flip_streak(seed, flips);
+            let won     = results == sides;
+            let gross   = calc_payout(bet, 1, 1u64 << flips, FLIP_STREAK_NUM_BPS);
+            (won, if won { gross } else { 0 }, GameOutcome::FlipStreak { flips, sides, results })
+        }
+        0 => {
+            let roll  = u64::from_le_bytes(seed[0..8].try_into().unwrap()) % 100;
+            let won   = roll < 50;
+            let gross = calc_payout(bet, 50, 100, num);
+            (won, if won { gross } else { 0 }, GameOutcome::Flip { roll: roll as u8 })
+        }
+        1 => {
+            let sx  = seed[0] % 16;
+            let sy  = seed[1] % 16;
+            let (zones, n) = sector_zones(cfg, ext);
+            let won = zones[..n].iter().any(|z| z[0].abs_diff(sx).max(z[1].abs_diff(sy)) <= z[2]);
+            let gross = if won { calc_payout(bet, sector_area(&zones[..n]), 256, num) } else { 0 };
+            (won, gross, GameOutcome::Sector { strike_x: sx, strike_y: sy })
+        }
+        2 if cfg[1] == DICE_MODE_CHANCE => {
+            let roll  = u64::from_le_bytes(seed[0..8].try_into().unwrap()) % DICE_CHANCE_STEPS;
+            let won   = roll < cfg[0] as u64;
+            let gross = scale_payout(bet, dice_chance_multiplier_bps(cfg[0], num));
+            (won, if won { gross } else { 0 }, GameOutcome::Dice { roll: roll as u8, target: cfg[0], is_over: false, mode: DICE_MODE_CHANCE, target_hi: 0 })
+        }
+        2 if cfg[1] == DICE_MODE_EXACT || cfg[1] == DICE_MODE_RANGE => {
+            let roll  = u64::from_le_bytes(seed[0..8].try_into().unwrap()) % 100;
+            let hi    = if cfg[1] == DICE_MODE_EXACT { cfg[0] } else { cfg[2] };
+            let won   = (cfg[0] as u64..=hi as u64).contains(&roll);
+            let gross = calc_payout(bet, (hi - cfg[0]) as u64 + 1, 100, num);
+            (won, if won { gross } else { 0 }, GameOutcome::Dice { roll: roll as u8, target: cfg[0], is_over: false, mode: cfg[1], target_hi: hi })
+        }
+        2 => {
+            let roll      = u64::from_le_bytes(seed[0..8].try_into().unwrap()) % 100;
+            let target    = cfg[0] as u64;
+            let is_over   = cfg[1] == 1;
+            let won       = if is_over { roll > target } else { roll < target };
+            let win_range = if is_over { 99u64.saturating_sub(target) } else { target };
+            let gross     = calc_payout(bet, win_range, 100, num);
+            (won, if won { gross } else { 0 }, GameOutcome::Dice { roll: roll as u8, target: target as u8, is_over, mode: cfg[1], target_hi: 0 })
+        }
+        3 => {
+            let floors = cfg[0];
+            let path   = cfg[1];
+            let doors  = tower_doors(cfg);
+            let (death, traps) = tower_walk(seed, floors, path, doors);
+            let won   = death == 0;
+            let (safe, total) = tower_odds(floors, doors);
+            let gross = calc_payout(bet, safe, total, num);
+            (won, if won { gross } else { 0 }, GameOutcome::Tower { floors, death_floor: death, path, traps, doors })
+        }
+        4 => {
+            let (picked, sub_cfg) = MYSTERY_GAMES[(seed[28] % 3) as usize];
+            let (won, gross, inner) = resolve_seed(picked, &sub_cfg, 0, bet, seed, num, curve)?;
+            let result = match inner {
+                GameOutcome::Flip   { roll }               => [roll, 0],
+                GameOutcome::Dice   { roll, .. }           => [roll, 0],
+                GameOutcome::Sector { strike_x, strike_y } => [strike_x, strike_y],
+                _                                          => [0, 0],
+            };
+            let gross = scale_payout(gross, MYSTERY_BONUS_BPS);
+            (won, gross, GameOutcome::Mystery { picked_game: picked, result })
+        }
+        5 => resolve_plinko(cfg[0], cfg[1], bet, seed, num),
+        7 => {
+            let target      = u16::from_le_bytes([cfg[0], cfg[1]]);
+            let crash_point = multiplier_roll_x100(seed, curve, fixed::BPS.saturating_sub(curve.edge_bps as u64));
+            let won         = crash_point >= target as u32;
+            let gross       = fixed::mul_div_floor(bet, target as u64, 100).unwrap_or(u64::MAX);
+            (won, if won { gross } else { 0 }, GameOutcome::Crash { crash_point, target })
+        }
+        8 => {
+            let pocket = (u64::from_le_bytes(seed[0..8].try_into().unwrap()) % ROULETTE_POCKETS) as u8;
+            let won    = roulette_hit(cfg[0], cfg[1], pocket);
+            let gross  = bet.saturating_mul(ROULETTE_PAYOUT_X[cfg[0] as usize]);
+            (won, if won { gross } else { 0 }, GameOutcome::Roulette { pocket, bet_class: cfg[0], selection: cfg[1] })
+        }
+        9 => {
+            let drawn          = keno_draw(seed);
+            let hits           = (drawn & ext).count_ones() as u8;
+            let multiplier_bps = keno_multiplier_bps(cfg[0], hits, num);
+            let gross          = scale_payout(bet, multiplier_bps);
+            (gross > bet, gross, GameOutcome::Keno { picks: ext, drawn, hits, multiplier_bps })
+        }
+        10 => {
+            let target = ext as u32;
+            let result = multiplier_roll_x100(seed, curve, fixed::BPS);
+            let won    = result >= target;
+            let keep   = fixed::BPS.saturating_sub(curve.edge_bps as u64);
+            let gross  = fixed::mul_div_floor(bet, ext * keep, 100 * fixed::BPS).unwrap_or(u64::MAX);
+            (won, if won { gross } else { 0 }, GameOutcome::Limbo { result, target })
+        }
+        11 => {
+            let risk           = cfg[0];
+            let segments       = WHEEL_PAYTABLE[risk as usize].len() as u64;
+            let segment        = (u64::from_le_bytes(seed[0..8].try_into().unwrap()) % segments) as u8;
+            let multiplier_bps = wheel_multiplier_bps(risk, segment, num);
+            let gross          = scale_payout(bet, multiplier_bps);
+            (gross > bet, gross, GameOutcome::Wheel { risk, segment, multiplier_bps })
+        }
+        13 => {
+            let deck           = poker_deal(seed);
+            let hold           = cfg[0];
+            let dealt: [u8; 5] = core::array::from_fn(|i| deck[i]);
+            let hand:  [u8; 5] = core::array::from_fn(|i| if hold >> i & 1 == 1 { deck[i] } else { deck[5 + i] });
+            let class          = poker_evaluate(&hand);
+            let multiplier_bps = video_poker_multiplier_bps(class, num);
+            let gross          = scale_payout(bet, multiplier_bps);
+            (gross > bet, gross, GameOutcome::VideoPoker { dealt, hold, hand, class, multiplier_bps })
+        }
+        14 => {
+            let side = cfg[0];
+            let (player_cards, banker_cards, player_total, banker_total) = baccarat_deal(seed);
+            let gross = match (player_total.cmp(&banker_total), side) {
+                (core::cmp::Ordering::Equal, BACCARAT_TIE)      => fixed::apply_bps(bet, BACCARAT_PAYOUT_BPS[2]),
+                (core::cmp::Ordering::Equal, _)                 => Some(bet), // push: the stake comes back, not a win
+                (core::cmp::Ordering::Greater, BACCARAT_PLAYER) => fixed::apply_bps(bet, BACCARAT_PAYOUT_BPS[0]),
+                (core::cmp::Ordering::Less, BACCARAT_BANKER)    => fixed::apply_bps(bet, BACCARAT_PAYOUT_BPS[1]),
+                _                                               => Some(0),
+            }.unwrap_or(u64::MAX);
+            (gross > bet, gross, GameOutcome::Baccarat { side, player_cards, banker_cards, player_total, banker_total })
+        }
+        15 => {
+            let reels          = slots_spin(seed);
+            let multiplier_bps = slots_multiplier_bps(&reels, num);
+            let gross          = scale_payout(bet, multiplier_bps);
+            (gross > bet, gross, GameOutcome::Slots { reels, multiplier_bps })
+        }
+        16 => {
+            let (bet_class, selection) = (cfg[0], cfg[1]);
+            let dice  = sicbo_roll(seed);
+            let won   = sicbo_hit(bet_class, selection, &dice);
+            let gross = calc_payout(bet, sicbo_ways(bet_class, selection), 216, num);
+            (won, if won { gross } else { 0 }, GameOutcome::SicBo { bet_class, selection, dice })
+        }
+        17 => {
+            let side = cfg[0];
+            let (dragon, tiger) = dragon_tiger_deal(seed);
+            let gross = match (dragon.cmp(&tiger), side) {
+                (core::cmp::Ordering::Equal, DT_TIE)      => fixed::apply_bps(bet, DT_PAYOUT_BPS[2]),
+                (core::cmp::Ordering::Equal, _)           => fixed::apply_bps(bet, DT_TIE_RETURN_BPS),
+                (core::cmp::Ordering::Greater, DT_DRAGON) => fixed::apply_bps(bet, DT_PAYOUT_BPS[0]),
+                (core::cmp::Ordering::Less, DT_TIGER)     => fixed::apply_bps(bet, DT_PAYOUT_BPS[1]),
+                _                                         => Some(0),
+            }.unwrap_or(u64::MAX);
+            (gross > bet, gross, GameOutcome::DragonTiger { side, dragon, tiger })
+        }
+        18 => {
+            let (mode, target) = (cfg[0], cfg[1]);
+            let dice  = dice_duo_roll(seed);
+            let sum   = dice[0] + dice[1];
+            let won   = match mode { DUO_UNDER => sum < target, DUO_OVER => sum > target, _ => sum == target };
+            let gross = calc_payout(bet, dice_duo_ways(mode, target), 36, DUO_NUM);
+            (won, if won { gross } else { 0 }, GameOutcome::DiceDuo { mode, target, dice })
+        }
+        20 => {
+            let rows  = cfg[0];
+            let path  = u16::from_le_bytes([cfg[1], cfg[2]]);
+            let (death, traps) = goal_walk(seed, rows, path);
+            let won   = death == 0;
+            let gross = scale_payout(bet, goal_multiplier_bps(rows));
+            (won, if won { gross } else { 0 }, GameOutcome::Goal { rows, death_row: death, path, traps })
+        }
+        23 => {
+            let roll  = seed[0] & 1;
+            let won   = roll == 1;
+            let gross = calc_payout(bet, 1, 2, DOUBLE_NUM_BPS);
+            (won, if won { gross } else { 0 }, GameOutcome::Double { roll })
+        }
+        _ => return Err(BlitzError::InvalidGameType.into()),
+    })
+}
+
+// ── Slots ─────────────────────────────────────────────────────────────────
+
+/// Symbols on the payline: reel i stops at SLOTS_REEL[seed[i] % 32].
+fn slots_spin(seed: &[u8; 32]) -> [u8; 3] {
+    core::array::from_fn(|i| SLOTS_REEL[seed[i] as usize % SLOTS_REEL.len()])
+}
+
+// ── Sic Bo ────────────────────────────────────────────────────────────────
+
+/// Three dice, 1–6 each, from the base-6 digits of seed bytes 0..8 (LE u64) mod 216.
+/// A single byte % 6 per die would favour 1–4; the u64 reduction keeps every roll at 1/216.
+fn sicbo_roll(seed: &[u8; 32]) -> [u8; 3] {
+    let r = u64::from_le_bytes(seed[0..8].try_into().unwrap()) % 216;
+    [(r / 36) as u8 + 1, (r / 6 % 6) as u8 + 1, (r % 6) as u8 + 1]
+}
+
+/// Whether `dice` win a (bet_class, selection) bet. Any triple loses Big and Small.
+fn sicbo_hit(bet_class: u8, selection: u8, dice: &[u8; 3]) -> bool {
+    let total  = dice.iter().sum::<u8>();
+    let triple = dice[0] == dice[1] && dice[1] == dice[2];
+    match bet_class {
+        SICBO_SMALL  => !triple && (4..=10).contains(&total),
+        SICBO_BIG    => !triple && (11..=17).contains(&total),
+        SICBO_TRIPLE => triple && dice[0] == selection,
+        SICBO_TOTAL  => total == selection,
+        _            => false,
+    }
+}
+
+// ── Dragon Tiger ──────────────────────────────────────────────────────────
+
+/// One card each from an infinite shoe, as in baccarat_deal: rank = LE u16 of seed bytes
+/// 0..2 (Dragon) / 2..4 (Tiger) % 13 + 1. Ace is low, King high; suits do not matter.
+fn dragon_tiger_deal(seed: &[u8; 32]) -> (u8, u8) {
+    let card = |k: usize| (u16::from_le_bytes([seed[2 * k], seed[2 * k + 1]]) % 13) as u8 + 1;
+    (card(0), card(1))
+}
+
+// ── Dice Duo ──────────────────────────────────────────────────────────────
+
+/// Two dice, 1–6 each, from the base-6 digits of seed bytes 0..8 (LE u64) mod 36 —
+/// unbiased, unlike a byte % 6 per die (see sicbo_roll).
+fn dice_duo_roll(seed: &[u8; 32]) -> [u8; 2] {
+    let r = u64::from_le_bytes(seed[0..8].try_into().unwrap()) % 36;
+    [(r / 6) as u8 + 1, (r % 6) as u8 + 1]
+}
+
+// ── Flip streak ───────────────────────────────────────────────────────────
+
+/// Results of a `flips`-long streak: bit i = bit 0 of seed byte i (1 = heads).
+fn flip_streak(seed: &[u8; 32], flips: u8) -> u8 {
+    seed.iter().take(flips as usize).enumerate().fold(0u8, |r, (i, b)| r | (b & 1) << i)
+}
+
+// ── Sector zones ──────────────────────────────────────────────────────────
+
+/// Target zones of a Sector bet: game_config, then the extra zones packed in config_ext.
+/// Returns the zone array and how many are in use; unused slots are whatever ext held.
+fn sector_zones(cfg: &[u8; 3], ext: u64) -> ([[u8; 3]; SECTOR_MAX_ZONES], usize) {
+    let b = ext.to_le_bytes();
+    let n = 1 + (b[6] as usize).min(SECTOR_MAX_ZONES - 1);
+    ([*cfg, [b[0], b[1], b[2]], [b[3], b[4], b[5]]], n)
+}
+
+/// Combined area of disjoint zones, Σ (2r+1)² with r clamped to 3.
+fn sector_area(zones: &[[u8; 3]]) -> u64 {
+    zones.iter().map(|z| { let w = z[2].min(3) as u64 * 2 + 1; w * w }).sum()
+}
+
+// ── Tower walk ────────────────────────────────────────────────────────────
+
+/// Walks `path` up the tower. Two doors use seed bytes 0..floors as trap lanes, one bit
+/// per floor. Wider towers take base-`doors` digit i of seed bytes 0..8 (LE u64) mod
+/// doors^floors as the trap on floor i — unbiased for 3 doors — packed 2 bits per floor.
+/// Returns (death_floor, traps) — death_floor 0 = survived every floor.
+fn tower_walk(seed: &[u8; 32], floors: u8, path: u8, doors: u8) -> (u8, u8) {
+    let (mut death, mut traps) = (0u8, 0u8);
+    if doors == 2 {
+        for (i, b) in seed.iter().enumerate().take(floors as usize) {
+            let trap = b % 2;
+            traps   |= trap << i;
+            if death == 0 && (path >> i) & 1 == trap { death = (i + 1) as u8; }
+        }
+        return (death, traps);
+    }
+    let d     = doors as u64;
+    let mut r = u64::from_le_bytes(seed[0..8].try_into().unwrap()) % d.pow(floors as u32);
+    for i in 0..floors {
+        let trap = (r % d) as u8;
+        r       /= d;
+        traps   |= trap << (2 * i);
+        if death == 0 && (path >> (2 * i)) & 3 == trap { death = i + 1; }
+    }
+    (death, traps)
+}
+
+/// Doors per floor from game_config[2]; 0 is the classic two.
+fn tower_doors(cfg: &[u8; 3]) -> u8 {
+    if cfg[2] == 0 { TOWER_MIN_DOORS } else { cfg[2] }
+}
+
+/// Win fraction (safe, total) for surviving `floors` floors of `doors` doors, one trapped each.
+fn tower_odds(floors: u8, doors: u8) -> (u64, u64) {
+    let d = doors as u64;
+    ((d - 1).pow(floors as u32), d.pow(floors as u32))
+}
+
+// ── Goal walk ─────────────────────────────────────────────────────────────
+
+/// Walks `path` across `rows` rows of three lanes. The trap lane of row i is base-3 digit i
+/// of seed bytes 0..8 (LE u64) mod 3^rows — unbiased, unlike a byte % 3 per row.
+/// Returns (death_row, traps) — death_row 0 = scored; traps packs 2 bits per row like `path`.
+fn goal_walk(seed: &[u8; 32], rows: u8, path: u16) -> (u8, u16) {
+    let mut r = u64::from_le_bytes(seed[0..8].try_into().unwrap()) % (GOAL_LANES as u64).pow(rows as u32);
+    let (mut death, mut traps) = (0u8, 0u16);
+    for i in 0..rows {
+        let trap = (r % GOAL_LANES as u64) as u16;
+        r       /= GOAL_LANES as u64;
+        traps   |= trap << (2 * i);
+        if death == 0 && (path >> (2 * i)) & 3 == trap { death = i + 1; }
+    }
+    (death, traps)
+}
+
+// ── Plinko drop ───────────────────────────────────────────────────────────
+
+/// Drops the ball through `rows` pegs: bit i of seed bytes 0..2 (LE) = 1 → right at row i.
+/// Returns (path bits, bucket) — bucket = rights taken, 0..=rows.
+fn plinko_drop(seed: &[u8; 32], rows: u8) -> (u16, u8) {
+    let mask = ((1u32 << rows) - 1) as u16;
+    let path = u16::from_le_bytes([seed[0], seed[1]]) & mask;
+    (path, path.count_ones() as u8)
+}
+
+/// Drops the ball and pays its bucket on the `num`-normalised table. A net win is a bucket
+/// above 1×; a sub-1× bucket is a partial return, not a win.
+fn resolve_plinko(rows: u8, risk: u8, bet: u64, seed: &[u8; 32], num: u64) -> (bool, u64, GameOutcome) {
+    let (path, bucket) = plinko_drop(seed, rows);
+    let multiplier_bps = plinko_multiplier_bps(rows, risk, bucket, num);
+    let gross          = scale_payout(bet, multiplier_bps);
+    (gross > bet, gross, GameOutcome::Plinko { rows, risk, path, bucket, multiplier_bps })
+}
+
+// ── Multiplier roll (Crash / Limbo) ───────────────────────────────────────
+
+/// Rolled multiplier ×100 from seed bytes 0..8: keep / U with U uniform on (0, 1], floored to
+/// 0.01× and clamped to [1.00×, max_multiplier]. P(roll ≥ t) = keep / t. Crash rolls with
+/// keep = 1 − edge (matching curve_win_ppm); Limbo rolls fair (keep = 1) and takes the edge
+/// from the payout. CURVE_INVERSE_BUST busts at 1.00× when seed bytes 8..12 (mod 10_000)
+/// fall under bust_bps.
+fn multiplier_roll_x100(seed: &[u8; 32], c: &PayoutCurve, keep: u64) -> u32 {
+    if c.shape == CURVE_INVERSE_BUST {
+        let roll = u32::from_le_bytes(seed[8..12].try_into().unwrap()) as u64 % fixed::BPS;
+        if roll < c.bust_bps as u64 { return 100; }
+    }
+    let u = (u64::from_le_bytes(seed[0..8].try_into().unwrap()) >> 11) + 1; // 1..=2^53
+    let x = fixed::mul_div_floor(keep, 1u64 << 53, u * 100).unwrap_or(u64::MAX);
+    x.clamp(100, c.max_multiplier_x100 as u64) as u32
+}
+
+// ── Roulette ──────────────────────────────────────────────────────────────
+
+/// Whether `pocket` wins a (bet_class, selection) bet. Zero loses every outside bet.
+fn roulette_hit(bet_class: u8, selection: u8, pocket: u8) -> bool {
+    if bet_class == ROULETTE_STRAIGHT { return pocket == selection; }
+    if pocket == 0 { return false; }
+    match bet_class {
+        ROULETTE_COLOUR => ((ROULETTE_RED >> pocket) & 1 == 1) == (selection == 0),
+        ROULETTE_PARITY => pocket.is_multiple_of(2) == (selection == 1),
+        ROULETTE_DOZEN  => (pocket - 1) / 12 == selection,
+        ROULETTE_COLUMN => (pocket - 1) % 3 == selection,
+        _               => false,
+    }
+}
+
+// ── Keno draw ─────────────────────────────────────────────────────────────
+
+/// Draws KENO_DRAWN distinct numbers by partial Fisher–Yates over 0..40, one LE u16 of
+/// seed bytes 0..20 per draw. Returns the drawn-number bitmask.
+fn keno_draw(seed: &[u8; 32]) -> u64 {
+    let mut balls: [u8; KENO_NUMBERS as usize] = core::array::from_fn(|i| i as u8);
+    let mut drawn = 0u64;
+    for i in 0..KENO_DRAWN as usize {
+        let r = u16::from_le_bytes([seed[2 * i], seed[2 * i + 1]]) as usize;
+        let j = i + r % (KENO_NUMBERS as usize - i);
+        balls.swap(i, j);
+        drawn |= 1 << balls[i];
+    }
+    drawn
+}
+
+// ── Video Poker ───────────────────────────────────────────────────────────
+
+/// Deals 10 distinct cards by partial Fisher–Yates over the 52-card deck, one LE u16 of
+/// seed bytes 0..20 per card. Cards 0..5 are the deal, 5..10 the draw replacing slot i − 5.
+fn poker_deal(seed: &[u8; 32]) -> [u8; 10] {
+    let mut deck: [u8; POKER_DECK as usize] = core::array::from_fn(|i| i as u8);
+    for i in 0..10 {
+        let r = u16::from_le_bytes([seed[2 * i], seed[2 * i + 1]]) as usize;
+        let j = i + r % (POKER_DECK as usize - i);
+        deck.swap(i, j);
+    }
+    core::array::from_fn(|i| deck[i])
+}
+
+/// @notice Jacks-or-Better class of a five-card hand (POKER_* constants). Ace plays high
+///         and low (A-2-3-4-5); only T-J-Q-K-A suited is a royal.
+pub fn poker_evaluate(hand: &[u8; 5]) -> u8 {
+    let mut counts = [0u8; 13];
+    for c in hand { counts[(c % 13) as usize] += 1; }
+    let flush = hand.iter().all(|c| c / 13 == hand[0] / 13);
+    let ranks = counts.iter().enumerate().filter(|(_, n)| **n > 0).map(|(r, _)| r as u8);
+    let (lo, hi, distinct) = ranks.fold((u8::MAX, 0u8, 0u8), |(lo, hi, d), r| (lo.min(r), hi.max(r), d + 1));
+    let straight = distinct == 5 && (hi - lo == 4 || counts[12] == 1 && hi == 12 && counts[..4].iter().all(|n| *n == 1));
+    let (mut pairs, mut trips, mut quads, mut high_pair) = (0, false, false, false);
+    for (r, n) in counts.iter().enumerate() {
+        match n {
+            2 => { pairs += 1; high_pair |= r >= 9; }
+            3 => trips = true,
+            4 => quads = true,
+            _ => {}
+        }
+    }
+    match () {
+        _ if straight && flush && lo == 8 => POKER_ROYAL,
+        _ if straight && flush            => POKER_STRAIGHT_FLUSH,
+        _ if quads                        => POKER_QUADS,
+        _ if trips && pairs == 1          => POKER_FULL_HOUSE,
+        _ if flush                        => POKER_FLUSH,
+        _ if straight                     => POKER_STRAIGHT,
+        _ if trips                        => POKER_TRIPS,
+        _ if pairs == 2                   => POKER_TWO_PAIR,
+        _ if high_pair                    => POKER_JACKS,
+        _                                 => POKER_NOTHING,
+    }
+}
+
+// ── Baccarat ──────────────────────────────────────────────────────────────
+
+/// Deals a coup from an infinite shoe: card k is rank 1–13 from the LE u16 at seed bytes
+/// 2k..2k+2, in P-B-P-B order, then the third cards in turn. Standard tableau: a natural
+/// (8/9) stands both hands; the Player draws on 0–5; the Banker draws on 0–5 when the Player
+/// stood, otherwise by the Banker total against the Player's third card.
+/// Returns (player ranks, banker ranks — 0 = no third card, player total, banker total).
+fn baccarat_deal(seed: &[u8; 32]) -> ([u8; 3], [u8; 3], u8, u8) {
+    let card  = |k: usize| (u16::from_le_bytes([seed[2 * k], seed[2 * k + 1]]) % 13) as u8 + 1;
+    let value = |rank: u8| if rank >= 10 { 0 } else { rank };
+    let mut p = [card(0), card(2), 0];
+    let mut b = [card(1), card(3), 0];
+    let mut next = 4;
+    let mut pt = (value(p[0]) + value(p[1])) % 10;
+    let mut bt = (value(b[0]) + value(b[1])) % 10;
+    if pt < 8 && bt < 8 {
+        let player_third = if pt <= 5 {
+            p[2] = card(next);
+            next += 1;
+            pt   = (pt + value(p[2])) % 10;
+            Some(value(p[2]))
+        } else { None };
+        let banker_draws = match player_third {
+            None    => bt <= 5,
+            Some(t) => match bt {
+                0..=2 => true,
+                3     => t != 8,
+                4     => (2..=7).contains(&t),
+                5     => (4..=7).contains(&t),
+                6     => (6..=7).contains(&t),
+                _     => false,
+            },
+        };
+        if banker_draws {
+            b[2] = card(next);
+            bt   = (bt + value(b[2])) % 10;
+        }
+    }
+    (p, b, pt, bt)
+}
+
+// ── Mines ─────────────────────────────────────────────────────────────────
+
+/// One sequential draw: the picked tile is a mine with probability mines / (25 − safe),
+/// the same odds as a fixed hidden board. BLAKE3(board seed || tile || safe || slot hash).
+fn mines_draw(seed: &[u8; 32], tile: u8, safe: u8, mines: u8, slot_hash: &[u8; 32]) -> bool {
+    let mut h = blake3::Hasher::new();
+    h.update(seed);
+    h.update(&[tile, safe]);
+    h.update(slot_hash);
+    let roll = u64::from_le_bytes(h.finalize().as_bytes()[0..8].try_into().unwrap());
+    roll % ((MINES_TILES - safe) as u64) < mines as u64
+}
+
+/// Hash of the first slot ≥ `slot` in SlotHashes. Fails unless an older entry is also
+/// still present — otherwise the answer would drift as the sysvar rolls over.
+fn slot_hash_from(slot_hashes_ai: &AccountInfo, slot: u64) -> Result<[u8; 32]> {
+    with_slot_hashes(slot_hashes_ai, |src| sysvars::hash_at_or_after(src, slot))?
+        .ok_or(BlitzError::SlotHashNotFound.into())
+}
+
+/// Runs a `sysvars` lookup against SlotHashes — the account data, or the sol_get_sysvar
+/// syscall under `slot-hashes-syscall`. A layout mismatch fails with MalformedSysvar.
+#[cfg(not(all(feature = "slot-hashes-syscall", target_os = "solana")))]
+fn with_slot_hashes<T>(
+    slot_hashes_ai: &AccountInfo,
+    f: impl FnOnce(&sysvars::AccountData) -> core::result::Result<T, sysvars::Malformed>,
+) -> Result<T> {
+    let data = slot_hashes_ai.data.borrow();
+    f(&sysvars::AccountData(&data)).map_err(|_| BlitzError::MalformedSysvar.into())
+}
+
+#[cfg(all(feature = "slot-hashes-syscall", target_os = "solana"))]
+fn with_slot_hashes<T>(
+    _slot_hashes_ai: &AccountInfo,
+    f: impl FnOnce(&sysvars::Syscall) -> core::result::Result<T, sysvars::Malformed>,
+) -> Result<T> {
+    f(&sysvars::Syscall).map_err(|_| BlitzError::MalformedSysvar.into())
+}
+
+/// Settles an open Mines board: cash-out at the current multiplier, or a loss on `hit_tile`.
+fn finish_mines<'info>(a: &mut RevealGame<'info>, won: bool, hit_tile: u8) -> Result<()> {
+    let safe    = a.session.mines_revealed.count_ones() as u8;
+    let payout  = if won {
+        scale_payout(a.session.bet_lamports, mines_multiplier_bps(a.session.target_x, safe, a.session.payout_num_bps as u64))
+    } else { 0 };
+    let dest    = payout_dest(&a.session, &a.player, &a.payout_wallet)?;
+    let partner = partner_of(&a.session, &mut a.partner, &a.player)?;
+    let cosign  = a.cosigner.is_some();
+    let seed    = a.session.mines_seed;
+    let plan    = settle(&mut a.pool, &dest, &a.referrer, &a.session, partner, a.referral.as_mut(), a.firehose.as_mut(), cosign, won, payout, seed)?;
+    unindex_session(&mut a.open_sessions, &a.session)?;
+    a.session.mines_pick = MINES_NO_PICK;
+    record_settlement(&mut a.session, a.receipt_tree.as_deref_mut(), &a.player, won, &plan)?;
+    let s = &a.session;
+    emit!(MinesSettled { player: s.player, won, mines: s.target_x, safe_tiles: safe, revealed: s.mines_revealed, hit_tile, payout });
+    notify_settled(&a.notification_prefs, s.player, won, plan.jackpot_prize);
+    Ok(())
+}
+
+// ── HiLo ──────────────────────────────────────────────────────────────────
+
+/// Winning ranks for `guess` on `card` (ties win): higher → 14 − card, lower → card.
+/// None for an invalid guess or one that cannot lose.
+fn hilo_odds(card: u8, guess: u8) -> Option<u64> {
+    let w = match guess {
+        HILO_HIGHER => HILO_RANKS + 1 - card,
+        HILO_LOWER  => card,
+        _           => return None,
+    };
+    (w < HILO_RANKS).then_some(w as u64)
+}
+
+/// Card dealt after `round` correct guesses: BLAKE3(seed || round || slot hash) % 13 + 1.
+fn hilo_draw(seed: &[u8; 32], round: u8, slot_hash: &[u8; 32]) -> u8 {
+    let mut h = blake3::Hasher::new();
+    h.update(seed);
+    h.update(&[b'h', round]);
+    h.update(slot_hash);
+    (u64::from_le_bytes(h.finalize().as_bytes()[0..8].try_into().unwrap()) % HILO_RANKS as u64) as u8 + 1
+}
+
+/// Settles an open HiLo chain: cash-out at the compounded multiplier, or a loss on a wrong guess.
+fn finish_hilo<'info>(a: &mut RevealGame<'info>, won: bool) -> Result<()> {
+    let rounds  = a.session.hilo_rounds;
+    let payout  = if won {
+        scale_payout(a.session.bet_lamports, hilo_multiplier_bps(a.session.hilo_odds_den, rounds, a.session.payout_num_bps as u64))
+    } else { 0 };
+    let dest    = payout_dest(&a.session, &a.player, &a.payout_wallet)?;
+    let partner = partner_of(&a.session, &mut a.partner, &a.player)?;
+    let cosign  = a.cosigner.is_some();
+    let seed    = a.session.mines_seed;
+    let plan    = settle(&mut a.pool, &dest, &a.referrer, &a.session, partner, a.referral.as_mut(), a.firehose.as_mut(), cosign, won, payout, seed)?;
+    unindex_session(&mut a.open_sessions, &a.session)?;
+    record_settlement(&mut a.session, a.receipt_tree.as_deref_mut(), &a.player, won, &plan)?;
+    let s = &a.session;
+    emit!(HiloSettled { player: s.player, won, rounds, last_card: s.hilo_card, payout });
+    notify_settled(&a.notification_prefs, s.player, won, plan.jackpot_prize);
+    Ok(())
+}
+
+// ── Stairs ────────────────────────────────────────────────────────────────
+
+/// Whether 1-based `step` gives way: BLAKE3(seed || step || slot hash) % 20 < step.
+fn stairs_draw(seed: &[u8; 32], step: u8, slot_hash: &[u8; 32]) -> bool {
+    let mut h = blake3::Hasher::new();
+    h.update(seed);
+    h.update(&[b's', step]);
+    h.update(slot_hash);
+    u64::from_le_bytes(h.finalize().as_bytes()[0..8].try_into().unwrap()) % STAIRS_DEN < step as u64
+}
+
+/// Settles an open climb: cash-out at the cleared steps' multiplier, or a loss on a fall.
+fn finish_stairs<'info>(a: &mut RevealGame<'info>, won: bool) -> Result<()> {
+    let steps   = a.session.stairs_step;
+    let payout  = if won { scale_payout(a.session.bet_lamports, stairs_multiplier_bps(steps, a.session.payout_num_bps as u64)) } else { 0 };
+    let dest    = payout_dest(&a.session, &a.player, &a.payout_wallet)?;
+    let partner = partner_of(&a.session, &mut a.partner, &a.player)?;
+    let cosign  = a.cosigner.is_some();
+    let seed    = a.session.mines_seed;
+    let plan    = settle(&mut a.pool, &dest, &a.referrer, &a.session, partner, a.referral.as_mut(), a.firehose.as_mut(), cosign, won, payout, seed)?;
+    unindex_session(&mut a.open_sessions, &a.session)?;
+    a.session.stairs_pending = false;
+    record_settlement(&mut a.session, a.receipt_tree.as_deref_mut(), &a.player, won, &plan)?;
+    let s = &a.session;
+    emit!(StairsSettled { player: s.player, won, steps, payout });
+    notify_settled(&a.notification_prefs, s.player, won, plan.jackpot_prize);
+    Ok(())
+}
+
+// ── Parlay ────────────────────────────────────────────────────────────────
+
+/// Chained worst-case multiplier (bps) of parlay legs: each leg's worst payout is taken on
+/// the previous one's, from a 1-SOL reference stake. Saturates, so an oversized parlay is
+/// refused by the payout cap rather than wrapping.
+/// @dev An exposure bound, so it rounds up: +1 after each leg's floored payout and a ceiling
+///      into bps. bet × result / BPS is then never below what resolve_parlay can pay.
+pub fn parlay_multiplier_bps(legs: &[ParlayLeg]) -> u64 {
+    const REF: u64 = 1_000_000_000;
+    let gross = legs.iter().fold(REF, |acc, l| get_worst_payout(acc, l.game_type, &l.game_config, l.config_ext).saturating_add(1));
+    fixed::mul_div_ceil(gross, fixed::BPS, REF).unwrap_or(u64::MAX)
+}
+
+/// Seed of parlay leg `leg`: BLAKE3(seed || leg), so the legs are independent of each other.
+fn parlay_leg_seed(seed: &[u8; 32], leg: u8) -> [u8; 32] {
+    let mut h = blake3::Hasher::new();
+    h.update(seed);
+    h.update(&[leg]);
+    *h.finalize().as_bytes()
+}
+
+/// Resolves the legs in order on the pinned terms; `results` bit i = leg i won.
+/// Stops at the first lost leg.
+fn resolve_parlay(p: &ParlaySession, bet: u64, seed: &[u8; 32], num: u64, curve: &PayoutCurve) -> Result<(bool, u64, GameOutcome)> {
+    let (mut gross, mut results) = (bet, 0u8);
+    for i in 0..p.legs {
+        let l = i as usize;
+        let (won, paid, _) = resolve_seed(p.games[l], &p.configs[l], p.exts[l], gross, &parlay_leg_seed(seed, i), num, curve)?;
+        if paid == 0 {
+            return Ok((false, 0, GameOutcome::Parlay { legs: p.legs, games: p.games, results }));
+        }
+        results |= (won as u8) << i;
+        gross    = paid;
+    }
+    Ok((gross > bet, gross, GameOutcome::Parlay { legs: p.legs, games: p.games, results }))
+}
+
+// ── Tower Climb ───────────────────────────────────────────────────────────
+
+/// Trap door on 1-based `floor`: BLAKE3(seed || floor || slot hash) % doors.
+fn tower_climb_trap(seed: &[u8; 32], floor: u8, doors: u8, slot_hash: &[u8; 32]) -> u8 {
+    let mut h = blake3::Hasher::new();
+    h.update(seed);
+    h.update(&[b't', floor]);
+    h.update(slot_hash);
+    (u64::from_le_bytes(h.finalize().as_bytes()[0..8].try_into().unwrap()) % doors as u64) as u8
+}
+
+/// Settles an open climb: cash-out at the accumulated multiplier, or a loss on a trap.
+fn finish_tower_climb<'info>(a: &mut RevealGame<'info>, won: bool) -> Result<()> {
+    let floors  = a.session.tower_floor;
+    let payout  = if won { scale_payout(a.session.bet_lamports, a.session.tower_multiplier_bps) } else { 0 };
+    let dest    = payout_dest(&a.session, &a.player, &a.payout_wallet)?;
+    let partner = partner_of(&a.session, &mut a.partner, &a.player)?;
+    let cosign  = a.cosigner.is_some();
+    let seed    = a.session.mines_seed;
+    let plan    = settle(&mut a.pool, &dest, &a.referrer, &a.session, partner, a.referral.as_mut(), a.firehose.as_mut(), cosign, won, payout, seed)?;
+    unindex_session(&mut a.open_sessions, &a.session)?;
+    a.session.mines_pick = MINES_NO_PICK;
+    record_settlement(&mut a.session, a.receipt_tree.as_deref_mut(), &a.player, won, &plan)?;
+    let s = &a.session;
+    emit!(TowerClimbSettled { player: s.player, won, floors, multiplier_bps: s.tower_multiplier_bps, payout });
+    notify_settled(&a.notification_prefs, s.player, won, plan.jackpot_prize);
+    Ok(())
+}
+
+// ── Tower race payout ─────────────────────────────────────────────────────
+
+/// Moves the pot out of the race PDA: winner gets pot − rake, rake lands in house fees.
+/// Returns the prize paid to the winner.
+fn pay_race<'info>(
+    pool:    &mut Account<'info, GlobalPool>,
+    race_ai: &AccountInfo<'info>,
+    winner:  &AccountInfo<'info>,
+    pot:     u64,
+) -> Result<u64> {
+    let rake  = fixed::share_bps(pot, RACE_RAKE_BPS);
+    let prize = pot.saturating_sub(rake);
+    let pool_ai = pool.to_account_info();
+    debit(race_ai, pot)?;
+    credit(winner, prize)?;
+    credit(&pool_ai, rake)?;
+    pool.house_fees_earned = checked_add(pool.house_fees_earned, rake)?;
+    sync_balance(pool, &pool_ai)?;
+    Ok(prize)
+}
+
+// ── Rock-Paper-Scissors commitment ────────────────────────────────────────
+
+/// SHA-256(player || move || salt): what a player commits to in create/join_rps_match.
+pub fn rps_commitment(player: &Pubkey, mv: u8, salt: &[u8; 32]) -> [u8; 32] {
+    hash::hashv(&[player.as_ref(), &[mv], salt]).to_bytes()
+}
+
+// ── Lottery draw ──────────────────────────────────────────────────────────
+
+/// Draws up to LOTTERY_MAX_WINNERS distinct ticket indices from `seed`, tier by tier, and the
+/// prize of each. Slots beyond `tickets` stay u32::MAX with no prize — their share rolls over.
+fn lottery_pick(seed: &[u8; 32], tickets: u32, pot: u64) -> ([u32; LOTTERY_MAX_WINNERS], [u64; LOTTERY_MAX_WINNERS]) {
+    let mut winners = [u32::MAX; LOTTERY_MAX_WINNERS];
+    let mut prizes  = [0u64; LOTTERY_MAX_WINNERS];
+    let mut i = 0;
+    for (count, bps) in LOTTERY_TIERS.iter() {
+        for _ in 0..*count {
+            if (i as u32) < tickets {
+                let h = hash::hashv(&[seed, &[i as u8]]).to_bytes();
+                let mut pick = (u64::from_le_bytes(h[0..8].try_into().unwrap()) % tickets as u64) as u32;
+                while winners[..i].contains(&pick) { pick = (pick + 1) % tickets; }
+                winners[i] = pick;
+                prizes[i]  = fixed::share_bps(pot, *bps);
+            }
+            i += 1;
+        }
+    }
+    (winners, prizes)
+}
+
+// ── Auditor rate limit ────────────────────────────────────────────────────
+
+/// Enforces the grant's min_interval_slots and records the call. Returns the current slot.
+fn touch_auditor(a: &mut Account<Auditor>) -> Result<u64> {
+    let slot = Clock::get()?.slot;
+    require!(a.calls == 0 || slot >= a.last_call_slot.saturating_add(a.min_interval_slots), BlitzError::AuditRateLimited);
+    a.last_call_slot = slot;
+    a.calls          = a.calls.saturating_add(1);
+    Ok(slot)
+}
+
+// ── Jackpot trigger cap ───────────────────────────────────────────────────
+
+/// Max trigger threshold out of 2³² — `cap_bps` of all rolls (0 = default 0.5%).
+fn jackpot_threshold_cap(cap_bps: u16) -> u64 {
+    let bps = if cap_bps == 0 { JACKPOT_CAP_DEFAULT_BPS } else { cap_bps };
+    fixed::share_bps(u32::MAX as u64, bps as u64)
+}
+
+// ── Settlement core ───────────────────────────────────────────────────────
+
+/// @notice Plain-value inputs to `compute_settlement` — no accounts, no sysvars.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SettlementInputs {
+    pub bet:               u64,
+    pub won:               bool, // net win (gross_payout > bet); a push or partial return is not one
+    pub gross_payout:      u64,  // everything returned to the player, stake included (0 on a loss)
+    pub seed:              [u8; 32],
+    pub has_ref:           bool,
+    pub ref_net_loss:      bool, // REFERRAL_MODE_NET_LOSS: no per-bet cut, accrued on ReferralAccount
+    pub referrer_lamports: u64,
+    pub partner_fee_bps:   Option<u16>,
+    pub total_balance:     u64, // liquid balance before settlement
+    pub jackpot_balance:   u64,
+    pub available:         u64, // physical − rent − every reserved compartment
+    pub jackpot_cap_bps:   u16, // GlobalPool.jackpot_cap_bps (0 = default)
+    pub insured:           u64, // first-bet insurance committed from the promo budget
+    pub cosign_threshold:  u64, // payouts above this need the co-signer (0 = off)
+    pub cosigned:          bool,
+    pub jackpot_feed_bps:  u64, // extra bet share moved from the house cut to the jackpot (Slots COIN)
+    pub boost_trigger_bps: u64, // jackpot boost on the trigger threshold (10_000 = none)
+    pub boost_contrib_bps: u64, // jackpot boost on jackpot_cut, topped up from the house cut (10_000 = none)
+    pub hold_for_double:   bool, // PLACE_FLAG_DOUBLE: hold a win for double_or_nothing
+    pub insurance_bps:     u64, // bet share moved from the house cut to the insurance reserve
+    pub flat_fee_bps:      Option<u64>, // house-only fee replacing the phase table (game 23: DOUBLE_FEE_BPS)
+    pub fee_bps:           [u16; 3], // GameSession.fee_bps pinned at placement (all-zero = phase table at total_balance)
+    pub executor_tip:      u64,  // reveal_public executor's tip, out of a net win's gross payout (0 = none)
+}
+
+/// @notice Every lamport movement of one settlement, decided before anything is written.
+/// @dev    Conservation: physical_out() leaves the pool; jackpot_cut + insurance_cut + claimable
+///         + reinvested move between compartments and stay; the jackpot prize comes out of jackpot_balance
+///         and the promo refund out of promo_balance.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SettlementPlan {
+    pub win_payout:        u64, // gross payout — wins, pushes and partial returns alike
+    pub jackpot_prize:     u64,
+    pub player_gets:       u64, // win_payout + jackpot_prize − executor_tip
+    pub ref_cut:           u64,
+    pub partner_cut:       u64,
+    pub jackpot_cut:       u64,
+    pub insurance_cut:     u64, // house cut → insurance_balance
+    pub claimable:         u64, // house cut → house_fees_earned
+    pub reinvested:        u64, // house cut → stays liquid
+    pub jackpot_threshold: u64, // trigger threshold out of 2³² (0 = not eligible)
+    pub promo_refund:      u64, // insured stake refunded on a loss, out of promo_balance
+    pub held:              u64, // part of player_gets parked in held_payouts awaiting co-sign or a double
+    pub double_offer:      bool, // `held` is a win open to double_or_nothing, not a co-sign hold
+    pub won:               bool, // a net win (SettlementInputs.won): counted in total_wins
+    pub executor_tip:      u64, // paid to the reveal_public executor now; waived when the win is held
+}
+
+impl SettlementPlan {
+    /// Lamports that physically leave the pool.
+    pub fn physical_out(&self) -> u64 {
+        self.player_gets - self.held + self.executor_tip + self.ref_cut + self.partner_cut + self.promo_refund
+    }
+}
+
+/// @dev MONEY INVARIANT:
+///      bet already in pool (deposited at place_bet).
+///      Win, push or partial return: gross_payout + ref_cut exit the pool physically.
+///      Loss: only ref_cut exits. `won` marks a net win only and moves no lamports itself.
+///      jackpot_cut, insurance_cut and house_cut are internal compartment moves only — lamports stay.
+///      Partner sessions: partner_cut = fee_bps of house_cut, paid out to the partner key.
+///      Net-loss referral mode: the turnover ref share stays with the house; the referrer's
+///      commission is accrued per epoch by settle, outside this plan.
+///      Doubles (game 23): flat_fee_bps of house cut only — no jackpot, referral or phase fees.
+///      Executor tip: carved out of a net win's gross payout and paid with it. A held win pays
+///      no tip — the whole payout waits for the co-signer or the double.
+///      Stages: (1) fee math → (2) jackpot roll → (3) solvency → (4) house split.
+///      Pure — unit-testable off-chain.
+pub fn compute_settlement(i: &SettlementInputs) -> Result<SettlementPlan> {
+    let (house_bps, ref_bps, jackpot_bps) = match i.flat_fee_bps {
+        Some(bps) => (bps, 0, 0),
+        None      => fee_table(i.fee_bps, i.total_balance, i.has_ref),
+    };
+    let bet = i.bet;
+
+    // (1) Fee math
+    let mut jackpot_cut = fixed::share_bps(bet, jackpot_bps);
+    let mut house_cut   = fixed::share_bps(bet, house_bps);
+    let mut ref_cut     = 0u64;
+    if i.has_ref {
+        let potential = fixed::share_bps(bet, ref_bps);
+        if !i.ref_net_loss && i.referrer_lamports >= 50_000_000 && potential >= 1_000_000 {
+            ref_cut = potential;
+        } else {
+            house_cut = house_cut.saturating_add(potential);
+        }
+    }
+    let partner_cut = i.partner_fee_bps.map_or(0, |bps| fixed::share_bps(house_cut, bps as u64));
+    house_cut -= partner_cut;
+    let feed     = fixed::share_bps(bet, i.jackpot_feed_bps).min(house_cut);
+    house_cut   -= feed;
+    jackpot_cut += feed;
+    let boost    = fixed::share_bps(jackpot_cut, i.boost_contrib_bps.saturating_sub(fixed::BPS)).min(house_cut);
+    house_cut   -= boost;
+    jackpot_cut += boost;
+    let insurance_cut = fixed::share_bps(bet, i.insurance_bps).min(house_cut);
+    house_cut        -= insurance_cut;
+
+    // (2) Jackpot trigger — uses seed bytes 24..28 (independent of game bytes 0..8)
+    let mut jackpot_prize     = 0u64;
+    let mut jackpot_threshold = 0u64;
+    if bet >= JACKPOT_MIN_BET && i.jackpot_balance >= JACKPOT_MIN_POOL {
+        let roll          = u32::from_le_bytes(i.seed[24..28].try_into().unwrap()) as u64;
+        let base          = bet.saturating_mul(JACKPOT_RATE) / JACKPOT_BASE;
+        jackpot_threshold = fixed::share_bps(base, i.boost_trigger_bps).min(jackpot_threshold_cap(i.jackpot_cap_bps));
+        if roll < jackpot_threshold {
+            jackpot_prize = fixed::share_bps(i.jackpot_balance, 9_000);
+        }
+    }
+
+    // (3) Solvency
+    let win_payout    = i.gross_payout;
+    let player_gets   = checked_add(win_payout, jackpot_prize)?;
+    let physical_out  = checked_add(checked_add(player_gets, ref_cut)?, partner_cut)?;
+    let internal_move = checked_add(checked_add(jackpot_cut, house_cut)?, insurance_cut)?;
+    require!(i.available >= checked_add(physical_out, internal_move)?, BlitzError::InsufficientLiquidity);
+
+    // (4) House split against the post-transfer liquid balance
+    let post_transfer_balance = i.total_balance
+        .saturating_sub(win_payout.saturating_add(ref_cut))
+        .saturating_sub(partner_cut);
+    let (claimable, reinvested) = split_house_fees(post_transfer_balance, house_cut, bet);
+
+    // (5) First-bet insurance — reserved at placement, so no liquidity check. Covers the net loss only.
+    let promo_refund = if i.won { 0 } else { i.insured.min(i.bet.saturating_sub(i.gross_payout)) };
+
+    // (6) Large payouts without the co-signer are held, not withheld — see release_held_payout.
+    //     A PLACE_FLAG_DOUBLE win is held the same way, unless co-signing already holds it.
+    let cosign_hold  = i.cosign_threshold > 0 && player_gets > i.cosign_threshold && !i.cosigned;
+    let double_offer = i.hold_for_double && player_gets > 0 && !cosign_hold;
+    let held         = if cosign_hold || double_offer { player_gets } else { 0 };
+
+    // (7) Executor tip — only out of a win paid now
+    let executor_tip = if i.won && held == 0 { i.executor_tip.min(win_payout) } else { 0 };
+    let player_gets  = player_gets - executor_tip;
+
+    Ok(SettlementPlan {
+        win_payout,
+        jackpot_prize,
+        player_gets,
+        ref_cut,
+        partner_cut,
+        jackpot_cut,
+        insurance_cut,
+        claimable,
+        reinvested,
+        jackpot_threshold,
+        promo_refund,
+        held,
+        double_offer,
+        won: i.won,
+        executor_tip,
+    })
+}
+
+/// @dev Executes a plan: (1) transfers → (2) compartments → (3) analytics → (4) sync.
+///      No decisions are made here beyond routing an unborrowable referrer's cut to the player.
+fn apply_settlement<'info>(
+    pool:        &mut Account<'info, GlobalPool>,
+    player_ai:   &AccountInfo<'info>,
+    referrer_ai: &AccountInfo<'info>,
+    session:     &Account<GameSession>,
+    partner:     Option<(&mut Account<'info, Partner>, AccountInfo<'info>)>,
+    executor:    Option<&AccountInfo<'info>>,
+    plan:        &SettlementPlan,
+) -> Result<()> {
+    #[cfg(feature = "debug-events")]
+    if plan.jackpot_threshold > 0 {
+        let threshold = plan.jackpot_threshold;
+        emit!(JackpotRoll { player: session.player, bet: session.bet_lamports, threshold, probability_ppm: (threshold * 1_000_000) >> 32 });
+    }
+
+    // (1) Physical transfers
+    if plan.physical_out() > 0 {
+        pool_debit(&pool.to_account_info(), plan.physical_out())?;
+        credit(player_ai, plan.player_gets - plan.held + plan.promo_refund)?;
+        if plan.ref_cut > 0 {
+            match referrer_ai.try_borrow_mut_lamports() {
+                Ok(mut l) => **l = l.checked_add(plan.ref_cut).ok_or(BlitzError::MathOverflow)?,
+                Err(_)    => credit(player_ai, plan.ref_cut)?,
+            }
+        }
+        if let Some((_, partner_ai)) = partner.as_ref() {
+            credit(partner_ai, plan.partner_cut)?;
+        }
+        if let Some(executor_ai) = executor {
+            credit(executor_ai, plan.executor_tip)?;
+        }
+    }
+
+    // (2) Internal compartments
+    pool.jackpot_balance   = checked_add(pool.jackpot_balance.saturating_sub(plan.jackpot_prize), plan.jackpot_cut)?;
+    pool.insurance_balance = checked_add(pool.insurance_balance, plan.insurance_cut)?;
+    pool.house_fees_earned = checked_add(pool.house_fees_earned, plan.claimable)?;
+    pool.total_reinvested  = pool.total_reinvested.saturating_add(plan.reinvested);
+    pool.promo_balance     = pool.promo_balance.saturating_sub(plan.promo_refund);
+    pool.promo_committed   = pool.promo_committed.saturating_sub(session.insured_amount);
+    pool.held_payouts      = checked_add(pool.held_payouts, plan.held)?;
+    if plan.promo_refund > 0 {
+        emit!(FirstBetRefunded { player: session.player, amount: plan.promo_refund });
+    }
+
+    // (3) Analytics — on-chain RTP: lifetime_paid_out × 10_000 / lifetime_wagered
+    pool.last_settled_player = session.player;
+    pool.last_settled_payout = plan.player_gets - plan.held;
+    pool.last_settled_slot   = Clock::get()?.slot;
+    if plan.won { pool.total_wins = pool.total_wins.saturating_add(1); }
+    if plan.win_payout > 0 {
+        pool.total_paid_out    = pool.total_paid_out.saturating_add(plan.win_payout);
+        pool.lifetime_paid_out = add_u128(pool.lifetime_paid_out, plan.win_payout)?;
+        if plan.win_payout > pool.biggest_win { pool.biggest_win = plan.win_payout; }
+        let game = pool.counters_mut(session.game_type);
+        game.paid_out = game.paid_out.saturating_add(plan.win_payout);
+        pool.record_game_outflow(session.game_type, plan.win_payout);
+    }
+    if plan.jackpot_prize > 0 {
+        pool.total_jackpot_won    = pool.total_jackpot_won.saturating_add(plan.jackpot_prize);
+        pool.total_paid_out       = pool.total_paid_out.saturating_add(plan.jackpot_prize);
+        pool.lifetime_jackpot_won = add_u128(pool.lifetime_jackpot_won, plan.jackpot_prize)?;
+        pool.lifetime_paid_out    = add_u128(pool.lifetime_paid_out, plan.jackpot_prize)?;
+        emit!(JackpotWon { player: session.player, amount: plan.jackpot_prize });
+    }
+    if let Some((p, _)) = partner {
+        p.settled_bets   = p.settled_bets.saturating_add(1);
+        p.total_paid_out = add_u128(p.total_paid_out, plan.player_gets)?;
+        p.fees_earned    = p.fees_earned.saturating_add(plan.partner_cut);
+        emit!(PartnerSettled { partner: p.key(), player_gets: plan.player_gets, fee: plan.partner_cut });
+    }
+
+    // (4) Sync, then advance the cap-smoothing EMA
+    let pool_ai = pool.to_account_info();
+    sync_balance(pool, &pool_ai)?;
+    pool.balance_ema = ema_step(pool.balance_ema, pool.total_balance);
+    Ok(())
+}
+
+/// One POOL_EMA_WEIGHT step of the balance EMA; an unset (0) EMA starts at `x`.
+fn ema_step(prev: u64, x: u64) -> u64 {
+    match prev {
+        0           => x,
+        p if x >= p => p + (x - p) / POOL_EMA_WEIGHT,
+        p           => p - (p - x) / POOL_EMA_WEIGHT,
+    }
+}
+
+/// @dev Gathers inputs → compute_settlement → apply_settlement. Returns the executed plan.
+#[allow(clippy::too_many_arguments)]
+fn settle<'info>(
+    pool:         &mut Account<'info, GlobalPool>,
+    player_ai:    &AccountInfo<'info>,
+    referrer_ai:  &AccountInfo<'info>,
+    session:      &Account<GameSession>,
+    partner:      Option<(&mut Account<'info, Partner>, AccountInfo<'info>)>,
+    referral:     Option<&mut Account<'info, ReferralAccount>>,
+    firehose:     Option<&mut Account<'info, Firehose>>,
+    cosigned:     bool,
+    won:          bool,
+    gross_payout: u64,
+    seed:         [u8; 32],
+) -> Result<SettlementPlan> {
+    settle_tipped(pool, player_ai, referrer_ai, session, partner, referral, firehose, cosigned, won, gross_payout, seed, None)
+}
+
+/// @dev settle with an executor tip: `executor` = (account, tip requested), as reveal_public pays.
+#[allow(clippy::too_many_arguments)]
+fn settle_tipped<'info>(
+    pool:         &mut Account<'info, GlobalPool>,
+    player_ai:    &AccountInfo<'info>,
+    referrer_ai:  &AccountInfo<'info>,
+    session:      &Account<GameSession>,
+    partner:      Option<(&mut Account<'info, Partner>, AccountInfo<'info>)>,
+    referral:     Option<&mut Account<'info, ReferralAccount>>,
+    firehose:     Option<&mut Account<'info, Firehose>>,
+    cosigned:     bool,
+    won:          bool,
+    gross_payout: u64,
+    seed:         [u8; 32],
+    executor:     Option<(&AccountInfo<'info>, u64)>,
+) -> Result<SettlementPlan> {
+    require!(firehose.is_some() || !pool.firehose_enabled, BlitzError::FirehoseRequired);
+    pool.open_stakes = pool.open_stakes.saturating_sub(session.bet_lamports);
+    if session.step_reserved > 0 {
+        release_step(pool, session);
+        let pool_ai = pool.to_account_info();
+        sync_balance(pool, &pool_ai)?;
+    }
+    let net_loss = pool.referral_mode == REFERRAL_MODE_NET_LOSS;
+    let has_ref  = is_valid_referrer(session.referrer, session.player);
+    let mut referral = referral;
+    if let Some(r) = referral.as_deref_mut() {
+        referral_roll(pool, r, Clock::get()?.epoch);
+    }
+    let rent  = Rent::get()?.minimum_balance(pool.to_account_info().data_len());
+    let boost = pool.jackpot_boost.at(Clock::get()?.unix_timestamp);
+    let inputs = SettlementInputs {
+        bet:               session.bet_lamports,
+        won,
+        gross_payout,
+        seed,
+        has_ref,
+        ref_net_loss:      net_loss,
+        referrer_lamports: referrer_ai.lamports(),
+        partner_fee_bps:   partner.as_ref().map(|(p, _)| p.fee_bps),
+        total_balance:     pool.total_balance,
+        jackpot_balance:   pool.jackpot_balance,
+        available:         pool.to_account_info().lamports()
+            .saturating_sub(rent)
+            .saturating_sub(pool.reserved()),
+        jackpot_cap_bps:   pool.jackpot_cap_bps,
+        insured:           session.insured_amount,
+        cosign_threshold:  pool.cosign_threshold,
+        cosigned,
+        jackpot_feed_bps:  if session.game_type == 15 { slots_jackpot_feed_bps(&seed) } else { 0 },
+        boost_trigger_bps: boost.trigger_bps as u64,
+        boost_contrib_bps: boost.contrib_bps as u64,
+        hold_for_double:   session.double_offer,
+        insurance_bps:     pool.insurance_bps as u64,
+        flat_fee_bps:      (session.game_type == 23).then_some(DOUBLE_FEE_BPS),
+        fee_bps:           session.fee_bps,
+        executor_tip:      executor.map_or(0, |(_, tip)| tip),
+    };
+    let plan = compute_settlement(&inputs)?;
+    apply_settlement(pool, player_ai, referrer_ai, session, partner, executor.map(|(ai, _)| ai), &plan)?;
+    accrue_net_loss(pool, referral, session, plan.win_payout)?;
+    if let Some(f) = firehose {
+        f.state_seq     = pool.state_seq;
+        f.slot          = Clock::get()?.slot;
+        f.session       = session.key();
+        f.player        = session.player;
+        f.game_type     = session.game_type;
+        f.won           = won;
+        f.bet           = session.bet_lamports;
+        f.payout        = plan.player_gets;
+        f.jackpot       = plan.jackpot_prize;
+        f.total_balance = pool.total_balance;
+        f.settlements   = f.settlements.wrapping_add(1);
+    }
+    Ok(plan)
+}
+
+/// @dev Counts one finished stake of a net-loss-bound session (net_loss_ref, set at placement
+///      when the referrer's ReferralAccount was passed) towards the referral's epoch. Every path
+///      that ends such a session — settle and resolve_terminal_state — must pass the account,
+///      so a referee cannot choose which results count.
+fn accrue_net_loss(
+    pool:     &GlobalPool,
+    referral: Option<&mut Account<ReferralAccount>>,
+    session:  &GameSession,
+    paid_out: u64,
+) -> Result<()> {
+    if pool.referral_mode != REFERRAL_MODE_NET_LOSS || !session.net_loss_ref { return Ok(()); }
+    let r = referral.ok_or(BlitzError::ReferralAccountRequired)?;
+    r.epoch_wagered  = r.epoch_wagered.saturating_add(session.bet_lamports);
+    r.epoch_paid_out = r.epoch_paid_out.saturating_add(paid_out);
+    Ok(())
+}
+
+/// @dev Closes the referral's epoch once Solana's epoch has moved on: the referees' net loss
+///      (wagered − paid out, jackpots excluded) earns referral_share_bps, reserved in
+///      pool.referral_accrued until claimed. A net-winning epoch earns nothing and does not
+///      carry over. Capped at total_balance so a drained pool never reserves more than it holds.
+fn referral_roll(pool: &mut GlobalPool, r: &mut ReferralAccount, epoch: u64) {
+    if epoch <= r.epoch { return; }
+    let net_loss   = r.epoch_wagered.saturating_sub(r.epoch_paid_out);
+    let commission = fixed::share_bps(net_loss, pool.referral_share_bps as u64).min(pool.total_balance);
+    if commission > 0 {
+        r.claimable           = r.claimable.saturating_add(commission);
+        pool.referral_accrued = pool.referral_accrued.saturating_add(commission);
+        pool.total_balance   -= commission;
+    }
+    emit!(ReferralEpochClosed { referrer: r.referrer, epoch: r.epoch, wagered: r.epoch_wagered, paid_out: r.epoch_paid_out, commission });
+    r.epoch          = epoch;
+    r.epoch_wagered  = 0;
+    r.epoch_paid_out = 0;
+}
+
+// ── Randomness providers ──────────────────────────────────────────────────
+
+/// A source of the seed a pending session resolves from. SlotHashes commit-reveal (the
+/// default) and the VRF backends of place_bet_vrf sit behind it, so resolve_with and the
+/// game code never see which one a bet used.
+pub trait RandomnessProvider {
+    /// The session's 32-byte seed, or the reason it cannot be drawn now.
+    fn seed(&self, session: &GameSession, clock: &Clock) -> Result<[u8; 32]>;
+}
+
+/// SlotHashes commit-reveal: the player's nonce (and client seed, for PLACE_FLAG_CLIENT_SEED
+/// sessions) mixed with three slot hashes from after resolve_slot (see build_seed).
+pub struct CommitReveal<'a, 'info> {
+    pub slot_hashes: &'a AccountInfo<'info>,
+    pub nonce:       &'a [u8; 32],
+    pub client_seed: Option<&'a [u8; 32]>,
+}
+
+impl RandomnessProvider for CommitReveal<'_, '_> {
+    fn seed(&self, session: &GameSession, clock: &Clock) -> Result<[u8; 32]> {
+        require!(session.vrf == Pubkey::default(),    BlitzError::VrfSession);
+        require!(clock.slot > session.resolve_slot + SLOT_SPREAD * 2,     BlitzError::TooEarlyToReveal);
+        require!(clock.slot <= session.forfeit_slot,  BlitzError::RevealWindowExpired);
+        require!(clock.slot.saturating_sub(session.resolve_slot) < 490, BlitzError::SlotTooOld);
+        require!(session.client_seeded == self.client_seed.is_some(), BlitzError::ClientSeedMismatch);
+        let commitment = match self.client_seed {
+            Some(cs) => hash::hashv(&[self.nonce, cs]),
+            None     => hash::hash(self.nonce),
+        };
+        require!(commitment.to_bytes() == session.commitment, BlitzError::InvalidNonce);
+
+        // Linked split legs hash the total stake so both resolve from the same seed.
+        let seed_bet = if session.seed_lamports > 0 { session.seed_lamports } else { session.bet_lamports };
+        build_seed(self.slot_hashes, session.resolve_slot, self.nonce, self.client_seed, seed_bet)
+    }
+}
+
+/// An oracle randomness account, decoded as `provider` (VRF_*) says.
+pub struct Vrf<'a, 'info> {
+    pub provider: u8,
+    pub account:  &'a AccountInfo<'info>,
+}
+
+impl Vrf<'_, '_> {
+    /// Binds a new bet at `slot` to the account: it must not be revealed yet, and for
+    /// Switchboard be committed in the slot before, so the outcome is fixed but unseen.
+    /// Records what seed() later checks the account still matches — Switchboard: the
+    /// seed_slot of the commit; ORAO: the request seed, which must also name the account.
+    fn bind(&self, session: &mut GameSession, slot: u64) -> Result<()> {
+        let fresh = match self.provider {
+            VRF_SWITCHBOARD => {
+                let r = switchboard::load(self.account).map_err(|_| BlitzError::InvalidRandomness)?;
+                session.vrf_seed_slot = r.seed_slot;
+                r.reveal_slot == 0 && r.seed_slot + 1 == slot
+            }
+            VRF_ORAO => {
+                let r = orao::load(self.account).map_err(|_| BlitzError::InvalidRandomness)?;
+                require!(self.account.key() == orao::request_address(&r.seed), BlitzError::InvalidRandomness);
+                session.vrf_request_seed = r.seed;
+                r.value.is_none()
+            }
+            _ => return err!(BlitzError::InvalidVrfProvider),
+        };
+        require!(fresh, BlitzError::RandomnessStale);
+        session.vrf          = self.account.key();
+        session.vrf_provider = self.provider;
+        Ok(())
+    }
+}
+
+impl RandomnessProvider for Vrf<'_, '_> {
+    fn seed(&self, session: &GameSession, clock: &Clock) -> Result<[u8; 32]> {
+        require!(session.vrf != Pubkey::default() && self.account.key() == session.vrf, BlitzError::InvalidRandomness);
+        require!(clock.slot <= session.forfeit_slot, BlitzError::RevealWindowExpired);
+        // Only the randomness of the commit / request the bet was bound to counts: a
+        // Switchboard account can be re-committed and re-revealed after placement.
+        let value = match self.provider {
+            VRF_SWITCHBOARD => {
+                let r = switchboard::load(self.account).map_err(|_| BlitzError::InvalidRandomness)?;
+                require!(r.seed_slot == session.vrf_seed_slot, BlitzError::RandomnessRecommitted);
+                r.revealed().map(|v| v.to_vec())
+            }
+            VRF_ORAO => {
+                let r = orao::load(self.account).map_err(|_| BlitzError::InvalidRandomness)?;
+                require!(r.seed == session.vrf_request_seed, BlitzError::RandomnessRecommitted);
+                r.value.map(|v| v.to_vec())
+            }
+            _ => return err!(BlitzError::InvalidVrfProvider),
+        }.ok_or(BlitzError::RandomnessNotRevealed)?;
+        Ok(vrf_seed(&value, &session.vrf, session.bet_lamports))
+    }
+}
+
+// ── Seed extraction & validation ──────────────────────────────────────────
+
+fn extract_and_validate_seed<'info>(
+    session:    &Account<'info, GameSession>,
+    slot_hashes: &AccountInfo<'info>,
+    clock:      &Clock,
+    nonce:      &[u8; 32],
+    game_type:  u8,
+) -> Result<[u8; 32]> {
+    require!(session.game_state == 0,             BlitzError::SessionNotPending);
+    require!(session.game_type  == game_type,     BlitzError::WrongGameType);
+    CommitReveal { slot_hashes, nonce, client_seed: None }.seed(session, clock)
+}
+
+/// BLAKE3(nonce || [client_seed] || hash[T] || hash[T+10] || hash[T+20] || target_slot || bet_lamports)
+/// Three hashes × SLOT_SPREAD → each from a different validator leader rotation. The client
+/// seed is only hashed in for PLACE_FLAG_CLIENT_SEED sessions; other seeds are unchanged.
+fn build_seed(
+    slot_hashes_ai: &AccountInfo,
+    target_slot:    u64,
+    nonce:          &[u8; 32],
+    client_seed:    Option<&[u8; 32]>,
+    bet_lamports:   u64,
+) -> Result<[u8; 32]> {
+    let hashes = with_slot_hashes(slot_hashes_ai, |src| {
+        let mut out = [[0u8; 32]; 3];
+        for (offset, h) in out.iter_mut().enumerate() {
+            match sysvars::hash_of(src, target_slot + offset as u64 * SLOT_SPREAD)? {
+                Some(found) => *h = found,
+                None        => return Ok(None),
+            }
+        }
+        Ok(Some(out))
+    })?.ok_or(BlitzError::SlotHashNotFound)?;
+
+    let mut h = blake3::Hasher::new();
+    h.update(nonce);
+    if let Some(cs) = client_seed { h.update(cs); }
+    h.update(&hashes[0]);
+    h.update(&hashes[1]);
+    h.update(&hashes[2]);
+    h.update(&target_slot.to_le_bytes());
+    h.update(&bet_lamports.to_le_bytes());
+    Ok(*h.finalize().as_bytes())
+}
+
+/// BLAKE3(vrf value || randomness account || bet_lamports). The value alone is uniform; the
+/// account and stake bind the seed to this bet, as target_slot and bet do in build_seed.
+fn vrf_seed(value: &[u8], randomness: &Pubkey, bet_lamports: u64) -> [u8; 32] {
+    let mut h = blake3::Hasher::new();
+    h.update(value);
+    h.update(randomness.as_ref());
+    h.update(&bet_lamports.to_le_bytes());
+    *h.finalize().as_bytes()
+}
+
+// ── Event emitter ─────────────────────────────────────────────────────────
+
+fn emit_outcome(player: Pubkey, won: bool, payout: u64, outcome: &GameOutcome) {
+    match outcome {
+        GameOutcome::Flip   { roll }                             => emit!(FlipSettled   { player, won, roll:     *roll,     payout }),
+        GameOutcome::FlipStreak { flips, sides, results }        => emit!(FlipStreakSettled { player, won, flips: *flips, sides: *sides, results: *results, payout }),
+        GameOutcome::Sector { strike_x, strike_y }              => emit!(SectorSettled { player, won, strike_x: *strike_x, strike_y: *strike_y, payout }),
+        GameOutcome::Dice   { roll, target, is_over, mode, target_hi } => emit!(DiceSettled { player, won, roll: *roll, target: *target, payout, is_over: *is_over, mode: *mode, target_hi: *target_hi }),
+        GameOutcome::Tower  { floors, death_floor, path, traps, doors } => emit!(TowerSettled  { player, won, floors:   *floors,   death_floor: *death_floor, payout, path: *path, traps: *traps, doors: *doors }),
+        GameOutcome::Mystery { picked_game, result }            => emit!(MysterySettled { player, won, picked_game: *picked_game, result: *result, payout }),
+        GameOutcome::Plinko { rows, risk, path, bucket, multiplier_bps } => emit!(PlinkoSettled { player, won, rows: *rows, risk: *risk, path: *path, bucket: *bucket, multiplier_bps: *multiplier_bps, payout }),
+        GameOutcome::Crash  { crash_point, target }             => emit!(CrashSettled  { player, won, crash_point: *crash_point, target: *target, payout }),
+        GameOutcome::Roulette { pocket, bet_class, selection }  => emit!(RouletteSettled { player, won, pocket: *pocket, bet_class: *bet_class, selection: *selection, payout }),
+        GameOutcome::Keno   { picks, drawn, hits, multiplier_bps } => emit!(KenoSettled { player, won, picks: *picks, drawn: *drawn, hits: *hits, multiplier_bps: *multiplier_bps, payout }),
+        GameOutcome::Limbo  { result, target }                  => emit!(LimboSettled  { player, won, result: *result, target: *target, payout }),
+        GameOutcome::Wheel  { risk, segment, multiplier_bps }   => emit!(WheelSettled  { player, won, risk: *risk, segment: *segment, multiplier_bps: *multiplier_bps, payout }),
+        GameOutcome::VideoPoker { dealt, hold, hand, class, multiplier_bps } => emit!(VideoPokerSettled { player, won, dealt: *dealt, hold: *hold, hand: *hand, class: *class, multiplier_bps: *multiplier_bps, payout }),
+        GameOutcome::Baccarat { side, player_cards, banker_cards, player_total, banker_total } => emit!(BaccaratSettled { player, won, side: *side, player_cards: *player_cards, banker_cards: *banker_cards, player_total: *player_total, banker_total: *banker_total, payout }),
+        GameOutcome::Slots  { reels, multiplier_bps }           => emit!(SlotsSettled  { player, won, reels: *reels, multiplier_bps: *multiplier_bps, payout }),
+        GameOutcome::SicBo  { bet_class, selection, dice }      => emit!(SicBoSettled  { player, won, bet_class: *bet_class, selection: *selection, dice: *dice, total: dice.iter().sum(), payout }),
+        GameOutcome::DragonTiger { side, dragon, tiger }        => emit!(DragonTigerSettled { player, won, side: *side, dragon: *dragon, tiger: *tiger, payout }),
+        GameOutcome::DiceDuo { mode, target, dice }             => emit!(DiceDuoSettled { player, won, mode: *mode, target: *target, dice: *dice, sum: dice[0] + dice[1], payout }),
+        GameOutcome::Goal { rows, death_row, path, traps }      => emit!(GoalSettled { player, won, rows: *rows, death_row: *death_row, payout, path: *path, traps: *traps }),
+        GameOutcome::Parlay { legs, games, results }            => emit!(ParlaySettled { player, won, legs: *legs, games: *games, results: *results, payout }),
+        GameOutcome::Double { roll }                            => emit!(DoubleOrNothingSettled { player, won, roll: *roll, payout }),
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════
+//  PUBLIC UTILITIES (used by client-side bet sizing)
+// ══════════════════════════════════════════════════════════════════════════
+
+/// Coordinated max bet — never advertises a bet that `payout_cap` would reject.
+/// `pool` is the game's sizing basis (GlobalPool::game_basis), so per-game budgets apply.
+pub fn get_max_bet(pool: u64, game: u8, cfg: &[u8; 3], ext: u64) -> u64 {
+    let cap     = get_max_payout_cap(pool);
+    let pct_lim = if pool < 5_000_000_000 {
+        pool / 100 // <5 SOL: 1% survival mode
+    } else {
+        match game {
+            0 | 2 => pool.saturating_mul(3) / 100, // Flip / Dice:   3%
+            _     => pool.saturating_mul(2) / 100, // Sector / Tower: 2%
+        }
+    };
+    let worst_1sol = get_worst_payout(1_000_000_000, game, cfg, ext);
+    let cap_lim    = cap.saturating_mul(1_000_000_000).checked_div(worst_1sol).unwrap_or(pct_lim);
+    let max        = pct_lim.min(cap_lim);
+    if game == 4 { max.min(MYSTERY_MAX_BET) } else { max }
+}
+
+/// Room-aware max bet. Main room = `get_max_bet`; high-roller scales it by HIGH_ROLLER_CAP_BPS.
+pub fn get_room_max_bet(room: u8, pool: u64, game: u8, cfg: &[u8; 3], ext: u64) -> u64 {
+    let base = get_max_bet(pool, game, cfg, ext);
+    if room == ROOM_HIGH_ROLLER { base.saturating_mul(HIGH_ROLLER_CAP_BPS) / 10_000 } else { base }
+}
+
+/// Room-aware payout cap. Main room = `get_max_payout_cap`.
+pub fn get_room_payout_cap(room: u8, pool: u64) -> u64 {
+    let base = get_max_payout_cap(pool);
+    if room == ROOM_HIGH_ROLLER { base.saturating_mul(HIGH_ROLLER_CAP_BPS) / 10_000 } else { base }
+}
+
+/// Minimum stake accepted by a room.
+pub fn room_min_bet(room: u8) -> u64 {
+    if room == ROOM_HIGH_ROLLER { HIGH_ROLLER_MIN_BET } else { 10_000_000 }
+}
+
+/// Minimum liquid balance below which a room stops accepting bets.
+pub fn room_min_pool(room: u8) -> u64 {
+    if room == ROOM_HIGH_ROLLER { HIGH_ROLLER_MIN_POOL } else { MIN_POOL }
+}
+
+/// PDA seed for a room's pool.
+pub fn room_seed(room: u8) -> &'static [u8] {
+    if room == ROOM_HIGH_ROLLER { b"high_roller_pool" } else { b"global_pool" }
+}
+
+/// HyperLogLog cardinality estimate over a register array (with small-range correction).
+/// Use on `PlayerSketch.lifetime` / `.current` to get unique players lifetime / this epoch.
+/// Integer-only: Σ 2^-r is held at 2^64 scale and the linear-counting log is `fixed::ln_q32`.
+pub fn hll_estimate(registers: &[u8; HLL_REGISTERS]) -> u64 {
+    let m = HLL_REGISTERS as u128;
+    // α·m² / Σ 2^-r with α = 0.7213 / (1 + 1.079 / m) = 7213·m / (10·(1000·m + 1079))
+    let sum: u128 = registers.iter().map(|&r| 1u128 << (64 - r.min(63) as u32)).sum();
+    let raw_num   = (7_213 * m * m * m) << 64;
+    let raw_den   = 10 * (1_000 * m + 1_079) * sum;
+    let zeros     = registers.iter().filter(|&&r| r == 0).count() as u64;
+    if 2 * raw_num <= 5 * m * raw_den && zeros > 0 {
+        let ln = fixed::ln_q32(HLL_REGISTERS as u64, zeros).unwrap_or(0);
+        fixed::mul_div_floor(HLL_REGISTERS as u64, ln, fixed::Q32_ONE).unwrap_or(0)
+    } else {
+        u64::try_from(raw_num / raw_den).unwrap_or(u64::MAX)
+    }
+}
+
+/// Dynamic payout cap — tiered by pool health. No hard ceiling.
+pub fn get_max_payout_cap(pool: u64) -> u64 {
+    match pool {
+        p if p < 5_000_000_000   => p.saturating_mul(3)  / 100,
+        p if p < 20_000_000_000  => p.saturating_mul(5)  / 100,
+        p if p < 100_000_000_000 => p.saturating_mul(8)  / 100,
+        p if p < 500_000_000_000 => p.saturating_mul(10) / 100,
+        p                        => p.saturating_mul(12) / 100,
+    }
+}
+
+/// Canonical chance-mode Dice multiplier in bps (×10_000), rounded down once (`fixed`).
+/// `steps` = win chance in 0.5% units. UI must display exactly this value:
+/// payout = bet × multiplier_bps / 10_000, no other rounding step.
+///   e.g. Phase 0, 100 steps (50%) → 9_750 × 200 / 100 = 19_500 → 1.9500×
+pub fn dice_chance_multiplier_bps(steps: u8, num: u64) -> u64 {
+    if steps == 0 { return 0; }
+    fixed::mul_div_floor(num, DICE_CHANCE_STEPS, steps as u64).unwrap_or(0)
+}
+
+/// Plinko bucket multiplier in bps. Bucket k of n rows has weight |2k − n|^(risk + 1) + 1;
+/// weights are normalised over the binomial drop distribution so EV = payout_num / 10_000:
+/// multiplier = num × 2^n × w(k) / Σ C(n, j) × w(j), rounded down once.
+pub fn plinko_multiplier_bps(rows: u8, risk: u8, bucket: u8, num: u64) -> u64 {
+    if bucket > rows { return 0; }
+    let weight = |k: u8| (k as i32 * 2 - rows as i32).unsigned_abs().pow(risk as u32 + 1) as u64 + 1;
+    let (mut c, mut total) = (1u64, 0u64);
+    for j in 0..=rows {
+        total = total.saturating_add(c.saturating_mul(weight(j)));
+        c     = c * (rows - j) as u64 / (j as u64 + 1);
+    }
+    fixed::mul_div_floor(num, (1u64 << rows) * weight(bucket), total).unwrap_or(0)
+}
+
+/// Mines cash-out multiplier in bps after `safe` safe tiles on a board with `mines` mines:
+/// num × C(25, safe) / C(25 − mines, safe), rounded down once. 0 past a full clear.
+pub fn mines_multiplier_bps(mines: u8, safe: u8, num: u64) -> u64 {
+    let choose = |n: u64, k: u64| if k > n { 0 } else { (0..k).fold(1u64, |c, i| c * (n - i) / (i + 1)) };
+    let tiles  = MINES_TILES as u64;
+    fixed::mul_div_floor(num, choose(tiles, safe as u64), choose(tiles.saturating_sub(mines as u64), safe as u64)).unwrap_or(0)
+}
+
+/// HiLo multiplier in bps after `rounds` correct guesses whose winning-rank counts multiply
+/// to `odds_den`: num × 13^rounds / odds_den, rounded down once.
+pub fn hilo_multiplier_bps(odds_den: u64, rounds: u8, num: u64) -> u64 {
+    let fair = (HILO_RANKS as u64).checked_pow(rounds.min(HILO_MAX_ROUNDS) as u32).unwrap_or(u64::MAX);
+    fixed::mul_div_floor(num, fair, odds_den).unwrap_or(0)
+}
+
+/// Stairs multiplier in bps after clearing `steps` steps:
+/// num × 20^steps / Π (20 − n) for n = 1..=steps, rounded down once.
+pub fn stairs_multiplier_bps(steps: u8, num: u64) -> u64 {
+    let steps = steps.min(STAIRS_STEPS) as u64;
+    let fair  = STAIRS_DEN.pow(steps as u32);
+    let safe  = (1..=steps).map(|n| STAIRS_DEN - n).product::<u64>();
+    fixed::mul_div_floor(num, fair, safe).unwrap_or(0)
+}
+
+/// Video Poker multiplier in bps for hand `class`. The hold mask is committed before the
+/// deal, so every mask draws a uniform five-card hand; POKER_PAYTABLE is normalised over
+/// POKER_HAND_COUNTS so EV = num: num × C(52, 5) × pay(class) / Σ count × pay, rounded down once.
+pub fn video_poker_multiplier_bps(class: u8, num: u64) -> u64 {
+    let Some(pay) = POKER_PAYTABLE.get(class as usize) else { return 0 };
+    let total     = POKER_PAYTABLE.iter().zip(POKER_HAND_COUNTS.iter()).map(|(p, n)| p * n).sum::<u64>();
+    fixed::mul_div_floor(num, POKER_HANDS * pay, total).unwrap_or(0)
+}
+
+/// Keno multiplier in bps for `hits` out of `picks`: the KENO_PAYTABLE weight, normalised so
+/// Σ P(h) × multiplier(h) = num, with P(h) = C(picks, h) × C(40 − picks, 10 − h) / C(40, 10).
+pub fn keno_multiplier_bps(picks: u8, hits: u8, num: u64) -> u64 {
+    if picks == 0 || picks > KENO_MAX_PICKS || hits > picks { return 0; }
+    let choose = |n: u64, k: u64| if k > n { 0 } else { (0..k).fold(1u64, |c, i| c * (n - i) / (i + 1)) };
+    let row    = &KENO_PAYTABLE[picks as usize - 1];
+    let (p, d) = (picks as u64, KENO_DRAWN as u64);
+    let total  = (0..=p).map(|h| choose(p, h) * choose(KENO_NUMBERS - p, d.saturating_sub(h)) * row[h as usize]).sum::<u64>();
+    fixed::mul_div_floor(num, choose(KENO_NUMBERS, d) * row[hits as usize], total).unwrap_or(0)
+}
+
+/// Wheel segment multiplier in bps: num × segments × w(segment) / Σ w over the risk's
+/// WHEEL_PAYTABLE, rounded down once. 0 for an unknown risk or segment.
+pub fn wheel_multiplier_bps(risk: u8, segment: u8, num: u64) -> u64 {
+    let Some(table) = WHEEL_PAYTABLE.get(risk as usize) else { return 0 };
+    let Some(w)     = table.get(segment as usize) else { return 0 };
+    let total       = table.iter().sum::<u64>();
+    fixed::mul_div_floor(num, table.len() as u64 * w, total).unwrap_or(0)
+}
+
+/// Top-segment multiplier of a Wheel risk preset — the bet's worst case for the pool.
+pub fn wheel_max_multiplier_bps(risk: u8, num: u64) -> u64 {
+    let n = WHEEL_PAYTABLE.get(risk as usize).map_or(0, |t| t.len());
+    (0..n as u8).map(|seg| wheel_multiplier_bps(risk, seg, num)).max().unwrap_or(0)
+}
+
+/// SLOTS_PAYLINES weight of the first line `reels` match, 0 for a losing spin.
+pub const fn slots_line_weight(reels: &[u8; 3]) -> u64 {
+    let mut l = 0;
+    while l < SLOTS_PAYLINES.len() {
+        let (pattern, weight) = SLOTS_PAYLINES[l];
+        let mut r = 0;
+        while r < 3 && (pattern[r] == SLOTS_ANY || pattern[r] == reels[r]) { r += 1; }
+        if r == 3 { return weight; }
+        l += 1;
+    }
+    0
+}
+
+/// Σ line weight over all 32³ stop combinations — evaluated at compile time.
+const SLOTS_WEIGHT_TOTAL: u64 = {
+    let (mut total, mut i) = (0u64, 0usize);
+    while i < SLOTS_REEL.len().pow(3) {
+        let n = SLOTS_REEL.len();
+        total += slots_line_weight(&[SLOTS_REEL[i / (n * n)], SLOTS_REEL[i / n % n], SLOTS_REEL[i % n]]);
+        i += 1;
+    }
+    total
+};
+
+/// Slots multiplier in bps: num × 32³ × w(line) / Σ w over every spin, rounded down once.
+pub fn slots_multiplier_bps(reels: &[u8; 3], num: u64) -> u64 {
+    let spins = (SLOTS_REEL.len() as u64).pow(3);
+    fixed::mul_div_floor(num, spins * slots_line_weight(reels), SLOTS_WEIGHT_TOTAL).unwrap_or(0)
+}
+
+/// Jackpot feed of a Slots spin in bps of the bet: SLOTS_JACKPOT_FEED_BPS if a COIN shows.
+pub fn slots_jackpot_feed_bps(seed: &[u8; 32]) -> u64 {
+    if slots_spin(seed).contains(&SLOTS_COIN) { SLOTS_JACKPOT_FEED_BPS } else { 0 }
+}
+
+/// Multiplier-curve win probability P(result ≥ target) in parts per million.
+/// `target_x100` = multiplier × 100 (e.g. 250 = 2.50×); above max_multiplier → 0.
+pub fn curve_win_ppm(c: &PayoutCurve, target_x100: u32) -> u64 {
+    if target_x100 > c.max_multiplier_x100 { return 0; }
+    let keep = fixed::BPS.saturating_sub(c.edge_bps as u64);
+    let tail = fixed::mul_div_floor(keep, 1_000_000, target_x100.max(100) as u64 * 100).unwrap_or(0);
+    let p    = if c.shape == CURVE_INVERSE_BUST {
+        fixed::share_bps(tail, fixed::BPS.saturating_sub(c.bust_bps as u64))
+    } else {
+        tail
+    };
+    p.min(1_000_000)
+}
+
+/// @notice EV sanity check for admin-supplied curves: shape and bounds, then
+///         EV = P(win) × target ≤ CURVE_MAX_EV_BPS at every sampled target.
+pub fn validate_curve(c: &PayoutCurve) -> Result<()> {
+    require!(c.shape == CURVE_INVERSE || c.shape == CURVE_INVERSE_BUST, BlitzError::InvalidCurve);
+    require!(c.shape == CURVE_INVERSE_BUST || c.bust_bps == 0,          BlitzError::InvalidCurve);
+    require!(c.edge_bps <= 5_000 && c.bust_bps <= 5_000,               BlitzError::InvalidCurve);
+    require!(c.max_multiplier_x100 >= 101 && c.max_multiplier_x100 <= CURVE_MAX_MULTIPLIER_X100, BlitzError::InvalidCurve);
+    for t in [101, 150, 200, 500, 1_000, 10_000, 100_000, c.max_multiplier_x100] {
+        if t > c.max_multiplier_x100 { continue; }
+        let ev_bps = curve_win_ppm(c, t) * t as u64 / 10_000;
+        require!(ev_bps <= CURVE_MAX_EV_BPS, BlitzError::CurveEvTooHigh);
+    }
+    Ok(())
+}
+
+/// Winning rolls out of 216 for a Sic Bo (bet_class, selection); 0 = invalid bet.
+pub fn sicbo_ways(bet_class: u8, selection: u8) -> u64 {
+    match (bet_class, selection) {
+        (SICBO_SMALL | SICBO_BIG, 0) => SICBO_SIDE_WAYS,
+        (SICBO_TRIPLE, 1..=6)        => 1,
+        (SICBO_TOTAL, 4..=17)        => SICBO_TOTAL_WAYS[selection as usize],
+        _                            => 0,
+    }
+}
+
+/// Winning rolls out of 36 for a Dice Duo (mode, target); 0 = invalid bet.
+/// A sum s has 6 − |s − 7| rolls.
+pub fn dice_duo_ways(mode: u8, target: u8) -> u64 {
+    let ways = |s: u8| 6 - (s as i64 - 7).unsigned_abs();
+    match (mode, target) {
+        (DUO_UNDER, 3..=12) => (2..target).map(ways).sum(),
+        (DUO_OVER,  2..=11) => (target + 1..=12).map(ways).sum(),
+        (DUO_EXACT, 2..=12) => ways(target),
+        _                   => 0,
+    }
+}
+
+/// Goal multiplier in bps after `rows` rows: 10_000 × (GOAL_ROW_BPS / 10_000)^rows, rounded down once.
+pub fn goal_multiplier_bps(rows: u8) -> u64 {
+    let rows = rows.min(GOAL_MAX_ROWS) as u32;
+    let m    = fixed::BPS as u128 * (GOAL_ROW_BPS as u128).pow(rows) / (fixed::BPS as u128).pow(rows);
+    m as u64
+}
+
+/// Tower Climb multiplier in bps after clearing `floors` floors of `doors` doors:
+/// num × doors^floors / (doors − 1)^floors, rounded down once.
+pub fn tower_climb_multiplier_bps(floors: u8, doors: u8, num: u64) -> u64 {
+    let (safe, total) = tower_odds(floors.min(TOWER_MAX_FLOORS), doors);
+    fixed::mul_div_floor(num, total, safe).unwrap_or(0)
+}
+
+/// Smooth linear resolve delay: +1 slot per 0.02 SOL, clamped to [10, 55].
+pub fn get_resolve_slot(slot: u64, bet: u64) -> u64 {
+    slot + (10u64).saturating_add(bet / 20_000_000).min(55)
+}
+
+/// Worst-case payout using Phase-2 numerator (most conservative).
+pub fn get_worst_payout(bet: u64, game: u8, cfg: &[u8; 3], ext: u64) -> u64 {
+    let num = payout_num(PHASE2_THRESHOLD);
+    match game {
+        0 if cfg[0] != 0 => calc_payout(bet, 1, 1u64 << cfg[0].clamp(FLIP_STREAK_MIN, FLIP_STREAK_MAX), FLIP_STREAK_NUM_BPS),
+        0 => calc_payout(bet, 50, 100, num),
+        1 => { let (zones, n) = sector_zones(cfg, ext); calc_payout(bet, sector_area(&zones[..n]), 256, num) }
+        2 if cfg[1] == DICE_MODE_CHANCE => {
+            let m = dice_chance_multiplier_bps(cfg[0].max(DICE_CHANCE_MIN_STEPS), num);
+            scale_payout(bet, m)
+        }
+        2 if cfg[1] == DICE_MODE_EXACT || cfg[1] == DICE_MODE_RANGE => {
+            let width = if cfg[1] == DICE_MODE_EXACT { 1 } else { cfg[2].saturating_sub(cfg[0]) as u64 + 1 };
+            calc_payout(bet, width.min(DICE_RANGE_MAX_WIDTH as u64), 100, num)
+        }
+        2 => {
+            let t  = if cfg[1] == 0 { cfg[0].max(2) } else { cfg[0].max(4) } as u64;
+            let wc = if cfg[1] == 1 { 99u64.saturating_sub(t) } else { t };
+            calc_payout(bet, wc, 100, num)
+        }
+        3 => {
+            let doors = tower_doors(cfg).clamp(TOWER_MIN_DOORS, TOWER_MAX_DOORS);
+            let cap   = if doors == 2 { TOWER_MAX_FLOORS } else { TOWER_MAX_WIDE_FLOORS };
+            let (safe, total) = tower_odds(cfg[0].clamp(1, cap), doors);
+            calc_payout(bet, safe, total, num)
+        }
+        4 => {
+            let worst = MYSTERY_GAMES.iter().map(|(g, c)| get_worst_payout(bet, *g, c, 0)).max().unwrap_or(0);
+            scale_payout(worst, MYSTERY_BONUS_BPS)
+        }
+        5 => scale_payout(bet, plinko_multiplier_bps(cfg[0], cfg[1], 0, num)),
+        6 => {
+            let m = cfg[0].clamp(MINES_MIN, MINES_MAX);
+            scale_payout(bet, mines_multiplier_bps(m, MINES_TILES - m, num).min(MINES_MAX_MULTIPLIER_BPS))
+        }
+        7 => fixed::mul_div_floor(bet, u16::from_le_bytes([cfg[0], cfg[1]]) as u64, 100).unwrap_or(u64::MAX),
+        8 => bet.saturating_mul(ROULETTE_PAYOUT_X.get(cfg[0] as usize).copied().unwrap_or(ROULETTE_PAYOUT_X[0])),
+        9 => { let p = cfg[0].clamp(1, KENO_MAX_PICKS); scale_payout(bet, keno_multiplier_bps(p, p, num)) }
+        10 => fixed::mul_div_floor(bet, ext, 100).unwrap_or(u64::MAX), // 1.00 × target bounds every curve edge
+        11 => scale_payout(bet, wheel_max_multiplier_bps(cfg[0].min(WHEEL_RISK_HIGH), num)),
+        12 => scale_payout(bet, HILO_MAX_MULTIPLIER_BPS),
+        13 => scale_payout(bet, video_poker_multiplier_bps(POKER_ROYAL, num)),
+        14 => fixed::apply_bps(bet, BACCARAT_PAYOUT_BPS[cfg[0].min(BACCARAT_TIE) as usize]).unwrap_or(u64::MAX),
+        15 => scale_payout(bet, slots_multiplier_bps(&[SLOTS_SEVEN; 3], num)),
+        16 => calc_payout(bet, sicbo_ways(cfg[0], cfg[1]).max(1), 216, num),
+        17 => fixed::apply_bps(bet, DT_PAYOUT_BPS[cfg[0].min(DT_TIE) as usize]).unwrap_or(u64::MAX),
+        18 => calc_payout(bet, dice_duo_ways(cfg[0], cfg[1]).max(1), 36, DUO_NUM),
+        19 => scale_payout(bet, stairs_multiplier_bps(STAIRS_STEPS, num)),
+        20 => scale_payout(bet, goal_multiplier_bps(cfg[0].clamp(1, GOAL_MAX_ROWS))),
+        21 => scale_payout(bet, tower_climb_multiplier_bps(cfg[0].clamp(1, TOWER_MAX_FLOORS), tower_doors(cfg).clamp(TOWER_MIN_DOORS, TOWER_MAX_DOORS), num)),
+        22 => fixed::apply_bps(bet, ext).unwrap_or(u64::MAX), // chained leg multiplier, pinned at placement
+        23 => calc_payout(bet, 1, 2, DOUBLE_NUM_BPS),
+        _ => 0,
+    }
+}
+
+// ══════════════════════════════════════════════════════════════════════════
+//  ACCOUNTS
+// ══════════════════════════════════════════════════════════════════════════
+
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    #[account(init, payer = authority, space = 8 + GlobalPool::LEN, seeds = [b"global_pool"], bump)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(mut)] pub authority:      Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(room: u8)]
+pub struct InitializeRoom<'info> {
+    #[account(seeds = [b"global_pool"], bump = main_pool.bump, has_one = authority)]
+    pub main_pool:      Account<'info, GlobalPool>,
+    #[account(init, payer = authority, space = 8 + GlobalPool::LEN, seeds = [room_seed(room)], bump)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(mut)] pub authority:      Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct FundPool<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(mut)] pub funder:         Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct EnrollPlayerStats<'info> {
+    #[account(mut)] pub player: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init, payer = player, space = 8 + PlayerStats::LEN,
+              seeds = [b"player_stats", player.key().as_ref()], bump)]
+    pub player_stats:   Account<'info, PlayerStats>,
+    pub system_program: Program<'info, System>,
+    /// Optional — the pool's promo attestor co-signing this enrollment.
+    pub attestor:       Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_type: u8, commitment: [u8; 32], bet_lamports: u64)]
+pub struct PlaceBet<'info> {
+    #[account(mut)] pub player:   Signer<'info>,
+    /// CHECK: Optional referrer — pass player's own key if none.
+    pub referrer: AccountInfo<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:     Account<'info, GlobalPool>,
+    #[account(init, payer = player, space = 8 + GameSession::LEN,
+              seeds = [b"session", player.key().as_ref(), commitment.as_ref()], bump)]
+    pub session:        Account<'info, GameSession>,
+    pub system_program: Program<'info, System>,
+    /// Optional — omit and the bet is simply not counted in the unique-player estimate.
+    #[account(mut, seeds = [b"player_sketch"], bump = player_sketch.bump)]
+    pub player_sketch:  Option<Box<Account<'info, PlayerSketch>>>,
+    /// CHECK: Address-validated sysvar. Required only when place_bet is invoked via CPI.
+    #[account(address = ix_sysvar::ID)]
+    pub instructions:   Option<UncheckedAccount<'info>>,
+    /// Required once the room indexes open sessions (pool.session_index_enabled).
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &open_sessions.page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions:  Option<Box<Account<'info, OpenSessionPage>>>,
+    /// CHECK: Optional system-owned wallet that receives winnings instead of the player.
+    pub payout_wallet:  Option<UncheckedAccount<'info>>,
+    /// Optional — present iff the signer is a registered partner placing a custodial bet.
+    #[account(mut, seeds = [b"partner", player.key().as_ref()], bump = partner.bump,
+              constraint = partner.active @ BlitzError::PartnerInactive)]
+    pub partner:        Option<Account<'info, Partner>>,
+    /// Required iff the pool runs the first-bet promo (promo_cap > 0); records stats and claims it.
+    #[account(mut, seeds = [b"player_stats", player.key().as_ref()], bump = player_stats.bump)]
+    pub player_stats:   Option<Box<Account<'info, PlayerStats>>>,
+    /// Required iff the pool is in net-loss referral mode and a referrer is passed.
+    #[account(seeds = [b"referral", pool.key().as_ref(), referrer.key().as_ref()], bump = referral.bump)]
+    pub referral:       Option<Box<Account<'info, ReferralAccount>>>,
+}
+
+/// PlaceBet without the session: the batch's session PDAs arrive in remaining_accounts.
+#[derive(Accounts)]
+pub struct PlaceBetsBatch<'info> {
+    #[account(mut)] pub player:   Signer<'info>,
+    /// CHECK: Optional referrer — pass player's own key if none. Shared by every bet.
+    pub referrer: AccountInfo<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:     Account<'info, GlobalPool>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"player_sketch"], bump = player_sketch.bump)]
+    pub player_sketch:  Option<Box<Account<'info, PlayerSketch>>>,
+    /// CHECK: Address-validated sysvar. Required only when invoked via CPI.
+    #[account(address = ix_sysvar::ID)]
+    pub instructions:   Option<UncheckedAccount<'info>>,
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &open_sessions.page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions:  Option<Box<Account<'info, OpenSessionPage>>>,
+    /// CHECK: Optional system-owned wallet that receives winnings instead of the player.
+    pub payout_wallet:  Option<UncheckedAccount<'info>>,
+    #[account(mut, seeds = [b"partner", player.key().as_ref()], bump = partner.bump,
+              constraint = partner.active @ BlitzError::PartnerInactive)]
+    pub partner:        Option<Account<'info, Partner>>,
+    #[account(mut, seeds = [b"player_stats", player.key().as_ref()], bump = player_stats.bump)]
+    pub player_stats:   Option<Box<Account<'info, PlayerStats>>>,
+    /// Required iff the pool is in net-loss referral mode and a referrer is passed.
+    #[account(seeds = [b"referral", pool.key().as_ref(), referrer.key().as_ref()], bump = referral.bump)]
+    pub referral:       Option<Box<Account<'info, ReferralAccount>>>,
+}
+
+/// PlaceBet plus the ParlaySession holding the legs.
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct PlaceParlay<'info> {
+    #[account(mut)] pub player:   Signer<'info>,
+    /// CHECK: Optional referrer — pass player's own key if none.
+    pub referrer: AccountInfo<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:     Account<'info, GlobalPool>,
+    #[account(init, payer = player, space = 8 + GameSession::LEN,
+              seeds = [b"session", player.key().as_ref(), commitment.as_ref()], bump)]
+    pub session:        Box<Account<'info, GameSession>>,
+    #[account(init, payer = player, space = 8 + ParlaySession::LEN,
+              seeds = [b"parlay", session.key().as_ref()], bump)]
+    pub parlay:         Box<Account<'info, ParlaySession>>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"player_sketch"], bump = player_sketch.bump)]
+    pub player_sketch:  Option<Box<Account<'info, PlayerSketch>>>,
+    /// CHECK: Address-validated sysvar. Required only when invoked via CPI.
+    #[account(address = ix_sysvar::ID)]
+    pub instructions:   Option<UncheckedAccount<'info>>,
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &open_sessions.page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions:  Option<Box<Account<'info, OpenSessionPage>>>,
+    /// CHECK: Optional system-owned wallet that receives winnings instead of the player.
+    pub payout_wallet:  Option<UncheckedAccount<'info>>,
+    #[account(mut, seeds = [b"partner", player.key().as_ref()], bump = partner.bump,
+              constraint = partner.active @ BlitzError::PartnerInactive)]
+    pub partner:        Option<Account<'info, Partner>>,
+    #[account(mut, seeds = [b"player_stats", player.key().as_ref()], bump = player_stats.bump)]
+    pub player_stats:   Option<Box<Account<'info, PlayerStats>>>,
+    /// Required iff the pool is in net-loss referral mode and a referrer is passed.
+    #[account(seeds = [b"referral", pool.key().as_ref(), referrer.key().as_ref()], bump = referral.bump)]
+    pub referral:       Option<Box<Account<'info, ReferralAccount>>>,
+}
+
+/// PlaceBet with the stake drawn from the player's vault; no payout wallet, no partner.
+#[derive(Accounts)]
+#[instruction(game_type: u8, commitment: [u8; 32])]
+pub struct PlaceBetFromVault<'info> {
+    #[account(mut)] pub player:   Signer<'info>,
+    /// CHECK: Optional referrer — pass player's own key if none.
+    pub referrer: AccountInfo<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:     Account<'info, GlobalPool>,
+    #[account(init, payer = player, space = 8 + GameSession::LEN,
+              seeds = [b"session", player.key().as_ref(), commitment.as_ref()], bump)]
+    pub session:        Account<'info, GameSession>,
+    #[account(mut, seeds = [b"vault", player.key().as_ref()], bump = vault.bump, has_one = player)]
+    pub vault:          Box<Account<'info, PlayerVault>>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"player_sketch"], bump = player_sketch.bump)]
+    pub player_sketch:  Option<Box<Account<'info, PlayerSketch>>>,
+    /// CHECK: Address-validated sysvar. Required only when invoked via CPI.
+    #[account(address = ix_sysvar::ID)]
+    pub instructions:   Option<UncheckedAccount<'info>>,
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &open_sessions.page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions:  Option<Box<Account<'info, OpenSessionPage>>>,
+    #[account(mut, seeds = [b"player_stats", player.key().as_ref()], bump = player_stats.bump)]
+    pub player_stats:   Option<Box<Account<'info, PlayerStats>>>,
+    /// Required iff the pool is in net-loss referral mode and a referrer is passed.
+    #[account(seeds = [b"referral", pool.key().as_ref(), referrer.key().as_ref()], bump = referral.bump)]
+    pub referral:       Option<Box<Account<'info, ReferralAccount>>>,
+    /// Optional — stake from this sponsor's grant to the player instead of the vault.
+    #[account(mut, seeds = [b"sponsor_grant", sponsor_grant.sponsor.as_ref(), player.key().as_ref()],
+              bump = sponsor_grant.bump)]
+    pub sponsor_grant:  Option<Box<Account<'info, SponsorGrant>>>,
+}
+
+#[derive(Accounts)]
+pub struct VaultDeposit<'info> {
+    #[account(mut)] pub player: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(init_if_needed, payer = player, space = 8 + PlayerVault::LEN,
+              seeds = [b"vault", player.key().as_ref()], bump)]
+    pub vault:          Account<'info, PlayerVault>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(beneficiary: Pubkey)]
+pub struct SponsorDeposit<'info> {
+    #[account(mut)] pub sponsor: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    /// Required for an unconstrained top-up.
+    #[account(init_if_needed, payer = sponsor, space = 8 + PlayerVault::LEN,
+              seeds = [b"vault", beneficiary.as_ref()], bump)]
+    pub vault:          Option<Account<'info, PlayerVault>>,
+    /// Required for a constrained grant.
+    #[account(init_if_needed, payer = sponsor, space = 8 + SponsorGrant::LEN,
+              seeds = [b"sponsor_grant", sponsor.key().as_ref(), beneficiary.as_ref()], bump)]
+    pub sponsor_grant:  Option<Account<'info, SponsorGrant>>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimSponsorGrant<'info> {
+    #[account(mut)] pub sponsor: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, close = sponsor, has_one = sponsor,
+              seeds = [b"sponsor_grant", sponsor.key().as_ref(), sponsor_grant.beneficiary.as_ref()], bump = sponsor_grant.bump)]
+    pub sponsor_grant: Account<'info, SponsorGrant>,
+}
+
+#[derive(Accounts)]
+pub struct VaultWithdraw<'info> {
+    #[account(mut)] pub player: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"vault", player.key().as_ref()], bump = vault.bump, has_one = player)]
+    pub vault: Account<'info, PlayerVault>,
+}
+
+#[derive(Accounts)]
+pub struct LpDeposit<'info> {
+    #[account(mut)] pub owner: Signer<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init_if_needed, payer = owner, space = 8 + LpPosition::LEN,
+              seeds = [b"lp", pool.key().as_ref(), owner.key().as_ref()], bump)]
+    pub position:       Account<'info, LpPosition>,
+    pub system_program: Program<'info, System>,
+    /// Required once the room has a NavSnapshot (pool.nav_snapshot_enabled).
+    #[account(seeds = [b"nav_snapshot", pool.key().as_ref()], bump = nav_snapshot.bump)]
+    pub nav_snapshot:   Option<Account<'info, NavSnapshot>>,
+}
+
+#[derive(Accounts)]
+pub struct LpWithdraw<'info> {
+    #[account(mut)] pub owner: Signer<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:     Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"lp", pool.key().as_ref(), owner.key().as_ref()], bump = position.bump,
+              has_one = owner, has_one = pool)]
+    pub position: Account<'info, LpPosition>,
+}
+
+/// remaining_accounts: (LpPosition, owner) pairs in ticket order, both writable.
+#[derive(Accounts)]
+pub struct ProcessLpQueue<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:         Account<'info, GlobalPool>,
+    /// Required once the room has a NavSnapshot (pool.nav_snapshot_enabled).
+    #[account(seeds = [b"nav_snapshot", pool.key().as_ref()], bump = nav_snapshot.bump)]
+    pub nav_snapshot: Option<Account<'info, NavSnapshot>>,
+}
+
+#[derive(Accounts)]
+pub struct InitLpLedger<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init, payer = authority, space = 8 + LpLedger::LEN,
+              seeds = [b"lp_ledger", pool.key().as_ref()], bump)]
+    pub lp_ledger:      AccountLoader<'info, LpLedger>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseLpEpoch<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:      Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"lp_ledger", pool.key().as_ref()], bump)]
+    pub lp_ledger: AccountLoader<'info, LpLedger>,
+}
+
+#[derive(Accounts)]
+pub struct InitNavSnapshot<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init, payer = authority, space = 8 + NavSnapshot::LEN,
+              seeds = [b"nav_snapshot", pool.key().as_ref()], bump)]
+    pub nav_snapshot:   Account<'info, NavSnapshot>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct TakeNavSnapshot<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:         Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"nav_snapshot", pool.key().as_ref()], bump = nav_snapshot.bump)]
+    pub nav_snapshot: Account<'info, NavSnapshot>,
+}
+
+/// PlaceBet with PlayerStats required; the bet itself comes from PlayerStats.
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct ReplayBet<'info> {
+    #[account(mut)] pub player:   Signer<'info>,
+    /// CHECK: Optional referrer — pass player's own key if none.
+    pub referrer: AccountInfo<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:     Account<'info, GlobalPool>,
+    #[account(init, payer = player, space = 8 + GameSession::LEN,
+              seeds = [b"session", player.key().as_ref(), commitment.as_ref()], bump)]
+    pub session:        Account<'info, GameSession>,
+    #[account(mut, seeds = [b"player_stats", player.key().as_ref()], bump = player_stats.bump)]
+    pub player_stats:   Box<Account<'info, PlayerStats>>,
+    pub system_program: Program<'info, System>,
+    #[account(mut, seeds = [b"player_sketch"], bump = player_sketch.bump)]
+    pub player_sketch:  Option<Box<Account<'info, PlayerSketch>>>,
+    /// CHECK: Address-validated sysvar. Required only when invoked via CPI.
+    #[account(address = ix_sysvar::ID)]
+    pub instructions:   Option<UncheckedAccount<'info>>,
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &open_sessions.page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions:  Option<Box<Account<'info, OpenSessionPage>>>,
+    /// CHECK: Optional system-owned wallet that receives winnings instead of the player.
+    pub payout_wallet:  Option<UncheckedAccount<'info>>,
+    #[account(mut, seeds = [b"partner", player.key().as_ref()], bump = partner.bump,
+              constraint = partner.active @ BlitzError::PartnerInactive)]
+    pub partner:        Option<Account<'info, Partner>>,
+    /// Required iff the pool is in net-loss referral mode and a referrer is passed.
+    #[account(seeds = [b"referral", pool.key().as_ref(), referrer.key().as_ref()], bump = referral.bump)]
+    pub referral:       Option<Box<Account<'info, ReferralAccount>>>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePlayerStats<'info> {
+    #[account(mut,
+              seeds = [b"player_stats", player.key().as_ref()],
+              bump = player_stats.bump,
+              has_one = player,
+              realloc = 8 + PlayerStats::LEN,
+              realloc::payer = player,
+              realloc::zero = true)]
+    pub player_stats:   Account<'info, PlayerStats>,
+    #[account(mut)] pub player: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+#[instruction(amount: u64, expires_at: i64, campaign_id: u64)]
+pub struct RedeemPromoVoucher<'info> {
+    #[account(mut)] pub player: Signer<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(mut, has_one = player, has_one = pool)]
+    pub session:        Account<'info, GameSession>,
+    #[account(init, payer = player, space = 8 + PromoVoucherClaim::LEN,
+              seeds = [b"promo_voucher", pool.key().as_ref(), player.key().as_ref(), &campaign_id.to_le_bytes()], bump)]
+    pub claim:          Account<'info, PromoVoucherClaim>,
+    /// CHECK: Address-validated sysvar.
+    #[account(address = ix_sysvar::ID)]
+    pub instructions:   UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClosePromoVoucher<'info> {
+    #[account(mut)] pub player: Signer<'info>,
+    #[account(mut, close = player, has_one = player)]
+    pub claim: Account<'info, PromoVoucherClaim>,
+}
+
+#[derive(Accounts)]
+#[instruction(game_type: u8, commitment: [u8; 32])]
+pub struct PlaceSplitBet<'info> {
+    #[account(mut)] pub player:   Signer<'info>,
+    /// CHECK: Optional referrer — pass player's own key if none.
+    pub referrer: AccountInfo<'info>,
+    #[account(mut, seeds = [b"global_pool"], bump = main_pool.bump)]
+    pub main_pool:        Box<Account<'info, GlobalPool>>,
+    #[account(mut, seeds = [b"high_roller_pool"], bump = high_roller_pool.bump)]
+    pub high_roller_pool: Box<Account<'info, GlobalPool>>,
+    #[account(init, payer = player, space = 8 + GameSession::LEN,
+              seeds = [b"session", player.key().as_ref(), commitment.as_ref()], bump)]
+    pub session:          Box<Account<'info, GameSession>>,
+    #[account(init, payer = player, space = 8 + GameSession::LEN,
+              seeds = [b"session", player.key().as_ref(), commitment.as_ref(), b"split"], bump)]
+    pub linked_session:   Box<Account<'info, GameSession>>,
+    pub system_program:   Program<'info, System>,
+    /// CHECK: Address-validated sysvar. Required only when invoked via CPI.
+    #[account(address = ix_sysvar::ID)]
+    pub instructions:     Option<UncheckedAccount<'info>>,
+    /// Required iff that pool is in net-loss referral mode and a referrer is passed.
+    #[account(seeds = [b"referral", main_pool.key().as_ref(), referrer.key().as_ref()], bump = main_referral.bump)]
+    pub main_referral:        Option<Box<Account<'info, ReferralAccount>>>,
+    #[account(seeds = [b"referral", high_roller_pool.key().as_ref(), referrer.key().as_ref()], bump = high_roller_referral.bump)]
+    pub high_roller_referral: Option<Box<Account<'info, ReferralAccount>>>,
+    /// Required iff either pool runs the first-bet promo.
+    #[account(mut, seeds = [b"player_stats", player.key().as_ref()], bump = player_stats.bump)]
+    pub player_stats:         Option<Box<Account<'info, PlayerStats>>>,
+    /// Required iff that pool indexes open sessions.
+    #[account(mut, seeds = [b"open_sessions", main_pool.key().as_ref(), &main_open_sessions.page.to_le_bytes()],
+              bump = main_open_sessions.bump)]
+    pub main_open_sessions:        Option<Box<Account<'info, OpenSessionPage>>>,
+    #[account(mut, seeds = [b"open_sessions", high_roller_pool.key().as_ref(), &high_roller_open_sessions.page.to_le_bytes()],
+              bump = high_roller_open_sessions.bump)]
+    pub high_roller_open_sessions: Option<Box<Account<'info, OpenSessionPage>>>,
+}
+
+#[derive(Accounts)]
+#[instruction(page: u32)]
+pub struct CreateOpenSessionPage<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init, payer = authority, space = 8 + OpenSessionPage::LEN,
+              seeds = [b"open_sessions", pool.key().as_ref(), &page.to_le_bytes()], bump)]
+    pub open_sessions:  Box<Account<'info, OpenSessionPage>>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct QuoteBet<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+}
+
+#[derive(Accounts)]
+pub struct GrantAuditor<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:            Account<'info, GlobalPool>,
+    /// CHECK: Key being granted watch-only access; never signs here.
+    pub auditor:         UncheckedAccount<'info>,
+    #[account(init, payer = authority, space = 8 + Auditor::LEN,
+              seeds = [b"auditor", pool.key().as_ref(), auditor.key().as_ref()], bump)]
+    pub auditor_account: Account<'info, Auditor>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program:  Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevokeAuditor<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:            Account<'info, GlobalPool>,
+    #[account(mut, close = authority, has_one = pool,
+              seeds = [b"auditor", pool.key().as_ref(), auditor_account.auditor.as_ref()], bump = auditor_account.bump)]
+    pub auditor_account: Account<'info, Auditor>,
+    #[account(mut)] pub authority: Signer<'info>,
+}
+
+/// Watch-only: the pool is never writable here.
+#[derive(Accounts)]
+pub struct Audit<'info> {
+    pub auditor: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, has_one = pool, has_one = auditor,
+              seeds = [b"auditor", pool.key().as_ref(), auditor.key().as_ref()], bump = auditor_account.bump)]
+    pub auditor_account: Account<'info, Auditor>,
+}
+
+#[derive(Accounts)]
+pub struct InitializePlayerSketch<'info> {
+    #[account(seeds = [b"global_pool"], bump = pool.bump, has_one = authority)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init, payer = authority, space = 8 + PlayerSketch::LEN, seeds = [b"player_sketch"], bump)]
+    pub player_sketch:  Box<Account<'info, PlayerSketch>>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RevealGame<'info> {
+    #[account(mut)] pub player:   Signer<'info>,
+    /// CHECK: Must match session.referrer. Writable to receive commission.
+    #[account(mut, address = session.referrer)] pub referrer: AccountInfo<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, has_one = player, has_one = pool)] pub session: Account<'info, GameSession>,
+    /// CHECK: Address-validated sysvar — not injectable.
+    #[account(address = slot_hashes::ID)] pub slot_hashes: UncheckedAccount<'info>,
+    /// Optional — pass only if the player opted in to notifications.
+    #[account(seeds = [b"notify", player.key().as_ref()], bump = notification_prefs.bump)]
+    pub notification_prefs: Option<Account<'info, NotificationPrefs>>,
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &session.index_page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions: Option<Box<Account<'info, OpenSessionPage>>>,
+    /// CHECK: Required iff the session names a payout wallet other than the player.
+    #[account(mut, address = session.payout_to)]
+    pub payout_wallet: Option<UncheckedAccount<'info>>,
+    /// Required iff the session was placed by a partner.
+    #[account(mut, seeds = [b"partner", session.player.as_ref()], bump = partner.bump)]
+    pub partner:       Option<Account<'info, Partner>>,
+    /// Optional — ops co-signer; without it payouts above cosign_threshold are held.
+    #[account(address = pool.cosigner @ BlitzError::InvalidCosigner)]
+    pub cosigner:      Option<Signer<'info>>,
+    /// Optional — the referrer's ReferralAccount; net-loss mode accrues only when passed.
+    #[account(mut, seeds = [b"referral", pool.key().as_ref(), session.referrer.as_ref()], bump = referral.bump)]
+    pub referral:      Option<Account<'info, ReferralAccount>>,
+    /// Optional — the room's ReceiptTree; compressed receipt mode when passed.
+    #[account(mut, seeds = [b"receipt_tree", pool.key().as_ref()], bump = receipt_tree.bump)]
+    pub receipt_tree:  Option<Box<Account<'info, ReceiptTree>>>,
+    /// Required once the room has a Firehose (pool.firehose_enabled); rewritten with this settlement.
+    #[account(mut, seeds = [b"firehose", pool.key().as_ref()], bump = firehose.bump)]
+    pub firehose:      Option<Account<'info, Firehose>>,
+    /// Optional — the room's SectorHeatmap; counts the strike of a Sector settlement.
+    #[account(mut, seeds = [b"sector_heatmap", pool.key().as_ref()], bump = sector_heatmap.load()?.bump)]
+    pub sector_heatmap: Option<AccountLoader<'info, SectorHeatmap>>,
+    /// Optional — the session's GameReceipt; written with this settlement when passed.
+    #[account(mut, seeds = [b"game_receipt", session.key().as_ref()], bump = game_receipt.bump)]
+    pub game_receipt:  Option<Box<Account<'info, GameReceipt>>>,
+}
+
+/// RevealGame for several sessions: (session, referrer) pairs arrive in remaining_accounts.
+#[derive(Accounts)]
+pub struct RevealBatch<'info> {
+    #[account(mut)] pub player:   Signer<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    /// CHECK: Address-validated sysvar — not injectable.
+    #[account(address = slot_hashes::ID)] pub slot_hashes: UncheckedAccount<'info>,
+    #[account(seeds = [b"notify", player.key().as_ref()], bump = notification_prefs.bump)]
+    pub notification_prefs: Option<Account<'info, NotificationPrefs>>,
+    /// Required iff any session is indexed — all of them on this page.
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &open_sessions.page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions: Option<Box<Account<'info, OpenSessionPage>>>,
+    #[account(address = pool.cosigner @ BlitzError::InvalidCosigner)]
+    pub cosigner:      Option<Signer<'info>>,
+    #[account(mut, seeds = [b"receipt_tree", pool.key().as_ref()], bump = receipt_tree.bump)]
+    pub receipt_tree:  Option<Box<Account<'info, ReceiptTree>>>,
+    #[account(mut, seeds = [b"firehose", pool.key().as_ref()], bump = firehose.bump)]
+    pub firehose:      Option<Account<'info, Firehose>>,
+    #[account(mut, seeds = [b"sector_heatmap", pool.key().as_ref()], bump = sector_heatmap.load()?.bump)]
+    pub sector_heatmap: Option<AccountLoader<'info, SectorHeatmap>>,
+}
+
+#[derive(Accounts)]
+pub struct DoubleOrNothing<'info> {
+    #[account(mut)] pub player: Signer<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, has_one = player, has_one = pool)] pub session: Account<'info, GameSession>,
+}
+
+/// RevealGame plus the parlay's legs (closed to the player); no sector heatmap.
+#[derive(Accounts)]
+pub struct RevealParlay<'info> {
+    #[account(mut)] pub player:   Signer<'info>,
+    /// CHECK: Must match session.referrer. Writable to receive commission.
+    #[account(mut, address = session.referrer)] pub referrer: AccountInfo<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, has_one = player, has_one = pool)] pub session: Account<'info, GameSession>,
+    #[account(mut, close = player, seeds = [b"parlay", session.key().as_ref()], bump = parlay.bump)]
+    pub parlay:        Account<'info, ParlaySession>,
+    /// CHECK: Address-validated sysvar — not injectable.
+    #[account(address = slot_hashes::ID)] pub slot_hashes: UncheckedAccount<'info>,
+    #[account(seeds = [b"notify", player.key().as_ref()], bump = notification_prefs.bump)]
+    pub notification_prefs: Option<Account<'info, NotificationPrefs>>,
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &session.index_page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions: Option<Box<Account<'info, OpenSessionPage>>>,
+    /// CHECK: Required iff the session names a payout wallet other than the player.
+    #[account(mut, address = session.payout_to)]
+    pub payout_wallet: Option<UncheckedAccount<'info>>,
+    #[account(mut, seeds = [b"partner", session.player.as_ref()], bump = partner.bump)]
+    pub partner:       Option<Account<'info, Partner>>,
+    #[account(address = pool.cosigner @ BlitzError::InvalidCosigner)]
+    pub cosigner:      Option<Signer<'info>>,
+    #[account(mut, seeds = [b"referral", pool.key().as_ref(), session.referrer.as_ref()], bump = referral.bump)]
+    pub referral:      Option<Account<'info, ReferralAccount>>,
+    #[account(mut, seeds = [b"receipt_tree", pool.key().as_ref()], bump = receipt_tree.bump)]
+    pub receipt_tree:  Option<Box<Account<'info, ReceiptTree>>>,
+    #[account(mut, seeds = [b"firehose", pool.key().as_ref()], bump = firehose.bump)]
+    pub firehose:      Option<Account<'info, Firehose>>,
+}
+
+#[derive(Accounts)]
+pub struct RevealDelegated<'info> {
+    #[account(mut)] pub delegate: Signer<'info>,
+    /// CHECK: Validated via has_one constraints on session + session_token.
+    #[account(mut)] pub player:   AccountInfo<'info>,
+    /// CHECK: Must match session.referrer. Writable to receive commission.
+    #[account(mut, address = session.referrer)] pub referrer: AccountInfo<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, has_one = player, has_one = pool)] pub session: Account<'info, GameSession>,
+    #[account(seeds = [b"session_key", player.key().as_ref()], bump = session_token.bump,
+              has_one = delegate, has_one = player)]
+    pub session_token: Account<'info, SessionToken>,
+    /// CHECK: Address-validated sysvar — not injectable.
+    #[account(address = slot_hashes::ID)] pub slot_hashes: UncheckedAccount<'info>,
+    /// Optional — pass only if the player opted in to notifications.
+    #[account(seeds = [b"notify", player.key().as_ref()], bump = notification_prefs.bump)]
+    pub notification_prefs: Option<Account<'info, NotificationPrefs>>,
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &session.index_page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions: Option<Box<Account<'info, OpenSessionPage>>>,
+    /// CHECK: Required iff the session names a payout wallet other than the player.
+    #[account(mut, address = session.payout_to)]
+    pub payout_wallet: Option<UncheckedAccount<'info>>,
+    /// Required iff the session was placed by a partner.
+    #[account(mut, seeds = [b"partner", session.player.as_ref()], bump = partner.bump)]
+    pub partner:       Option<Account<'info, Partner>>,
+    /// Optional — ops co-signer; without it payouts above cosign_threshold are held.
+    #[account(address = pool.cosigner @ BlitzError::InvalidCosigner)]
+    pub cosigner:      Option<Signer<'info>>,
+    /// Optional — the referrer's ReferralAccount; net-loss mode accrues only when passed.
+    #[account(mut, seeds = [b"referral", pool.key().as_ref(), session.referrer.as_ref()], bump = referral.bump)]
+    pub referral:      Option<Account<'info, ReferralAccount>>,
+    /// Optional — the room's ReceiptTree; compressed receipt mode when passed.
+    #[account(mut, seeds = [b"receipt_tree", pool.key().as_ref()], bump = receipt_tree.bump)]
+    pub receipt_tree:  Option<Box<Account<'info, ReceiptTree>>>,
+    /// Required once the room has a Firehose (pool.firehose_enabled); rewritten with this settlement.
+    #[account(mut, seeds = [b"firehose", pool.key().as_ref()], bump = firehose.bump)]
+    pub firehose:      Option<Account<'info, Firehose>>,
+    /// Optional — the room's SectorHeatmap; counts the strike of a Sector settlement.
+    #[account(mut, seeds = [b"sector_heatmap", pool.key().as_ref()], bump = sector_heatmap.load()?.bump)]
+    pub sector_heatmap: Option<AccountLoader<'info, SectorHeatmap>>,
+    /// Optional — the session's GameReceipt; written with this settlement when passed.
+    #[account(mut, seeds = [b"game_receipt", session.key().as_ref()], bump = game_receipt.bump)]
+    pub game_receipt:  Option<Box<Account<'info, GameReceipt>>>,
+}
+
+#[derive(Accounts)]
+pub struct RevealPublic<'info> {
+    /// Anyone holding the nonce; receives the tip.
+    #[account(mut)] pub executor: Signer<'info>,
+    /// CHECK: Validated via has_one on session.
+    #[account(mut)] pub player:   AccountInfo<'info>,
+    /// CHECK: Must match session.referrer. Writable to receive commission.
+    #[account(mut, address = session.referrer)] pub referrer: AccountInfo<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, has_one = player, has_one = pool)] pub session: Account<'info, GameSession>,
+    /// CHECK: Address-validated sysvar — not injectable.
+    #[account(address = slot_hashes::ID)] pub slot_hashes: UncheckedAccount<'info>,
+    /// Optional — pass only if the player opted in to notifications.
+    #[account(seeds = [b"notify", player.key().as_ref()], bump = notification_prefs.bump)]
+    pub notification_prefs: Option<Account<'info, NotificationPrefs>>,
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &session.index_page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions: Option<Box<Account<'info, OpenSessionPage>>>,
+    /// CHECK: Required iff the session names a payout wallet other than the player.
+    #[account(mut, address = session.payout_to)]
+    pub payout_wallet: Option<UncheckedAccount<'info>>,
+    /// Required iff the session was placed by a partner.
+    #[account(mut, seeds = [b"partner", session.player.as_ref()], bump = partner.bump)]
+    pub partner:       Option<Account<'info, Partner>>,
+    /// Optional — ops co-signer; without it payouts above cosign_threshold are held.
+    #[account(address = pool.cosigner @ BlitzError::InvalidCosigner)]
+    pub cosigner:      Option<Signer<'info>>,
+    /// Optional — the referrer's ReferralAccount; net-loss mode accrues only when passed.
+    #[account(mut, seeds = [b"referral", pool.key().as_ref(), session.referrer.as_ref()], bump = referral.bump)]
+    pub referral:      Option<Account<'info, ReferralAccount>>,
+    /// Optional — the room's ReceiptTree; compressed receipt mode when passed.
+    #[account(mut, seeds = [b"receipt_tree", pool.key().as_ref()], bump = receipt_tree.bump)]
+    pub receipt_tree:  Option<Box<Account<'info, ReceiptTree>>>,
+    /// Required once the room has a Firehose (pool.firehose_enabled); rewritten with this settlement.
+    #[account(mut, seeds = [b"firehose", pool.key().as_ref()], bump = firehose.bump)]
+    pub firehose:      Option<Account<'info, Firehose>>,
+    /// Optional — the room's SectorHeatmap; counts the strike of a Sector settlement.
+    #[account(mut, seeds = [b"sector_heatmap", pool.key().as_ref()], bump = sector_heatmap.load()?.bump)]
+    pub sector_heatmap: Option<AccountLoader<'info, SectorHeatmap>>,
+    /// Optional — the session's GameReceipt; written with this settlement when passed.
+    #[account(mut, seeds = [b"game_receipt", session.key().as_ref()], bump = game_receipt.bump)]
+    pub game_receipt:  Option<Box<Account<'info, GameReceipt>>>,
+}
+
+#[derive(Accounts)]
+pub struct SettleVrf<'info> {
+    /// Anyone — the house crank or the player.
+    pub settler: Signer<'info>,
+    /// CHECK: Validated via has_one on session.
+    #[account(mut)] pub player:   AccountInfo<'info>,
+    /// CHECK: Must match session.referrer. Writable to receive commission.
+    #[account(mut, address = session.referrer)] pub referrer: AccountInfo<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, has_one = player, has_one = pool)] pub session: Account<'info, GameSession>,
+    /// CHECK: Pinned to session.vrf; owner and layout checked by the session's VRF decoder.
+    #[account(address = session.vrf @ BlitzError::InvalidRandomness)]
+    pub randomness: UncheckedAccount<'info>,
+    /// Optional — pass only if the player opted in to notifications.
+    #[account(seeds = [b"notify", player.key().as_ref()], bump = notification_prefs.bump)]
+    pub notification_prefs: Option<Account<'info, NotificationPrefs>>,
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &session.index_page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions: Option<Box<Account<'info, OpenSessionPage>>>,
+    /// CHECK: Required iff the session names a payout wallet other than the player.
+    #[account(mut, address = session.payout_to)]
+    pub payout_wallet: Option<UncheckedAccount<'info>>,
+    /// Required iff the session was placed by a partner.
+    #[account(mut, seeds = [b"partner", session.player.as_ref()], bump = partner.bump)]
+    pub partner:       Option<Account<'info, Partner>>,
+    /// Optional — ops co-signer; without it payouts above cosign_threshold are held.
+    #[account(address = pool.cosigner @ BlitzError::InvalidCosigner)]
+    pub cosigner:      Option<Signer<'info>>,
+    /// Optional — the referrer's ReferralAccount; net-loss mode accrues only when passed.
+    #[account(mut, seeds = [b"referral", pool.key().as_ref(), session.referrer.as_ref()], bump = referral.bump)]
+    pub referral:      Option<Account<'info, ReferralAccount>>,
+    /// Optional — the room's ReceiptTree; compressed receipt mode when passed.
+    #[account(mut, seeds = [b"receipt_tree", pool.key().as_ref()], bump = receipt_tree.bump)]
+    pub receipt_tree:  Option<Box<Account<'info, ReceiptTree>>>,
+    /// Required once the room has a Firehose (pool.firehose_enabled); rewritten with this settlement.
+    #[account(mut, seeds = [b"firehose", pool.key().as_ref()], bump = firehose.bump)]
+    pub firehose:      Option<Account<'info, Firehose>>,
+    /// Optional — the room's SectorHeatmap; counts the strike of a Sector settlement.
+    #[account(mut, seeds = [b"sector_heatmap", pool.key().as_ref()], bump = sector_heatmap.load()?.bump)]
+    pub sector_heatmap: Option<AccountLoader<'info, SectorHeatmap>>,
+    /// Optional — the session's GameReceipt; written with this settlement when passed.
+    #[account(mut, seeds = [b"game_receipt", session.key().as_ref()], bump = game_receipt.bump)]
+    pub game_receipt:  Option<Box<Account<'info, GameReceipt>>>,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct CreateTowerRace<'info> {
+    #[account(mut)] pub creator: Signer<'info>,
+    #[account(seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(init, payer = creator, space = 8 + TowerRace::LEN,
+              seeds = [b"tower_race", creator.key().as_ref(), commitment.as_ref()], bump)]
+    pub race:           Account<'info, TowerRace>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinTowerRace<'info> {
+    #[account(mut)] pub opponent: Signer<'info>,
+    #[account(seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"tower_race", race.creator.as_ref(), race.creator_commitment.as_ref()], bump = race.bump)]
+    pub race:           Account<'info, TowerRace>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelTowerRace<'info> {
+    #[account(mut)] pub creator: Signer<'info>,
+    #[account(mut, close = creator, has_one = creator)] pub race: Account<'info, TowerRace>,
+    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+}
+
+#[derive(Accounts)]
+pub struct TowerRaceAction<'info> {
+    pub signer: Signer<'info>,
+    /// CHECK: Must match race.creator. Receives rent and any winnings.
+    #[account(mut, address = race.creator)]  pub creator:  AccountInfo<'info>,
+    /// CHECK: Must match race.opponent. Receives any winnings.
+    #[account(mut, address = race.opponent)] pub opponent: AccountInfo<'info>,
+    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"tower_race", race.creator.as_ref(), race.creator_commitment.as_ref()], bump = race.bump)]
+    pub race: Account<'info, TowerRace>,
+    /// CHECK: Address-validated sysvar — not injectable.
+    #[account(address = slot_hashes::ID)] pub slot_hashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+#[instruction(commitment: [u8; 32])]
+pub struct CreateRpsMatch<'info> {
+    #[account(mut)] pub creator: Signer<'info>,
+    #[account(seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(init, payer = creator, space = 8 + RpsMatch::LEN,
+              seeds = [b"rps_match", creator.key().as_ref(), commitment.as_ref()], bump)]
+    pub rps_match:      Account<'info, RpsMatch>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinRpsMatch<'info> {
+    #[account(mut)] pub opponent: Signer<'info>,
+    #[account(seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"rps_match", rps_match.creator.as_ref(), rps_match.creator_commitment.as_ref()], bump = rps_match.bump)]
+    pub rps_match:      Account<'info, RpsMatch>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CancelRpsMatch<'info> {
+    #[account(mut)] pub creator: Signer<'info>,
+    #[account(mut, close = creator, has_one = creator)] pub rps_match: Account<'info, RpsMatch>,
+    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+}
+
+#[derive(Accounts)]
+pub struct RpsAction<'info> {
+    pub signer: Signer<'info>,
+    /// CHECK: Must match rps_match.creator. Receives rent and any winnings.
+    #[account(mut, address = rps_match.creator)]  pub creator:  AccountInfo<'info>,
+    /// CHECK: Must match rps_match.opponent. Receives any winnings.
+    #[account(mut, address = rps_match.opponent)] pub opponent: AccountInfo<'info>,
+    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"rps_match", rps_match.creator.as_ref(), rps_match.creator_commitment.as_ref()], bump = rps_match.bump)]
+    pub rps_match: Account<'info, RpsMatch>,
+}
+
+#[derive(Accounts)]
+#[instruction(round: u64)]
+pub struct OpenLotteryRound<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init, payer = authority, space = 8 + LotteryRound::LEN,
+              seeds = [b"lottery_round", pool.key().as_ref(), round.to_le_bytes().as_ref()], bump)]
+    pub lottery_round:  Account<'info, LotteryRound>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct BuyLotteryTicket<'info> {
+    #[account(mut)] pub player: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, address = lottery_round.pool)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"lottery_round", lottery_round.pool.as_ref(), lottery_round.round.to_le_bytes().as_ref()], bump = lottery_round.bump)]
+    pub lottery_round:  Account<'info, LotteryRound>,
+    #[account(init, payer = player, space = 8 + LotteryTicket::LEN,
+              seeds = [b"lottery_ticket", lottery_round.key().as_ref(), lottery_round.tickets.to_le_bytes().as_ref()], bump)]
+    pub ticket:         Account<'info, LotteryTicket>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct DrawLottery<'info> {
+    #[account(mut, address = lottery_round.pool)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"lottery_round", lottery_round.pool.as_ref(), lottery_round.round.to_le_bytes().as_ref()], bump = lottery_round.bump)]
+    pub lottery_round: Account<'info, LotteryRound>,
+    #[account(address = slot_hashes::ID)] pub slot_hashes: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimLotteryTicket<'info> {
+    #[account(mut)] pub owner: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, address = lottery_round.pool)]
+    pub pool:          Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"lottery_round", lottery_round.pool.as_ref(), lottery_round.round.to_le_bytes().as_ref()], bump = lottery_round.bump)]
+    pub lottery_round: Account<'info, LotteryRound>,
+    #[account(mut, close = owner, has_one = owner, constraint = ticket.round == lottery_round.key() @ BlitzError::InvalidLotteryRound)]
+    pub ticket:        Account<'info, LotteryTicket>,
+}
+
+#[derive(Accounts)]
+pub struct RolloverLottery<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, address = lottery_round.pool)]
+    pub pool:          Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"lottery_round", lottery_round.pool.as_ref(), lottery_round.round.to_le_bytes().as_ref()], bump = lottery_round.bump)]
+    pub lottery_round: Account<'info, LotteryRound>,
+    #[account(mut, seeds = [b"lottery_round", lottery_round.pool.as_ref(), (lottery_round.round + 1).to_le_bytes().as_ref()], bump = next_round.bump)]
+    pub next_round:    Account<'info, LotteryRound>,
+}
+
+#[derive(Accounts)]
+#[instruction(code_key: Pubkey)]
+pub struct MintGiftCard<'info> {
+    #[account(mut)] pub funder: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(init, payer = funder, space = 8 + GiftCard::LEN, seeds = [b"gift_card", code_key.as_ref()], bump)]
+    pub gift_card:      Account<'info, GiftCard>,
+    pub system_program: Program<'info, System>,
+}
+
+/// The code's key is the authorization; the transaction's fee payer can be anyone.
+#[derive(Accounts)]
+pub struct RedeemGiftCard<'info> {
+    #[account(address = gift_card.code_key @ BlitzError::InvalidGiftCardCode)]
+    pub code_signer: Signer<'info>,
+    #[account(mut)] pub fee_payer: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, close = funder, has_one = funder, seeds = [b"gift_card", gift_card.code_key.as_ref()], bump = gift_card.bump)]
+    pub gift_card: Account<'info, GiftCard>,
+    /// CHECK: Must match gift_card.funder. Receives the card's rent.
+    #[account(mut)] pub funder: UncheckedAccount<'info>,
+    pub recipient: SystemAccount<'info>,
+    #[account(init_if_needed, payer = fee_payer, space = 8 + PlayerVault::LEN,
+              seeds = [b"vault", recipient.key().as_ref()], bump)]
+    pub vault:          Account<'info, PlayerVault>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReclaimGiftCard<'info> {
+    #[account(mut)] pub funder: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, close = funder, has_one = funder, seeds = [b"gift_card", gift_card.code_key.as_ref()], bump = gift_card.bump)]
+    pub gift_card: Account<'info, GiftCard>,
+}
+
+#[derive(Accounts)]
+#[instruction(round: u64)]
+pub struct OpenRocketRound<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init, payer = authority, space = 8 + RocketRound::LEN,
+              seeds = [b"rocket_round", pool.key().as_ref(), round.to_le_bytes().as_ref()], bump)]
+    pub rocket_round:   Account<'info, RocketRound>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct JoinRocket<'info> {
+    #[account(mut)] pub player: Signer<'info>,
+    #[account(mut, address = rocket_round.pool)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"rocket_round", rocket_round.pool.as_ref(), rocket_round.round.to_le_bytes().as_ref()], bump = rocket_round.bump)]
+    pub rocket_round:   Account<'info, RocketRound>,
+    #[account(init, payer = player, space = 8 + RocketEntry::LEN,
+              seeds = [b"rocket_entry", rocket_round.key().as_ref(), player.key().as_ref()], bump)]
+    pub entry:          Account<'info, RocketEntry>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct LaunchRocket<'info> {
+    #[account(mut, seeds = [b"rocket_round", rocket_round.pool.as_ref(), rocket_round.round.to_le_bytes().as_ref()], bump = rocket_round.bump)]
+    pub rocket_round: Account<'info, RocketRound>,
+    #[account(address = slot_hashes::ID)] pub slot_hashes: UncheckedAccount<'info>,
+}
+
+/// remaining_accounts: (RocketEntry, player) pairs, both writable.
+#[derive(Accounts)]
+pub struct CrankRocket<'info> {
+    #[account(mut, address = rocket_round.pool)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"rocket_round", rocket_round.pool.as_ref(), rocket_round.round.to_le_bytes().as_ref()], bump = rocket_round.bump)]
+    pub rocket_round: Account<'info, RocketRound>,
+}
+
+#[derive(Accounts)]
+pub struct RefundRocketEntry<'info> {
+    #[account(mut, address = rocket_round.pool)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"rocket_round", rocket_round.pool.as_ref(), rocket_round.round.to_le_bytes().as_ref()], bump = rocket_round.bump)]
+    pub rocket_round: Account<'info, RocketRound>,
+    #[account(mut, close = player, has_one = player,
+              seeds = [b"rocket_entry", rocket_round.key().as_ref(), player.key().as_ref()], bump = entry.bump)]
+    pub entry:        Account<'info, RocketEntry>,
+    /// CHECK: Must match entry.player. Receives the stake and the entry rent.
+    #[account(mut)] pub player: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseRocketRound<'info> {
+    #[account(address = rocket_round.pool, has_one = authority)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, close = authority, seeds = [b"rocket_round", rocket_round.pool.as_ref(), rocket_round.round.to_le_bytes().as_ref()], bump = rocket_round.bump)]
+    pub rocket_round: Account<'info, RocketRound>,
+    #[account(mut)] pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSettledSession<'info> {
+    /// CHECK: Must match session.player. Receives the session rent.
+    #[account(mut)] pub player: UncheckedAccount<'info>,
+    #[account(mut, has_one = player, close = player)] pub session: Account<'info, GameSession>,
+}
+
+#[derive(Accounts)]
+pub struct InitReceiptTree<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init, payer = authority, space = 8 + ReceiptTree::LEN,
+              seeds = [b"receipt_tree", pool.key().as_ref()], bump)]
+    pub receipt_tree:   Box<Account<'info, ReceiptTree>>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitGameReceipt<'info> {
+    pub session:        Account<'info, GameSession>,
+    #[account(init, payer = payer, space = 8 + GameReceipt::LEN,
+              seeds = [b"game_receipt", session.key().as_ref()], bump)]
+    pub game_receipt:   Box<Account<'info, GameReceipt>>,
+    #[account(mut)] pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct VerifyGame<'info> {
+    pub game_receipt: Account<'info, GameReceipt>,
+}
+
+#[derive(Accounts)]
+pub struct InitSectorHeatmap<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init, payer = authority, space = 8 + SectorHeatmap::LEN,
+              seeds = [b"sector_heatmap", pool.key().as_ref()], bump)]
+    pub sector_heatmap: AccountLoader<'info, SectorHeatmap>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct InitFirehose<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init, payer = authority, space = 8 + Firehose::LEN,
+              seeds = [b"firehose", pool.key().as_ref()], bump)]
+    pub firehose:       Account<'info, Firehose>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ReleaseHeldPayout<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, has_one = pool)] pub session: Account<'info, GameSession>,
+    /// CHECK: Winnings destination recorded at place_bet.
+    #[account(mut, address = session.payout_to)] pub payout_wallet: UncheckedAccount<'info>,
+    #[account(address = pool.cosigner @ BlitzError::InvalidCosigner)]
+    pub cosigner: Option<Signer<'info>>,
+    /// Optional — the player collects a double_or_nothing offer without waiting out the window.
+    #[account(address = session.player @ BlitzError::NotSessionPlayer)]
+    pub player:   Option<Signer<'info>>,
+}
+
+#[derive(Accounts)]
+pub struct OpenReferralAccount<'info> {
+    #[account(mut)] pub referrer: Signer<'info>,
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init, payer = referrer, space = 8 + ReferralAccount::LEN,
+              seeds = [b"referral", pool.key().as_ref(), referrer.key().as_ref()], bump)]
+    pub referral:       Account<'info, ReferralAccount>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimReferral<'info> {
+    #[account(mut)] pub referrer: Signer<'info>,
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"referral", pool.key().as_ref(), referrer.key().as_ref()], bump = referral.bump,
+              has_one = referrer, has_one = pool)]
+    pub referral: Account<'info, ReferralAccount>,
+}
+
+#[derive(Accounts)]
+pub struct CheckRtp<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+}
+
+#[derive(Accounts)]
+pub struct PingExpiringSessions<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+}
+
+#[derive(Accounts)]
+pub struct AssertPlayerReceived<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    /// CHECK: Only its key is compared against the last-settlement record.
+    pub player: UncheckedAccount<'info>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSession<'info> {
+    #[account(mut)] pub player:   Signer<'info>,
+    /// CHECK: Ephemeral key generated client-side.
+    #[account(mut)] pub delegate: AccountInfo<'info>,
+    #[account(init, payer = player, space = 8 + SessionToken::LEN,
+              seeds = [b"session_key", player.key().as_ref()], bump)]
+    pub session_token:  Account<'info, SessionToken>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CreateSessionWithSig<'info> {
+    #[account(mut)] pub payer:    Signer<'info>,
+    /// CHECK: Authorizes via the Ed25519 verify instruction, not as a transaction signer.
+    pub player:   UncheckedAccount<'info>,
+    /// CHECK: Ephemeral key generated client-side.
+    pub delegate: UncheckedAccount<'info>,
+    #[account(init, payer = payer, space = 8 + SessionToken::LEN,
+              seeds = [b"session_key", player.key().as_ref()], bump)]
+    pub session_token:  Account<'info, SessionToken>,
+    #[account(init_if_needed, payer = payer, space = 8 + SessionNonce::LEN,
+              seeds = [b"session_nonce", player.key().as_ref()], bump)]
+    pub session_nonce:  Account<'info, SessionNonce>,
+    /// CHECK: Address-validated sysvar.
+    #[account(address = ix_sysvar::ID)]
+    pub instructions:   UncheckedAccount<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseSession<'info> {
+    #[account(mut)] pub player:   Signer<'info>,
+    /// CHECK: Validated via session_token address constraint.
+    #[account(mut, address = session_token.delegate)] pub delegate: AccountInfo<'info>,
+    #[account(mut, close = player, seeds = [b"session_key", player.key().as_ref()],
+              bump = session_token.bump, has_one = player)]
+    pub session_token: Account<'info, SessionToken>,
+    #[account(init_if_needed, payer = player, space = 8 + SessionNonce::LEN,
+              seeds = [b"session_nonce", player.key().as_ref()], bump)]
+    pub session_nonce: Account<'info, SessionNonce>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct RegisterPartner<'info> {
+    #[account(seeds = [b"global_pool"], bump = pool.bump, has_one = authority)]
+    pub pool:              Account<'info, GlobalPool>,
+    #[account(init, payer = authority, space = 8 + Partner::LEN,
+              seeds = [b"partner", partner_authority.key().as_ref()], bump)]
+    pub partner:           Account<'info, Partner>,
+    /// CHECK: Key the partner signs custodial bets with.
+    pub partner_authority: UncheckedAccount<'info>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program:    Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePartner<'info> {
+    #[account(seeds = [b"global_pool"], bump = pool.bump, has_one = authority)]
+    pub pool:      Account<'info, GlobalPool>,
+    #[account(mut, seeds = [b"partner", partner.authority.as_ref()], bump = partner.bump)]
+    pub partner:   Account<'info, Partner>,
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SetNotificationPrefs<'info> {
+    #[account(mut)] pub player: Signer<'info>,
+    #[account(init_if_needed, payer = player, space = 8 + NotificationPrefs::LEN,
+              seeds = [b"notify", player.key().as_ref()], bump)]
+    pub notification_prefs: Account<'info, NotificationPrefs>,
+    pub system_program:     Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct CloseNotificationPrefs<'info> {
+    #[account(mut)] pub player: Signer<'info>,
+    #[account(mut, close = player, seeds = [b"notify", player.key().as_ref()],
+              bump = notification_prefs.bump, has_one = player)]
+    pub notification_prefs: Account<'info, NotificationPrefs>,
+}
+
+#[derive(Accounts)]
+pub struct ResolveTerminal<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    /// Closed manually: rent to player (default) or pool, never the caller — no griefing incentive.
+    #[account(mut, has_one = pool)] pub session: Account<'info, GameSession>,
+    /// Anyone — terminal_policy decides what the caller may trigger.
+    #[account(mut)] pub caller: Signer<'info>,
+    /// CHECK: Must match session.player. Receives refunds and, by default, session rent.
+    #[account(mut, address = session.player)] pub player: AccountInfo<'info>,
+    #[account(seeds = [b"notify", session.player.as_ref()], bump = notification_prefs.bump)]
+    pub notification_prefs: Option<Account<'info, NotificationPrefs>>,
+    #[account(mut, seeds = [b"open_sessions", pool.key().as_ref(), &session.index_page.to_le_bytes()],
+              bump = open_sessions.bump)]
+    pub open_sessions: Option<Box<Account<'info, OpenSessionPage>>>,
+    /// Required iff the session is a parlay; closed to the player with it.
+    #[account(mut, seeds = [b"parlay", session.key().as_ref()], bump = parlay.bump)]
+    pub parlay:        Option<Account<'info, ParlaySession>>,
+    /// Required iff the session is bound to net-loss referral (net_loss_ref).
+    #[account(mut, seeds = [b"referral", pool.key().as_ref(), session.referrer.as_ref()], bump = referral.bump)]
+    pub referral:      Option<Account<'info, ReferralAccount>>,
+}
+
+#[derive(Accounts)]
+pub struct AdminOnly<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:      Account<'info, GlobalPool>,
+    #[account(mut)] pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateLiquidity<'info> {
+    #[account(mut, seeds = [from_pool.room_seed()], bump = from_pool.bump, has_one = authority)]
+    pub from_pool: Account<'info, GlobalPool>,
+    #[account(mut, seeds = [to_pool.room_seed()], bump = to_pool.bump, has_one = authority)]
+    pub to_pool:   Account<'info, GlobalPool>,
+    #[account(mut)] pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimHouseFeesCtx<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:      Account<'info, GlobalPool>,
+    #[account(mut)] pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct ExecuteAuthorityTransfer<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+    /// New authority must sign — prevents transfer to an inaccessible wallet.
+    #[account(mut)] pub new_authority: Signer<'info>,
+}
+
+#[cfg(feature = "devnet")]
+#[derive(Accounts)]
+#[instruction(params: DevSeedParams)]
+pub struct DevSeedState<'info> {
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+    /// Optional — pass to also create an expired (forfeitable) session.
+    #[account(init, payer = authority, space = 8 + GameSession::LEN,
+              seeds = [b"session", authority.key().as_ref(), params.session_commitment.as_ref()], bump)]
+    pub session:        Option<Account<'info, GameSession>>,
+}
+
+/// Non-devnet stand-in: no pool, no session init — dev_seed_state can only fail.
+#[cfg(not(feature = "devnet"))]
+#[derive(Accounts)]
+pub struct DevSeedState<'info> {
+    pub authority: Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct MigratePool<'info> {
+    #[account(mut,
+              seeds = [pool.room_seed()],
+              bump = pool.bump,
+              has_one = authority,
+              realloc = 8 + GlobalPool::LEN,
+              realloc::payer = authority,
+              realloc::zero = true)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct MigrateSession<'info> {
+    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)]
+    pub pool:           Account<'info, GlobalPool>,
+    /// CHECK: Pre-room GameSession; it cannot deserialize as the current layout, so
+    ///        migrate_session checks the discriminator and the legacy length itself.
+    #[account(mut, owner = crate::ID)]
+    pub session:        UncheckedAccount<'info>,
+    #[account(mut)] pub payer: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct AttestBuild<'info> {
+    #[account(seeds = [b"global_pool"], bump = pool.bump, has_one = authority)]
+    pub pool:              Account<'info, GlobalPool>,
+    #[account(init_if_needed, payer = authority, space = 8 + BuildAttestation::LEN,
+              seeds = [b"build_attestation"], bump)]
+    pub build_attestation: Account<'info, BuildAttestation>,
+    #[account(mut)] pub authority: Signer<'info>,
+    /// Independent party that reproduced the build. Omit for a self-attestation.
+    pub verifier:          Option<Signer<'info>>,
+    pub system_program:    Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposePoolMetadata<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:           Account<'info, GlobalPool>,
+    #[account(init_if_needed, payer = authority, space = 8 + PoolMetadata::LEN,
+              seeds = [b"pool_metadata", pool.key().as_ref()], bump)]
+    pub pool_metadata:  Box<Account<'info, PoolMetadata>>,
+    #[account(mut)] pub authority: Signer<'info>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ApplyPoolMetadata<'info> {
+    #[account(mut, seeds = [b"pool_metadata", pool_metadata.pool.as_ref()], bump = pool_metadata.bump)]
+    pub pool_metadata: Box<Account<'info, PoolMetadata>>,
+}
+
+#[derive(Accounts)]
+pub struct CancelPoolMetadata<'info> {
+    #[account(seeds = [pool.room_seed()], bump = pool.bump, has_one = authority)]
+    pub pool:          Account<'info, GlobalPool>,
+    #[account(mut, has_one = pool, seeds = [b"pool_metadata", pool.key().as_ref()], bump = pool_metadata.bump)]
+    pub pool_metadata: Box<Account<'info, PoolMetadata>>,
+    pub authority:     Signer<'info>,
+}
+
+#[derive(Accounts)]
+pub struct SyncSchema<'info> {
+    #[account(mut)] pub payer: Signer<'info>,
+    #[account(init_if_needed, payer = payer, space = 8 + SchemaRegistry::LEN,
+              seeds = [b"schema_registry"], bump)]
+    pub schema_registry: Account<'info, SchemaRegistry>,
+    pub system_program:  Program<'info, System>,
+}
+
+/// Player-signed step of an open interactive round: Mines tile pick, HiLo guess, Stairs step.
+#[derive(Accounts)]
+pub struct MinesAction<'info> {
+    pub player: Signer<'info>,
+    #[account(mut, has_one = player, has_one = pool)] pub session: Account<'info, GameSession>,
+    /// The room the stake sits in — each step reserves its exposure here.
+    #[account(mut, seeds = [pool.room_seed()], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
+}
+
+// ══════════════════════════════════════════════════════════════════════════
+//  STATE
+// ══════════════════════════════════════════════════════════════════════════
+
+/// @notice Per-room PDA for all protocol state. Main room seeds: [b"global_pool"].
+///
+/// On-chain RTP (Return-to-Player):
+///   rtp_bps = lifetime_paid_out × 10_000 / lifetime_wagered
+///   Computable directly from chain — no server trust required.
+///   The u64 `total_*` counters saturate and are kept for layout compatibility;
+///   the u128 `lifetime_*` counters are authoritative.
+///
+/// Pause timelock:
+///   pause_expires_at = now + MAX_PAUSE_DURATION (24h) when paused.
+///   place_bet auto-expires a forgotten pause on the next call.
+#[account]
+pub struct GlobalPool {
+    // ── Core ──────────────────────────────────────────────────────────────
+    pub authority:             Pubkey,                    // 32
+    pub total_balance:         u64,                       // 8
+    pub jackpot_balance:       u64,                       // 8
+    pub total_wagered:         u64,                       // 8
+    pub house_fees_earned:     u64,                       // 8  — claimable portion only
+    pub paused:                bool,                      // 1
+    pub withdrawal_request:    Option<WithdrawalRequest>, // 1+24 = 25
+    pub bump:                  u8,                        // 1
+    // ── Analytics ─────────────────────────────────────────────────────────
+    pub total_bets:            u64,                       // 8
+    pub total_wins:            u64,                       // 8
+    pub total_jackpot_won:     u64,                       // 8
+    pub biggest_win:           u64,                       // 8
+    pub total_paid_out:        u64,                       // 8  — RTP numerator
+    // ── Authority transfer ─────────────────────────────────────────────────
+    pub pending_authority:     Option<Pubkey>,            // 1+32 = 33
+    pub authority_transfer_at: i64,                       // 8
+    // ── Auto-reinvest tracking ────────────────────────────────────────────
+    pub total_reinvested:      u64,                       // 8
+    pub operational_extracted: u64,                       // 8
+    pub reinvest_request:      Option<WithdrawalRequest>, // 1+24 = 25
+    // ── Pause timelock ────────────────────────────────────────────────────
+    pub pause_expires_at:      i64,                       // 8
+    // ── Room ──────────────────────────────────────────────────────────────
+    pub room:                  u8,                        // 1  — ROOM_* (0 = main)
+    pub migration_request:     Option<LiquidityMigration>, // 1+56 = 57
+    // ── CPI guard ─────────────────────────────────────────────────────────
+    pub cpi_whitelist:         [Pubkey; CPI_WHITELIST_LEN], // 32 × 4 = 128
+    // ── Last settlement (assert_player_received) ──────────────────────────
+    pub last_settled_player:   Pubkey,                    // 32
+    pub last_settled_payout:   u64,                       // 8  — gross + jackpot credited
+    pub last_settled_slot:     u64,                       // 8
+    // ── Forfeit policy ────────────────────────────────────────────────────
+    pub forfeit_rent_to_pool:  bool,                      // 1  — false = rent back to player
+    // ── Lifetime counters (u128 — never saturate) ────────────────────────
+    pub lifetime_wagered:      u128,                      // 16
+    pub lifetime_paid_out:     u128,                      // 16 — gross wins + jackpots
+    pub lifetime_jackpot_won:  u128,                      // 16
+    // ── Change feed ───────────────────────────────────────────────────────
+    pub state_seq:             u64,                       // 8  — bumped on every write
+    // ── Jackpot config ────────────────────────────────────────────────────
+    pub jackpot_cap_bps:       u16,                       // 2  — trigger probability cap, 0 = default
+    // ── Multiplier curve ──────────────────────────────────────────────────
+    pub payout_curve:          PayoutCurve,               // 9  — Crash / Limbo, zeroed = default
+    // ── First-bet promo ───────────────────────────────────────────────────
+    pub promo_balance:         u64,                       // 8  — reserved budget (incl. committed)
+    pub promo_committed:       u64,                       // 8  — insured stakes still open
+    pub promo_cap:             u64,                       // 8  — max insured stake, 0 = promo off
+    pub promo_min_wallet:      u64,                       // 8  — min wallet balance to enroll
+    pub promo_attestor:        Pubkey,                    // 32 — co-signer for enrollment, default = none
+    // ── Large payout co-signing ───────────────────────────────────────────
+    pub cosign_threshold:      u64,                       // 8  — 0 = off
+    pub cosigner:              Pubkey,                    // 32
+    pub held_payouts:          u64,                       // 8  — reserved until released
+    // ── RTP alarms ────────────────────────────────────────────────────────
+    pub game_counters:         [GameCounters; LEGACY_GAME_TYPES], // 24 × 16 = 384 — games 0..16, see counters()
+    pub rtp_bands:             [RtpBand; LEGACY_GAME_TYPES], // 9 × 16 = 144 — games 0..16, see rtp_band()
+    pub global_rtp_band:       RtpBand,                   // 9
+    pub rtp_anomalies:         u32,                       // 4  — bit per game, RTP_GLOBAL_FLAG
+    pub games_paused:          u16,                       // 2  — games 0..16, see paused_games()
+    // ── Cap smoothing ─────────────────────────────────────────────────────
+    pub balance_ema:           u64,                       // 8  — EMA of total_balance, per settlement
+    // ── Referral economics ────────────────────────────────────────────────
+    pub referral_mode:         u8,                        // 1  — REFERRAL_MODE_*
+    pub referral_share_bps:    u16,                       // 2  — net-loss share
+    pub referral_accrued:      u64,                       // 8  — closed-epoch commission, reserved until claimed
+    // ── Upgrade handshake ─────────────────────────────────────────────────
+    pub program_version:       u32,                       // 4  — PROGRAM_VERSION at last finalize_upgrade
+    // ── Config feed ───────────────────────────────────────────────────────
+    pub config_seq:            u64,                       // 8  — bumped on every config change
+    // ── Interactive exposure ──────────────────────────────────────────────
+    pub step_exposure:         u64,                       // 8  — Σ GameSession.step_reserved of open rounds
+    // ── Jackpot boost ─────────────────────────────────────────────────────
+    pub jackpot_boost:         JackpotBoost,              // 20 — scheduled promo window, all-zero = none
+    // ── Liquidity providers ───────────────────────────────────────────────
+    pub lp_shares:             u64,                       // 8  — all shares; 0 = no LP has deposited this generation
+    pub lp_outside_shares:     u64,                       // 8  — held by LpPositions; the rest are the house's
+    pub lp_queue_head:         u64,                       // 8  — next exit ticket process_lp_queue pays
+    pub lp_queue_tail:         u64,                       // 8  — next exit ticket lp_withdraw hands out
+    pub lp_exit_epoch:         u64,                       // 8  — epoch lp_exit_budget belongs to
+    pub lp_exit_budget:        u64,                       // 8  — lamports still payable to LPs this epoch
+    // ── Per-game budgets ──────────────────────────────────────────────────
+    pub game_budget_bps:       [u16; MAX_GAME_TYPES],     // 48  — share of cap_basis a game sizes bets from, 0 = all
+    pub game_drawdown:         [u64; MAX_GAME_TYPES],     // 192 — net paid out by a game since it was last flat
+    // ── LP performance ────────────────────────────────────────────────────
+    pub lp_hwm_price:          u64,                       // 8  — high-water mark of lp_share_price, 0 = unset
+    pub performance_fee_bps:   u16,                       // 2  — authority's cut of LP profit above the mark
+    pub performance_fees:      u64,                       // 8  — accrued fee, reserved until claimed
+    // ── Insurance reserve ─────────────────────────────────────────────────
+    pub insurance_balance:     u64,                       // 8  — reserved, only tap_insurance releases it
+    pub insurance_bps:         u16,                       // 2  — bet share routed from the house cut
+    pub insurance_threshold:   u64,                       // 8  — tap allowed while total_balance is below this
+    pub insurance_tap:         Option<WithdrawalRequest>, // 1+24 = 25
+    // ── Randomness ────────────────────────────────────────────────────────
+    pub vrf_provider:          u8,                        // 1  — VRF_* backend of place_bet_vrf
+    // ── Rocket rounds ─────────────────────────────────────────────────────
+    pub rocket_exposure:       u64,                       // 8  — Σ RocketEntry.reserved of uncranked entries
+    // ── LP book ───────────────────────────────────────────────────────────
+    pub open_stakes:           u64,                       // 8  — Σ bet_lamports of unsettled sessions, left out of lp_nav
+    pub lp_generation:         u32,                       // 4  — bumped by lp_writeoff; older LpPositions read as empty
+    // ── Game types 16..24 (appended, not widened in place) ────────────────
+    pub game_counters_hi:      [GameCounters; MAX_GAME_TYPES - LEGACY_GAME_TYPES], // 24 × 8 = 192
+    pub rtp_bands_hi:          [RtpBand; MAX_GAME_TYPES - LEGACY_GAME_TYPES], // 9 × 8 = 72
+    pub games_paused_hi:       u8,                        // 1  — auto_pause bits of games 16..24
+    // ── Firehose ──────────────────────────────────────────────────────────
+    pub firehose_enabled:      bool,                      // 1  — set by init_firehose; settle then requires it
+    // ── Open-session index ────────────────────────────────────────────────
+    pub session_index_enabled: bool,                      // 1  — set by create_open_session_page; placement then requires a page
+    // ── NAV snapshot ──────────────────────────────────────────────────────
+    pub nav_snapshot_enabled:  bool,                      // 1  — set by init_nav_snapshot; LP deposits and exits then require it
+}
+
+impl GlobalPool {
+    pub const LEN: usize =
+        32 + 8 + 8 + 8 + 8 + 1 + 25 + 1 +  // core       = 91
+        8 + 8 + 8 + 8 + 8 +                 // analytics  = 40
+        33 + 8 +                             // auth xfer  = 41
+        8 + 8 + 25 +                         // reinvest   = 41
+        8 +                                  // pause      = 8
+        1 + 57 +                             // room       = 58
+        32 * CPI_WHITELIST_LEN +             // cpi guard  = 128
+        32 + 8 + 8 +                         // last settle = 48
+        1 +                                  // forfeit    = 1
+        16 * 3 +                             // lifetime   = 48
+        8 +                                  // state seq  = 8
+        2 +                                  // jackpot cap = 2
+        9 +                                  // curve      = 9
+        8 * 4 + 32 +                         // promo      = 64
+        8 + 32 + 8 +                         // co-sign    = 48
+        24 * LEGACY_GAME_TYPES + 9 * LEGACY_GAME_TYPES + 9 + 4 + 2 + // rtp = 543
+        8 +                                  // ema        = 8
+        1 + 2 + 8 +                          // referral   = 11
+        4 +                                  // version    = 4
+        8 +                                  // config seq = 8
+        8 +                                  // step exp   = 8
+        20 +                                 // jp boost   = 20
+        8 + 8 + 8 * 4 +                      // lp         = 48
+        2 * MAX_GAME_TYPES + 8 * MAX_GAME_TYPES + // game budget = 240
+        8 + 2 + 8 +                          // lp perf    = 18
+        8 + 2 + 8 + 25 +                     // insurance  = 43
+        1 +                                  // vrf        = 1
+        8 +                                  // rocket     = 8
+        8 + 4 +                              // lp book    = 12
+        (24 + 9) * (MAX_GAME_TYPES - LEGACY_GAME_TYPES) + 1 + // games hi = 265
+        1 +                                  // firehose   = 1
+        1 +                                  // session ix = 1
+        1;                                   // nav snap   = 1
+    // total = 1875 bytes
+
+    /// Every reserved compartment: lamports the pool holds that are not free liquidity.
+    /// sync_balance, audit_pool and settle all subtract exactly this.
+    pub fn reserved(&self) -> u64 {
+        self.house_fees_earned
+            .saturating_add(self.jackpot_balance)
+            .saturating_add(self.promo_balance)
+            .saturating_add(self.held_payouts)
+            .saturating_add(self.referral_accrued)
+            .saturating_add(self.step_exposure)
+            .saturating_add(self.performance_fees)
+            .saturating_add(self.insurance_balance)
+            .saturating_add(self.rocket_exposure)
     }
-    Ok(())
-}
 
-// ── Unified resolver ──────────────────────────────────────────────────────
+    /// PDA seed of this pool's room — lets every context accept any room's pool.
+    pub fn room_seed(&self) -> &'static [u8] { room_seed(self.room) }
 
-/// Validates the reveal, extracts the BLAKE3 seed, resolves the game.
-/// Returns (won, gross_payout, GameOutcome, seed).
-fn resolve<'a>(
-    game_type:    u8,
-    session:      &Account<'a, GameSession>,
-    slot_hashes:  &AccountInfo<'a>,
-    nonce:        &[u8; 32],
-    pool_balance: u64,
-) -> Result<(bool, u64, GameOutcome, [u8; 32])> {
-    require!(session.game_type == game_type, BlitzError::WrongGameType);
-    let clock = Clock::get()?;
-    let seed  = extract_and_validate_seed(session, slot_hashes, &clock, nonce, game_type)?;
+    /// Pool size bet limits are computed from: min(total_balance, balance_ema). A deposit only
+    /// raises caps as the EMA catches up; an unset EMA (fresh or migrated pool) = total_balance.
+    pub fn cap_basis(&self) -> u64 {
+        if self.balance_ema == 0 { self.total_balance } else { self.total_balance.min(self.balance_ema) }
+    }
 
-    Ok(match game_type {
-        0 => {
-            let roll  = u64::from_le_bytes(seed[0..8].try_into().unwrap()) % 100;
-            let won   = roll < 50;
-            let gross = calc_payout(session.bet_lamports, 50, 100, pool_balance);
-            (won, if won { gross } else { 0 }, GameOutcome::Flip { roll: roll as u8 }, seed)
-        }
-        1 => {
-            let sx  = seed[0] % 16;
-            let sy  = seed[1] % 16;
-            let won = session.target_x.abs_diff(sx).max(session.target_y.abs_diff(sy)) <= session.target_radius;
-            let gross = if won {
-                let w = session.target_radius as u64 * 2 + 1;
-                calc_payout(session.bet_lamports, w * w, 256, pool_balance)
-            } else { 0 };
-             (won, gross, GameOutcome::Sector { strike_x: sx, strike_y: sy }, seed)
-        }
-        2 => {
-            let roll      = u64::from_le_bytes(seed[0..8].try_into().unwrap()) % 100;
-            let target    = session.target_x as u64;
-            let is_over   = session.target_y == 1;
-            let won       = if is_over { roll > target } else { roll < target };
-            let win_range = if is_over { 99u64.saturating_sub(target) } else { target };
-            let gross     = calc_payout(session.bet_lamports, win_range, 100, pool_balance);
-             (won, if won { gross } else { 0 }, GameOutcome::Dice { roll: roll as u8, target: target as u8, is_over }, seed)
-        }
-        3 => {
-            let floors = session.target_x as usize;
-            let path   = session.target_y;
-            let (mut death, mut traps) = (0u8, 0u8);
-            for i in 0..floors {
-                let trap = seed[i] % 2;
-                traps   |= trap << i;
-                if death == 0 && (path >> i) & 1 == trap { death = (i + 1) as u8; }
-            }
-            let won   = death == 0;
-            let gross = calc_payout(session.bet_lamports, 1, 1u64 << floors, pool_balance);
-             (won, if won { gross } else { 0 }, GameOutcome::Tower { floors: session.target_x, death_floor: death, path, traps }, seed)
-        }
-        _ => return Err(BlitzError::InvalidGameType.into()),
-    })
-}
+    /// Pool size `game`'s bet limits are computed from: its game_budget_bps share of cap_basis,
+    /// less the drawdown it has run up. A hot streak on one game shrinks that game's limits,
+    /// not the others'. No budget set = cap_basis.
+    pub fn game_basis(&self, game: u8) -> u64 {
+        let bps = self.game_budget_bps.get(game as usize).copied().unwrap_or(0);
+        if bps == 0 { return self.cap_basis(); }
+        fixed::share_bps(self.cap_basis(), bps as u64).saturating_sub(self.game_drawdown[game as usize])
+    }
 
-// ── Settlement core ───────────────────────────────────────────────────────
+    /// Books a stake on `game`: its counters, and the stake pays down the game's drawdown.
+    fn record_game_bet(&mut self, game: u8, amount: u64) {
+        self.counters_mut(game).record_bet(amount);
+        self.game_drawdown[game as usize] = self.game_drawdown[game as usize].saturating_sub(amount);
+    }
 
-/// @dev MONEY INVARIANT:
-///      bet already in pool (deposited at place_bet).
-///      Win:  gross_payout + ref_cut exit the pool physically.
-///      Loss: only ref_cut exits.
-///      jackpot_cut and house_cut are internal compartment moves only — lamports stay.
-///
-///      Steps: (1) math → (2) jackpot → (3) solvency → (4) transfers → (5) compartments → (6) analytics → (7) sync
-fn settle<'info>(
-    pool:         &mut Account<'info, GlobalPool>,
-    player_ai:    &AccountInfo<'info>,
-    referrer_ai:  &AccountInfo<'info>,
-    session:      &Account<GameSession>,
-    won:          bool,
-    gross_payout: u64,
-    seed:         [u8; 32],
-) -> Result<()> {
-    let has_ref  = is_valid_referrer(session.referrer, session.player);
-    let (house_bps, ref_bps, jackpot_bps) = get_fee_bps(pool.total_balance, has_ref);
-    let bet      = session.bet_lamports;
-
-    // (1) Math
-    let jackpot_cut   = bet.saturating_mul(jackpot_bps) / 10_000;
-    let mut house_cut = bet.saturating_mul(house_bps) / 10_000;
-    let mut ref_cut   = 0u64;
-    if has_ref {
-        let potential = bet.saturating_mul(ref_bps) / 10_000;
-        if referrer_ai.lamports() >= 50_000_000 && potential >= 1_000_000 {
-            ref_cut = potential;
-        } else {
-            house_cut = house_cut.saturating_add(potential);
-        }
+    /// Books lamports `game` paid out of total_balance (a win or a refund) against its drawdown.
+    fn record_game_outflow(&mut self, game: u8, amount: u64) {
+        self.game_drawdown[game as usize] = self.game_drawdown[game as usize].saturating_add(amount);
     }
 
-    // (2) Jackpot trigger — uses seed bytes 24..28 (independent of game bytes 0..8)
-    let mut jackpot_prize = 0u64;
-    if bet >= JACKPOT_MIN_BET && pool.jackpot_balance >= JACKPOT_MIN_POOL {
-        let roll      = u32::from_le_bytes(seed[24..28].try_into().unwrap()) as u64;
-        let threshold = (bet.saturating_mul(JACKPOT_RATE) / JACKPOT_BASE).min(u32::MAX as u64 / 200);
-        if roll < threshold {
-            jackpot_prize = pool.jackpot_balance.saturating_mul(90) / 100;
-        }
+    /// `game`'s RTP counters, wherever its slot lives (in place or in the appended *_hi block).
+    pub fn counters(&self, game: u8) -> &GameCounters {
+        let g = game as usize;
+        if g < LEGACY_GAME_TYPES { &self.game_counters[g] } else { &self.game_counters_hi[g - LEGACY_GAME_TYPES] }
     }
 
-    // (3) Solvency
-    let physical_out  = if won { gross_payout } else { 0 } + ref_cut + jackpot_prize;
-    let internal_move = jackpot_cut + house_cut;
-    let rent      = Rent::get()?.minimum_balance(pool.to_account_info().data_len());
-    let available = pool.to_account_info().lamports()
-        .saturating_sub(rent)
-        .saturating_sub(pool.house_fees_earned)
-        .saturating_sub(pool.jackpot_balance);
-    require!(available >= physical_out + internal_move, BlitzError::InsufficientLiquidity);
-
-    // (4) Physical transfers
-    if physical_out > 0 {
-        **pool.to_account_info().try_borrow_mut_lamports()? -= physical_out;
-        let player_gets = if won { gross_payout } else { 0 } + jackpot_prize;
-        if player_gets > 0 { **player_ai.try_borrow_mut_lamports()? += player_gets; }
-        if ref_cut > 0 {
-            match referrer_ai.try_borrow_mut_lamports() {
-                Ok(mut l) => **l += ref_cut,
-                Err(_)    => **player_ai.try_borrow_mut_lamports()? += ref_cut,
-            }
-        }
+    fn counters_mut(&mut self, game: u8) -> &mut GameCounters {
+        let g = game as usize;
+        if g < LEGACY_GAME_TYPES { &mut self.game_counters[g] } else { &mut self.game_counters_hi[g - LEGACY_GAME_TYPES] }
     }
 
-    // (5) Internal compartments
-    pool.jackpot_balance  = pool.jackpot_balance.saturating_sub(jackpot_prize).saturating_add(jackpot_cut);
-    let post_transfer_balance = pool.total_balance.saturating_sub(if won { gross_payout + ref_cut } else { ref_cut });
-    let (claimable, reinvested) = split_house_fees(post_transfer_balance, house_cut, bet);
-    pool.house_fees_earned = pool.house_fees_earned.saturating_add(claimable);
-    pool.total_reinvested  = pool.total_reinvested.saturating_add(reinvested);
+    /// `game`'s RTP band, as counters().
+    pub fn rtp_band(&self, game: u8) -> RtpBand {
+        let g = game as usize;
+        if g < LEGACY_GAME_TYPES { self.rtp_bands[g] } else { self.rtp_bands_hi[g - LEGACY_GAME_TYPES] }
+    }
+
+    fn set_rtp_band(&mut self, game: u8, band: RtpBand) {
+        let g = game as usize;
+        if g < LEGACY_GAME_TYPES { self.rtp_bands[g] = band; } else { self.rtp_bands_hi[g - LEGACY_GAME_TYPES] = band; }
+    }
 
-    // (6) Analytics — on-chain RTP: total_paid_out × 10_000 / total_wagered
-    if won {
-        pool.total_wins     = pool.total_wins.saturating_add(1);
-        pool.total_paid_out = pool.total_paid_out.saturating_add(gross_payout);
-        if gross_payout > pool.biggest_win { pool.biggest_win = gross_payout; }
+    /// Auto-pause bits of every game type, bit g = game g.
+    pub fn paused_games(&self) -> u32 {
+        self.games_paused as u32 | (self.games_paused_hi as u32) << LEGACY_GAME_TYPES
     }
-    if jackpot_prize > 0 {
-        pool.total_jackpot_won = pool.total_jackpot_won.saturating_add(jackpot_prize);
-        pool.total_paid_out    = pool.total_paid_out.saturating_add(jackpot_prize);
-        emit!(JackpotWon { player: session.player, amount: jackpot_prize });
+
+    fn set_paused_games(&mut self, bits: u32) {
+        self.games_paused    = bits as u16;
+        self.games_paused_hi = (bits >> LEGACY_GAME_TYPES) as u8;
     }
 
-     // (7) Sync
-    let pool_ai = pool.to_account_info();
-    sync_balance(pool, &pool_ai)
+    /// Effective multiplier curve — DEFAULT_CURVE until an admin sets one.
+    pub fn curve(&self) -> PayoutCurve {
+        if self.payout_curve == PayoutCurve::default() { DEFAULT_CURVE } else { self.payout_curve }
+    }
 }
 
-// ── Seed extraction & validation ──────────────────────────────────────────
+#[account]
+pub struct GameSession {
+    pub player:        Pubkey,    // 32
+    pub referrer:      Pubkey,    // 32
+    pub bet_lamports:  u64,       // 8
+    pub commitment:    [u8; 32],  // 32
+    pub commit_slot:   u64,       // 8
+    pub resolve_slot:  u64,       // 8
+    pub forfeit_slot:  u64,       // 8
+    pub game_type:     u8,        // 1
+    pub game_state:    u8,        // 1  — 0=pending, 1=Mines board / HiLo chain / Stairs or Tower climb open, 2=settled (kept as receipt until closed)
+    pub target_x:      u8,        // 1
+    pub target_y:      u8,        // 1
+    pub target_radius: u8,        // 1
+    pub bump:          u8,        // 1
+    pub pool:          Pubkey,    // 32 — room pool the stake sits in
+    pub index_page:    u32,       // 4  — OpenSessionPage index, NOT_INDEXED if none
+    pub payout_to:     Pubkey,    // 32 — winnings destination (player unless set at place_bet)
+    pub partner:       bool,      // 1  — placed by a registered partner (player = partner key)
+    pub linked:        Pubkey,    // 32 — sibling leg of a split bet, default if none
+    pub seed_lamports: u64,       // 8  — seed stake input for split legs (0 = bet_lamports)
+    // ── Receipt (game_state = 2) ──────────────────────────────────────────
+    pub settled_slot:  u64,       // 8
+    pub won:           bool,      // 1
+    pub paid_out:      u64,       // 8  — win + jackpot credited
+    pub jackpot_won:   u64,       // 8
+    pub insured_amount: u64,      // 8  — first-bet insurance reserved from promo_balance
+    pub held_payout:   u64,       // 8  — awaiting co-sign (part of pool.held_payouts)
+    pub held_until:    i64,       // 8  — unconditional release time
+    // ── Mines board (game_state = 1) ──────────────────────────────────────
+    pub mines_seed:      [u8; 32], // 32 — board seed fixed by start_mines (HiLo: start_hilo, Stairs: start_stairs)
+    pub mines_revealed:  u32,      // 4  — safe tile bitmap
+    pub mines_pick:      u8,       // 1  — tile awaiting resolve_tile (Tower Climb: door), MINES_NO_PICK if none
+    pub mines_pick_slot: u64,      // 8  — slot of the pending tile pick / HiLo guess / Stairs step
+    // ── Wide config (place_bet_v2) ────────────────────────────────────────
+    pub config_ext:      u64,      // 8  — Keno pick bitmask | Limbo target ×100, 0 otherwise
+    // ── Compressed receipt ────────────────────────────────────────────────
+    pub receipt_index:   u64,      // 8  — ReceiptTree leaf index + 1, 0 = not logged
+    // ── HiLo chain (game_state = 1) ───────────────────────────────────────
+    pub hilo_card:       u8,       // 1  — current card, 1–13
+    pub hilo_guess:      u8,       // 1  — guess awaiting resolve_hilo, HILO_NO_GUESS if none
+    pub hilo_rounds:     u8,       // 1  — correct guesses so far
+    pub hilo_odds_den:   u64,      // 8  — Π winning-rank counts of those guesses
+    // ── Placement flags (place_bet_v3) ────────────────────────────────────
+    pub public_reveal:   bool,     // 1  — PLACE_FLAG_PUBLIC_REVEAL
+    // ── Pinned terms ──────────────────────────────────────────────────────
+    pub payout_num_bps:  u16,         // 2  — payout_num at placement; every multiplier derives from it
+    pub payout_curve:    PayoutCurve, // 9  — pool.curve() at placement (Crash / Limbo)
+    // ── Stairs climb (game_state = 1) ─────────────────────────────────────
+    pub stairs_step:     u8,          // 1  — steps cleared so far
+    pub stairs_pending:  bool,        // 1  — a step awaits resolve_step
+    // ── Tower Climb (game_state = 1) ──────────────────────────────────────
+    pub tower_floor:          u8,     // 1  — floors cleared so far (door pick in mines_pick)
+    pub tower_multiplier_bps: u64,    // 8  — accumulated multiplier of the cleared floors
+    // ── Step exposure (game_state = 1) ────────────────────────────────────
+    pub step_reserved:   u64,      // 8  — payout reserved in pool.step_exposure for the pending step
+    // ── Double or nothing ─────────────────────────────────────────────────
+    pub double_offer:    bool,     // 1  — PLACE_FLAG_DOUBLE; once settled: held_payout is open to double_or_nothing
+    // ── VRF (place_bet_vrf) ───────────────────────────────────────────────
+    pub vrf:             Pubkey,   // 32 — VRF randomness account, default = commit-reveal
+    pub vrf_provider:    u8,       // 1  — VRF_* that decodes `vrf`, pinned at placement
+    // ── Client seed ───────────────────────────────────────────────────────
+    pub client_seeded:   bool,     // 1  — PLACE_FLAG_CLIENT_SEED: commitment covers nonce || client_seed
+    // ── VRF binding (place_bet_vrf) ───────────────────────────────────────
+    pub vrf_seed_slot:   u64,      // 8  — Switchboard seed_slot the bet was placed against
+    pub vrf_request_seed: [u8; 32], // 32 — ORAO request seed the bet was placed against
+    // ── Net-loss referral ─────────────────────────────────────────────────
+    pub net_loss_ref:    bool,     // 1  — placed under REFERRAL_MODE_NET_LOSS with the ReferralAccount: every end path must pass it
+    // ── Fee pin ───────────────────────────────────────────────────────────
+    pub fee_bps:         [u16; 3], // 6  — (house, ref, jackpot) at placement; all-zero = unset (phase table at settle)
+}
+impl GameSession { pub const LEN: usize = 477; }
 
-fn extract_and_validate_seed<'info>(
-    session:    &Account<'info, GameSession>,
-    slot_hashes: &AccountInfo<'info>,
-    clock:      &Clock,
-    nonce:      &[u8; 32],
-    game_type:  u8,
-) -> Result<[u8; 32]> {
-    require!(session.game_state == 0,             BlitzError::SessionNotPending);
-    require!(session.game_type  == game_type,     BlitzError::WrongGameType);
-    require!(clock.slot >= session.resolve_slot + SLOT_SPREAD * 2 + 1, BlitzError::TooEarlyToReveal);
-    require!(clock.slot <= session.forfeit_slot,  BlitzError::RevealWindowExpired);
-    require!(clock.slot.saturating_sub(session.resolve_slot) < 490, BlitzError::SlotTooOld);
-    require!(hash::hash(nonce).to_bytes() == session.commitment, BlitzError::InvalidNonce);
+/// Legs of a parlay session (game 22), PDA [b"parlay", session]. Closed with the session.
+#[account]
+pub struct ParlaySession {
+    pub session: Pubkey,        // 32
+    pub legs:    u8,            // 1
+    pub games:   [u8; 4],       // 4  — leg game types (0–3)
+    pub configs: [[u8; 3]; 4],  // 12 — leg game_config, as for place_bet
+    pub exts:    [u64; 4],      // 32 — leg config_ext (Sector99 extra zones)
+    pub bump:    u8,            // 1
+}
+impl ParlaySession { pub const LEN: usize = 82; }
 
-     let seed = build_seed(slot_hashes, session.resolve_slot, nonce, session.bet_lamports)?;
-    Ok(seed)
+#[account]
+pub struct SessionToken {
+    pub player:     Pubkey, // 32
+    pub delegate:   Pubkey, // 32
+    pub expires_at: i64,    // 8
+    pub bump:       u8,     // 1
 }
+impl SessionToken { pub const LEN: usize = 73; }
 
-/// BLAKE3(nonce || hash[T] || hash[T+10] || hash[T+20] || target_slot || bet_lamports)
-/// Three hashes × SLOT_SPREAD → each from a different validator leader rotation.
-fn build_seed(
-    slot_hashes_ai: &AccountInfo,
-    target_slot:    u64,
-    nonce:          &[u8; 32],
-    bet_lamports:   u64,
-) -> Result<[u8; 32]> {
-    let data = slot_hashes_ai.data.borrow();
-    let n    = u64::from_le_bytes(data[0..8].try_into().unwrap()) as usize;
-    let mut hashes = [[0u8; 32]; 3];
+/// @notice Revocation counter of a player's signed session keys. PDA [b"session_nonce", player].
+///         Signed into session_auth_message; close_session bumps it, voiding earlier signatures.
+#[account]
+pub struct SessionNonce {
+    pub player: Pubkey, // 32
+    pub nonce:  u64,    // 8
+    pub bump:   u8,     // 1
+}
+impl SessionNonce { pub const LEN: usize = 41; }
 
-    for offset in 0u64..3 {
-        let slot = target_slot + offset * SLOT_SPREAD;
-        let idx  = (0..n.min(512)).find(|&i| {
-            u64::from_le_bytes(data[8 + i*40..16 + i*40].try_into().unwrap()) == slot
-        });
-        match idx {
-            Some(i) => hashes[offset as usize] = data[16 + i*40..48 + i*40].try_into().unwrap(),
-            None    => return Err(BlitzError::SlotHashNotFound.into()),
-        }
-    }
+/// @notice Head-to-head Tower race. Holds both stakes until resolution.
+#[account]
+pub struct TowerRace {
+    pub creator:             Pubkey,   // 32
+    pub opponent:            Pubkey,   // 32 — default until joined
+    pub stake:               u64,      // 8  — per racer; pot = 2 × stake
+    pub floors:              u8,       // 1
+    pub creator_path:        u8,       // 1
+    pub opponent_path:       u8,       // 1
+    pub creator_commitment:  [u8; 32], // 32
+    pub opponent_commitment: [u8; 32], // 32
+    pub creator_nonce:       [u8; 32], // 32 — zero until revealed
+    pub opponent_nonce:      [u8; 32], // 32 — zero until revealed
+    pub revealed:            u8,       // 1  — bit 0 = creator, bit 1 = opponent
+    pub created_slot:        u64,      // 8
+    pub resolve_slot:        u64,      // 8
+    pub forfeit_slot:        u64,      // 8
+    pub state:               u8,       // 1  — 0=open, 1=joined, 2=settled
+    pub bump:                u8,       // 1
+    pub bond:                u64,      // 8  — creator's anti-griefing bond, escrowed with the rent
+}
+impl TowerRace { pub const LEN: usize = 238; }
 
-    let mut h = blake3::Hasher::new();
-    h.update(nonce);
-    h.update(&hashes[0]);
-    h.update(&hashes[1]);
-    h.update(&hashes[2]);
-    h.update(&target_slot.to_le_bytes());
-    h.update(&bet_lamports.to_le_bytes());
-    Ok(*h.finalize().as_bytes())
+/// @notice Head-to-head Rock-Paper-Scissors match. Holds both stakes until resolution.
+#[account]
+pub struct RpsMatch {
+    pub creator:             Pubkey,   // 32
+    pub opponent:            Pubkey,   // 32 — default until joined
+    pub stake:               u64,      // 8  — per player; pot = 2 × stake
+    pub creator_commitment:  [u8; 32], // 32 — SHA-256(move || salt)
+    pub opponent_commitment: [u8; 32], // 32
+    pub creator_move:        u8,       // 1  — RPS_UNREVEALED until revealed
+    pub opponent_move:       u8,       // 1
+    pub revealed:            u8,       // 1  — bit 0 = creator, bit 1 = opponent
+    pub created_slot:        u64,      // 8
+    pub forfeit_slot:        u64,      // 8  — end of the reveal window
+    pub state:               u8,       // 1  — 0=open, 1=joined, 2=settled
+    pub bump:                u8,       // 1
+    pub bond:                u64,      // 8  — creator's anti-griefing bond, escrowed with the rent
 }
+impl RpsMatch { pub const LEN: usize = 165; }
 
-// ── Event emitter ─────────────────────────────────────────────────────────
+/// @notice Watch-only grant: lets `auditor` call the read instructions (audit_pool,
+///         export_game_counters) at most once per `min_interval_slots`.
+#[account]
+pub struct Auditor {
+    pub pool:               Pubkey, // 32
+    pub auditor:            Pubkey, // 32
+    pub min_interval_slots: u64,    // 8
+    pub last_call_slot:     u64,    // 8
+    pub calls:              u64,    // 8
+    pub bump:               u8,     // 1
+}
+impl Auditor { pub const LEN: usize = 89; }
 
-fn emit_outcome(player: Pubkey, won: bool, payout: u64, outcome: &GameOutcome) {
-    match outcome {
-        GameOutcome::Flip   { roll }                             => emit!(FlipSettled   { player, won, roll:     *roll,     payout }),
-        GameOutcome::Sector { strike_x, strike_y }              => emit!(SectorSettled { player, won, strike_x: *strike_x, strike_y: *strike_y, payout }),
-        GameOutcome::Dice   { roll, target, is_over }           => emit!(DiceSettled   { player, won, roll:     *roll,     target: *target, payout, is_over: *is_over }),
-        GameOutcome::Tower  { floors, death_floor, path, traps} => emit!(TowerSettled  { player, won, floors:   *floors,   death_floor: *death_floor, payout, path: *path, traps: *traps }),
-    }
+/// @notice One lottery round. Escrows ticket sales and rollover until claimed or rolled forward.
+#[account]
+pub struct LotteryRound {
+    pub pool:         Pubkey,                         // 32
+    pub round:        u64,                            // 8
+    pub ticket_price: u64,                            // 8
+    pub draw_slot:    u64,                            // 8  — sales close here; its slot hash seeds the draw
+    pub tickets:      u32,                            // 4  — tickets sold; next ticket index
+    pub entropy:      [u8; 32],                       // 32 — running SHA-256 over every buyer's entropy
+    pub pot:          u64,                            // 8  — ticket sales + rollover from round − 1
+    pub state:        u8,                             // 1  — LOTTERY_OPEN / DRAWN / CLOSED
+    pub drawn_slot:   u64,                            // 8  — start of the claim window
+    pub winners:      [u32; LOTTERY_MAX_WINNERS],     // 32 — ticket index per winner slot, u32::MAX = unfilled
+    pub prizes:       [u64; LOTTERY_MAX_WINNERS],     // 64
+    pub claimed:      u8,                             // 1  — bit i = winner slot i paid
+    pub bump:         u8,                             // 1
+    pub cutoff_slot:  u64,                            // 8  — sales close here; the original draw_slot, never moved
 }
+impl LotteryRound { pub const LEN: usize = 215; }
 
-// ══════════════════════════════════════════════════════════════════════════
-//  PUBLIC UTILITIES (used by client-side bet sizing)
-// ══════════════════════════════════════════════════════════════════════════
+/// @notice A numbered ticket. PDA [b"lottery_ticket", round, index]; closed on claim.
+#[account]
+pub struct LotteryTicket {
+    pub round: Pubkey, // 32
+    pub owner: Pubkey, // 32
+    pub index: u32,    // 4
+    pub bump:  u8,     // 1
+}
+impl LotteryTicket { pub const LEN: usize = 69; }
 
-/// Coordinated max bet — never advertises a bet that `payout_cap` would reject.
-pub fn get_max_bet(pool: u64, game: u8, cfg: &[u8; 3]) -> u64 {
-    let cap     = get_max_payout_cap(pool);
-    let pct_lim = if pool < 5_000_000_000 {
-        pool / 100 // <5 SOL: 1% survival mode
-    } else {
-        match game {
-            0 | 2 => pool.saturating_mul(3) / 100, // Flip / Dice:   3%
-            _     => pool.saturating_mul(2) / 100, // Sector / Tower: 2%
-        }
-    };
-    let worst_1sol = get_worst_payout(1_000_000_000, game, cfg);
-    let cap_lim    = if worst_1sol > 0 { cap.saturating_mul(1_000_000_000) / worst_1sol } else { pct_lim };
-    pct_lim.min(cap_lim)
+/// @notice Prepaid bearer voucher. PDA [b"gift_card", SHA-256(code)]; escrows `amount` above rent
+///         and is closed on redemption or reclaim.
+#[account]
+pub struct GiftCard {
+    pub funder:       Pubkey,   // 32
+    pub code_key:     Pubkey,   // 32 — signs redemption; keypair seeded by SHA-256(code)
+    pub recipient:    Pubkey,   // 32 — Pubkey::default() = any wallet
+    pub amount:       u64,      // 8
+    pub expires_slot: u64,      // 8
+    pub bump:         u8,       // 1
 }
+impl GiftCard { pub const LEN: usize = 113; }
 
-/// Dynamic payout cap — tiered by pool health. No hard ceiling.
-pub fn get_max_payout_cap(pool: u64) -> u64 {
-    match pool {
-        p if p < 5_000_000_000   => p.saturating_mul(3)  / 100,
-        p if p < 20_000_000_000  => p.saturating_mul(5)  / 100,
-        p if p < 100_000_000_000 => p.saturating_mul(8)  / 100,
-        p if p < 500_000_000_000 => p.saturating_mul(10) / 100,
-        p                        => p.saturating_mul(12) / 100,
-    }
+/// @notice Shared Crash round. Stakes sit in the pool; the round only tracks entries and exposure.
+#[account]
+pub struct RocketRound {
+    pub pool:        Pubkey,      // 32
+    pub round:       u64,         // 8
+    pub cutoff_slot: u64,         // 8  — joins close here; its slot hash seeds the crash point
+    pub curve:       PayoutCurve, // 9  — pool.curve() at open
+    pub entries:     u32,         // 4
+    pub settled:     u32,         // 4  — entries closed by crank_rocket or refund_rocket_entry
+    pub stakes:      u64,         // 8
+    pub liability:   u64,         // 8  — Σ worst-case payout of the entries
+    pub entropy:     [u8; 32],    // 32 — running SHA-256 over every joiner's entropy; the round seed once launched
+    pub crash_point: u32,         // 4  — ×100, set at launch
+    pub state:       u8,          // 1  — ROCKET_OPEN / LAUNCHED / SETTLED
+    pub bump:        u8,          // 1
+    pub refund_slot: u64,         // 8  — cutoff_slot + ROCKET_REFUND_SLOTS at open; unlaunched past it = refund
 }
+impl RocketRound { pub const LEN: usize = 127; }
 
-/// Smooth linear resolve delay: +1 slot per 0.02 SOL, clamped to [10, 55].
-pub fn get_resolve_slot(slot: u64, bet: u64) -> u64 {
-    slot + (10u64).saturating_add(bet / 20_000_000).min(55)
+/// @notice One player's stake in a rocket round. PDA [b"rocket_entry", round, player]; closed when cranked or refunded.
+#[account]
+pub struct RocketEntry {
+    pub round:       Pubkey,   // 32
+    pub player:      Pubkey,   // 32
+    pub stake:       u64,      // 8
+    pub target_x100: u16,      // 2
+    pub bump:        u8,       // 1
+    pub reserved:    u64,      // 8  — worst-case payout held in pool.rocket_exposure
+    pub fee_bps:     [u16; 3], // 6  — (house, ref, jackpot) at join, as GameSession.fee_bps
 }
+impl RocketEntry { pub const LEN: usize = 89; }
 
-/// Worst-case payout using Phase-2 numerator (most conservative).
-pub fn get_worst_payout(bet: u64, game: u8, cfg: &[u8; 3]) -> u64 {
-    match game {
-        0 => calc_payout(bet, 50, 100, PHASE2_THRESHOLD),
-        1 => { let w = cfg[2].min(3) as u64 * 2 + 1; calc_payout(bet, w * w, 256, PHASE2_THRESHOLD) }
-        2 => {
-            let t  = if cfg[1] == 0 { cfg[0].max(2) } else { cfg[0].max(4) } as u64;
-            let wc = if cfg[1] == 1 { 99u64.saturating_sub(t) } else { t };
-            calc_payout(bet, wc, 100, PHASE2_THRESHOLD)
-        }
-        3 => calc_payout(bet, 1, 1u64 << cfg[0].max(1).min(6), PHASE2_THRESHOLD),
-        _ => 0,
-    }
+/// @notice Display metadata of a pool. Text fields are UTF-8, zero-padded.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PoolBranding {
+    pub name:             [u8; 32],  // 32
+    pub icon_uri:         [u8; 128], // 128
+    pub operator_url:     [u8; 96],  // 96
+    pub fee_summary_hash: [u8; 32],  // 32 — SHA-256 of the operator's published fee summary
 }
 
-// ══════════════════════════════════════════════════════════════════════════
-//  ACCOUNTS
-// ══════════════════════════════════════════════════════════════════════════
+impl PoolBranding {
+    pub const LEN: usize = 288;
 
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(init, payer = authority, space = 8 + GlobalPool::LEN, seeds = [b"global_pool"], bump)]
-    pub pool:           Account<'info, GlobalPool>,
-    #[account(mut)] pub authority:      Signer<'info>,
-    pub system_program: Program<'info, System>,
+    /// Non-empty name; every text field valid UTF-8 up to its zero padding.
+    fn is_valid(&self) -> bool {
+        let text = |b: &[u8]| core::str::from_utf8(&b[..b.iter().position(|c| *c == 0).unwrap_or(b.len())]).is_ok();
+        self.name[0] != 0 && text(&self.name) && text(&self.icon_uri) && text(&self.operator_url)
+    }
 }
 
-#[derive(Accounts)]
-pub struct FundPool<'info> {
-    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)]
-    pub pool:           Account<'info, GlobalPool>,
-    #[account(mut)] pub funder:         Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// @notice Per-pool PDA [b"pool_metadata", pool]: live branding and a timelocked pending update.
+#[account]
+pub struct PoolMetadata {
+    pub pool:        Pubkey,       // 32
+    pub live:        PoolBranding, // 288 — all zero until the first update applies
+    pub pending:     PoolBranding, // 288
+    pub has_pending: bool,         // 1
+    pub unlocks_at:  i64,          // 8  — apply_pool_metadata allowed from here
+    pub updated_at:  i64,          // 8  — last time `live` changed
+    pub bump:        u8,           // 1
 }
+impl PoolMetadata { pub const LEN: usize = 32 + PoolBranding::LEN * 2 + 1 + 8 + 8 + 1; }
 
-#[derive(Accounts)]
-#[instruction(game_type: u8, commitment: [u8; 32], bet_lamports: u64)]
-pub struct PlaceBet<'info> {
-    #[account(mut)] pub player:   Signer<'info>,
-    /// CHECK: Optional referrer — pass player's own key if none.
-    pub referrer: AccountInfo<'info>,
-    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)]
-    pub pool:     Account<'info, GlobalPool>,
-    #[account(init, payer = player, space = 8 + GameSession::LEN,
-              seeds = [b"session", player.key().as_ref(), commitment.as_ref()], bump)]
-    pub session:        Account<'info, GameSession>,
-    pub system_program: Program<'info, System>,
+/// @notice HyperLogLog sketch of distinct bettors — no per-player account required.
+/// Estimate off-chain or on-chain with `hll_estimate`.
+#[account]
+pub struct PlayerSketch {
+    pub epoch:    u64,                 // 8   — epoch `current` covers
+    pub lifetime: [u8; HLL_REGISTERS], // 256
+    pub current:  [u8; HLL_REGISTERS], // 256 — reset at each epoch rollover
+    pub bump:     u8,                  // 1
 }
 
-#[derive(Accounts)]
-pub struct RevealGame<'info> {
-    #[account(mut)] pub player:   Signer<'info>,
-    /// CHECK: Must match session.referrer. Writable to receive commission.
-    #[account(mut, address = session.referrer)] pub referrer: AccountInfo<'info>,
-    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
-    #[account(mut, has_one = player, close = player)] pub session: Account<'info, GameSession>,
-    /// CHECK: Address-validated sysvar — not injectable.
-    #[account(address = slot_hashes::ID)] pub slot_hashes: UncheckedAccount<'info>,
+impl PlayerSketch {
+    pub const LEN: usize = 8 + HLL_REGISTERS * 2 + 1;
+
+    /// Folds `player` into both sketches. Rolls `current` over on a new epoch.
+    pub fn observe(&mut self, player: &Pubkey, epoch: u64) {
+        if epoch != self.epoch {
+            emit!(UniquePlayersEpochClosed { epoch: self.epoch, estimate: hll_estimate(&self.current) });
+            self.current = [0u8; HLL_REGISTERS];
+            self.epoch   = epoch;
+        }
+        let h    = hash::hash(player.as_ref()).to_bytes();
+        let idx  = h[0] as usize;
+        let rank = (u64::from_le_bytes(h[1..9].try_into().unwrap()).leading_zeros() + 1) as u8;
+        if rank > self.lifetime[idx] { self.lifetime[idx] = rank; }
+        if rank > self.current[idx]  { self.current[idx]  = rank; }
+    }
 }
 
-#[derive(Accounts)]
-pub struct RevealDelegated<'info> {
-    #[account(mut)] pub delegate: Signer<'info>,
-    /// CHECK: Validated via has_one constraints on session + session_token.
-    #[account(mut)] pub player:   AccountInfo<'info>,
-    /// CHECK: Must match session.referrer. Writable to receive commission.
-    #[account(mut, address = session.referrer)] pub referrer: AccountInfo<'info>,
-    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
-    #[account(mut, has_one = player, close = player)] pub session: Account<'info, GameSession>,
-    #[account(seeds = [b"session_key", player.key().as_ref()], bump = session_token.bump,
-              has_one = delegate, has_one = player)]
-    pub session_token: Account<'info, SessionToken>,
-    /// CHECK: Address-validated sysvar — not injectable.
-    #[account(address = slot_hashes::ID)] pub slot_hashes: UncheckedAccount<'info>,
+/// @notice One page of a pool's pending-session index. Entries are unordered.
+#[account]
+pub struct OpenSessionPage {
+    pub pool:     Pubkey,                 // 32
+    pub page:     u32,                    // 4
+    pub count:    u16,                    // 2
+    pub sessions: [Pubkey; OPEN_PAGE_CAP], // 32 × 32 = 1024
+    pub bump:     u8,                     // 1
 }
+impl OpenSessionPage { pub const LEN: usize = 32 + 4 + 2 + 32 * OPEN_PAGE_CAP + 1; }
 
-#[derive(Accounts)]
-pub struct CreateSession<'info> {
-    #[account(mut)] pub player:   Signer<'info>,
-    /// CHECK: Ephemeral key generated client-side.
-    #[account(mut)] pub delegate: AccountInfo<'info>,
-    #[account(init, payer = player, space = 8 + SessionToken::LEN,
-              seeds = [b"session_key", player.key().as_ref()], bump)]
-    pub session_token:  Account<'info, SessionToken>,
-    pub system_program: Program<'info, System>,
+/// @notice Custodial partner. Aggregates settlement reporting for everything it placed.
+#[account]
+pub struct Partner {
+    pub authority:      Pubkey, // 32 — signs bets/reveals as the player
+    pub fee_bps:        u16,    // 2  — share of the house cut, ≤ PARTNER_MAX_FEE_BPS
+    pub active:         bool,   // 1  — false blocks new bets only
+    pub total_bets:     u64,    // 8
+    pub settled_bets:   u64,    // 8
+    pub total_wagered:  u128,   // 16
+    pub total_paid_out: u128,   // 16 — wins + jackpots to the partner's sessions
+    pub fees_earned:    u64,    // 8
+    pub bump:           u8,     // 1
 }
+impl Partner { pub const LEN: usize = 92; }
 
-#[derive(Accounts)]
-pub struct CloseSession<'info> {
-    #[account(mut)] pub player:   Signer<'info>,
-    /// CHECK: Validated via session_token address constraint.
-    #[account(mut, address = session_token.delegate)] pub delegate: AccountInfo<'info>,
-    #[account(mut, close = player, seeds = [b"session_key", player.key().as_ref()],
-              bump = session_token.bump, has_one = player)]
-    pub session_token: Account<'info, SessionToken>,
+/// @notice Per-wallet stats. Created by enroll_player_stats; drives the first-bet promo.
+#[account]
+pub struct PlayerStats {
+    pub player:            Pubkey,  // 32
+    pub total_bets:        u64,     // 8
+    pub total_wagered:     u128,    // 16
+    pub first_bet_used:    bool,    // 1  — insured first bet consumed (or ineligible)
+    pub attested:          bool,    // 1  — enrollment co-signed by the promo attestor
+    pub bump:              u8,      // 1
+    // ── Last bet (replay_last_bet) ────────────────────────────────────────
+    pub last_game_type:    u8,      // 1
+    pub last_config:       [u8; 3], // 3
+    pub last_config_ext:   u64,     // 8
+    pub last_bet_lamports: u64,     // 8  — 0 = nothing to replay
+    pub last_flags:        u8,      // 1
+}
+impl PlayerStats { pub const LEN: usize = 80; }
+
+/// @notice Per-voucher PDA [b"promo_voucher", pool, player, campaign_id]: marks it spent.
+#[account]
+pub struct PromoVoucherClaim {
+    pub player:      Pubkey, // 32
+    pub campaign_id: u64,    // 8
+    pub expires_at:  i64,    // 8  — signed voucher expiry; closable after it
+    pub bump:        u8,     // 1
 }
+impl PromoVoucherClaim { pub const LEN: usize = 49; }
 
-#[derive(Accounts)]
-pub struct ClaimForfeit<'info> {
-    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
-    /// Rent returned to pool, not caller — eliminates griefing incentive.
-    #[account(mut, close = pool)] pub session: Account<'info, GameSession>,
-    #[account(mut)] pub caller: Signer<'info>,
+/// @notice Opt-in notification settings for an off-chain relayer.
+#[account]
+pub struct NotificationPrefs {
+    pub player:  Pubkey,   // 32
+    pub id_hash: [u8; 32], // 32 — SHA-256 of webhook URL / Telegram chat id
+    pub flags:   u8,       // 1  — NOTIFY_* bitmask
+    pub bump:    u8,       // 1
 }
+impl NotificationPrefs { pub const LEN: usize = 66; }
 
-#[derive(Accounts)]
-pub struct VoluntaryForfeit<'info> {
-    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
-    #[account(mut, close = player, has_one = player)] pub session: Account<'info, GameSession>,
-    #[account(mut)] pub player: Signer<'info>,
+/// @notice Per-player chip stack, PDA [b"vault", player]. Its lamports above rent are the
+///         balance; the totals are lifetime counters for the player's own bookkeeping.
+#[account]
+pub struct PlayerVault {
+    pub player:          Pubkey, // 32
+    pub total_deposited: u64,    // 8
+    pub total_withdrawn: u64,    // 8
+    pub total_wagered:   u64,    // 8  — staked via place_bet_from_vault
+    pub bump:            u8,     // 1
+}
+impl PlayerVault { pub const LEN: usize = 57; }
+
+/// @notice sponsor_deposit limits on a grant's stakes. 0 = no limit for max_bet and games_mask.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct SponsorConstraints {
+    pub expires_slot: u64, // 8  — last slot the grant can stake; the sponsor may reclaim after it
+    pub max_bet:      u64, // 8
+    pub games_mask:   u32, // 4  — bit g = game type g allowed
 }
 
-#[derive(Accounts)]
-pub struct EmergencyRefund<'info> {
-    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
-    #[account(mut, close = player, has_one = player)] pub session: Account<'info, GameSession>,
-    #[account(mut)] pub player: Signer<'info>,
+/// @notice Sponsor-funded, play-only balance for one beneficiary, PDA
+///         [b"sponsor_grant", sponsor, beneficiary]. Escrows `amount` above rent.
+#[account]
+pub struct SponsorGrant {
+    pub sponsor:       Pubkey,             // 32
+    pub beneficiary:   Pubkey,             // 32
+    pub amount:        u64,                // 8  — unspent escrow
+    pub total_granted: u64,                // 8
+    pub total_staked:  u64,                // 8
+    pub constraints:   SponsorConstraints, // 20
+    pub bump:          u8,                 // 1
 }
+impl SponsorGrant { pub const LEN: usize = 109; }
 
-#[derive(Accounts)]
-pub struct BotRefund<'info> {
-    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
-    #[account(mut, close = player)] pub session: Account<'info, GameSession>,
-    /// CHECK: Lamports returned to original player.
-    #[account(mut, address = session.player)] pub player: AccountInfo<'info>,
-    #[account(mut)] pub caller: Signer<'info>,
+/// @notice One liquidity provider's stake in one pool, PDA [b"lp", pool, owner].
+///         Its value is shares × lp_nav / GlobalPool.lp_shares.
+#[account]
+pub struct LpPosition {
+    pub pool:            Pubkey, // 32
+    pub owner:           Pubkey, // 32
+    pub shares:          u64,    // 8
+    pub locked_until:    i64,    // 8  — lp_withdraw refused before this
+    pub total_deposited: u64,    // 8
+    pub total_withdrawn: u64,    // 8
+    pub bump:            u8,     // 1
+    pub queued_shares:   u64,    // 8  — part of `shares` waiting in the exit queue
+    pub queue_ticket:    u64,    // 8  — position in the pool's exit queue while queued
+    pub queued_epoch:    u64,    // 8  — epoch the exit was queued in; paid from the next one
+    pub generation:      u32,    // 4  — GlobalPool.lp_generation its shares belong to
+}
+impl LpPosition { pub const LEN: usize = 125; }
+
+/// @notice One close_lp_epoch snapshot. `pnl` is the epoch's change in share price across all
+///         shares, net of the performance fee and of LP and house capital flows.
+#[zero_copy]
+#[derive(Default)]
+pub struct LpEpochSnapshot {
+    pub epoch:         u64, // 8
+    pub total_balance: u64, // 8
+    pub lp_shares:     u64, // 8
+    pub share_price:   u64, // 8  — lp_share_price after the fee
+    pub hwm_price:     u64, // 8
+    pub pnl:           i64, // 8  — lamports, vs. the previous snapshot's price
+    pub fee:           u64, // 8  — performance fee accrued at this close
 }
 
-#[derive(Accounts)]
-pub struct AdminOnly<'info> {
-    #[account(mut, seeds = [b"global_pool"], bump = pool.bump, has_one = authority)]
-    pub pool:      Account<'info, GlobalPool>,
-    #[account(mut)] pub authority: Signer<'info>,
+/// @notice Per-room zero-copy PDA [b"lp_ledger", pool]: a ring of the last LP_LEDGER_EPOCHS
+///         epoch snapshots, oldest overwritten first. `count` is the lifetime number of closes.
+#[account(zero_copy)]
+#[repr(C)]
+pub struct LpLedger {
+    pub pool:      Pubkey,                              // 32
+    pub count:     u64,                                 // 8
+    pub snapshots: [LpEpochSnapshot; LP_LEDGER_EPOCHS], // 56 × 64 = 3584
+    pub bump:      u8,                                  // 1
+    pub _pad:      [u8; 7],                             // 7  — Pod alignment
 }
+impl LpLedger {
+    pub const LEN: usize = 3632;
 
-#[derive(Accounts)]
-pub struct ClaimHouseFeesCtx<'info> {
-    #[account(mut, seeds = [b"global_pool"], bump = pool.bump, has_one = authority)]
-    pub pool:      Account<'info, GlobalPool>,
-    #[account(mut)] pub authority: Signer<'info>,
+    /// The most recent snapshot. Only meaningful once `count > 0`.
+    pub fn last(&self) -> &LpEpochSnapshot {
+        &self.snapshots[((self.count + LP_LEDGER_EPOCHS as u64 - 1) % LP_LEDGER_EPOCHS as u64) as usize]
+    }
+
+    fn push(&mut self, snap: LpEpochSnapshot) {
+        self.snapshots[(self.count % LP_LEDGER_EPOCHS as u64) as usize] = snap;
+        self.count += 1;
+    }
 }
 
-#[derive(Accounts)]
-pub struct ExecuteAuthorityTransfer<'info> {
-    #[account(mut, seeds = [b"global_pool"], bump = pool.bump)] pub pool: Account<'info, GlobalPool>,
-    /// New authority must sign — prevents transfer to an inaccessible wallet.
-    #[account(mut)] pub new_authority: Signer<'info>,
+/// @notice Per-room PDA [b"nav_snapshot", pool]: LP share prices taken by take_nav_snapshot,
+///         which deposits and exits are priced against next to the live ones.
+#[account]
+pub struct NavSnapshot {
+    pub pool:          Pubkey, // 32
+    pub slot:          u64,    // 8
+    pub generation:    u32,    // 4  — GlobalPool.lp_generation it was taken in; ignored after a write-off
+    pub deposit_price: u64,    // 8  — lp_share_price(total_balance): open stakes counted (0 = no shares)
+    pub exit_price:    u64,    // 8  — lp_share_price(lp_nav): open stakes left out (0 = no shares)
+    pub bump:          u8,     // 1
 }
+impl NavSnapshot { pub const LEN: usize = 61; }
 
-#[derive(Accounts)]
-pub struct MigratePool<'info> {
-    #[account(mut,
-              seeds = [b"global_pool"],
-              bump = pool.bump,
-              has_one = authority,
-              realloc = 8 + GlobalPool::LEN,
-              realloc::payer = authority,
-              realloc::zero = true)]
-    pub pool:           Account<'info, GlobalPool>,
-    #[account(mut)] pub authority: Signer<'info>,
-    pub system_program: Program<'info, System>,
+/// @notice Per-referrer, per-pool PDA [b"referral", pool, referrer]. Tracks the referees'
+///         current-epoch volume for REFERRAL_MODE_NET_LOSS and the commission of closed epochs.
+#[account]
+pub struct ReferralAccount {
+    pub pool:           Pubkey, // 32
+    pub referrer:       Pubkey, // 32
+    pub epoch:          u64,    // 8  — Solana epoch being accumulated
+    pub epoch_wagered:  u64,    // 8
+    pub epoch_paid_out: u64,    // 8  — win payouts, jackpots excluded
+    pub claimable:      u64,    // 8  — part of pool.referral_accrued
+    pub total_claimed:  u64,    // 8
+    pub bump:           u8,     // 1
 }
+impl ReferralAccount { pub const LEN: usize = 105; }
 
-// ══════════════════════════════════════════════════════════════════════════
-//  STATE
-// ══════════════════════════════════════════════════════════════════════════
+/// @notice Per-room PDA [b"receipt_tree", pool]. Append-only merkle log of settlements;
+///         leaves themselves live only in ReceiptLogged events.
+#[account]
+pub struct ReceiptTree {
+    pub pool:       Pubkey,                           // 32
+    pub root:       [u8; 32],                         // 32
+    pub leaf_count: u64,                              // 8
+    pub frontier:   [[u8; 32]; RECEIPT_TREE_DEPTH],   // 640
+    pub bump:       u8,                               // 1
+}
+impl ReceiptTree { pub const LEN: usize = 713; }
 
-/// @notice Single global PDA for all protocol state.
-///
-/// On-chain RTP (Return-to-Player):
-///   rtp_bps = total_paid_out × 10_000 / total_wagered
-///   Computable directly from chain — no server trust required.
-///
-/// Pause timelock:
-///   pause_expires_at = now + MAX_PAUSE_DURATION (24h) when paused.
-///   place_bet auto-expires a forgotten pause on the next call.
+/// @notice PDA [b"game_receipt", session]. One settled single-shot bet, kept for good:
+///         everything verify_game needs to replay it from the seed.
 #[account]
-pub struct GlobalPool {
-    // ── Core ──────────────────────────────────────────────────────────────
-    pub authority:             Pubkey,                    // 32
-    pub total_balance:         u64,                       // 8
-    pub jackpot_balance:       u64,                       // 8
-    pub total_wagered:         u64,                       // 8
-    pub house_fees_earned:     u64,                       // 8  — claimable portion only
-    pub paused:                bool,                      // 1
-    pub withdrawal_request:    Option<WithdrawalRequest>, // 1+24 = 25
-    pub bump:                  u8,                        // 1
-    // ── Analytics ─────────────────────────────────────────────────────────
-    pub total_bets:            u64,                       // 8
-    pub total_wins:            u64,                       // 8
-    pub total_jackpot_won:     u64,                       // 8
-    pub biggest_win:           u64,                       // 8
-    pub total_paid_out:        u64,                       // 8  — RTP numerator
-    // ── Authority transfer ─────────────────────────────────────────────────
-    pub pending_authority:     Option<Pubkey>,            // 1+32 = 33
-    pub authority_transfer_at: i64,                       // 8
-    // ── Auto-reinvest tracking ────────────────────────────────────────────
-    pub total_reinvested:      u64,                       // 8
-    pub operational_extracted: u64,                       // 8
-    pub reinvest_request:      Option<WithdrawalRequest>, // 1+24 = 25
-    // ── Pause timelock ────────────────────────────────────────────────────
-    pub pause_expires_at:      i64,                       // 8
+pub struct GameReceipt {
+    pub session:        Pubkey,      // 32
+    pub player:         Pubkey,      // 32
+    pub pool:           Pubkey,      // 32
+    pub game_type:      u8,          // 1
+    pub config:         [u8; 3],     // 3  — target_x, target_y, target_radius
+    pub config_ext:     u64,         // 8
+    pub bet_lamports:   u64,         // 8
+    pub payout_num_bps: u16,         // 2
+    pub payout_curve:   PayoutCurve, // 9
+    pub seed:           [u8; 32],    // 32
+    pub vrf:            Pubkey,      // 32 — randomness account, default = commit-reveal
+    pub settled_slot:   u64,         // 8  — 0 = not written yet
+    pub won:            bool,        // 1
+    pub gross_payout:   u64,         // 8  — resolve_seed payout before fees and jackpot
+    pub outcome:        [u8; 32],    // 32 — Borsh GameOutcome, zero-padded
+    pub bump:           u8,          // 1
 }
+impl GameReceipt { pub const LEN: usize = 241; }
 
-impl GlobalPool {
-    pub const LEN: usize =
-        32 + 8 + 8 + 8 + 8 + 1 + 25 + 1 +  // core       = 91
-        8 + 8 + 8 + 8 + 8 +                 // analytics  = 40
-        33 + 8 +                             // auth xfer  = 41
-        8 + 8 + 25 +                         // reinvest   = 41
-        8;                                   // pause      = 8
-    // total = 221 bytes
+/// @notice Per-room PDA [b"firehose", pool]. Latest settlement only — a subscriber that
+///         sees `state_seq` jump knows it missed updates and backfills from history.
+#[account]
+pub struct Firehose {
+    pub pool:          Pubkey, // 32
+    pub state_seq:     u64,    // 8  — pool.state_seq after the settlement
+    pub slot:          u64,    // 8
+    pub session:       Pubkey, // 32
+    pub player:        Pubkey, // 32
+    pub game_type:     u8,     // 1
+    pub won:           bool,   // 1
+    pub bet:           u64,    // 8
+    pub payout:        u64,    // 8  — win + jackpot credited
+    pub jackpot:       u64,    // 8
+    pub total_balance: u64,    // 8  — pool liquid balance after the settlement
+    pub settlements:   u64,    // 8  — writes so far
+    pub bump:          u8,     // 1
+}
+impl Firehose { pub const LEN: usize = 155; }
+
+/// @notice Per-room zero-copy PDA [b"sector_heatmap", pool]. `counts[y][x]` = strikes on
+///         cell (x, y); under a fair seed every cell converges on strikes / 256.
+#[account(zero_copy)]
+#[repr(C)]
+pub struct SectorHeatmap {
+    pub pool:    Pubkey,          // 32
+    pub strikes: u64,             // 8  — Σ counts
+    pub counts:  [[u32; 16]; 16], // 1024
+    pub bump:    u8,              // 1
+    pub _pad:    [u8; 7],         // 7  — Pod alignment
 }
+impl SectorHeatmap { pub const LEN: usize = 1072; }
 
+/// @notice Singleton PDA [b"schema_registry"]. Indexers compare `layout_hash` against the
+///         hash they were built for and stop decoding on a mismatch instead of mis-parsing.
 #[account]
-pub struct GameSession {
-    pub player:        Pubkey,    // 32
-    pub referrer:      Pubkey,    // 32
-    pub bet_lamports:  u64,       // 8
-    pub commitment:    [u8; 32],  // 32
-    pub commit_slot:   u64,       // 8
-    pub resolve_slot:  u64,       // 8
-    pub forfeit_slot:  u64,       // 8
-    pub game_type:     u8,        // 1
-    pub game_state:    u8,        // 1  — 0=pending, 2=settled
-    pub target_x:      u8,        // 1
-    pub target_y:      u8,        // 1
-    pub target_radius: u8,        // 1
-    pub bump:          u8,        // 1
+pub struct SchemaRegistry {
+    pub version:      u32,      // 4  — SCHEMA_VERSION of the deployed binary
+    pub layout_hash:  [u8; 32], // 32 — schema_hash()
+    pub updated_slot: u64,      // 8
+    pub bump:         u8,       // 1
 }
-impl GameSession { pub const LEN: usize = 134; }
+impl SchemaRegistry { pub const LEN: usize = 45; }
 
+/// @notice Singleton PDA [b"build_attestation"] — on-chain provenance of the deployed binary.
+///         Compare `build_hash` with `solana-verify get-program-hash` and `commit_hash` with
+///         the public repository; a default `verifier` means the build is self-attested.
 #[account]
-pub struct SessionToken {
-    pub player:     Pubkey, // 32
-    pub delegate:   Pubkey, // 32
-    pub expires_at: i64,    // 8
-    pub bump:       u8,     // 1
+pub struct BuildAttestation {
+    pub commit_hash:     [u8; 20], // 20 — source commit (SHA-1)
+    pub build_hash:      [u8; 32], // 32 — verifiable-build executable hash
+    pub schema_version:  u32,      // 4  — SCHEMA_VERSION at attestation
+    pub program_version: u32,      // 4  — PROGRAM_VERSION at attestation
+    pub attested_by:     Pubkey,   // 32 — pool authority
+    pub verifier:        Pubkey,   // 32 — third-party verifier, default = none
+    pub attested_slot:   u64,      // 8
+    pub bump:            u8,       // 1
+}
+impl BuildAttestation { pub const LEN: usize = 133; }
+
+/// @notice dev_seed_state input. Zero amounts leave the matching feature off.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct DevSeedParams {
+    pub fund_lamports:       u64,
+    pub jackpot_balance:     u64,
+    pub house_fees_earned:   u64,
+    pub total_wagered:       u64,
+    pub total_bets:          u64,
+    pub total_wins:          u64,
+    pub biggest_win:         u64,
+    pub total_paid_out:      u64,
+    pub paused:              bool,
+    pub pending_withdrawal:  u64,
+    pub expired_session_bet: u64,
+    pub session_commitment:  [u8; 32],
+}
+
+/// @notice One leg of place_parlay: a Flip, Sector99, Dice or Tower bet config.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ParlayLeg {
+    pub game_type:   u8,
+    pub game_config: [u8; 3],
+    pub config_ext:  u64,
+}
+
+/// @notice One bet of place_bets_batch — the place_bet_v3 arguments.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BatchBet {
+    pub game_type:    u8,
+    pub commitment:   [u8; 32],
+    pub bet_lamports: u64,
+    pub game_config:  [u8; 3],
+    pub config_ext:   u64,
+    pub flags:        u8,
+}
+
+/// @notice Return data of a reveal that lost the race to an earlier reveal of the same session.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RevealReceipt {
+    pub won:          bool,
+    pub paid_out:     u64, // includes held_payout
+    pub jackpot_won:  u64,
+    pub settled_slot: u64,
+    pub held_payout:  u64, // awaiting co-sign / fallback release
 }
-impl SessionToken { pub const LEN: usize = 73; }
 
 #[derive(AnchorSerialize, AnchorDeserialize, Clone)]
 pub struct WithdrawalRequest {
@@ -1196,6 +9571,110 @@ pub struct WithdrawalRequest {
     pub unlocks_at:   i64, // 8
 }
 
+/// @notice Payout curve shared by the multiplier games. All-zero = unset (DEFAULT_CURVE).
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PayoutCurve {
+    pub edge_bps:            u16, // 2
+    pub max_multiplier_x100: u32, // 4  — 100 = 1.00×
+    pub shape:               u8,  // 1  — CURVE_*
+    pub bust_bps:            u16, // 2  — CURVE_INVERSE_BUST only
+}
+
+/// @notice Time-boxed jackpot promotion. Outside [start, end) it reads as no boost.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JackpotBoost {
+    pub start:       i64, // 8
+    pub end:         i64, // 8
+    pub trigger_bps: u16, // 2  — trigger threshold multiplier, 10_000 = 1×
+    pub contrib_bps: u16, // 2  — jackpot contribution multiplier, 10_000 = 1×
+}
+
+impl JackpotBoost {
+    /// The multipliers in force at `now` — 1× each outside the window.
+    pub fn at(&self, now: i64) -> JackpotBoost {
+        if now >= self.start && now < self.end { *self } else {
+            JackpotBoost { trigger_bps: fixed::BPS as u16, contrib_bps: fixed::BPS as u16, ..*self }
+        }
+    }
+}
+
+/// @notice Per-game RTP counters. Wagers are counted at placement, wins at settlement.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct GameCounters {
+    pub bets:     u64, // 8
+    pub wagered:  u64, // 8
+    pub paid_out: u64, // 8  — gross wins, jackpots excluded
+}
+
+impl GameCounters {
+    fn record_bet(&mut self, amount: u64) {
+        self.bets    = self.bets.saturating_add(1);
+        self.wagered = self.wagered.saturating_add(amount);
+    }
+}
+
+/// @notice Expected RTP band checked by check_rtp. max_bps = 0 = no alarm.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default)]
+pub struct RtpBand {
+    pub min_bps:    u16,  // 2
+    pub max_bps:    u16,  // 2
+    pub min_bets:   u32,  // 4  — sample size before the band is enforced
+    pub auto_pause: bool, // 1
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LiquidityMigration {
+    pub to:           Pubkey, // 32
+    pub amount:       u64,    // 8
+    pub requested_at: i64,    // 8
+    pub unlocks_at:   i64,    // 8
+}
+
+/// Return data of `quote_bet`. Fee bps are for the given referrer flag at the current phase.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BetQuote {
+    pub accepted:        bool,
+    pub error_code:      u32,  // BlitzError code if rejected, 0 if accepted
+    pub worst_payout:    u64,
+    pub min_bet:         u64,
+    pub max_bet:         u64,
+    pub payout_cap:      u64,
+    pub house_bps:       u64,
+    pub ref_bps:         u64,
+    pub jackpot_bps:     u64,
+    pub max_allowed_bet: u64,  // largest stake accepted right now — what PLACE_FLAG_DOWNSIZE would place
+}
+
+/// Return data of `audit_pool`. `solvent` = physical ≥ reserved and total_balance = physical − reserved.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PoolAudit {
+    pub slot:              u64,
+    pub state_seq:         u64,
+    pub program_version:   u32,
+    pub physical:          u64, // pool lamports above rent
+    pub reserved:          u64, // GlobalPool::reserved(): every compartment below + step exposure
+    pub total_balance:     u64,
+    pub house_fees_earned: u64,
+    pub jackpot_balance:   u64,
+    pub promo_balance:     u64,
+    pub held_payouts:      u64,
+    pub referral_accrued:  u64,
+    pub performance_fees:  u64,
+    pub insurance_balance: u64,
+    pub rocket_exposure:   u64,
+    pub solvent:           bool,
+}
+
+/// Return data of `export_game_counters`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct GameCountersExport {
+    pub slot:          u64,
+    pub state_seq:     u64,
+    pub counters:      [GameCounters; MAX_GAME_TYPES],
+    pub rtp_anomalies: u32,
+    pub games_paused:  u32,
+}
+
 // ══════════════════════════════════════════════════════════════════════════
 //  ERRORS
 // ══════════════════════════════════════════════════════════════════════════
@@ -1204,9 +9683,9 @@ pub struct WithdrawalRequest {
 pub enum BlitzError {
     #[msg("Contract is paused")]                                             ContractPaused,
     #[msg("Pool balance too low (min 0.1 SOL)")]                             PoolTooLow,
-    #[msg("Bet too small (min 0.01 SOL)")]                                   BetTooSmall,
+    #[msg("Bet too small (min 0.01 SOL, 1 SOL in high-roller room)")]       BetTooSmall,
     #[msg("Bet exceeds per-game limit")]                                     BetExceedsLimit,
-    #[msg("Invalid game type (0–3)")]                                        InvalidGameType,
+    #[msg("Invalid game type (0–23)")]                                       InvalidGameType,
     #[msg("Invalid game config")]                                            InvalidGameConfig,
     #[msg("Invalid coordinate (0–15)")]                                      InvalidCoordinate,
     #[msg("Invalid radius (0–3)")]                                           InvalidRadius,
@@ -1228,14 +9707,171 @@ pub enum BlitzError {
     #[msg("Gas funding too high (max 0.01 SOL)")]                           GasTooHigh,
     #[msg("Invalid dice target")]                                            InvalidDiceTarget,
     #[msg("Referrer must be a regular wallet, not a PDA")]                  InvalidReferrer,
-    #[msg("Slot hash too old (>490 slots) — use emergency_refund")]         SlotTooOld,
+    #[msg("Slot hash too old (>490 slots) — use resolve_terminal_state")]   SlotTooOld,
     #[msg("Caller is not the session player")]                               NotSessionPlayer,
     #[msg("Payout exceeds pool safety cap")]                                 PayoutExceedsPoolCap,
-    #[msg("Tower floors must be 1–6")]                                       InvalidTowerFloors,
+    #[msg("Tower floors must be 1–6 (1–4 with 3 or 4 doors)")]              InvalidTowerFloors,
     #[msg("Accounting invariant violated — contact support")]                AccountingBroken,
     #[msg("Invalid authority — cannot transfer to self or default pubkey")] InvalidAuthority,
-    #[msg("Slot hash still available — use voluntary_forfeit")]              SlotHashStillAvailable,
-    #[msg("Slot hash expired — use emergency_refund instead")]               UseEmergencyRefund,
+    #[msg("Retired (resolve_terminal_state); kept for stable codes")]       SlotHashStillAvailable,
+    #[msg("Retired (resolve_terminal_state); kept for stable codes")]       UseEmergencyRefund,
+    #[msg("Invalid notification flags")]                                     InvalidNotifyFlags,
+    #[msg("Race is not open for joining")]                                   RaceNotOpen,
+    #[msg("Race is not in progress")]                                        RaceNotActive,
+    #[msg("Cannot join your own race")]                                      CannotJoinOwnRace,
+    #[msg("Signer is not a participant in this race")]                       NotRaceParticipant,
+    #[msg("Nonce already revealed")]                                         AlreadyRevealed,
+    #[msg("Unknown room")]                                                   InvalidRoom,
+    #[msg("Open-session index page is full")]                                IndexPageFull,
+    #[msg("Session is indexed — pass its open-session page")]                IndexPageRequired,
+    #[msg("place_bet via CPI is only allowed from whitelisted programs")]    CpiNotAllowed,
+    #[msg("Player was credited less than expected by this slot's settlement")] PayoutBelowExpected,
+    #[msg("Arithmetic overflow in lamport accounting")]                      MathOverflow,
+    #[msg("Transfer would leave the pool below rent exemption")]             WouldBreakRentExemption,
+    #[msg("Payout wallet must be a system-owned account")]                   InvalidPayoutWallet,
+    #[msg("Session pays out to a separate wallet — pass it")]               PayoutWalletRequired,
+    #[msg("Partner fee share exceeds the maximum")]                          InvalidPartnerFee,
+    #[msg("Partner is suspended")]                                           PartnerInactive,
+    #[msg("Partner session — pass its Partner account")]                    PartnerRequired,
+    #[msg("Bet fits a single room — use place_bet")]                        SplitNotNeeded,
+    #[msg("Only available in devnet builds")]                                DevnetOnly,
+    #[msg("Reminder window exceeds the reveal window")]                      InvalidPingWindow,
+    #[msg("Jackpot trigger cap out of bounds (1–200 bps)")]                  InvalidJackpotCap,
+    #[msg("Session already settled — receipt window has passed")]           AlreadySettled,
+    #[msg("Session is not settled")]                                         SessionNotSettled,
+    #[msg("Receipt window still open")]                                      ReceiptWindowActive,
+    #[msg("Invalid payout curve parameters")]                                InvalidCurve,
+    #[msg("Payout curve EV exceeds 96%")]                                    CurveEvTooHigh,
+    #[msg("Wallet balance below the promo enrollment minimum")]             PromoWalletBalanceTooLow,
+    #[msg("Signer is not the promo attestor")]                               InvalidAttestor,
+    #[msg("Co-signer does not match the pool's co-sign policy")]            InvalidCosigner,
+    #[msg("Session has no held payout")]                                     NoHeldPayout,
+    #[msg("Session still has a held payout — release it first")]            PayoutStillHeld,
+    #[msg("Plinko config: rows 8–16, risk 0–2")]                            InvalidPlinkoConfig,
+    #[msg("Registry holds a newer schema than this binary")]                SchemaDowngrade,
+    #[msg("Mines config: 1–24 mines")]                                       InvalidMinesConfig,
+    #[msg("No open Mines board on this session")]                            MinesNotActive,
+    #[msg("A tile pick is awaiting resolve_tile")]                           MinesPickPending,
+    #[msg("No tile pick to resolve")]                                        NoMinesPick,
+    #[msg("Tile index out of range (0–24)")]                                 InvalidTile,
+    #[msg("Tile already revealed")]                                          TileAlreadyRevealed,
+    #[msg("Next tile would exceed the Mines multiplier cap — cash out")]     MinesCapReached,
+    #[msg("Reveal at least one safe tile before cashing out")]               MinesNothingToCashOut,
+    #[msg("Crash target must be 1.01× up to the curve's max multiplier")]    InvalidCrashTarget,
+    #[msg("RTP band: min ≤ max ≤ 200%")]                                     InvalidRtpBand,
+    #[msg("No RTP band configured for this game")]                           RtpBandNotSet,
+    #[msg("Not enough bets for an RTP check yet")]                           RtpSampleTooSmall,
+    #[msg("Game paused by an RTP alarm")]                                    GamePaused,
+    #[msg("Roulette bet class or selection out of range")]                   InvalidRouletteBet,
+    #[msg("Missing or mismatched Ed25519 session authorization")]            InvalidSessionSignature,
+    #[msg("Unsupported session scope")]                                      InvalidSessionScope,
+    #[msg("Keno: 1–10 distinct picks from 1–40, matching game_config[0]")]   InvalidKenoPicks,
+    #[msg("Limbo target must be 1.01× up to the curve's max multiplier")]    InvalidLimboTarget,
+    #[msg("Referral mode must be 0–1 with share ≤ REFERRAL_MAX_SHARE_BPS")]  InvalidReferralConfig,
+    #[msg("No closed-epoch referral commission to claim")]                   NoReferralCommission,
+    #[msg("Wheel risk must be 0 (10), 1 (20) or 2 (40 segments)")]           InvalidWheelRisk,
+    #[msg("No open HiLo chain on this session")]                             HiloNotActive,
+    #[msg("A guess is awaiting resolve_hilo")]                               HiloGuessPending,
+    #[msg("No guess to resolve")]                                            NoHiloGuess,
+    #[msg("HiLo guess must be 0 (lower) or 1 (higher) and able to lose")]    InvalidHiloGuess,
+    #[msg("Next guess would exceed the HiLo multiplier cap — cash out")]     HiloCapReached,
+    #[msg("Win at least one guess before cashing out")]                      HiloNothingToCashOut,
+    #[msg("Video Poker hold mask must be 5 bits")]                           InvalidPokerHold,
+    #[msg("Unknown placement flag, or public reveal on an interactive game")] InvalidBetFlags,
+    #[msg("Session did not opt in to public reveal")]                        PublicRevealNotAllowed,
+    #[msg("Baccarat side must be 0 (Player), 1 (Banker) or 2 (Tie)")]        InvalidBaccaratSide,
+    #[msg("Program upgraded — run finalize_upgrade before resuming")]        UpgradeNotFinalized,
+    #[msg("Pool already records this or a newer program version")]          UpgradeAlreadyFinalized,
+    #[msg("No previous bet recorded in PlayerStats to replay")]             NoBetToReplay,
+    #[msg("RPS move must be 0 (Rock), 1 (Paper) or 2 (Scissors)")]          InvalidRpsMove,
+    #[msg("Payout above the room cap — max_allowed_bet in return data")]    CapWouldTruncatePayout,
+    #[msg("Lottery round is not selling tickets")]                          LotteryClosed,
+    #[msg("Lottery round cannot be drawn yet")]                             LotteryNotDrawable,
+    #[msg("Lottery round has not been drawn")]                              LotteryNotDrawn,
+    #[msg("Lottery round or ticket does not match")]                        InvalidLotteryRound,
+    #[msg("Lottery claim window is still open")]                            LotteryClaimWindowOpen,
+    #[msg("Sic Bo bet: Small/Big, a triple of 1–6 or a total of 4–17")]     InvalidSicBoBet,
+    #[msg("Dragon Tiger side must be 0 (Dragon), 1 (Tiger) or 2 (Tie)")]    InvalidDragonTigerSide,
+    #[msg("Auditor called again before min_interval_slots elapsed")]        AuditRateLimited,
+    #[msg("SlotHashes sysvar does not match the expected layout")]          MalformedSysvar,
+    #[msg("Dice Duo bet must be under 3–12, over 2–11 or exactly 2–12")]    InvalidDiceDuoBet,
+    #[msg("Redemption not signed by the gift card's code key")]             InvalidGiftCardCode,
+    #[msg("Gift card has expired")]                                         GiftCardExpired,
+    #[msg("Gift card has not expired yet")]                                 GiftCardNotExpired,
+    #[msg("Gift card is locked to another recipient")]                      GiftCardRecipientLocked,
+    #[msg("No open Stairs climb on this session")]                          StairsNotActive,
+    #[msg("A step is awaiting resolve_step")]                               StairsStepPending,
+    #[msg("No step to resolve")]                                            NoStairsStep,
+    #[msg("Clear at least one step before cashing out")]                    StairsNothingToCashOut,
+    #[msg("Rocket round cutoff must be at least SLOT_SPREAD slots ahead")]  InvalidRocketRound,
+    #[msg("Rocket round is closed to new entries")]                         RocketClosed,
+    #[msg("Rocket round cannot launch yet")]                                RocketNotLaunchable,
+    #[msg("Rocket round has not launched")]                                 RocketNotLaunched,
+    #[msg("Rocket round exposure would exceed the payout cap or pool")]     RocketLiabilityCap,
+    #[msg("Entry does not belong to this rocket round or player")]          InvalidRocketEntry,
+    #[msg("Crank takes up to ROCKET_CRANK_BATCH (entry, player) pairs")]    InvalidRocketBatch,
+    #[msg("Goal: 1–8 rows, lane 0–2 per row, no bits past the last row")]   InvalidGoalConfig,
+    #[msg("Pool metadata needs a name and UTF-8 text fields")]              InvalidPoolMetadata,
+    #[msg("No pool metadata update is pending")]                            NoPendingPoolMetadata,
+    #[msg("Tower doors must be 2–4")]                                       InvalidTowerDoors,
+    #[msg("Missing or mismatched Ed25519 promo voucher")]                   InvalidPromoVoucher,
+    #[msg("Promo voucher has expired")]                                     PromoVoucherExpired,
+    #[msg("Promo voucher has not expired yet")]                             PromoVoucherNotExpired,
+    #[msg("Voucher needs a pending, uninsured bet placed this slot")]       PromoVoucherSessionIneligible,
+    #[msg("No open Tower Climb on this session")]                           TowerClimbNotActive,
+    #[msg("A door is awaiting resolve_tower_floor")]                        TowerDoorPending,
+    #[msg("No door to resolve")]                                            NoTowerDoor,
+    #[msg("Door is out of range for this tower")]                           InvalidTowerDoor,
+    #[msg("Clear at least one floor before cashing out")]                   TowerNothingToCashOut,
+    #[msg("Sector zones: at most 3, unused bytes zero")]                    InvalidSectorZones,
+    #[msg("Sector target zones overlap")]                                   SectorZonesOverlap,
+    #[msg("Jackpot boost window or multipliers out of bounds")]             InvalidJackpotBoost,
+    #[msg("Flip streak needs 2–6 flips and sides within them")]             InvalidFlipStreak,
+    #[msg("No terminal policy applies to this session yet")]                TerminalStateNotReached,
+    #[msg("Batch must hold 1–5 bets with one account set each")]            InvalidBatchSize,
+    #[msg("Batch account is not this player's session")]                    InvalidBatchSession,
+    #[msg("Parlay needs 2–4 Flip, Sector99, Dice or Tower legs")]           InvalidParlay,
+    #[msg("Parlay sessions must pass their ParlaySession")]                 ParlayRequired,
+    #[msg("No held win open to double_or_nothing")]                         NoDoubleOffer,
+    #[msg("The double-or-nothing window has closed")]                       DoubleWindowClosed,
+    #[msg("Vault balance too low")]                                         InsufficientVaultBalance,
+    #[msg("LP position still locked")]                                      LpPositionLocked,
+    #[msg("Not enough LP shares")]                                          InsufficientLpShares,
+    #[msg("Withdrawal would draw on LP capital")]                           LpCapitalLocked,
+    #[msg("Not the head of the LP exit queue")]                             NotLpQueueHead,
+    #[msg("LP exit is paid from the epoch after it was queued")]            LpExitNotDue,
+    #[msg("LP exit budget for this epoch is spent")]                        LpExitBudgetSpent,
+    #[msg("Game budget above 10_000 bps")]                                  InvalidGameBudget,
+    #[msg("This epoch is already closed in the LP ledger")]                 LpEpochNotOver,
+    #[msg("Performance fee above PERFORMANCE_FEE_MAX_BPS")]                 InvalidPerformanceFee,
+    #[msg("Insurance share above INSURANCE_MAX_BPS")]                       InvalidInsurancePolicy,
+    #[msg("Pool balance is not below the insurance threshold")]             InsuranceNotNeeded,
+    #[msg("Not the VRF randomness account of this bet")]                    InvalidRandomness,
+    #[msg("Randomness must be committed in the previous slot, unrevealed")] RandomnessStale,
+    #[msg("Randomness not revealed yet")]                                   RandomnessNotRevealed,
+    #[msg("Randomness re-committed or replaced since the bet was placed")]  RandomnessRecommitted,
+    #[msg("Referrer's ReferralAccount required in net-loss referral mode")] ReferralAccountRequired,
+    #[msg("Session settles from VRF randomness — use settle_vrf")]          VrfSession,
+    #[msg("Unknown VRF provider")]                                          InvalidVrfProvider,
+    #[msg("Game receipt already written")]                                  GameReceiptWritten,
+    #[msg("Game receipt not written yet — session unsettled")]              GameReceiptPending,
+    #[msg("Client seed required iff placed with PLACE_FLAG_CLIENT_SEED")]   ClientSeedMismatch,
+    #[msg("Rocket round launched or refund_slot not reached")]              RocketNotRefundable,
+    #[msg("Rocket round still has open entries")]                           RocketRoundActive,
+    #[msg("LP shares back nothing until open bets settle")]                 LpBookUnpriced,
+    #[msg("LP ticket is below the minimum size")]                           LpTicketTooSmall,
+    #[msg("LP position does not belong to this pool and owner")]            InvalidLpPosition,
+    #[msg("This room runs the first-bet promo: pass PlayerStats")]          PlayerStatsRequired,
+    #[msg("This room has a Firehose: pass it to every settlement")]         FirehoseRequired,
+    #[msg("This room indexes open sessions: pass an open-session page")]    SessionIndexRequired,
+    #[msg("Session is not in the pre-room layout")]                         NotLegacySession,
+    #[msg("Only the session's player or pool authority may migrate it")]    MigrationNotAuthorized,
+    #[msg("This room prices LPs off a NavSnapshot: pass it")]               NavSnapshotRequired,
+    #[msg("NavSnapshot was taken less than NAV_SNAPSHOT_SLOTS ago")]        NavSnapshotTooSoon,
+    #[msg("Pass the vault to top up, the grant for a constrained deposit")] InvalidSponsorGrant,
+    #[msg("Sponsor grant has expired")]                                     SponsorGrantExpired,
+    #[msg("Sponsor grant has not expired yet")]                             SponsorGrantNotExpired,
+    #[msg("Bet is outside the sponsor grant's balance, games or max bet")]  SponsorGrantConstraint,
 }
 
 // ══════════════════════════════════════════════════════════════════════════
@@ -1243,11 +9879,80 @@ pub enum BlitzError {
 // ══════════════════════════════════════════════════════════════════════════
 
 #[event] pub struct PoolFunded                 { pub amount: u64,       pub funder: Pubkey }
-#[event] pub struct BetPlaced                  { pub player: Pubkey,    pub game_type: u8, pub amount: u64, pub resolve_slot: u64 }
+#[event] pub struct PromoFunded                { pub amount: u64,       pub funder: Pubkey }
+#[event] pub struct PromoWithdrawn             { pub amount: u64 }
+#[event] pub struct PromoConfigUpdated         { pub pool: Pubkey,      pub cap_lamports: u64, pub min_wallet_lamports: u64, pub attestor: Pubkey }
+#[event] pub struct FirstBetInsured            { pub player: Pubkey,    pub amount: u64 }
+#[event] pub struct FirstBetRefunded           { pub player: Pubkey,    pub amount: u64 }
+#[event] pub struct PromoVoucherRedeemed       { pub pool: Pubkey,      pub player: Pubkey, pub campaign_id: u64, pub session: Pubkey, pub amount: u64 }
+#[event] pub struct CosignPolicyUpdated        { pub pool: Pubkey,      pub threshold: u64, pub cosigner: Pubkey }
+#[event] pub struct PayoutHeld                 { pub player: Pubkey,    pub amount: u64, pub release_at: i64 }
+#[event] pub struct PayoutReleased             { pub player: Pubkey,    pub amount: u64, pub cosigned: bool }
+#[event] pub struct ReceiptTreeInitialized     { pub pool: Pubkey,      pub tree: Pubkey, pub depth: u8 }
+#[event] pub struct ReceiptLogged              { pub tree: Pubkey,      pub index: u64, pub leaf: [u8; 32], pub root: [u8; 32] }
+#[event] pub struct RtpBandUpdated             { pub pool: Pubkey,      pub game: u8, pub min_bps: u16, pub max_bps: u16, pub min_bets: u32, pub auto_pause: bool }
+#[event] pub struct RtpAnomaly                 { pub pool: Pubkey,      pub game: u8, pub rtp_bps: u64, pub min_bps: u16, pub max_bps: u16, pub bets: u64, pub paused: bool }
+#[event] pub struct GameResumed                { pub pool: Pubkey,      pub game: u8 }
+#[event] pub struct ReferralModeUpdated        { pub pool: Pubkey,      pub mode: u8, pub share_bps: u16 }
+#[event] pub struct ReferralEpochClosed        { pub referrer: Pubkey,  pub epoch: u64, pub wagered: u64, pub paid_out: u64, pub commission: u64 }
+#[event] pub struct ReferralClaimed            { pub referrer: Pubkey,  pub pool: Pubkey, pub amount: u64 }
+#[event] pub struct RoomInitialized            { pub pool: Pubkey,      pub room: u8, pub authority: Pubkey }
+#[event] pub struct LiquidityMigrationRequested { pub from: Pubkey,     pub to: Pubkey, pub amount: u64, pub unlocks_at: i64 }
+#[event] pub struct LiquidityMigrated          { pub from: Pubkey,      pub to: Pubkey, pub amount: u64 }
+#[event] pub struct LiquidityMigrationCancelled { pub from: Pubkey }
+#[event] pub struct UniquePlayersEpochClosed   { pub epoch: u64,        pub estimate: u64 }
+#[event] pub struct CpiWhitelistUpdated        { pub pool: Pubkey,      pub programs: [Pubkey; CPI_WHITELIST_LEN] }
+#[event] pub struct ForfeitRentPolicyUpdated   { pub pool: Pubkey,      pub to_pool: bool }
+#[event] pub struct PoolStateChanged           { pub pool: Pubkey,      pub seq: u64, pub config_seq: u64 }
+#[event] pub struct SchemaUpdated              { pub version: u32,      pub layout_hash: [u8; 32], pub previous_hash: [u8; 32] }
+#[event] pub struct JackpotTriggerCapUpdated   { pub pool: Pubkey,      pub cap_bps: u16 }
+#[event] pub struct JackpotBoostScheduled      { pub pool: Pubkey,      pub start: i64, pub end: i64, pub trigger_bps: u16, pub contrib_bps: u16 }
+#[event] pub struct PayoutCurveUpdated         { pub pool: Pubkey,      pub edge_bps: u16, pub max_multiplier_x100: u32, pub shape: u8, pub bust_bps: u16 }
+#[cfg(feature = "debug-events")]
+#[event] pub struct JackpotRoll                { pub player: Pubkey,    pub bet: u64, pub threshold: u64, pub probability_ppm: u64 }
+#[event] pub struct PartnerUpdated             { pub partner: Pubkey,   pub authority: Pubkey, pub fee_bps: u16, pub active: bool }
+#[event] pub struct PartnerSettled             { pub partner: Pubkey,   pub player_gets: u64, pub fee: u64 }
+#[event] pub struct BetPlaced                  { pub player: Pubkey,    pub game_type: u8, pub amount: u64, pub resolve_slot: u64, pub forfeit_slot: u64, pub grace_deadline: u64, pub config_seq: u64 }
+#[event] pub struct SessionExpiringSoon        { pub session: Pubkey,   pub player: Pubkey, pub forfeit_slot: u64, pub slots_left: u64 }
+#[event] pub struct SplitBetPlaced             { pub player: Pubkey,    pub game_type: u8, pub main_session: Pubkey, pub main_amount: u64, pub high_roller_session: Pubkey, pub high_roller_amount: u64, pub resolve_slot: u64 }
+#[event] pub struct PublicRevealTipped         { pub session: Pubkey,   pub executor: Pubkey, pub tip: u64 }
 #[event] pub struct FlipSettled                { pub player: Pubkey,    pub won: bool, pub roll: u8, pub payout: u64 }
-#[event] pub struct DiceSettled                { pub player: Pubkey,    pub won: bool, pub roll: u8, pub target: u8, pub payout: u64, pub is_over: bool }
+#[event] pub struct FlipStreakSettled          { pub player: Pubkey,    pub won: bool, pub flips: u8, pub sides: u8, pub results: u8, pub payout: u64 }
+#[event] pub struct DiceSettled                { pub player: Pubkey,    pub won: bool, pub roll: u8, pub target: u8, pub payout: u64, pub is_over: bool, pub mode: u8, pub target_hi: u8 }
 #[event] pub struct SectorSettled              { pub player: Pubkey,    pub won: bool, pub strike_x: u8, pub strike_y: u8, pub payout: u64 }
-#[event] pub struct TowerSettled               { pub player: Pubkey,    pub won: bool, pub floors: u8, pub death_floor: u8, pub payout: u64, pub path: u8, pub traps: u8 }
+#[event] pub struct TowerSettled               { pub player: Pubkey,    pub won: bool, pub floors: u8, pub death_floor: u8, pub payout: u64, pub path: u8, pub traps: u8, pub doors: u8 }
+#[event] pub struct MysterySettled             { pub player: Pubkey,    pub won: bool, pub picked_game: u8, pub result: [u8; 2], pub payout: u64 }
+#[event] pub struct PlinkoSettled              { pub player: Pubkey,    pub won: bool, pub rows: u8, pub risk: u8, pub path: u16, pub bucket: u8, pub multiplier_bps: u64, pub payout: u64 }
+#[event] pub struct CrashSettled               { pub player: Pubkey,    pub won: bool, pub crash_point: u32, pub target: u16, pub payout: u64 }
+#[event] pub struct RouletteSettled            { pub player: Pubkey,    pub won: bool, pub pocket: u8, pub bet_class: u8, pub selection: u8, pub payout: u64 }
+#[event] pub struct KenoSettled                { pub player: Pubkey,    pub won: bool, pub picks: u64, pub drawn: u64, pub hits: u8, pub multiplier_bps: u64, pub payout: u64 }
+#[event] pub struct LimboSettled               { pub player: Pubkey,    pub won: bool, pub result: u32, pub target: u32, pub payout: u64 }
+#[event] pub struct WheelSettled               { pub player: Pubkey,    pub won: bool, pub risk: u8, pub segment: u8, pub multiplier_bps: u64, pub payout: u64 }
+#[event] pub struct VideoPokerSettled          { pub player: Pubkey,    pub won: bool, pub dealt: [u8; 5], pub hold: u8, pub hand: [u8; 5], pub class: u8, pub multiplier_bps: u64, pub payout: u64 }
+#[event] pub struct BaccaratSettled            { pub player: Pubkey,    pub won: bool, pub side: u8, pub player_cards: [u8; 3], pub banker_cards: [u8; 3], pub player_total: u8, pub banker_total: u8, pub payout: u64 }
+#[event] pub struct SlotsSettled               { pub player: Pubkey,    pub won: bool, pub reels: [u8; 3], pub multiplier_bps: u64, pub payout: u64 }
+#[event] pub struct SicBoSettled               { pub player: Pubkey,    pub won: bool, pub bet_class: u8, pub selection: u8, pub dice: [u8; 3], pub total: u8, pub payout: u64 }
+#[event] pub struct DragonTigerSettled         { pub player: Pubkey,    pub won: bool, pub side: u8, pub dragon: u8, pub tiger: u8, pub payout: u64 }
+#[event] pub struct DiceDuoSettled             { pub player: Pubkey,    pub won: bool, pub mode: u8, pub target: u8, pub dice: [u8; 2], pub sum: u8, pub payout: u64 }
+#[event] pub struct GoalSettled                { pub player: Pubkey,    pub won: bool, pub rows: u8, pub death_row: u8, pub payout: u64, pub path: u16, pub traps: u16 }
+#[event] pub struct UpgradeFinalized           { pub pool: Pubkey,      pub from: u32, pub to: u32 }
+#[event] pub struct BuildAttested              { pub commit_hash: [u8; 20], pub build_hash: [u8; 32], pub program_version: u32, pub verifier: Pubkey }
+#[event] pub struct MinesStarted               { pub player: Pubkey,    pub mines: u8, pub forfeit_slot: u64 }
+#[event] pub struct MinesTilePicked            { pub player: Pubkey,    pub tile: u8, pub pick_slot: u64 }
+#[event] pub struct MinesTileRevealed          { pub player: Pubkey,    pub tile: u8, pub safe_tiles: u8, pub multiplier_bps: u64 }
+#[event] pub struct MinesSettled               { pub player: Pubkey,    pub won: bool, pub mines: u8, pub safe_tiles: u8, pub revealed: u32, pub hit_tile: u8, pub payout: u64 }
+#[event] pub struct HiloStarted                { pub player: Pubkey,    pub card: u8, pub forfeit_slot: u64 }
+#[event] pub struct HiloGuessed                { pub player: Pubkey,    pub card: u8, pub guess: u8, pub pick_slot: u64 }
+#[event] pub struct HiloCardDrawn              { pub player: Pubkey,    pub card: u8, pub rounds: u8, pub multiplier_bps: u64 }
+#[event] pub struct HiloSettled                { pub player: Pubkey,    pub won: bool, pub rounds: u8, pub last_card: u8, pub payout: u64 }
+#[event] pub struct StairsStarted              { pub player: Pubkey,    pub forfeit_slot: u64 }
+#[event] pub struct StairsStepTaken            { pub player: Pubkey,    pub step: u8, pub pick_slot: u64 }
+#[event] pub struct StairsStepCleared          { pub player: Pubkey,    pub step: u8, pub multiplier_bps: u64 }
+#[event] pub struct StairsSettled              { pub player: Pubkey,    pub won: bool, pub steps: u8, pub payout: u64 }
+#[event] pub struct TowerClimbStarted          { pub player: Pubkey,    pub floors: u8, pub doors: u8, pub forfeit_slot: u64 }
+#[event] pub struct TowerDoorPicked            { pub player: Pubkey,    pub floor: u8, pub door: u8, pub pick_slot: u64 }
+#[event] pub struct TowerFloorResolved         { pub player: Pubkey,    pub floor: u8, pub door: u8, pub trap: u8 }
+#[event] pub struct TowerClimbSettled          { pub player: Pubkey,    pub won: bool, pub floors: u8, pub multiplier_bps: u64, pub payout: u64 }
 #[event] pub struct BetForfeited               { pub player: Pubkey,    pub amount: u64 }
 #[event] pub struct JackpotWon                 { pub player: Pubkey,    pub amount: u64 }
 #[event] pub struct WithdrawalRequested        { pub amount: u64,       pub unlocks_at: i64 }
@@ -1260,4 +9965,659 @@ pub enum BlitzError {
 #[event] pub struct ContractUnpaused           { pub authority: Pubkey }
 #[event] pub struct AuthorityTransferProposed  { pub current: Pubkey,   pub proposed: Pubkey, pub unlocks_at: i64 }
 #[event] pub struct AuthorityTransferCancelled { pub authority: Pubkey }
-#[event] pub struct AuthorityTransferred       { pub old_authority: Pubkey, pub new_authority: Pubkey }
\ No newline at end of file
+#[event] pub struct AuthorityTransferred       { pub old_authority: Pubkey, pub new_authority: Pubkey }
+#[event] pub struct NotificationPrefsUpdated   { pub player: Pubkey,    pub id_hash: [u8; 32], pub flags: u8 }
+#[event] pub struct Notify                     { pub player: Pubkey,    pub kind: u8 }
+#[event] pub struct TowerRaceCreated           { pub race: Pubkey,      pub creator: Pubkey, pub stake: u64, pub floors: u8 }
+#[event] pub struct TowerRaceJoined            { pub race: Pubkey,      pub opponent: Pubkey, pub resolve_slot: u64 }
+#[event] pub struct TowerRaceCancelled         { pub race: Pubkey,      pub bond_forfeited: u64 }
+#[event] pub struct TowerRaceSettled           { pub race: Pubkey,      pub winner: Pubkey, pub creator_floors: u8, pub opponent_floors: u8, pub traps: u8, pub payout: u64 }
+#[event] pub struct TowerRaceTimedOut          { pub race: Pubkey,      pub winner: Pubkey, pub payout: u64 }
+#[event] pub struct RpsMatchCreated            { pub rps_match: Pubkey, pub creator: Pubkey, pub stake: u64 }
+#[event] pub struct RpsMatchJoined             { pub rps_match: Pubkey, pub opponent: Pubkey, pub forfeit_slot: u64 }
+#[event] pub struct RpsMatchCancelled          { pub rps_match: Pubkey, pub bond_forfeited: u64 }
+#[event] pub struct RpsMatchSettled            { pub rps_match: Pubkey, pub winner: Pubkey, pub creator_move: u8, pub opponent_move: u8, pub payout: u64 }
+#[event] pub struct RpsMatchTimedOut           { pub rps_match: Pubkey, pub winner: Pubkey, pub payout: u64 }
+#[event] pub struct BetDownsized               { pub player: Pubkey,    pub session: Pubkey, pub requested: u64, pub placed: u64 }
+#[event] pub struct LotteryRoundOpened         { pub lottery_round: Pubkey, pub round: u64, pub ticket_price: u64, pub draw_slot: u64 }
+#[event] pub struct LotteryTicketBought        { pub lottery_round: Pubkey, pub owner: Pubkey, pub ticket: u32 }
+#[event] pub struct LotteryDrawn               { pub lottery_round: Pubkey, pub tickets: u32, pub pot: u64, pub winners: [u32; LOTTERY_MAX_WINNERS], pub prizes: [u64; LOTTERY_MAX_WINNERS] }
+#[event] pub struct LotteryPrizeClaimed        { pub lottery_round: Pubkey, pub owner: Pubkey, pub ticket: u32, pub amount: u64 }
+#[event] pub struct LotteryRolledOver          { pub from: Pubkey, pub to: Pubkey, pub amount: u64 }
+#[event] pub struct AuditorGranted             { pub pool: Pubkey, pub auditor: Pubkey, pub min_interval_slots: u64 }
+#[event] pub struct AuditorRevoked             { pub pool: Pubkey, pub auditor: Pubkey }
+#[event] pub struct PoolAudited                { pub pool: Pubkey, pub auditor: Pubkey, pub seq: u64, pub solvent: bool }
+#[event] pub struct GiftCardMinted             { pub gift_card: Pubkey, pub funder: Pubkey, pub amount: u64, pub recipient: Pubkey, pub expires_slot: u64 }
+#[event] pub struct GiftCardRedeemed           { pub gift_card: Pubkey, pub recipient: Pubkey, pub amount: u64 }
+#[event] pub struct GiftCardReclaimed          { pub gift_card: Pubkey, pub funder: Pubkey, pub amount: u64 }
+#[event] pub struct RocketRoundOpened          { pub rocket_round: Pubkey, pub round: u64, pub cutoff_slot: u64 }
+#[event] pub struct RocketJoined               { pub rocket_round: Pubkey, pub player: Pubkey, pub stake: u64, pub target_x100: u16 }
+#[event] pub struct RocketLaunched             { pub rocket_round: Pubkey, pub crash_point: u32, pub entries: u32, pub stakes: u64 }
+#[event] pub struct RocketEntrySettled         { pub rocket_round: Pubkey, pub player: Pubkey, pub stake: u64, pub target_x100: u16, pub payout: u64 }
+#[event] pub struct RocketEntryRefunded        { pub rocket_round: Pubkey, pub player: Pubkey, pub stake: u64 }
+#[event] pub struct RocketRoundClosed          { pub rocket_round: Pubkey, pub round: u64 }
+#[event] pub struct PoolMetadataProposed       { pub pool: Pubkey, pub branding: PoolBranding, pub unlocks_at: i64 }
+#[event] pub struct PoolMetadataUpdated        { pub pool: Pubkey, pub branding: PoolBranding }
+#[event] pub struct PoolMetadataCancelled      { pub pool: Pubkey }
+#[event] pub struct StepExposureReserved       { pub pool: Pubkey,      pub player: Pubkey, pub reserved: u64, pub pool_exposure: u64 }
+#[event] pub struct TerminalStateResolved      { pub session: Pubkey,   pub player: Pubkey, pub reason: u8, pub refund: u64, pub kept: u64 }
+#[event] pub struct BetsBatchPlaced            { pub player: Pubkey,    pub pool: Pubkey, pub count: u8, pub total: u64 }
+#[event] pub struct BatchRevealed              { pub player: Pubkey,    pub pool: Pubkey, pub count: u8, pub paid_out: u64 }
+#[event] pub struct ParlaySettled              { pub player: Pubkey,    pub won: bool, pub legs: u8, pub games: [u8; 4], pub results: u8, pub payout: u64 }
+#[event] pub struct DoubleOffered              { pub session: Pubkey,   pub player: Pubkey, pub amount: u64, pub expires_at: i64 }
+#[event] pub struct DoubleOrNothingPlaced      { pub session: Pubkey,   pub player: Pubkey, pub stake: u64, pub resolve_slot: u64, pub forfeit_slot: u64 }
+#[event] pub struct DoubleOrNothingSettled     { pub player: Pubkey,    pub won: bool, pub roll: u8, pub payout: u64 }
+#[event] pub struct VaultDeposited             { pub player: Pubkey,    pub amount: u64, pub balance: u64 }
+#[event] pub struct VaultWithdrawn             { pub player: Pubkey,    pub amount: u64, pub balance: u64 }
+#[event] pub struct LpDeposited                { pub pool: Pubkey,      pub owner: Pubkey, pub amount: u64, pub shares: u64, pub locked_until: i64 }
+#[event] pub struct LpWithdrawn                { pub pool: Pubkey,      pub owner: Pubkey, pub shares: u64, pub amount: u64, pub queued: u64 }
+#[event] pub struct LpExitQueued               { pub pool: Pubkey,      pub owner: Pubkey, pub shares: u64, pub ticket: u64 }
+#[event] pub struct GameBudgetUpdated          { pub pool: Pubkey,      pub game: u8, pub bps: u16, pub drawdown: u64 }
+#[event] pub struct LpEpochClosed              { pub pool: Pubkey,      pub epoch: u64, pub share_price: u64, pub hwm_price: u64, pub pnl: i64, pub fee: u64 }
+#[event] pub struct PerformanceFeeUpdated      { pub pool: Pubkey,      pub bps: u16, pub hwm_price: u64 }
+#[event] pub struct PerformanceFeeAccrued      { pub pool: Pubkey,      pub epoch: u64, pub fee: u64, pub accrued: u64 }
+#[event] pub struct PerformanceFeeClaimed      { pub pool: Pubkey,      pub amount: u64, pub authority: Pubkey }
+#[event] pub struct InsurancePolicyUpdated     { pub pool: Pubkey,      pub bps: u16, pub threshold: u64 }
+#[event] pub struct InsuranceTapRequested      { pub pool: Pubkey,      pub amount: u64, pub unlocks_at: i64 }
+#[event] pub struct InsuranceTapped            { pub pool: Pubkey,      pub amount: u64, pub total_balance: u64 }
+#[event] pub struct VrfProviderUpdated         { pub pool: Pubkey,      pub provider: u8 }
+#[event] pub struct GameVerified               { pub session: Pubkey,   pub game_type: u8, pub won: bool, pub matches: bool }
+#[event] pub struct LpBookWrittenOff           { pub pool: Pubkey,      pub generation: u32, pub shares: u64, pub nav: u64 }
+#[event] pub struct NavSnapshotTaken           { pub pool: Pubkey,      pub slot: u64, pub deposit_price: u64, pub exit_price: u64 }
+#[event] pub struct SponsorDeposited           { pub sponsor: Pubkey,   pub beneficiary: Pubkey, pub amount: u64, pub expires_slot: u64 }
+#[event] pub struct SponsorGrantReclaimed      { pub sponsor: Pubkey,   pub beneficiary: Pubkey, pub amount: u64 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL: u64 = 1_000_000_000;
+
+    // ── compute_settlement ────────────────────────────────────────────────
+
+    /// A 1 SOL phase-0 bet with plenty of liquidity, no referrer and a seed that never rolls the jackpot.
+    fn inputs() -> SettlementInputs {
+        SettlementInputs {
+            bet:               SOL,
+            won:               false,
+            gross_payout:      0,
+            seed:              [0xff; 32],
+            has_ref:           false,
+            ref_net_loss:      false,
+            referrer_lamports: 0,
+            partner_fee_bps:   None,
+            total_balance:     10 * SOL,
+            jackpot_balance:   0,
+            available:         10 * SOL,
+            jackpot_cap_bps:   0,
+            insured:           0,
+            cosign_threshold:  0,
+            cosigned:          false,
+            jackpot_feed_bps:  0,
+            boost_trigger_bps: fixed::BPS,
+            boost_contrib_bps: fixed::BPS,
+            hold_for_double:   false,
+            insurance_bps:     0,
+            flat_fee_bps:      None,
+            fee_bps:           [0; 3],
+            executor_tip:      0,
+        }
+    }
+
+    /// Every fee the plan takes out of the bet, wherever it goes.
+    fn fees(p: &SettlementPlan) -> u64 {
+        p.jackpot_cut + p.insurance_cut + p.claimable + p.reinvested + p.ref_cut + p.partner_cut
+    }
+
+    #[test]
+    fn settlement_conserves_lamports() {
+        let referrer = |i: &mut SettlementInputs| { i.has_ref = true; i.referrer_lamports = SOL; };
+        let partner  = |i: &mut SettlementInputs| i.partner_fee_bps = Some(2_000);
+        let insured  = |i: &mut SettlementInputs| { i.insurance_bps = 20; i.insured = SOL / 2; };
+        let boosted  = |i: &mut SettlementInputs| { i.boost_contrib_bps = 20_000; i.jackpot_feed_bps = 30; };
+        let variants: [fn(&mut SettlementInputs); 5] = [|_| {}, referrer, partner, insured, boosted];
+        for (balance, variant, won) in [10 * SOL, PHASE1_THRESHOLD, PHASE2_THRESHOLD]
+            .into_iter()
+            .flat_map(|b| variants.iter().flat_map(move |v| [(b, v, false), (b, v, true)]))
+        {
+            let mut i = inputs();
+            variant(&mut i);
+            (i.total_balance, i.available) = (balance, balance);
+            (i.won, i.gross_payout)        = (won, if won { 2 * SOL } else { 0 });
+            let p = compute_settlement(&i).unwrap();
+            let internal = p.jackpot_cut + p.insurance_cut + p.claimable + p.reinvested;
+            assert!(p.physical_out() - p.promo_refund + internal <= i.available, "{p:?}");
+            assert!(fees(&p) <= fixed::share_bps(i.bet, 250), "fees above 2.5%: {p:?}");
+            assert_eq!(p.win_payout, i.gross_payout);
+        }
+    }
+
+    #[test]
+    fn settlement_refuses_what_liquidity_cannot_cover() {
+        let mut i = inputs();
+        (i.won, i.gross_payout) = (true, 2 * SOL);
+        i.available = 2 * SOL;
+        assert!(compute_settlement(&i).is_err());
+        i.available = 2 * SOL + fees(&compute_settlement(&inputs()).unwrap());
+        assert_eq!(compute_settlement(&i).unwrap().player_gets, 2 * SOL);
+    }
+
+    #[test]
+    fn settlement_fee_phases_switch_at_thresholds() {
+        let total_at = |balance: u64| {
+            let mut i = inputs();
+            (i.total_balance, i.available) = (balance, balance);
+            fees(&compute_settlement(&i).unwrap())
+        };
+        assert_eq!(total_at(PHASE1_THRESHOLD - 1), 250 * SOL / 10_000);
+        assert_eq!(total_at(PHASE1_THRESHOLD),     200 * SOL / 10_000);
+        assert_eq!(total_at(PHASE2_THRESHOLD - 1), 200 * SOL / 10_000);
+        assert_eq!(total_at(PHASE2_THRESHOLD),     150 * SOL / 10_000);
+    }
+
+    #[test]
+    fn settlement_pays_referrer_only_when_eligible() {
+        let mut i = inputs();
+        (i.has_ref, i.referrer_lamports) = (true, 50_000_000);
+        let paid = compute_settlement(&i).unwrap();
+        assert_eq!(paid.ref_cut, 150 * SOL / 10_000);
+        assert_eq!(paid.claimable + paid.reinvested, 50 * SOL / 10_000);
+
+        // Below the referrer's minimum balance, or in net-loss mode, the cut stays with the house.
+        for tweak in [|i: &mut SettlementInputs| i.referrer_lamports = 49_999_999,
+                      |i: &mut SettlementInputs| i.ref_net_loss = true] {
+            let mut j = i;
+            tweak(&mut j);
+            let kept = compute_settlement(&j).unwrap();
+            assert_eq!(kept.ref_cut, 0);
+            assert_eq!(kept.claimable + kept.reinvested, 200 * SOL / 10_000);
+            assert_eq!(fees(&kept), fees(&paid));
+        }
+
+        // A referred bet too small for a 0.001 SOL cut routes it to the house as well.
+        i.bet = 60_000_000;
+        assert_eq!(compute_settlement(&i).unwrap().ref_cut, 0);
+    }
+
+    #[test]
+    fn settlement_flat_fee_replaces_phase_table() {
+        let mut i = inputs();
+        (i.has_ref, i.referrer_lamports, i.flat_fee_bps) = (true, SOL, Some(DOUBLE_FEE_BPS));
+        let p = compute_settlement(&i).unwrap();
+        assert_eq!((p.ref_cut, p.jackpot_cut), (0, 0));
+        assert_eq!(fees(&p), fixed::share_bps(SOL, DOUBLE_FEE_BPS));
+    }
+
+    #[test]
+    fn settlement_pays_pushes_and_partial_returns_as_non_wins() {
+        for gross in [SOL, SOL / 2] {
+            let mut i = inputs();
+            (i.gross_payout, i.insured) = (gross, SOL);
+            let p = compute_settlement(&i).unwrap();
+            assert_eq!((p.win_payout, p.player_gets), (gross, gross));
+            assert_eq!(p.promo_refund, SOL - gross, "insurance covers the net loss only");
+        }
+    }
+
+    #[test]
+    fn dragon_tiger_tie_is_a_half_return_not_a_win() {
+        let seed = (0u8..=255).map(|b| [b; 32]).find(|s| { let (d, t) = dragon_tiger_deal(s); d == t }).unwrap();
+        let curve = PayoutCurve::default();
+        let (won, gross, _) = resolve_seed(17, &[DT_DRAGON, 0, 0], 0, SOL, &seed, 0, &curve).unwrap();
+        assert_eq!((won, gross), (false, SOL / 2));
+        let (won, gross, _) = resolve_seed(17, &[DT_TIE, 0, 0], 0, SOL, &seed, 0, &curve).unwrap();
+        assert_eq!((won, gross), (true, 8 * SOL));
+    }
+
+    #[test]
+    fn settlement_charges_the_pinned_fees_not_the_current_phase() {
+        let mut i = inputs();
+        (i.total_balance, i.available) = (PHASE2_THRESHOLD, PHASE2_THRESHOLD);
+        i.fee_bps = pin_fee_bps(10 * SOL, false);
+        assert_eq!(fees(&compute_settlement(&i).unwrap()), 250 * SOL / 10_000);
+        let mut pool = pool_with(PHASE2_THRESHOLD);
+        accrue_terminal_fees(&mut pool, SOL, SOL, i.fee_bps).unwrap();
+        let (j, _, c, r) = accrued(&pool);
+        assert_eq!(j + c + r, 250 * SOL / 10_000);
+    }
+
+    #[test]
+    fn settlement_pays_the_executor_tip_out_of_a_win_paid_now() {
+        let mut i = inputs();
+        (i.won, i.gross_payout, i.executor_tip) = (true, 2 * SOL, CRANK_TIP_LAMPORTS);
+        let p = compute_settlement(&i).unwrap();
+        assert_eq!(p.executor_tip, CRANK_TIP_LAMPORTS);
+        assert_eq!(p.player_gets + p.executor_tip, p.win_payout);
+        assert_eq!(p.physical_out(), 2 * SOL, "the tip leaves the pool inside the gross payout");
+
+        // A loss, and a win held for the co-signer or a double, pay no tip.
+        let mut lost = i;
+        (lost.won, lost.gross_payout) = (false, 0);
+        let mut cosign = i;
+        cosign.cosign_threshold = SOL;
+        let mut double = i;
+        double.hold_for_double = true;
+        for j in [lost, cosign, double] {
+            let p = compute_settlement(&j).unwrap();
+            assert_eq!(p.executor_tip, 0);
+            assert_eq!(p.held, p.player_gets);
+            assert_eq!(p.player_gets, p.win_payout);
+        }
+    }
+
+    // ── Jackpot trigger ───────────────────────────────────────────────────
+
+    /// The fixture's seed with `roll` in the jackpot bytes 24..28.
+    fn rolled(roll: u64) -> [u8; 32] {
+        let mut seed = [0xff; 32];
+        seed[24..28].copy_from_slice(&(roll as u32).to_le_bytes());
+        seed
+    }
+
+    /// Physical and internal moves of the plan fit in what was available.
+    fn balances(i: &SettlementInputs, p: &SettlementPlan) -> bool {
+        p.physical_out() + p.jackpot_cut + p.insurance_cut + p.claimable + p.reinvested <= i.available
+    }
+
+    #[test]
+    fn settlement_jackpot_hits_only_below_the_threshold() {
+        let mut i = inputs();
+        i.jackpot_balance = JACKPOT_MIN_POOL;
+        let threshold = compute_settlement(&i).unwrap().jackpot_threshold;
+        assert_eq!(threshold, SOL * JACKPOT_RATE / JACKPOT_BASE);
+
+        i.seed = rolled(threshold - 1);
+        let hit = compute_settlement(&i).unwrap();
+        assert_eq!(hit.jackpot_prize, fixed::share_bps(JACKPOT_MIN_POOL, 9_000));
+        assert_eq!(hit.player_gets, hit.win_payout + hit.jackpot_prize);
+        assert!(balances(&i, &hit), "{hit:?}");
+
+        i.seed = rolled(threshold);
+        assert_eq!(compute_settlement(&i).unwrap().jackpot_prize, 0);
+
+        // Below the pool or bet minimum the bet is not even eligible.
+        for tweak in [|i: &mut SettlementInputs| i.jackpot_balance = JACKPOT_MIN_POOL - 1,
+                      |i: &mut SettlementInputs| i.bet = JACKPOT_MIN_BET - 1] {
+            let mut j = i;
+            j.seed = rolled(0);
+            tweak(&mut j);
+            let p = compute_settlement(&j).unwrap();
+            assert_eq!((p.jackpot_threshold, p.jackpot_prize), (0, 0));
+        }
+    }
+
+    #[test]
+    fn settlement_jackpot_threshold_respects_the_cap() {
+        let mut i = inputs();
+        (i.bet, i.total_balance, i.available, i.jackpot_balance) = (10 * SOL, 100 * SOL, 100 * SOL, 5 * SOL);
+        i.boost_trigger_bps = 20_000;
+        for cap_bps in [0, 1, JACKPOT_CAP_DEFAULT_BPS, JACKPOT_CAP_MAX_BPS] {
+            i.jackpot_cap_bps = cap_bps;
+            let cap  = jackpot_threshold_cap(cap_bps);
+            let base = fixed::share_bps(i.bet * JACKPOT_RATE / JACKPOT_BASE, i.boost_trigger_bps);
+            assert!(base > cap, "cap {cap_bps} must bind");
+
+            i.seed = rolled(cap);
+            let miss = compute_settlement(&i).unwrap();
+            assert_eq!((miss.jackpot_threshold, miss.jackpot_prize), (cap, 0), "a roll at the cap never hits");
+
+            i.seed = rolled(cap - 1);
+            let hit = compute_settlement(&i).unwrap();
+            assert_eq!(hit.jackpot_prize, fixed::share_bps(i.jackpot_balance, 9_000));
+            assert!(hit.jackpot_prize <= i.jackpot_balance);
+            assert!(balances(&i, &hit) && balances(&i, &miss), "{hit:?}");
+        }
+    }
+
+    #[test]
+    fn settlement_jackpot_prize_needs_liquidity_like_any_payout() {
+        let mut i = inputs();
+        (i.jackpot_balance, i.seed) = (JACKPOT_MIN_POOL, rolled(0));
+        (i.won, i.gross_payout)     = (true, 2 * SOL);
+        let hit = compute_settlement(&i).unwrap();
+        i.available = hit.physical_out() + hit.jackpot_cut + hit.insurance_cut + hit.claimable + hit.reinvested;
+        assert_eq!(compute_settlement(&i).unwrap(), hit);
+        i.available -= 1;
+        assert!(compute_settlement(&i).is_err());
+    }
+
+    // ── promo_gate ────────────────────────────────────────────────────────
+
+    #[test]
+    fn promo_rooms_require_player_stats_on_every_bet() {
+        let mut pool = pool_with(10 * SOL);
+        assert!(promo_gate(&pool, false).is_ok());
+        pool.promo_cap = SOL;
+        assert!(promo_gate(&pool, false).is_err());
+        assert!(promo_gate(&pool, true).is_ok());
+    }
+
+    // ── bet_quote ─────────────────────────────────────────────────────────
+
+    #[test]
+    fn quote_reports_the_max_allowed_bet_of_the_placed_config() {
+        let pool  = pool_with(100 * SOL);
+        let size  = [BlitzError::BetExceedsLimit, BlitzError::CapWouldTruncatePayout, BlitzError::InsufficientLiquidity]
+            .map(|e| anchor_lang::error::ERROR_CODE_OFFSET + e as u32);
+        for target in [200, 1_000, 10_000] {
+            let quote = bet_quote(&pool, false, 10, &[0; 3], target, 100 * SOL, false);
+            assert!(!quote.accepted);
+            assert!(size.contains(&quote.error_code));
+            assert_eq!(quote.max_allowed_bet, max_allowed_bet(&pool, 10, &[0; 3], target));
+            assert!(quote.max_allowed_bet > 0);
+            // What PLACE_FLAG_DOWNSIZE would place is accepted, one lamport more is not.
+            assert!(check_bet(&pool, 10, &[0; 3], target, quote.max_allowed_bet).is_ok());
+            assert!(bet_quote(&pool, false, 10, &[0; 3], target, quote.max_allowed_bet, false).accepted);
+        }
+        let low  = bet_quote(&pool, false, 10, &[0; 3], 200, 100 * SOL, false).max_allowed_bet;
+        let high = bet_quote(&pool, false, 10, &[0; 3], 10_000, 100 * SOL, false).max_allowed_bet;
+        assert!(high < low);
+    }
+
+    // ── rps_commitment ────────────────────────────────────────────────────
+
+    #[test]
+    fn rps_commitment_is_bound_to_the_player() {
+        let (a, b, salt) = (Pubkey::new_unique(), Pubkey::new_unique(), [7u8; 32]);
+        assert_ne!(rps_commitment(&a, RPS_ROCK, &salt), rps_commitment(&b, RPS_ROCK, &salt));
+        assert_ne!(rps_commitment(&a, RPS_ROCK, &salt), rps_commitment(&a, RPS_PAPER, &salt));
+    }
+
+    // ── Account layouts ───────────────────────────────────────────────────
+
+    #[test]
+    fn account_lens_match_their_layouts() {
+        fn zeroed<T: AnchorDeserialize>(len: usize) -> T {
+            T::deserialize(&mut &vec![0u8; len][..]).unwrap()
+        }
+        // LEN sizes every Option as Some.
+        let mut pool: GlobalPool = zeroed(GlobalPool::LEN);
+        let request = || Some(WithdrawalRequest { amount: 0, requested_at: 0, unlocks_at: 0 });
+        (pool.withdrawal_request, pool.reinvest_request, pool.insurance_tap) = (request(), request(), request());
+        pool.pending_authority = Some(Pubkey::default());
+        pool.migration_request = Some(LiquidityMigration { to: Pubkey::default(), amount: 0, requested_at: 0, unlocks_at: 0 });
+        assert_eq!(pool.try_to_vec().unwrap().len(), GlobalPool::LEN);
+        assert_eq!(zeroed::<GameSession>(GameSession::LEN).try_to_vec().unwrap().len(), GameSession::LEN);
+        assert_eq!(zeroed::<RocketEntry>(RocketEntry::LEN).try_to_vec().unwrap().len(), RocketEntry::LEN);
+        assert_eq!(zeroed::<NavSnapshot>(NavSnapshot::LEN).try_to_vec().unwrap().len(), NavSnapshot::LEN);
+        assert_eq!(zeroed::<SponsorGrant>(SponsorGrant::LEN).try_to_vec().unwrap().len(), SponsorGrant::LEN);
+    }
+
+    #[test]
+    fn game_slots_past_the_legacy_block_live_in_the_appended_fields() {
+        let mut pool = pool_with(0);
+        pool.counters_mut(3).bets  = 1;
+        pool.counters_mut(17).bets = 2;
+        pool.set_paused_games(1 << 3 | 1 << 17);
+        assert_eq!((pool.game_counters[3].bets, pool.game_counters_hi[1].bets), (1, 2));
+        assert_eq!((pool.games_paused, pool.games_paused_hi), (1 << 3, 1 << 1));
+        assert_eq!(pool.paused_games(), 1 << 3 | 1 << 17);
+    }
+
+    #[test]
+    fn legacy_session_prefix_survives_the_grown_layout() {
+        let mut s = GameSession::deserialize(&mut &[0u8; GameSession::LEN][..]).unwrap();
+        (s.player, s.bet_lamports, s.forfeit_slot, s.bump) = (Pubkey::new_unique(), 42, 7, 254);
+        s.pool = Pubkey::new_unique();
+        // What migrate_session sees: the pre-room bytes, then realloc's zeroed tail.
+        let mut data = s.try_to_vec().unwrap();
+        data[LEGACY_SESSION_LEN..].fill(0);
+        let m = GameSession::deserialize(&mut &data[..]).unwrap();
+        assert_eq!((m.player, m.bet_lamports, m.forfeit_slot, m.bump), (s.player, 42, 7, 254));
+        assert_eq!(m.pool, Pubkey::default());
+    }
+
+    #[test]
+    fn migration_books_only_unsettled_stakes_in_open_stakes() {
+        let mut pool = pool_with(10 * SOL);
+        pool.open_stakes = SOL;
+        let key = Pubkey::new_unique();
+        for (state, open) in [(0, 2 * SOL), (1, 3 * SOL), (2, 3 * SOL)] {
+            let mut s = GameSession::deserialize(&mut &[0u8; GameSession::LEN][..]).unwrap();
+            (s.player, s.bet_lamports, s.game_state) = (Pubkey::new_unique(), SOL, state);
+            migrate_legacy_session(&mut pool, key, &mut s);
+            assert_eq!(pool.open_stakes, open);
+            assert_eq!((s.pool, s.payout_to, s.index_page), (key, s.player, NOT_INDEXED));
+            assert!(s.payout_curve == pool.curve());
+        }
+    }
+
+    // ── accrue_terminal_fees ──────────────────────────────────────────────
+
+    /// A zeroed pool with `balance` liquid lamports.
+    fn pool_with(balance: u64) -> GlobalPool {
+        let mut pool = GlobalPool::deserialize(&mut &[0u8; GlobalPool::LEN][..]).unwrap();
+        pool.total_balance = balance;
+        pool
+    }
+
+    /// (jackpot, insurance, claimable, reinvested) accrued into `pool`.
+    fn accrued(pool: &GlobalPool) -> (u64, u64, u64, u64) {
+        (pool.jackpot_balance, pool.insurance_balance, pool.house_fees_earned, pool.total_reinvested)
+    }
+
+    #[test]
+    fn terminal_fees_nothing_kept_accrues_nothing() {
+        let mut pool = pool_with(10 * SOL);
+        pool.insurance_bps = 20;
+        accrue_terminal_fees(&mut pool, SOL, 0, [0; 3]).unwrap();
+        accrue_terminal_fees(&mut pool, 0, 0, [0; 3]).unwrap();
+        assert_eq!(accrued(&pool), (0, 0, 0, 0));
+    }
+
+    #[test]
+    fn terminal_fees_forfeit_matches_a_lost_settlement() {
+        for balance in [10 * SOL, PHASE1_THRESHOLD, PHASE2_THRESHOLD] {
+            let mut pool = pool_with(balance);
+            pool.insurance_bps = 20;
+            accrue_terminal_fees(&mut pool, SOL, SOL, [0; 3]).unwrap();
+            let mut i = inputs();
+            (i.total_balance, i.available, i.insurance_bps) = (balance, balance, 20);
+            let p = compute_settlement(&i).unwrap();
+            assert_eq!(accrued(&pool), (p.jackpot_cut, p.insurance_cut, p.claimable, p.reinvested));
+        }
+    }
+
+    #[test]
+    fn terminal_fees_capped_by_kept_stake_and_liquidity() {
+        // A 4% retention covers the full 2.5% of phase-0 cuts.
+        let mut pool = pool_with(10 * SOL);
+        accrue_terminal_fees(&mut pool, SOL, SOL * 4 / 100, [0; 3]).unwrap();
+        assert_eq!(pool.jackpot_balance, 50 * SOL / 10_000);
+        assert_eq!(pool.house_fees_earned + pool.total_reinvested, 200 * SOL / 10_000);
+
+        // 1% kept: the jackpot cut first, the house gets what is left of the kept part.
+        let mut pool = pool_with(10 * SOL);
+        accrue_terminal_fees(&mut pool, SOL, SOL / 100, [0; 3]).unwrap();
+        assert_eq!(pool.jackpot_balance, 50 * SOL / 10_000);
+        assert_eq!(pool.house_fees_earned + pool.total_reinvested, 50 * SOL / 10_000);
+
+        // Never more than the liquid balance, whatever was kept.
+        let mut pool = pool_with(1_000);
+        accrue_terminal_fees(&mut pool, SOL, SOL, [0; 3]).unwrap();
+        let (j, ins, c, r) = accrued(&pool);
+        assert!(j + ins + c <= 1_000 && r <= 1_000);
+    }
+
+    #[test]
+    fn terminal_fees_house_cut_rolls_over_by_phase() {
+        let house = |balance: u64| {
+            let mut pool = pool_with(balance);
+            accrue_terminal_fees(&mut pool, SOL, SOL, [0; 3]).unwrap();
+            (pool.house_fees_earned, pool.total_reinvested)
+        };
+        let op = fixed::share_bps(SOL, OPERATIONAL_BPS);
+        assert_eq!(house(10 * SOL),         (op, 200 * SOL / 10_000 - op));          // phase 0: all but ops stays liquid
+        let rest = 150 * SOL / 10_000 - op;
+        assert_eq!(house(PHASE1_THRESHOLD), (op + rest - rest / 2, rest / 2));       // phase 1: half rolls over
+        assert_eq!(house(PHASE2_THRESHOLD), (125 * SOL / 10_000, 0));                // phase 2: all claimable
+    }
+
+    // ── Plinko ────────────────────────────────────────────────────────────
+
+    #[test]
+    fn plinko_resolves_through_resolve_plinko() {
+        let curve = PayoutCurve::default();
+        for (rows, risk) in [(8, 0), (12, 1), (16, 2)] {
+            for b in [0x00u8, 0x5a, 0xff] {
+                let seed = [b; 32];
+                let (won, gross, outcome) = resolve_plinko(rows, risk, SOL, &seed, payout_num(0));
+                assert_eq!(resolve_seed(5, &[rows, risk, 0], 0, SOL, &seed, payout_num(0), &curve).unwrap().1, gross);
+                let GameOutcome::Plinko { path, bucket, multiplier_bps, .. } = outcome else { panic!() };
+                assert_eq!(bucket as u32, path.count_ones());
+                assert_eq!(gross, scale_payout(SOL, multiplier_bps));
+                assert_eq!(won, gross > SOL);
+            }
+        }
+    }
+
+    // ── NAV snapshot ──────────────────────────────────────────────────────
+
+    #[test]
+    fn nav_snapshot_prices_each_side_against_the_lp() {
+        let mut pool = pool_with(100 * SOL);
+        pool.lp_shares = 100 * SOL;
+        let mut n = NavSnapshot::deserialize(&mut &[0u8; NavSnapshot::LEN][..]).unwrap();
+        nav_snapshot_take(&pool, &mut n, 1);
+        assert_eq!((n.deposit_price, n.exit_price), (LP_PRICE_SCALE, LP_PRICE_SCALE));
+
+        // 10 SOL staked on pending bets: a deposit pays for them, an exit is not paid them.
+        pool.open_stakes = 10 * SOL;
+        assert_eq!(lp_deposit_price(&pool, &n), LP_PRICE_SCALE);
+        assert_eq!(lp_exit_price(&pool, &n), LP_PRICE_SCALE * 9 / 10);
+
+        // Moves since the snapshot go against the LP either way.
+        pool.open_stakes   = 0;
+        pool.total_balance = 80 * SOL;
+        assert_eq!(lp_deposit_price(&pool, &n), LP_PRICE_SCALE);
+        pool.total_balance = 120 * SOL;
+        assert_eq!(lp_exit_price(&pool, &n), LP_PRICE_SCALE);
+
+        // A snapshot from a written-off book, or taken with no shares, does not price.
+        pool.lp_generation = 1;
+        assert_eq!(lp_exit_price(&pool, &n), LP_PRICE_SCALE * 6 / 5);
+        pool.lp_generation = 0;
+        n.exit_price       = 0;
+        assert_eq!(lp_exit_price(&pool, &n), LP_PRICE_SCALE * 6 / 5);
+    }
+
+    // ── Sponsor grants ────────────────────────────────────────────────────
+
+    #[test]
+    fn sponsor_grant_stakes_only_within_its_constraints() {
+        let mut g = SponsorGrant::deserialize(&mut &[0u8; SponsorGrant::LEN][..]).unwrap();
+        g.amount      = 5 * SOL;
+        g.constraints = SponsorConstraints { expires_slot: 100, max_bet: 0, games_mask: 0 };
+        assert!(sponsor_grant_allows(&g, 3, 5 * SOL, 100));
+        assert!(!sponsor_grant_allows(&g, 3, 5 * SOL, 101));
+        assert!(!sponsor_grant_allows(&g, 3, 5 * SOL + 1, 100));
+
+        g.constraints.max_bet    = SOL;
+        g.constraints.games_mask = 1 << 3;
+        assert!(sponsor_grant_allows(&g, 3, SOL, 50));
+        assert!(!sponsor_grant_allows(&g, 3, SOL + 1, 50));
+        assert!(!sponsor_grant_allows(&g, 4, SOL, 50));
+    }
+
+    // ── Video poker ───────────────────────────────────────────────────────
+
+    /// Card from rank (0 = deuce … 12 = ace) and suit 0–3.
+    fn card(rank: u8, suit: u8) -> u8 { suit * 13 + rank }
+
+    #[test]
+    fn poker_ranks_straights_with_ace_high_and_low() {
+        let wheel = [card(12, 0), card(0, 1), card(1, 2), card(2, 3), card(3, 0)];
+        assert_eq!(poker_evaluate(&wheel), POKER_STRAIGHT);
+        let steel = [card(12, 2), card(0, 2), card(1, 2), card(2, 2), card(3, 2)];
+        assert_eq!(poker_evaluate(&steel), POKER_STRAIGHT_FLUSH);
+        let broadway = [card(8, 0), card(9, 1), card(10, 0), card(11, 0), card(12, 0)];
+        assert_eq!(poker_evaluate(&broadway), POKER_STRAIGHT);
+        let royal = [card(12, 3), card(11, 3), card(10, 3), card(9, 3), card(8, 3)];
+        assert_eq!(poker_evaluate(&royal), POKER_ROYAL);
+        let king_high = [card(7, 1), card(8, 1), card(9, 1), card(10, 1), card(11, 1)];
+        assert_eq!(poker_evaluate(&king_high), POKER_STRAIGHT_FLUSH);
+        let around_the_corner = [card(10, 0), card(11, 1), card(12, 2), card(0, 3), card(1, 0)];
+        assert_eq!(poker_evaluate(&around_the_corner), POKER_NOTHING);
+    }
+
+    #[test]
+    fn poker_ranks_made_hands() {
+        let flush = [card(0, 1), card(3, 1), card(6, 1), card(9, 1), card(12, 1)];
+        assert_eq!(poker_evaluate(&flush), POKER_FLUSH);
+        let boat = [card(4, 0), card(4, 1), card(4, 2), card(7, 0), card(7, 3)];
+        assert_eq!(poker_evaluate(&boat), POKER_FULL_HOUSE);
+        assert!(POKER_PAYTABLE[POKER_FULL_HOUSE as usize] > POKER_PAYTABLE[POKER_FLUSH as usize]);
+        let quads = [card(2, 0), card(2, 1), card(2, 2), card(2, 3), card(12, 0)];
+        assert_eq!(poker_evaluate(&quads), POKER_QUADS);
+        let trips = [card(0, 0), card(0, 1), card(0, 2), card(5, 3), card(9, 0)];
+        assert_eq!(poker_evaluate(&trips), POKER_TRIPS);
+        let two_pair = [card(0, 0), card(0, 1), card(5, 2), card(5, 3), card(9, 0)];
+        assert_eq!(poker_evaluate(&two_pair), POKER_TWO_PAIR);
+    }
+
+    #[test]
+    fn poker_pays_jacks_or_better_only() {
+        let pair = |rank: u8| [card(rank, 0), card(rank, 1), card(1, 2), card(4, 3), card(6, 0)];
+        assert_eq!(poker_evaluate(&pair(8)),  POKER_NOTHING); // tens
+        assert_eq!(poker_evaluate(&pair(9)),  POKER_JACKS);
+        assert_eq!(poker_evaluate(&pair(12)), POKER_JACKS);
+        // Kickers do not lift a low pair.
+        let tens_ace_king = [card(8, 0), card(8, 1), card(12, 2), card(11, 3), card(10, 0)];
+        assert_eq!(poker_evaluate(&tens_ace_king), POKER_NOTHING);
+    }
+
+    #[test]
+    fn poker_hand_counts_match_full_enumeration() {
+        let mut counts = [0u64; 10];
+        for a in 0..48u8 { for b in a + 1..49 { for c in b + 1..50 { for d in c + 1..51 { for e in d + 1..52 {
+            counts[poker_evaluate(&[a, b, c, d, e]) as usize] += 1;
+        }}}}}
+        assert_eq!(counts, POKER_HAND_COUNTS);
+        assert_eq!(counts.iter().sum::<u64>(), POKER_HANDS);
+    }
+
+    #[test]
+    fn poker_deal_never_repeats_a_card() {
+        for n in 0u32..4_096 {
+            let seed  = hash::hash(&n.to_le_bytes()).to_bytes();
+            let cards = poker_deal(&seed);
+            let mut seen = 0u64;
+            for c in cards {
+                assert!(c < POKER_DECK);
+                assert_eq!(seen & 1 << c, 0, "seed {n} dealt {c} twice");
+                seen |= 1 << c;
+            }
+        }
+    }
+
+    // ── Player sketch ─────────────────────────────────────────────────────
+
+    /// The f64 estimator hll_estimate replaced, kept here as the reference.
+    fn hll_reference(registers: &[u8; HLL_REGISTERS]) -> f64 {
+        let m     = HLL_REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = registers.iter().map(|&r| 1.0 / (1u64 << r.min(63)) as f64).sum();
+        let raw   = alpha * m * m / sum;
+        let zeros = registers.iter().filter(|&&r| r == 0).count();
+        if raw <= 2.5 * m && zeros > 0 { m * (m / zeros as f64).ln() } else { raw }
+    }
+
+    #[test]
+    fn hll_estimate_matches_the_float_formula() {
+        assert_eq!(hll_estimate(&[0; HLL_REGISTERS]), 0);
+        let mut regs = [0u8; HLL_REGISTERS];
+        let mut x    = 0x9e37_79b9_7f4a_7c15u64;
+        for step in 0..4 * HLL_REGISTERS {
+            x ^= x << 13;
+            x ^= x >> 7;
+            x ^= x << 17;
+            let i = (x % HLL_REGISTERS as u64) as usize;
+            regs[i] = regs[i].max((x >> 32).trailing_zeros() as u8 + 1);
+            if step % 16 == 0 {
+                let want = hll_reference(&regs);
+                assert!((hll_estimate(&regs) as f64 - want.floor()).abs() <= 1.0, "step {step}: want {want}");
+            }
+        }
+        assert_eq!(hll_estimate(&[63; HLL_REGISTERS]), u64::MAX);           // saturates, as `as u64` did
+    }
+}
@@ -0,0 +1,79 @@
+//! # ORAO VRF randomness decoding
+//!
+//! One decoder for an ORAO VRF randomness request account, read from raw account data so
+//! the program takes no SDK dependency. Two layouts are accepted, told apart by their
+//! Anchor discriminator:
+//!
+//! - `Randomness` (v1): seed [u8; 32], randomness [u8; 64], then the oracle responses.
+//!   All-zero randomness = not fulfilled yet.
+//! - `RandomnessV2`: a Borsh enum tag (0 = pending, 1 = fulfilled), client Pubkey,
+//!   seed [u8; 32], then — when fulfilled — randomness [u8; 64].
+//!
+//! The randomness is fixed by the request seed and the oracles' keys, so a bet bound to
+//! a request that is still pending cannot be steered by whoever fulfils it. A request lives
+//! at the PDA [REQUEST_SEED, seed] of the ORAO program, so its seed names it for good.
+//!
+//! Owner and discriminator checks live here too; callers only pass the AccountInfo.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash;
+
+/// ORAO VRF program (same ID on mainnet and devnet).
+pub const PROGRAM_ID: Pubkey = pubkey!("VRFzZoJdhFWL8rkvu87LpKM3RbcVezpMEc6X5GVDr7y");
+
+/// PDA prefix of a randomness request account.
+pub const REQUEST_SEED: &[u8] = b"orao-vrf-randomness-request";
+
+const V1_SEED_AT:       usize = 8;
+const V1_RANDOMNESS_AT: usize = V1_SEED_AT + 32;
+const V2_TAG_AT:        usize = 8;
+const V2_SEED_AT:       usize = V2_TAG_AT + 1 + 32;
+const V2_RANDOMNESS_AT: usize = V2_SEED_AT + 32;
+
+/// The account is not an ORAO randomness request: wrong owner, unknown discriminator,
+/// unknown state tag or short data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Malformed;
+
+/// The fields a bet needs from a randomness request.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Randomness {
+    pub seed:  [u8; 32],
+    pub value: Option<[u8; 64]>, // None = pending
+}
+
+/// Address of the request account for `seed`.
+pub fn request_address(seed: &[u8; 32]) -> Pubkey {
+    Pubkey::find_program_address(&[REQUEST_SEED, seed], &PROGRAM_ID).0
+}
+
+/// Decodes `ai` after checking it is owned by the ORAO VRF program.
+pub fn load(ai: &AccountInfo) -> core::result::Result<Randomness, Malformed> {
+    if *ai.owner != PROGRAM_ID { return Err(Malformed); }
+    let data = ai.try_borrow_data().map_err(|_| Malformed)?;
+    decode(&data)
+}
+
+/// Decodes raw account data; see the module docs for the layouts.
+pub fn decode(data: &[u8]) -> core::result::Result<Randomness, Malformed> {
+    let disc = data.get(..8).ok_or(Malformed)?;
+    let seed = |at: usize| -> core::result::Result<[u8; 32], Malformed> {
+        data.get(at..at + 32).ok_or(Malformed)?.try_into().map_err(|_| Malformed)
+    };
+    let read = |at: usize| -> core::result::Result<[u8; 64], Malformed> {
+        data.get(at..at + 64).ok_or(Malformed)?.try_into().map_err(|_| Malformed)
+    };
+    if disc == &hash::hash(b"account:Randomness").to_bytes()[..8] {
+        let r = read(V1_RANDOMNESS_AT)?;
+        return Ok(Randomness { seed: seed(V1_SEED_AT)?, value: (r != [0u8; 64]).then_some(r) });
+    }
+    if disc == &hash::hash(b"account:RandomnessV2").to_bytes()[..8] {
+        let value = match data.get(V2_TAG_AT).ok_or(Malformed)? {
+            0 => None,
+            1 => Some(read(V2_RANDOMNESS_AT)?),
+            _ => return Err(Malformed),
+        };
+        return Ok(Randomness { seed: seed(V2_SEED_AT)?, value });
+    }
+    Err(Malformed)
+}
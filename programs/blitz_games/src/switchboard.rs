@@ -0,0 +1,70 @@
+//! # Switchboard On-Demand randomness decoding
+//!
+//! One decoder for a Switchboard On-Demand `RandomnessAccountData` account, read from
+//! raw account data so the program takes no SDK dependency. Layout (after the 8-byte
+//! Anchor discriminator): authority, queue, seed_slothash, seed_slot u64 LE, oracle,
+//! reveal_slot u64 LE, value [u8; 32], then reserved bytes.
+//!
+//! A randomness account is committed to a slot hash (`seed_slot`) and later revealed
+//! by an oracle (`reveal_slot` > 0, `value` set). The value is a function of the
+//! committed slot hash and the oracle's key only, so once a bet is bound to an account
+//! committed in the previous slot and not yet revealed, nobody can steer its outcome.
+//!
+//! Owner and discriminator checks live here too; callers only pass the AccountInfo.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash;
+
+/// Switchboard On-Demand program.
+#[cfg(not(feature = "devnet"))]
+pub const PROGRAM_ID: Pubkey = pubkey!("SBondMDrcV3K4kxZR1HNVT7osZxAHVHgYXL5Ze1oMUv");
+/// Switchboard On-Demand program (devnet deployment).
+#[cfg(feature = "devnet")]
+pub const PROGRAM_ID: Pubkey = pubkey!("Aio4gaXjXzJNVLtzwMNVmdSCBEjpGumLtvDRM5aM5jdt");
+
+const SEED_SLOT_AT:   usize = 8 + 32 + 32 + 32;
+const REVEAL_SLOT_AT: usize = SEED_SLOT_AT + 8 + 32;
+const VALUE_AT:       usize = REVEAL_SLOT_AT + 8;
+/// Bytes read from the account: everything up to and including `value`.
+pub const MIN_LEN:    usize = VALUE_AT + 32;
+
+/// The account is not a Switchboard randomness account: wrong owner, wrong
+/// discriminator or short data.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Malformed;
+
+/// The fields a bet needs from a randomness account.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Randomness {
+    pub seed_slot:   u64,
+    pub reveal_slot: u64, // 0 = not revealed yet
+    pub value:       [u8; 32],
+}
+
+impl Randomness {
+    /// The revealed value, or None while the oracle has not revealed.
+    pub fn revealed(&self) -> Option<[u8; 32]> {
+        (self.reveal_slot > 0).then_some(self.value)
+    }
+}
+
+/// Decodes `ai` after checking it is owned by the Switchboard program and carries the
+/// `RandomnessAccountData` discriminator.
+pub fn load(ai: &AccountInfo) -> core::result::Result<Randomness, Malformed> {
+    if *ai.owner != PROGRAM_ID { return Err(Malformed); }
+    let data = ai.try_borrow_data().map_err(|_| Malformed)?;
+    decode(&data)
+}
+
+/// Decodes raw account data; see the module docs for the layout.
+pub fn decode(data: &[u8]) -> core::result::Result<Randomness, Malformed> {
+    if data.len() < MIN_LEN { return Err(Malformed); }
+    let disc = hash::hash(b"account:RandomnessAccountData").to_bytes();
+    if data[..8] != disc[..8] { return Err(Malformed); }
+    let u64_at = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+    Ok(Randomness {
+        seed_slot:   u64_at(SEED_SLOT_AT),
+        reveal_slot: u64_at(REVEAL_SLOT_AT),
+        value:       data[VALUE_AT..VALUE_AT + 32].try_into().unwrap(),
+    })
+}
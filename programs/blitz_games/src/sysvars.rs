@@ -0,0 +1,119 @@
+//! # SlotHashes decoding
+//!
+//! One versioned decoder for the SlotHashes sysvar, so no caller hand-parses offsets.
+//! Layout V1 (the only one the runtime has shipped): u64 LE entry count, then up to
+//! 512 entries of (slot u64 LE, hash [u8; 32]), newest first — slots strictly descending.
+//!
+//! Every read is bounds-checked against the declared count and the bytes actually
+//! present. Any entry that would break the descending order is reported as `Malformed`,
+//! never silently accepted as entropy. Lookups are binary searches (≤ 10 probes).
+//!
+//! With the `slot-hashes-syscall` feature, on-chain reads go through `sol_get_sysvar`
+//! (SIMD-0127) instead of the account data. The account is still passed and
+//! address-checked; it then only needs to deserialize as a sysvar, not be parsed.
+
+/// Bytes before the first entry: the u64 entry count.
+pub const HEADER_LEN:  usize = 8;
+/// Bytes per entry: slot (8) + hash (32).
+pub const ENTRY_LEN:   usize = 40;
+/// Maximum entries the runtime keeps (MAX_ENTRIES in the sysvar definition).
+pub const MAX_ENTRIES: usize = 512;
+
+/// The sysvar bytes do not match layout V1: short data, an impossible count, or
+/// entries out of order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Malformed;
+
+/// Random access to SlotHashes entries, wherever they are read from.
+pub trait Source {
+    /// Validated entry count, ≤ MAX_ENTRIES.
+    fn count(&self) -> Result<usize, Malformed>;
+    /// Entry `i` (0 = newest). Callers keep `i < count()`.
+    fn entry(&self, i: usize) -> Result<(u64, [u8; 32]), Malformed>;
+}
+
+/// Layout V1 over raw account data.
+pub struct AccountData<'a>(pub &'a [u8]);
+
+impl Source for AccountData<'_> {
+    fn count(&self) -> Result<usize, Malformed> {
+        let head = self.0.get(..HEADER_LEN).ok_or(Malformed)?;
+        let n    = u64::from_le_bytes(head.try_into().map_err(|_| Malformed)?);
+        let n    = usize::try_from(n).map_err(|_| Malformed)?;
+        if n > MAX_ENTRIES || self.0.len() < HEADER_LEN + n * ENTRY_LEN { return Err(Malformed); }
+        Ok(n)
+    }
+
+    fn entry(&self, i: usize) -> Result<(u64, [u8; 32]), Malformed> {
+        let at  = HEADER_LEN + i * ENTRY_LEN;
+        let raw = self.0.get(at..at + ENTRY_LEN).ok_or(Malformed)?;
+        Ok((u64::from_le_bytes(raw[..8].try_into().unwrap()), raw[8..].try_into().unwrap()))
+    }
+}
+
+/// Layout V1 read piecewise through the `sol_get_sysvar` syscall.
+#[cfg(all(feature = "slot-hashes-syscall", target_os = "solana"))]
+pub struct Syscall;
+
+#[cfg(all(feature = "slot-hashes-syscall", target_os = "solana"))]
+impl Syscall {
+    fn read(offset: usize, buf: &mut [u8]) -> Result<(), Malformed> {
+        extern "C" {
+            fn sol_get_sysvar(sysvar_id_addr: *const u8, var_addr: *mut u8, offset: u64, length: u64) -> u64;
+        }
+        let id = anchor_lang::solana_program::sysvar::slot_hashes::ID;
+        // SAFETY: `id` is a 32-byte key and `buf` is writable for `buf.len()` bytes.
+        let rc = unsafe { sol_get_sysvar(id.as_ref().as_ptr(), buf.as_mut_ptr(), offset as u64, buf.len() as u64) };
+        if rc == 0 { Ok(()) } else { Err(Malformed) }
+    }
+}
+
+#[cfg(all(feature = "slot-hashes-syscall", target_os = "solana"))]
+impl Source for Syscall {
+    fn count(&self) -> Result<usize, Malformed> {
+        let mut head = [0u8; HEADER_LEN];
+        Self::read(0, &mut head)?;
+        let n = usize::try_from(u64::from_le_bytes(head)).map_err(|_| Malformed)?;
+        if n > MAX_ENTRIES { return Err(Malformed); }
+        Ok(n)
+    }
+
+    fn entry(&self, i: usize) -> Result<(u64, [u8; 32]), Malformed> {
+        let mut raw = [0u8; ENTRY_LEN];
+        Self::read(HEADER_LEN + i * ENTRY_LEN, &mut raw)?;
+        Ok((u64::from_le_bytes(raw[..8].try_into().unwrap()), raw[8..].try_into().unwrap()))
+    }
+}
+
+/// Index of the oldest entry whose slot is ≥ `slot`, or None if every entry is older.
+/// Each probe must sit strictly between its bracketing entries, so a decreasing run is
+/// verified along the search path.
+fn oldest_at_or_after<S: Source>(src: &S, slot: u64) -> Result<Option<(usize, u64, [u8; 32])>, Malformed> {
+    let n = src.count()?;
+    if n == 0 { return Ok(None); }
+    let (newest, newest_hash) = src.entry(0)?;
+    if newest < slot { return Ok(None); }
+    let (oldest, _) = src.entry(n - 1)?;
+    if oldest > newest || (oldest == newest && n > 1) { return Err(Malformed); }
+    // Invariant: entry(lo).slot ≥ slot; everything past `hi` is < slot or out of range.
+    let (mut lo, mut lo_slot, mut lo_hash, mut hi, mut hi_slot) = (0, newest, newest_hash, n, 0u64);
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        let (s, h) = src.entry(mid)?;
+        if s >= lo_slot || (hi < n && s <= hi_slot) { return Err(Malformed); }
+        if s >= slot { (lo, lo_slot, lo_hash) = (mid, s, h); } else { (hi, hi_slot) = (mid, s); }
+    }
+    Ok(Some((lo, lo_slot, lo_hash)))
+}
+
+/// Hash of exactly `slot`, if it is still in the sysvar.
+pub fn hash_of<S: Source>(src: &S, slot: u64) -> Result<Option<[u8; 32]>, Malformed> {
+    Ok(oldest_at_or_after(src, slot)?.and_then(|(_, s, h)| (s == slot).then_some(h)))
+}
+
+/// Hash of the first slot at or after `slot` (skipped slots have no entry). None when the
+/// oldest entry is already past `slot`: the true first slot may have aged out.
+pub fn hash_at_or_after<S: Source>(src: &S, slot: u64) -> Result<Option<[u8; 32]>, Malformed> {
+    let n = src.count()?;
+    Ok(oldest_at_or_after(src, slot)?.and_then(|(i, s, h)| (i + 1 < n || s == slot).then_some(h)))
+}